@@ -64,6 +64,7 @@ fn bench_diff_filtered(c: &mut Criterion) {
             node_types: Some(node_filter),
             edge_types: Some(edge_filter),
             ignore_fields: HashSet::new(),
+            expr: None,
         };
 
         group.bench_function(BenchmarkId::new("org_supplies_only", name), |b| {
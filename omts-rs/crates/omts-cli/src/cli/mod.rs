@@ -2,6 +2,7 @@
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand, ValueEnum};
+use omts_core::CalendarDate;
 
 /// Import format for the `import` subcommand.
 ///
@@ -34,7 +35,7 @@ pub enum ExportFormat {
 /// Parsing `"-"` yields [`PathOrStdin::Stdin`]; anything else yields
 /// [`PathOrStdin::Path`].  This avoids stringly-typed handling of the stdin
 /// sentinel throughout the codebase.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PathOrStdin {
     /// Read from standard input.
     Stdin,
@@ -87,7 +88,26 @@ pub enum DisclosureScope {
     Internal,
 }
 
-/// Graph traversal direction for the `reach` subcommand.
+/// Conflict-resolution policy for a `merge --resolve FIELD=POLICY` rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ResolvePolicy {
+    /// Prefer the value from the earliest FILE argument.
+    Priority,
+    /// Prefer the most recent `data_quality.last_verified`.
+    LastVerified,
+    /// Prefer the highest `data_quality.confidence`.
+    Confidence,
+    /// Prefer the file with the newest `snapshot_date`.
+    Snapshot,
+    /// Take the largest value (numeric properties).
+    Max,
+    /// Take the smallest value (numeric properties).
+    Min,
+    /// Take the mean value (numeric properties).
+    Mean,
+}
+
+/// Graph traversal direction for `reach` and `redact --expand`.
 #[derive(Clone, Debug, ValueEnum)]
 pub enum Direction {
     /// Follow edges away from the source node (default).
@@ -110,6 +130,28 @@ pub enum TargetEncoding {
     Cbor,
 }
 
+/// Edge weight used by the `segment` subcommand.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum SegmentWeight {
+    /// Every edge counts equally (default).
+    Uniform,
+    /// Weight by the edge `annual_value` property.
+    AnnualValue,
+    /// Weight by the edge `volume` property.
+    Volume,
+}
+
+/// Edge cost used by the `path` subcommand.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum PathCost {
+    /// Every edge costs 1 (default).
+    Hops,
+    /// Cost by the inverse of the edge `share_of_buyer_demand` property.
+    ShareOfBuyerDemand,
+    /// Cost by the inverse of the edge `annual_value` property.
+    AnnualValue,
+}
+
 /// All top-level subcommands exposed by the `omts` binary.
 #[derive(Subcommand)]
 pub enum Command {
@@ -131,6 +173,95 @@ pub enum Command {
         /// Merge strategy: union (default) or intersect.
         #[arg(long, default_value = "union")]
         strategy: MergeStrategy,
+        /// Input FILE whose reporting-entity perspective supplies the merged
+        /// `tier` values; tiers from other perspectives go to `_conflicts`.
+        #[arg(long, value_name = "FILE")]
+        primary_perspective: Option<PathOrStdin>,
+        /// Fill a conflicting property instead of leaving it empty, e.g.
+        /// `name=priority` or `annual_value=max`; the conflict is still
+        /// recorded. Policies: priority, last-verified, confidence, snapshot,
+        /// max, min, mean. Repeatable.
+        #[arg(long, value_name = "FIELD=POLICY", value_parser = parse_resolve_rule)]
+        resolve: Vec<(String, ResolvePolicy)>,
+        /// Report to stderr why each merge group formed (shared identifiers,
+        /// `same_as` edges) and which candidate pairs were rejected.
+        #[arg(long)]
+        explain: bool,
+        /// Record on every merged node and edge which source elements it was
+        /// built from (`_provenance`), so it can later be split with
+        /// `omts unmerge`.
+        #[arg(long)]
+        provenance: bool,
+        /// A previous merge result whose node and edge IDs are kept for
+        /// matching nodes (by identifier, or by source node when it was
+        /// written with `--provenance`) and edges (by type and endpoints).
+        /// ID changes are reported to stderr.
+        #[arg(long, value_name = "FILE")]
+        previous: Option<PathOrStdin>,
+        /// A pre-enrichment merge result, written with `--provenance`, whose
+        /// merge groups are kept: a new identifier match that would extend one
+        /// of its groups is emitted as a `same_as` edge (`confidence: probable`,
+        /// `basis: enrichment_match`) for review instead of being merged.
+        #[arg(long, value_name = "FILE")]
+        enrichment_base: Option<PathOrStdin>,
+        /// Mark the result as a pre-enrichment merge
+        /// (`merge_metadata.enrichment: pre_enrichment`) for later use as
+        /// `--enrichment-base`. Implies `--provenance`.
+        #[arg(long, conflicts_with = "enrichment_base")]
+        pre_enrichment: bool,
+        /// Only resolve identities and print a preview instead of the merged
+        /// file: group sizes, oversized groups, expected conflicts per
+        /// property, and nodes that would stay unmerged.
+        #[arg(long, conflicts_with_all = ["explain", "provenance", "pre_enrichment", "previous"])]
+        dry_run: bool,
+        /// Target output encoding: json (default) or cbor.
+        #[arg(long, default_value = "json", value_enum)]
+        to: TargetEncoding,
+        /// Compress output with zstd after serialization.
+        #[arg(long)]
+        compress: bool,
+    },
+
+    /// Find duplicate nodes within one file: shared external identifiers and
+    /// fuzzy legal-name matches.
+    Dedup {
+        /// Path to an .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// Collapse nodes that share an external identifier and write the
+        /// deduplicated file to stdout.
+        #[arg(long)]
+        apply: bool,
+        /// Add a `same_as` edge (confidence "probable") for every fuzzy name
+        /// match and write the file to stdout.
+        #[arg(long)]
+        suggest: bool,
+        /// Minimum normalised-name similarity for a fuzzy match (0.0-1.0).
+        #[arg(long, value_name = "SCORE", default_value_t = 0.85, value_parser = parse_threshold)]
+        threshold: f64,
+        /// Target output encoding with --apply/--suggest: json (default) or cbor.
+        #[arg(long, default_value = "json", value_enum)]
+        to: TargetEncoding,
+        /// Compress --apply/--suggest output with zstd after serialization.
+        #[arg(long)]
+        compress: bool,
+    },
+
+    /// Undo a merge using the provenance recorded by `merge --provenance`:
+    /// split merged nodes apart, or rebuild one source file.
+    Unmerge {
+        /// Path to a merged .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// Split this merged node back into its source nodes, rewiring its
+        /// edges to the constituent that contributed each one. Repeatable.
+        #[arg(long, value_name = "ID", required_unless_present = "source")]
+        node: Vec<String>,
+        /// Rebuild the contribution of one input, by its label in
+        /// `merge_metadata.source_files` (e.g. `file_1`) or by its file name
+        /// (e.g. `suppliers.omts`, recorded by `merge --provenance`).
+        #[arg(long, value_name = "SOURCE", conflicts_with = "node")]
+        source: Option<String>,
         /// Target output encoding: json (default) or cbor.
         #[arg(long, default_value = "json", value_enum)]
         to: TargetEncoding,
@@ -139,14 +270,59 @@ pub enum Command {
         compress: bool,
     },
 
+    /// Review the conflicts a merge recorded: list them, or apply decisions.
+    Conflicts {
+        #[command(subcommand)]
+        command: ConflictsCommand,
+    },
+
     /// Redact a file for a target disclosure scope.
     Redact {
         /// Path to an .omts file, or `-` for stdin.
         #[arg(value_name = "FILE")]
         file: PathOrStdin,
+        /// Node IDs to retain; all other eligible nodes become boundary refs.
+        #[arg(value_name = "NODE_ID", num_args = 0..)]
+        node_ids: Vec<String>,
         /// Target disclosure scope (required).
         #[arg(long)]
         scope: DisclosureScope,
+        /// Retain nodes of this type (repeatable; e.g. organization, facility).
+        #[arg(long, value_name = "TYPE")]
+        node_type: Vec<String>,
+        /// Retain the endpoints of edges of this type (repeatable; e.g. supplies).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Retain elements with this label key, or key=value pair (repeatable).
+        #[arg(long, value_name = "KEY[=VALUE]")]
+        label: Vec<String>,
+        /// Retain nodes with this identifier scheme, or scheme:value pair (repeatable).
+        #[arg(long, value_name = "SCHEME[:VALUE]")]
+        identifier: Vec<String>,
+        /// Retain nodes whose jurisdiction equals this ISO 3166-1 alpha-2 code (repeatable).
+        #[arg(long, value_name = "CC")]
+        jurisdiction: Vec<String>,
+        /// Retain nodes whose name contains this pattern (case-insensitive substring, repeatable).
+        #[arg(long, value_name = "PATTERN")]
+        name: Vec<String>,
+        /// Boolean selector expression for the nodes to retain, combined with
+        /// the other selector flags by AND.
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
+        /// Also retain nodes up to N hops from the selected nodes (default: 0).
+        #[arg(long, default_value = "0")]
+        expand: u32,
+        /// Direction of `--expand`: both (default), outgoing, or incoming.
+        #[arg(long, default_value = "both")]
+        direction: Direction,
+        /// Redaction policy file (TOML if named `*.toml`, JSON otherwise)
+        /// with per-partner node, identifier, and edge property rules.
+        #[arg(long, value_name = "FILE")]
+        policy: Option<PathBuf>,
+        /// Write an audit report of what was retained, replaced, omitted, and
+        /// stripped to FILE, in the `--format` output format.
+        #[arg(long, value_name = "FILE")]
+        report: Option<PathBuf>,
         /// Target output encoding: json (default) or cbor.
         #[arg(long, default_value = "json", value_enum)]
         to: TargetEncoding,
@@ -155,11 +331,40 @@ pub enum Command {
         compress: bool,
     },
 
+    /// Map the boundary references of a redacted file to known candidate nodes.
+    ResolveBoundary {
+        /// Path to the redacted .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// Path to an .omts file of candidate nodes: the unredacted original
+        /// or master data (cannot be `-` if FILE is `-`).
+        #[arg(value_name = "CANDIDATES")]
+        candidates: PathOrStdin,
+    },
+
+    /// Check a file for leaks beyond what its disclosure scope permits.
+    AuditDisclosure {
+        /// Path to an .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// Audit against this scope instead of the file's declared
+        /// `disclosure_scope`.
+        #[arg(long)]
+        scope: Option<DisclosureScope>,
+    },
+
     /// Print summary statistics for a graph.
     Inspect {
         /// Path to an .omts file, or `-` for stdin.
         #[arg(value_name = "FILE")]
         file: PathOrStdin,
+        /// Also compute network metrics (degree distributions, components,
+        /// centrality). Builds the full graph; slower on large files.
+        #[arg(long)]
+        metrics: bool,
+        /// Number of organizations to list in the `PageRank` ranking.
+        #[arg(long, value_name = "N", default_value_t = 10, requires = "metrics")]
+        top: usize,
     },
 
     /// Compute a structural diff between two .omts files.
@@ -185,6 +390,9 @@ pub enum Command {
         /// Exclude this property from comparison (repeatable).
         #[arg(long, value_name = "FIELD")]
         ignore_field: Vec<String>,
+        /// Restrict diff to elements matching a boolean selector expression.
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
     },
 
     /// Re-serialize an .omts file (normalize whitespace, key ordering).
@@ -224,6 +432,18 @@ pub enum Command {
         /// Traversal direction: outgoing (default), incoming, or both.
         #[arg(long, default_value = "outgoing")]
         direction: Direction,
+        /// Traverse only edges of this type (repeatable; e.g. supplies).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Emit the reached region as an .omts file instead of listing node IDs.
+        #[arg(long)]
+        subgraph: bool,
+        /// Target output encoding for `--subgraph`: json (default) or cbor.
+        #[arg(long, default_value = "json", value_enum, requires = "subgraph")]
+        to: TargetEncoding,
+        /// Compress `--subgraph` output with zstd after serialization.
+        #[arg(long, requires = "subgraph")]
+        compress: bool,
     },
 
     /// Find paths between two nodes.
@@ -243,6 +463,28 @@ pub enum Command {
         /// Maximum path length in edges (default: 20).
         #[arg(long, default_value = "20")]
         max_depth: u32,
+        /// Edge cost: hops (default), share-of-buyer-demand, or annual-value.
+        #[arg(long, default_value = "hops", value_enum)]
+        cost: PathCost,
+        /// Multiply the cost of edges of this type (repeatable; e.g. ownership=5).
+        #[arg(long, value_name = "TYPE=N")]
+        edge_cost: Vec<String>,
+        /// Constrain the edge-type sequence, e.g. "supplies+ operates".
+        #[arg(long, value_name = "PATTERN")]
+        pattern: Option<String>,
+        /// Traverse only edges of this type (repeatable; e.g. supplies).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Emit the union of the found paths as an .omts file instead of
+        /// listing them.
+        #[arg(long)]
+        subgraph: bool,
+        /// Target output encoding for `--subgraph`: json (default) or cbor.
+        #[arg(long = "to", default_value = "json", value_enum, requires = "subgraph")]
+        encoding: TargetEncoding,
+        /// Compress `--subgraph` output with zstd after serialization.
+        #[arg(long, requires = "subgraph")]
+        compress: bool,
     },
 
     /// Extract the induced subgraph for a set of nodes and/or selector-matched elements.
@@ -271,6 +513,10 @@ pub enum Command {
         /// Match nodes whose name contains this pattern (case-insensitive substring, repeatable).
         #[arg(long, value_name = "PATTERN")]
         name: Vec<String>,
+        /// Boolean selector expression, combined with the other selector flags
+        /// by AND (e.g. `type=organization AND NOT label:risk.cleared`).
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
         /// Include neighbors up to N hops from the specified nodes (default: 0).
         #[arg(long, default_value = "0")]
         expand: u32,
@@ -289,6 +535,33 @@ pub enum Command {
         example: bool,
     },
 
+    /// Write a binary index sidecar (`<FILE>.idx`) for fast repeated queries.
+    Index {
+        /// Path to an .omts file (stdin is not supported).
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Explore a graph interactively: load once, then query, traverse, and save selections.
+    Shell {
+        /// Path to an .omts file (stdin is reserved for shell commands).
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Serve JSON endpoints (validate, query, reach, path, ...) over local HTTP.
+    Serve {
+        /// Paths to .omts files to load; each is reloaded when it changes on disk.
+        #[arg(value_name = "FILE", required = true, num_args = 1..)]
+        files: Vec<PathBuf>,
+        /// Address to bind.
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Port to listen on (0 picks a free port).
+        #[arg(long, default_value = "8437")]
+        port: u16,
+    },
+
     /// Import a supply-chain graph from an external format (e.g. Excel).
     Import {
         /// Path to the input file (e.g. `.xlsx`).
@@ -346,10 +619,133 @@ pub enum Command {
         /// Match nodes whose name contains this pattern (case-insensitive substring, repeatable).
         #[arg(long, value_name = "PATTERN")]
         name: Vec<String>,
+        /// Boolean selector expression, combined with the other selector flags
+        /// by AND (e.g. `type=organization AND NOT label:risk.cleared`).
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
         /// Print only match counts (nodes: N, edges: M) without listing individual results.
         #[arg(long)]
         count: bool,
     },
+
+    /// Cluster the supply network into segments (Louvain community detection).
+    Segment {
+        /// Path to an .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// Cluster over edges of this type (repeatable; default: supplies,
+        /// subcontracts, tolls, distributes, brokers, `sells_to`).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Edge weight: uniform (default), annual-value, or volume.
+        #[arg(long, default_value = "uniform", value_enum)]
+        weight: SegmentWeight,
+        /// Emit the input file with a segment label on every clustered node
+        /// instead of printing the mapping.
+        #[arg(long)]
+        write_labels: bool,
+        /// Label key written by `--write-labels`.
+        #[arg(
+            long,
+            value_name = "KEY",
+            default_value = "omts.segment",
+            requires = "write_labels"
+        )]
+        label_key: String,
+        /// Target output encoding for `--write-labels`: json (default) or cbor.
+        #[arg(long, default_value = "json", value_enum, requires = "write_labels")]
+        to: TargetEncoding,
+        /// Compress `--write-labels` output with zstd after serialization.
+        #[arg(long, requires = "write_labels")]
+        compress: bool,
+    },
+}
+
+/// Subcommands of `omts conflicts`.
+#[derive(Subcommand)]
+pub enum ConflictsCommand {
+    /// List recorded conflicts as a table (or CSV, or JSON with `--format json`).
+    List {
+        /// Path to a merged .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// Only conflicts on this property (repeatable; e.g. name, tier).
+        #[arg(long, value_name = "FIELD")]
+        field: Vec<String>,
+        /// Only conflicts on nodes of this type (repeatable); excludes edges.
+        #[arg(long, value_name = "TYPE")]
+        node_type: Vec<String>,
+        /// Only conflicts with a value from this source, by its label in
+        /// `merge_metadata.source_files` (repeatable; e.g. `file_1`).
+        #[arg(long, value_name = "LABEL")]
+        source: Vec<String>,
+        /// Write CSV instead of a table, one row per conflicting value.
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Apply the decisions in a JSON file and remove the resolved conflicts;
+    /// each resolution is recorded in the element's `_resolutions`.
+    Resolve {
+        /// Path to a merged .omts file, or `-` for stdin.
+        #[arg(value_name = "FILE")]
+        file: PathOrStdin,
+        /// JSON array of `{ "id", "field", "value" | "source", "note"? }`
+        /// decisions.
+        #[arg(long, value_name = "FILE")]
+        decisions: PathBuf,
+        /// Target output encoding: json (default) or cbor.
+        #[arg(long, default_value = "json", value_enum)]
+        to: TargetEncoding,
+        /// Compress output with zstd after serialization.
+        #[arg(long)]
+        compress: bool,
+    },
+}
+
+impl Command {
+    /// Returns `true` if this subcommand honours the global `--as-of` flag.
+    ///
+    /// Commands that rewrite or compare whole files (`validate`, `merge`,
+    /// `redact`, `diff`, `convert`) operate on the file as written and reject
+    /// `--as-of` rather than silently ignoring it.
+    pub fn supports_as_of(&self) -> bool {
+        matches!(
+            self,
+            Command::Query { .. }
+                | Command::Reach { .. }
+                | Command::Path { .. }
+                | Command::Subgraph { .. }
+                | Command::Shell { .. }
+                | Command::Inspect { .. }
+                | Command::Segment { .. }
+                | Command::Export { .. }
+        )
+    }
+}
+
+/// Parses a `--threshold` argument: a similarity score between 0 and 1.
+fn parse_threshold(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(v) if (0.0..=1.0).contains(&v) => Ok(v),
+        _ => Err(format!("{s:?} is not a number between 0.0 and 1.0")),
+    }
+}
+
+/// Parses a `--resolve` argument of the form `FIELD=POLICY`.
+fn parse_resolve_rule(s: &str) -> Result<(String, ResolvePolicy), String> {
+    let Some((field, policy)) = s.split_once('=') else {
+        return Err(format!("{s:?} is not of the form FIELD=POLICY"));
+    };
+    if field.is_empty() {
+        return Err(format!("{s:?} has an empty FIELD"));
+    }
+    let policy = ResolvePolicy::from_str(policy, true)?;
+    Ok((field.to_owned(), policy))
+}
+
+/// Parses a `--as-of` argument into a [`CalendarDate`].
+fn parse_calendar_date(s: &str) -> Result<CalendarDate, String> {
+    CalendarDate::try_from(s).map_err(|e| e.to_string())
 }
 
 /// Root CLI struct for the `omts` binary.
@@ -396,6 +792,15 @@ pub struct Cli {
     )]
     pub max_file_size: u64,
 
+    /// Evaluate the graph as it stood on this date (YYYY-MM-DD).
+    ///
+    /// Edges, identifiers, and attestations whose `valid_from`/`valid_to`
+    /// interval excludes the date are dropped before the command runs.
+    /// Honoured by `query`, `reach`, `path`, `subgraph`, `shell`, `inspect`,
+    /// `segment`, and `export`.
+    #[arg(long, global = true, value_name = "DATE", value_parser = parse_calendar_date)]
+    pub as_of: Option<CalendarDate>,
+
     /// Disable ANSI color codes in human output.
     ///
     /// Also respects the `NO_COLOR` environment variable per
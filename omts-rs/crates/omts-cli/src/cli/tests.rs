@@ -14,7 +14,7 @@ fn test_root_help_lists_all_subcommands() {
 
     let expected_subcommands = [
         "validate", "merge", "redact", "inspect", "diff", "convert", "reach", "path", "subgraph",
-        "init", "query",
+        "init", "query", "segment",
     ];
     for name in &expected_subcommands {
         assert!(
@@ -36,6 +36,7 @@ fn test_root_help_lists_global_flags() {
         "--verbose",
         "--max-file-size",
         "--no-color",
+        "--as-of",
         "--help",
         "--version",
     ];
@@ -607,6 +608,28 @@ fn test_query_count_flag_parses() {
     }
 }
 
+/// `omts query --where` captures the expression verbatim.
+#[test]
+fn test_query_where_flag_parses() {
+    let cli = Cli::try_parse_from([
+        "omts",
+        "query",
+        "--where",
+        "type=organization AND NOT label:risk.cleared",
+        "graph.omts",
+    ])
+    .expect("should parse query --where");
+    match cli.command {
+        Command::Query { where_expr, .. } => {
+            assert_eq!(
+                where_expr.as_deref(),
+                Some("type=organization AND NOT label:risk.cleared")
+            );
+        }
+        _ => panic!("expected Query subcommand"),
+    }
+}
+
 /// `omts query` with multiple `--node-type` flags parses all values.
 #[test]
 fn test_query_multiple_node_types_parse() {
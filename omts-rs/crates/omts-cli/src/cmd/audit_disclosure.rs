@@ -0,0 +1,84 @@
+//! Implementation of `omts audit-disclosure <file>`.
+//!
+//! Scans an `.omts` file for content that its disclosure scope does not
+//! permit but that L1-SDI-02 cannot see: person names in public files,
+//! identifiers copied into `extra` fields or labels, `_conflicts` carrying
+//! stripped values, `boundary_ref` nodes their neighbours re-identify, and
+//! source file paths in merge metadata ([`omts_core::audit_disclosure`],
+//! redaction.md Section 7.5). Diagnostics are written to stderr in the same
+//! format as `omts validate`.
+//!
+//! Flags:
+//! - `--scope <scope>`: audit against this scope instead of the file's
+//!   declared `disclosure_scope`.
+//!
+//! Exit codes:
+//! - 0 = no errors (warnings may have been reported)
+//! - 1 = at least one leak reported as an error
+//! - 2 = no scope to audit against, or parse failure
+use omts_core::{OmtsFile, audit_disclosure};
+
+use crate::DisclosureScope;
+use crate::OutputFormat;
+use crate::cmd::redact::cli_scope_to_core;
+use crate::error::CliError;
+use crate::format::{FormatMode, FormatterConfig, write_diagnostic, write_summary};
+
+/// Runs the `audit-disclosure` command.
+///
+/// # Errors
+///
+/// - [`CliError::InvalidArgument`] (exit code 2) if neither `scope` nor the
+///   file declares a disclosure scope.
+/// - [`CliError::ValidationErrors`] (exit code 1) if a leak is reported as an
+///   error.
+pub fn run(
+    file: &OmtsFile,
+    scope: Option<&DisclosureScope>,
+    format: &OutputFormat,
+    quiet: bool,
+    no_color: bool,
+) -> Result<(), CliError> {
+    let scope = match scope {
+        Some(scope) => cli_scope_to_core(scope),
+        None => file
+            .disclosure_scope
+            .clone()
+            .ok_or_else(|| CliError::InvalidArgument {
+                detail: "the file declares no disclosure_scope; pass --scope".to_owned(),
+            })?,
+    };
+    let result = audit_disclosure(file, &scope);
+
+    let mode = match format {
+        OutputFormat::Human => FormatMode::Human,
+        OutputFormat::Json => FormatMode::Json,
+    };
+    let fmt_config = FormatterConfig::from_flags(no_color, quiet, false);
+
+    let stderr = std::io::stderr();
+    let mut err_out = stderr.lock();
+    let io_error = |e: std::io::Error| CliError::IoError {
+        source: "stderr".to_owned(),
+        detail: e.to_string(),
+    };
+
+    for diag in &result.diagnostics {
+        write_diagnostic(&mut err_out, diag, mode, &fmt_config).map_err(io_error)?;
+    }
+    write_summary(
+        &mut err_out,
+        result.errors().count(),
+        result.warnings().count(),
+        result.infos().count(),
+        mode,
+        &fmt_config,
+    )
+    .map_err(io_error)?;
+
+    if result.has_errors() {
+        Err(CliError::ValidationErrors)
+    } else {
+        Ok(())
+    }
+}
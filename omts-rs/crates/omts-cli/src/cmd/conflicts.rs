@@ -0,0 +1,187 @@
+//! Implementation of `omts conflicts list|resolve <file>`.
+//!
+//! Works through the `_conflicts` records a merge left on nodes and edges
+//! (merge-semantics Section 4.9):
+//!
+//! - `list`: one row per conflicting value, filtered by `--field`,
+//!   `--node-type`, and `--source` (each repeatable). Human format prints a
+//!   tab-separated table, `--csv` the same columns as CSV, and
+//!   `--format json` the conflict records as a JSON array.
+//! - `resolve --decisions <FILE>`: applies a JSON array of
+//!   `{ "id", "field", "value" | "source", "note" }` decisions. Each chosen
+//!   value is written to its property, the conflict is removed from
+//!   `_conflicts`, and a record of the decision and the values it replaced is
+//!   appended to the element's `_resolutions`.
+//!
+//! Output: `list` writes to stdout; `resolve` writes the rewritten file to
+//! stdout and a one-line summary to stderr.
+//!
+//! Exit codes: 0 = success, 1 = a decision names a node or edge not in the
+//! file, 2 = parse failure, malformed decisions or conflict records, or write
+//! error.
+use std::io::Write as _;
+use std::path::Path;
+
+use omts_core::{
+    ConflictDecision, ConflictFilter, ConflictRecord, ConflictReviewError, OmtsFile,
+    list_conflicts, resolve_conflicts,
+};
+
+use crate::cmd::merge::encode_output;
+use crate::error::CliError;
+use crate::{OutputFormat, TargetEncoding};
+
+/// Column names shared by the table and CSV output.
+const COLUMNS: [&str; 7] = [
+    "element",
+    "id",
+    "type",
+    "field",
+    "source_file",
+    "value",
+    "resolution",
+];
+
+/// Runs `conflicts list`.
+///
+/// # Errors
+///
+/// - [`CliError::InvalidArgument`] — a `_conflicts` record is malformed.
+/// - [`CliError::IoError`] — a stdout write fails.
+pub fn list(
+    file: &OmtsFile,
+    filter: &ConflictFilter,
+    csv: bool,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let records = list_conflicts(file, filter).map_err(|e| review_error(&e))?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    match (format, csv) {
+        (OutputFormat::Json, _) => write_json(&mut out, &records),
+        (OutputFormat::Human, true) => write_csv(&mut out, &records),
+        (OutputFormat::Human, false) => write_table(&mut out, &records),
+    }
+    .map_err(|e| stdout_error(&e))?;
+
+    eprintln!("conflicts: {} listed", records.len());
+    Ok(())
+}
+
+/// Runs `conflicts resolve`.
+///
+/// # Errors
+///
+/// - [`CliError::NodeNotFound`] — a decision names an unknown node or edge.
+/// - [`CliError::InvalidArgument`] — the decisions file is not a JSON array
+///   of decisions, or a decision does not apply.
+/// - [`CliError::IoError`] — the decisions file cannot be read, or
+///   serialization or a stdout write fails.
+pub fn resolve(
+    file: &OmtsFile,
+    decisions_path: &Path,
+    to: &TargetEncoding,
+    compress: bool,
+) -> Result<(), CliError> {
+    let bytes = std::fs::read(decisions_path).map_err(|e| CliError::IoError {
+        source: decisions_path.display().to_string(),
+        detail: e.to_string(),
+    })?;
+    let decisions: Vec<ConflictDecision> =
+        serde_json::from_slice(&bytes).map_err(|e| CliError::InvalidArgument {
+            detail: format!("invalid decisions file {}: {e}", decisions_path.display()),
+        })?;
+
+    let output = resolve_conflicts(file, &decisions).map_err(|e| review_error(&e))?;
+
+    let encoded = encode_output(&output.file, to, compress)?;
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    out.write_all(&encoded).map_err(|e| stdout_error(&e))?;
+    // Binary outputs must not have an appended newline.
+    if matches!(to, TargetEncoding::Json) && !compress {
+        out.write_all(b"\n").map_err(|e| stdout_error(&e))?;
+    }
+
+    eprintln!(
+        "conflicts: resolved {}, {} remaining",
+        output.resolved, output.remaining
+    );
+    Ok(())
+}
+
+/// One output row per conflicting value, in [`COLUMNS`] order.
+fn rows(records: &[ConflictRecord]) -> Vec<[String; 7]> {
+    records
+        .iter()
+        .flat_map(|record| {
+            record.values.iter().map(|entry| {
+                [
+                    record.element.as_str().to_owned(),
+                    record.id.clone(),
+                    record.element_type.clone(),
+                    record.field.clone(),
+                    entry.source_file.clone(),
+                    entry
+                        .value
+                        .as_str()
+                        .map_or_else(|| entry.value.to_string(), str::to_owned),
+                    record.resolution.clone().unwrap_or_default(),
+                ]
+            })
+        })
+        .collect()
+}
+
+fn write_table<W: std::io::Write>(w: &mut W, records: &[ConflictRecord]) -> std::io::Result<()> {
+    writeln!(w, "{}", COLUMNS.map(str::to_uppercase).join("\t"))?;
+    for row in rows(records) {
+        writeln!(w, "{}", row.join("\t"))?;
+    }
+    Ok(())
+}
+
+fn write_csv<W: std::io::Write>(w: &mut W, records: &[ConflictRecord]) -> std::io::Result<()> {
+    writeln!(w, "{}", COLUMNS.join(","))?;
+    for row in rows(records) {
+        writeln!(w, "{}", row.map(|cell| csv_cell(&cell)).join(","))?;
+    }
+    Ok(())
+}
+
+fn write_json<W: std::io::Write>(w: &mut W, records: &[ConflictRecord]) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(records).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+/// Quotes a CSV cell that contains a comma, quote, or line break.
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+/// Maps an engine error to its CLI error.
+fn review_error(e: &ConflictReviewError) -> CliError {
+    match e {
+        ConflictReviewError::ElementNotFound(id) => CliError::NodeNotFound {
+            node_id: id.clone(),
+        },
+        ConflictReviewError::InvalidConflicts(_)
+        | ConflictReviewError::NoSuchConflict { .. }
+        | ConflictReviewError::InvalidDecision(_) => CliError::InvalidArgument {
+            detail: e.to_string(),
+        },
+    }
+}
+
+/// Wraps a stdout write failure.
+fn stdout_error(e: &std::io::Error) -> CliError {
+    CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    }
+}
@@ -0,0 +1,156 @@
+//! Implementation of `omts dedup <file>`.
+//!
+//! Finds duplicate nodes within one `.omts` file (merge-semantics Section 8)
+//! using [`omts_core::dedup()`]:
+//!
+//! - **Identifier groups**: nodes of the same type sharing an external
+//!   identifier (`internal` identifiers never match).
+//! - **Fuzzy matches**: organizations whose legal names agree after
+//!   normalisation (case, diacritics, legal forms such as `GmbH`/`Ltd`/`Inc`),
+//!   compared only within the same jurisdiction and, when both carry one, a
+//!   similar address.
+//!
+//! Flags:
+//! - `--apply`: collapse each identifier group into its first node.
+//! - `--suggest`: add a `same_as` edge with `confidence: "probable"` for each
+//!   fuzzy match.
+//! - `--threshold <score>` (default 0.85): minimum name similarity.
+//! - `--to`, `--compress`: output encoding with `--apply`/`--suggest`.
+//!
+//! Output:
+//! - Without `--apply`/`--suggest`: the duplicate report on stdout.
+//!   Human: one line per group or match. JSON:
+//!   `{ "identifier_groups": [...], "fuzzy_matches": [...] }`.
+//! - With either flag: the rewritten file on stdout and a one-line summary on
+//!   stderr.
+//!
+//! Exit codes: 0 = success, 2 = parse failure or write error.
+use std::io::Write as _;
+
+use omts_core::{DedupConfig, DedupOutput, OmtsFile, dedup};
+
+use crate::cmd::merge::encode_output;
+use crate::error::CliError;
+use crate::{OutputFormat, TargetEncoding};
+
+/// Runs the `dedup` command.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 2 if serialization or a stdout write fails.
+pub fn run(
+    file: &OmtsFile,
+    config: &DedupConfig,
+    to: &TargetEncoding,
+    compress: bool,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let output = dedup(file, config);
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    if !config.apply && !config.suggest {
+        return match format {
+            OutputFormat::Human => print_human(&mut out, &output),
+            OutputFormat::Json => print_json(&mut out, &output),
+        }
+        .map_err(|e| stdout_error(&e));
+    }
+
+    let bytes = encode_output(&output.file, to, compress)?;
+    out.write_all(&bytes).map_err(|e| stdout_error(&e))?;
+    // Binary outputs must not have an appended newline.
+    if matches!(to, TargetEncoding::Json) && !compress {
+        out.write_all(b"\n").map_err(|e| stdout_error(&e))?;
+    }
+
+    eprintln!("{}", summary(config, &output));
+    Ok(())
+}
+
+/// One-line description of what `--apply`/`--suggest` changed.
+fn summary(config: &DedupConfig, output: &DedupOutput) -> String {
+    let mut parts = Vec::new();
+    if config.apply {
+        parts.push(format!(
+            "collapsed {} identifier group(s), removing {} node(s) and {} edge(s)",
+            output.identifier_groups.len(),
+            output.nodes_removed,
+            output.edges_removed
+        ));
+    }
+    if config.suggest {
+        parts.push(format!(
+            "added {} same_as edge(s) for fuzzy matches",
+            output.edges_added
+        ));
+    }
+    format!("dedup: {}", parts.join("; "))
+}
+
+/// Writes the duplicate report in human-readable form.
+fn print_human<W: std::io::Write>(w: &mut W, output: &DedupOutput) -> std::io::Result<()> {
+    for group in &output.identifier_groups {
+        let nodes: Vec<&str> = group.nodes.iter().map(|id| &**id).collect();
+        writeln!(
+            w,
+            "identifier group: {} (shared: {})",
+            nodes.join(", "),
+            group.shared_identifiers.join(", ")
+        )?;
+    }
+    for m in &output.fuzzy_matches {
+        writeln!(
+            w,
+            "fuzzy match: {} ~ {} (similarity {:.2}, {})",
+            m.source, m.target, m.similarity, m.basis
+        )?;
+    }
+    writeln!(
+        w,
+        "{} identifier group(s), {} fuzzy match(es)",
+        output.identifier_groups.len(),
+        output.fuzzy_matches.len()
+    )
+}
+
+/// Writes the duplicate report as a single JSON object.
+fn print_json<W: std::io::Write>(w: &mut W, output: &DedupOutput) -> std::io::Result<()> {
+    let groups: Vec<serde_json::Value> = output
+        .identifier_groups
+        .iter()
+        .map(|g| {
+            serde_json::json!({
+                "nodes": g.nodes,
+                "shared_identifiers": g.shared_identifiers,
+            })
+        })
+        .collect();
+    let matches: Vec<serde_json::Value> = output
+        .fuzzy_matches
+        .iter()
+        .map(|m| {
+            serde_json::json!({
+                "source": m.source,
+                "target": m.target,
+                "similarity": m.similarity,
+                "basis": m.basis,
+            })
+        })
+        .collect();
+    let value = serde_json::json!({
+        "identifier_groups": groups,
+        "fuzzy_matches": matches,
+    });
+    let json = serde_json::to_string_pretty(&value).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+/// Wraps a stdout write failure.
+fn stdout_error(e: &std::io::Error) -> CliError {
+    CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    }
+}
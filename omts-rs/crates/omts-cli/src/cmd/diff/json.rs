@@ -6,7 +6,7 @@ use omts_core::{
 /// Writes the diff result as a single JSON object to stdout.
 ///
 /// The structure mirrors the diff spec Section 5.2.
-pub(super) fn write_json<W: std::io::Write>(w: &mut W, result: &DiffResult) -> std::io::Result<()> {
+pub(crate) fn write_json<W: std::io::Write>(w: &mut W, result: &DiffResult) -> std::io::Result<()> {
     let summary = result.summary();
 
     let summary_obj = serde_json::json!({
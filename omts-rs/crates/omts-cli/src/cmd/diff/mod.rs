@@ -9,19 +9,21 @@
 //! - `--node-type <TYPE>` (repeatable): Restrict diff to nodes of this type.
 //! - `--edge-type <TYPE>` (repeatable): Restrict diff to edges of this type.
 //! - `--ignore-field <FIELD>` (repeatable): Exclude this property from comparison.
+//! - `--where <EXPR>`: Restrict diff to elements matching a selector expression.
 //!
 //! Exit codes:
 //! - 0 = files are identical
 //! - 1 = differences found
 //! - 2 = parse failure on either file
 mod human;
-mod json;
+pub(crate) mod json;
 
 use std::collections::HashSet;
 
 use omts_core::{DiffFilter, DiffResult, OmtsFile, diff_filtered};
 
 use crate::OutputFormat;
+use crate::cmd::selectors::parse_where;
 use crate::error::CliError;
 use crate::format::{FormatterConfig, write_timing};
 
@@ -37,6 +39,7 @@ use crate::format::{FormatterConfig, write_timing};
 /// # Errors
 ///
 /// - [`CliError::DiffHasDifferences`] — the diff is non-empty.
+/// - [`CliError::InvalidArgument`] — the `--where` expression is malformed.
 /// - [`CliError::IoError`] — stdout write failed.
 #[allow(clippy::too_many_arguments)]
 pub fn run(
@@ -47,23 +50,12 @@ pub fn run(
     node_types: &[String],
     edge_types: &[String],
     ignore_fields: &[String],
+    where_expr: Option<&str>,
     format: &OutputFormat,
     verbose: bool,
     no_color: bool,
 ) -> Result<(), CliError> {
-    let filter = DiffFilter {
-        node_types: if node_types.is_empty() {
-            None
-        } else {
-            Some(node_types.iter().cloned().collect::<HashSet<_>>())
-        },
-        edge_types: if edge_types.is_empty() {
-            None
-        } else {
-            Some(edge_types.iter().cloned().collect::<HashSet<_>>())
-        },
-        ignore_fields: ignore_fields.iter().cloned().collect::<HashSet<_>>(),
-    };
+    let filter = build_filter(node_types, edge_types, ignore_fields, where_expr)?;
 
     let diff_start = std::time::Instant::now();
     let result = diff_filtered(file_a, file_b, Some(&filter));
@@ -88,6 +80,37 @@ pub fn run(
     }
 }
 
+/// Builds the [`DiffFilter`] for the `--node-type`, `--edge-type`,
+/// `--ignore-field`, and `--where` flags.
+///
+/// Shared by [`run`] and `omts serve`.
+///
+/// # Errors
+///
+/// Returns [`CliError::InvalidArgument`] if the `--where` expression is
+/// malformed.
+pub(crate) fn build_filter(
+    node_types: &[String],
+    edge_types: &[String],
+    ignore_fields: &[String],
+    where_expr: Option<&str>,
+) -> Result<DiffFilter, CliError> {
+    Ok(DiffFilter {
+        node_types: if node_types.is_empty() {
+            None
+        } else {
+            Some(node_types.iter().cloned().collect::<HashSet<_>>())
+        },
+        edge_types: if edge_types.is_empty() {
+            None
+        } else {
+            Some(edge_types.iter().cloned().collect::<HashSet<_>>())
+        },
+        ignore_fields: ignore_fields.iter().cloned().collect::<HashSet<_>>(),
+        expr: where_expr.map(parse_where).transpose()?,
+    })
+}
+
 fn write_result(
     result: &DiffResult,
     ids_only: bool,
@@ -180,6 +203,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Human,
             false,
             true,
@@ -202,6 +226,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Human,
             false,
             true,
@@ -221,6 +246,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Human,
             false,
             true,
@@ -243,6 +269,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Human,
             false,
             true,
@@ -263,6 +290,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Human,
             false,
             true,
@@ -283,6 +311,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Human,
             false,
             true,
@@ -303,6 +332,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Json,
             false,
             true,
@@ -322,6 +352,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
             &OutputFormat::Json,
             false,
             true,
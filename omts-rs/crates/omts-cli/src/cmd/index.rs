@@ -0,0 +1,240 @@
+//! Implementation of `omts index <file>`, and index-aware input loading for
+//! `query`, `reach`, `path`, and `subgraph`.
+//!
+//! `omts index` parses an `.omts` file, builds an [`omts_core::GraphIndex`]
+//! (node ID table, adjacency lists per edge type, canonical-identifier index,
+//! name and label inverted indexes, and a SHA-256 content hash), and writes it
+//! to the sidecar `<file>.idx` next to the input.
+//!
+//! The query commands load their input through [`IndexedInput`]. When the
+//! input is a disk file with a sidecar:
+//! - a **fresh** sidecar (content hash matches the file bytes) replaces graph
+//!   construction, and lets `reach` and `query` answer without parsing the
+//!   file at all when their output needs only indexed data;
+//! - a **stale** or unreadable sidecar is rebuilt from the file and rewritten.
+//!
+//! Files without a sidecar, stdin, and `--as-of` views are handled exactly as
+//! before: the file is parsed and the graph is built in memory.
+//!
+//! Output (human mode): one summary line on stdout.
+//! Output (JSON mode): `{"index": path, "nodes": N, "edges": M, "edge_types": T,
+//! "content_hash": hex}`.
+//!
+//! Exit codes: 0 = index written, 2 = read/parse/build failure or write error.
+use std::cell::OnceCell;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use omts_core::graph::{OmtsGraph, build_graph};
+use omts_core::{GraphIndex, OmtsFile, content_hash};
+
+use crate::error::CliError;
+use crate::{Cli, OutputFormat, PathOrStdin, io};
+
+/// Suffix appended to an input path to form its index sidecar path.
+const SIDECAR_SUFFIX: &str = ".idx";
+
+/// Runs the `index` command.
+///
+/// Reads and parses `path`, builds its [`GraphIndex`], and writes the sidecar
+/// next to it, replacing any existing sidecar.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 2 if the file cannot be read or parsed, the
+/// graph is invalid (duplicate node IDs, dangling edges), or the sidecar
+/// cannot be written.
+pub fn run(
+    path: &Path,
+    max_file_size: u64,
+    verbose: bool,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let source = PathOrStdin::Path(path.to_path_buf());
+    let bytes = io::read_input_bytes(&source, max_file_size)?;
+    let (file, _encoding) = io::parse_bytes(&bytes, &source, max_file_size, verbose)?;
+
+    let index = build_index(&file, &bytes)?;
+    let sidecar = sidecar_path(path);
+    write_sidecar(&sidecar, &index)?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        OutputFormat::Human => writeln!(
+            out,
+            "indexed {} node(s), {} edge(s), {} edge type(s) -> {}",
+            index.nodes.len(),
+            index.edges.len(),
+            index.adjacency.len(),
+            sidecar.display()
+        ),
+        OutputFormat::Json => {
+            let value = serde_json::json!({
+                "index": sidecar.display().to_string(),
+                "nodes": index.nodes.len(),
+                "edges": index.edges.len(),
+                "edge_types": index.adjacency.len(),
+                "content_hash": index.content_hash,
+            });
+            match serde_json::to_string_pretty(&value) {
+                Ok(json) => writeln!(out, "{json}"),
+                Err(e) => Err(std::io::Error::new(std::io::ErrorKind::Other, e)),
+            }
+        }
+    }
+    .map_err(|e| CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    })
+}
+
+/// Returns the sidecar path for `path`: the same path with `.idx` appended.
+pub(crate) fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(SIDECAR_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Input for a query command, served from an index sidecar when possible.
+///
+/// The file is parsed lazily: [`IndexedInput::file`] parses on first use, so
+/// a command that only needs [`IndexedInput::index`] or
+/// [`IndexedInput::graph`] never pays for parsing when the sidecar is fresh.
+/// Both the file and the graph are cached, so `omts shell` can run many
+/// commands against one input.
+pub(crate) struct IndexedInput<'a> {
+    source: &'a PathOrStdin,
+    bytes: Vec<u8>,
+    cli: &'a Cli,
+    index: Option<GraphIndex>,
+    file: OnceCell<OmtsFile>,
+    graph: OnceCell<OmtsGraph>,
+}
+
+impl<'a> IndexedInput<'a> {
+    /// Reads `source` and loads, or rebuilds, its index sidecar.
+    ///
+    /// The sidecar is consulted only for a disk file without `--as-of`. A
+    /// sidecar that is fresh is used as-is; one that is stale or unreadable is
+    /// rebuilt from the file and rewritten. Failing to rewrite it is reported
+    /// as a warning (unless `--quiet`) and does not fail the command.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError`] exit code 2 if the file cannot be read, or if a
+    /// stale sidecar forces a parse that fails.
+    pub(crate) fn open(source: &'a PathOrStdin, cli: &'a Cli) -> Result<Self, CliError> {
+        let bytes = io::read_input_bytes(source, cli.max_file_size)?;
+        let mut input = IndexedInput {
+            source,
+            bytes,
+            cli,
+            index: None,
+            file: OnceCell::new(),
+            graph: OnceCell::new(),
+        };
+
+        let sidecar = match (source, &cli.as_of) {
+            (PathOrStdin::Path(path), None) => sidecar_path(path),
+            (PathOrStdin::Path(_), Some(_)) | (PathOrStdin::Stdin, _) => return Ok(input),
+        };
+        if !sidecar.exists() {
+            return Ok(input);
+        }
+
+        let hash = content_hash(&input.bytes);
+        let cached = std::fs::read(&sidecar)
+            .ok()
+            .and_then(|b| GraphIndex::decode(&b).ok())
+            .filter(|index| index.is_fresh(&hash));
+        if let Some(index) = cached {
+            if cli.verbose {
+                eprintln!("index: using {}", sidecar.display());
+            }
+            input.index = Some(index);
+            return Ok(input);
+        }
+
+        let index = build_index(input.file()?, &input.bytes)?;
+        match encode_to(&sidecar, &index) {
+            Ok(()) if cli.verbose => eprintln!("index: rebuilt stale {}", sidecar.display()),
+            Ok(()) => {}
+            Err(detail) if !cli.quiet => eprintln!(
+                "warning: could not rewrite stale index {}: {detail}",
+                sidecar.display()
+            ),
+            Err(_) => {}
+        }
+        input.index = Some(index);
+        Ok(input)
+    }
+
+    /// Returns the fresh index, if the input has one.
+    pub(crate) fn index(&self) -> Option<&GraphIndex> {
+        self.index.as_ref()
+    }
+
+    /// Returns the parsed file (the `--as-of` view when set), parsing it on
+    /// first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError`] exit code 2 if parsing fails.
+    pub(crate) fn file(&self) -> Result<&OmtsFile, CliError> {
+        if let Some(file) = self.file.get() {
+            return Ok(file);
+        }
+        let (file, _encoding) = io::parse_bytes(
+            &self.bytes,
+            self.source,
+            self.cli.max_file_size,
+            self.cli.verbose,
+        )?;
+        let view = match &self.cli.as_of {
+            Some(date) => omts_core::as_of(&file, date),
+            None => file,
+        };
+        Ok(self.file.get_or_init(|| view))
+    }
+
+    /// Returns the graph, building it on first use: expanded from the index
+    /// when present, otherwise built from the parsed file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError`] exit code 2 if parsing or graph construction fails.
+    pub(crate) fn graph(&self) -> Result<&OmtsGraph, CliError> {
+        if let Some(graph) = self.graph.get() {
+            return Ok(graph);
+        }
+        let graph = match &self.index {
+            Some(index) => index.to_graph(),
+            None => build_graph(self.file()?).map_err(|e| CliError::GraphBuildError {
+                detail: e.to_string(),
+            })?,
+        };
+        Ok(self.graph.get_or_init(|| graph))
+    }
+}
+
+/// Builds the index for a parsed file and its raw bytes.
+fn build_index(file: &OmtsFile, bytes: &[u8]) -> Result<GraphIndex, CliError> {
+    GraphIndex::build(file, content_hash(bytes)).map_err(|e| CliError::GraphBuildError {
+        detail: e.to_string(),
+    })
+}
+
+/// Encodes `index` and writes it to `sidecar`.
+fn write_sidecar(sidecar: &Path, index: &GraphIndex) -> Result<(), CliError> {
+    encode_to(sidecar, index).map_err(|detail| CliError::IoError {
+        source: sidecar.display().to_string(),
+        detail,
+    })
+}
+
+/// Encodes `index` and writes it to `sidecar`, returning a failure message.
+fn encode_to(sidecar: &Path, index: &GraphIndex) -> Result<(), String> {
+    let bytes = index.encode().map_err(|e| e.to_string())?;
+    std::fs::write(sidecar, bytes).map_err(|e| e.to_string())
+}
@@ -9,14 +9,21 @@
 //! - snapshot date
 //! - reporting entity (if present)
 //!
+//! With `--metrics`, the graph is also built and network metrics are
+//! appended: degree distributions per edge type, weak/strong components,
+//! eccentricity from the reporting entity, top-N organizations by `PageRank`,
+//! isolated nodes, and average identifiers per organization.
+//!
 //! In `--format json` mode a single JSON object is emitted to stdout.
 //! In human mode, aligned key/value lines are printed.
 //!
-//! Exit codes: 0 = success, 2 = parse failure.
+//! Exit codes: 0 = success, 2 = parse failure or graph build failure.
 use std::collections::BTreeMap;
 
-use omts_core::OmtsFile;
 use omts_core::enums::{EdgeType, EdgeTypeTag, NodeType, NodeTypeTag};
+use omts_core::{
+    CentralityScore, DegreeDistribution, GraphMetrics, OmtsFile, build_graph, graph_metrics,
+};
 use serde_json;
 
 use crate::OutputFormat;
@@ -44,6 +51,8 @@ pub struct InspectStats {
     pub disclosure_scope: Option<String>,
     /// Reporting entity node ID, or `None` if not set.
     pub reporting_entity: Option<String>,
+    /// Network metrics, present only when `--metrics` was requested.
+    pub metrics: Option<GraphMetrics>,
 }
 
 impl InspectStats {
@@ -102,6 +111,7 @@ impl InspectStats {
             snapshot_date: file.snapshot_date.to_string(),
             disclosure_scope,
             reporting_entity,
+            metrics: None,
         }
     }
 }
@@ -109,13 +119,27 @@ impl InspectStats {
 /// Runs the `inspect` command.
 ///
 /// Computes statistics from the pre-parsed `file` and writes them to stdout
-/// in the requested format.
+/// in the requested format. When `metrics` is set, the graph is built and
+/// network metrics are included, listing the `top` organizations by
+/// `PageRank`.
 ///
 /// # Errors
 ///
-/// Returns [`CliError`] with exit code 2 if a stdout write fails.
-pub fn run(file: &OmtsFile, format: &OutputFormat) -> Result<(), CliError> {
-    let stats = InspectStats::from_file(file);
+/// Returns [`CliError`] with exit code 2 if the graph cannot be built or a
+/// stdout write fails.
+pub fn run(
+    file: &OmtsFile,
+    metrics: bool,
+    top: usize,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let mut stats = InspectStats::from_file(file);
+    if metrics {
+        let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
+            detail: e.to_string(),
+        })?;
+        stats.metrics = Some(graph_metrics(&graph, file, top));
+    }
 
     let stdout = std::io::stdout();
     let mut out = stdout.lock();
@@ -152,6 +176,55 @@ fn print_human<W: std::io::Write>(w: &mut W, stats: &InspectStats) -> std::io::R
     for (scheme, count) in &stats.identifier_counts {
         writeln!(w, "  {scheme}: {count}")?;
     }
+    if let Some(metrics) = &stats.metrics {
+        print_metrics_human(w, metrics)?;
+    }
+    Ok(())
+}
+
+/// Writes the `--metrics` section in human-readable aligned format.
+fn print_metrics_human<W: std::io::Write>(
+    w: &mut W,
+    metrics: &GraphMetrics,
+) -> std::io::Result<()> {
+    writeln!(w, "metrics:")?;
+    writeln!(
+        w,
+        "  weak_components:    {} (largest: {})",
+        metrics.weak_component_count, metrics.largest_weak_component
+    )?;
+    writeln!(
+        w,
+        "  strong_components:  {} (largest: {})",
+        metrics.strong_component_count, metrics.largest_strong_component
+    )?;
+    match metrics.diameter_from_reporting_entity {
+        Some(d) => writeln!(w, "  reporting_reach:    {d} hops")?,
+        None => writeln!(w, "  reporting_reach:    n/a")?,
+    }
+    match metrics.avg_identifiers_per_organization {
+        Some(avg) => writeln!(w, "  ids_per_org:        {avg:.2}")?,
+        None => writeln!(w, "  ids_per_org:        n/a")?,
+    }
+    writeln!(w, "  isolated_nodes:     {}", metrics.isolated_nodes.len())?;
+    for id in &metrics.isolated_nodes {
+        writeln!(w, "    {id}")?;
+    }
+    writeln!(w, "  degree:")?;
+    for (edge_type, dist) in &metrics.degree_distributions {
+        writeln!(
+            w,
+            "    {edge_type}: nodes={} mean={:.2} max_out={} max_in={}",
+            dist.node_count, dist.mean_degree, dist.max_out_degree, dist.max_in_degree
+        )?;
+    }
+    writeln!(w, "  top_pagerank:")?;
+    for entry in &metrics.top_pagerank {
+        match &entry.name {
+            Some(name) => writeln!(w, "    {:.4}  {} ({name})", entry.score, entry.node_id)?,
+            None => writeln!(w, "    {:.4}  {}", entry.score, entry.node_id)?,
+        }
+    }
     Ok(())
 }
 
@@ -226,11 +299,67 @@ fn print_json<W: std::io::Write>(w: &mut W, stats: &InspectStats) -> std::io::Re
         serde_json::Value::Object(id_counts_obj),
     );
 
+    if let Some(metrics) = &stats.metrics {
+        obj.insert("metrics".to_owned(), metrics_to_json(metrics));
+    }
+
     let json = serde_json::to_string_pretty(&serde_json::Value::Object(obj))
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
     writeln!(w, "{json}")
 }
 
+/// Converts [`GraphMetrics`] into the `metrics` JSON object.
+fn metrics_to_json(metrics: &GraphMetrics) -> serde_json::Value {
+    let degree: serde_json::Map<String, serde_json::Value> = metrics
+        .degree_distributions
+        .iter()
+        .map(|(k, v)| (k.clone(), degree_distribution_to_json(v)))
+        .collect();
+    let top: Vec<serde_json::Value> = metrics
+        .top_pagerank
+        .iter()
+        .map(centrality_to_json)
+        .collect();
+
+    serde_json::json!({
+        "degree_distributions": degree,
+        "weak_component_count": metrics.weak_component_count,
+        "largest_weak_component": metrics.largest_weak_component,
+        "strong_component_count": metrics.strong_component_count,
+        "largest_strong_component": metrics.largest_strong_component,
+        "diameter_from_reporting_entity": metrics.diameter_from_reporting_entity,
+        "top_pagerank": top,
+        "isolated_nodes": metrics.isolated_nodes,
+        "avg_identifiers_per_organization": metrics.avg_identifiers_per_organization,
+    })
+}
+
+/// Converts one [`DegreeDistribution`] into JSON; histogram keys are degrees.
+fn degree_distribution_to_json(dist: &DegreeDistribution) -> serde_json::Value {
+    let histogram = |h: &BTreeMap<usize, usize>| -> serde_json::Map<String, serde_json::Value> {
+        h.iter()
+            .map(|(deg, count)| (deg.to_string(), serde_json::Value::Number((*count).into())))
+            .collect()
+    };
+    serde_json::json!({
+        "node_count": dist.node_count,
+        "mean_degree": dist.mean_degree,
+        "max_out_degree": dist.max_out_degree,
+        "max_in_degree": dist.max_in_degree,
+        "out_degree": histogram(&dist.out_degree),
+        "in_degree": histogram(&dist.in_degree),
+    })
+}
+
+/// Converts one [`CentralityScore`] into JSON.
+fn centrality_to_json(entry: &CentralityScore) -> serde_json::Value {
+    serde_json::json!({
+        "node_id": entry.node_id,
+        "name": entry.name,
+        "score": entry.score,
+    })
+}
+
 /// Returns the JSON string representation of a [`NodeTypeTag`].
 fn node_type_tag_to_str(tag: &NodeTypeTag) -> String {
     match tag {
@@ -4,6 +4,41 @@
 //! merged file to stdout. Diagnostics (warnings, conflict counts) go to
 //! stderr.
 //!
+//! `--primary-perspective <FILE>` names the input whose reporting entity's
+//! `tier` values are kept when the inputs were written from different
+//! perspectives (merge-semantics Section 4.2).
+//!
+//! `--explain` writes a report to stderr listing each merge group's members
+//! and the identifiers or `same_as` edges that linked them, plus candidate
+//! pairs that were rejected and why. Human format prints one line per item;
+//! `--format json` prints the report as one JSON object.
+//!
+//! `--provenance` records on each merged node and edge the source elements it
+//! was built from, which `omts unmerge` uses to undo a wrong merge.
+//!
+//! `--previous <FILE>` keeps the node and edge IDs of an earlier merge result
+//! for nodes that still match it by identifier or, when it carries
+//! `_provenance`, by source node, and reports to stderr which
+//! IDs were minted, split, coalesced, or retired (merge-semantics Section
+//! 4.7).
+//!
+//! `--enrichment-base <FILE>` holds the merge to the groups of an earlier,
+//! pre-enrichment merge written with `--provenance`. Identifier matches that
+//! would extend one of its groups are written as `same_as` edges with
+//! `confidence: "probable"` and `basis: "enrichment_match"` for review, and
+//! `merge_metadata.enrichment` is set to `post_enrichment` (merge-semantics
+//! Section 9). `--pre-enrichment` marks such a base as `pre_enrichment` and
+//! turns on `--provenance`.
+//!
+//! `--dry-run` runs only identity resolution and writes a preview to stdout
+//! instead of the merged file: group sizes, groups over the size limit,
+//! expected conflicts per property, and the count of unmerged nodes (the
+//! JSON form lists every group and singleton).
+//!
+//! `--resolve FIELD=POLICY` fills a conflicting property with the policy's
+//! choice instead of leaving it empty; the full conflict is still written to
+//! `_conflicts`.
+//!
 //! Exit codes:
 //! - 0 = success
 //! - 1 = merge conflict (unresolvable property collision or internal error)
@@ -11,93 +46,120 @@
 use std::io::Write as _;
 
 use omts_core::validation::{ValidationConfig, validate};
-use omts_core::{OmtsFile, merge};
+use omts_core::{
+    ConflictPolicy, EnrichmentBase, GroupMember, IdChanges, LinkBasis, MergeConfig, MergeError,
+    MergeExplanation, MergeOutput, MergePreview, OmtsFile, RejectionReason, StableIds,
+    merge_preview, merge_with_config,
+};
 
 use crate::MergeStrategy;
+use crate::OutputFormat;
 use crate::PathOrStdin;
+use crate::ResolvePolicy;
 use crate::TargetEncoding;
 use crate::error::CliError;
-use crate::io::read_and_parse;
+use crate::io::{read_and_parse, source_label};
 
 /// Runs the `merge` command.
 ///
 /// Reads each path in `files` using the multi-encoding pipeline, runs L1
 /// validation on each, runs the merge engine, and writes the merged output to
 /// stdout in the requested encoding. Warnings and conflict statistics are
-/// written to stderr.
+/// written to stderr. With `dry_run`, only the merge preview is written.
 ///
 /// # Errors
 ///
-/// - [`CliError::ParseFailed`] — any input file is not a valid OMTS file.
+/// - [`CliError::ParseFailed`] — any input file, or the `previous` or
+///   `enrichment_base` file, is not a valid OMTS file.
 /// - [`CliError::ValidationErrors`] — any input file fails L1 validation.
+/// - [`CliError::InvalidArgument`] — `primary_perspective` is not one of
+///   `files`, a `--resolve` rule does not apply to its property, or
+///   `enrichment_base` has no `_provenance` or was merged from other inputs.
 /// - [`CliError::MergeConflict`] — the merge engine reports an internal error.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     files: &[PathOrStdin],
     strategy: &MergeStrategy,
+    primary_perspective: Option<&PathOrStdin>,
+    resolve: &[(String, ResolvePolicy)],
+    explain: bool,
+    provenance: bool,
+    previous: Option<&PathOrStdin>,
+    enrichment_base: Option<&PathOrStdin>,
+    pre_enrichment: bool,
+    dry_run: bool,
     to: &TargetEncoding,
     compress: bool,
     max_file_size: u64,
     verbose: bool,
+    format: &OutputFormat,
 ) -> Result<(), CliError> {
-    if matches!(strategy, MergeStrategy::Intersect) {
-        eprintln!("error: intersect strategy is not yet implemented");
-        return Err(CliError::MergeConflict {
-            detail: "intersect strategy is not yet implemented".to_owned(),
-        });
-    }
-
-    let l1_config = ValidationConfig {
-        run_l1: true,
-        run_l2: false,
-        run_l3: false,
+    ensure_supported(strategy)?;
+    let config = MergeConfig {
+        primary_perspective: primary_perspective
+            .map(|primary| perspective_index(files, primary))
+            .transpose()?,
+        conflict_policies: resolve
+            .iter()
+            .map(|(field, policy)| (field.clone(), conflict_policy(*policy, files.len())))
+            .collect(),
+        explain,
+        record_provenance: provenance || pre_enrichment,
+        stable_ids: previous
+            .map(|previous| {
+                read_and_parse(previous, max_file_size, verbose)
+                    .map(|(file, _encoding)| StableIds::from_file(&file))
+            })
+            .transpose()?,
+        enrichment_base: enrichment_base
+            .map(|base| {
+                let (file, _encoding) = read_and_parse(base, max_file_size, verbose)?;
+                EnrichmentBase::from_file(&file).map_err(|e| merge_error(&e))
+            })
+            .transpose()?,
+        pre_enrichment,
+        source_paths: files.iter().map(source_label).collect(),
+        ..MergeConfig::default()
     };
+
     let stderr = std::io::stderr();
     let mut err_out = stderr.lock();
 
     let mut parsed: Vec<OmtsFile> = Vec::with_capacity(files.len());
     for source in files {
         let (file, _encoding) = read_and_parse(source, max_file_size, verbose)?;
-
-        let validation_result = validate(&file, &l1_config, None);
-        if validation_result.has_errors() {
-            for diag in validation_result.errors() {
-                writeln!(
-                    err_out,
-                    "error: {} {}: {}",
-                    diag.rule_id, diag.location, diag.message
-                )
-                .map_err(|e| CliError::IoError {
-                    source: "stderr".to_owned(),
-                    detail: e.to_string(),
-                })?;
-            }
-            return Err(CliError::ValidationErrors);
-        }
-
+        check_l1(&file, &mut err_out)?;
         parsed.push(file);
     }
 
-    let output = merge(&parsed).map_err(|e| CliError::MergeConflict {
-        detail: e.to_string(),
-    })?;
-
-    for warning in &output.warnings {
-        writeln!(err_out, "warning: {warning}").map_err(|e| CliError::IoError {
-            source: "stderr".to_owned(),
+    if dry_run {
+        let preview = merge_preview(&parsed, &config).map_err(|e| merge_error(&e))?;
+        let stdout = std::io::stdout();
+        let mut out = stdout.lock();
+        return match format {
+            OutputFormat::Human => write_preview_human(&mut out, &preview),
+            OutputFormat::Json => write_preview_json(&mut out, &preview),
+        }
+        .map_err(|e| CliError::IoError {
+            source: "stdout".to_owned(),
             detail: e.to_string(),
-        })?;
+        });
     }
 
-    if output.conflict_count > 0 {
-        writeln!(
-            err_out,
-            "merge complete: {} conflict(s) recorded",
-            output.conflict_count
-        )
-        .map_err(|e| CliError::IoError {
-            source: "stderr".to_owned(),
-            detail: e.to_string(),
-        })?;
+    let output = merge_checked(&parsed, &config, &mut err_out)?;
+    if let Some(explanation) = &output.explanation {
+        match format {
+            OutputFormat::Human => write_explanation_human(&mut err_out, explanation),
+            OutputFormat::Json => write_explanation_json(&mut err_out, explanation),
+        }
+        .map_err(|e| log_error(&e))?;
+    }
+    if let Some(changes) = &output.id_changes {
+        match format {
+            OutputFormat::Human => write_id_changes_human(&mut err_out, changes),
+            OutputFormat::Json => write_id_changes_json(&mut err_out, changes),
+        }
+        .map_err(|e| log_error(&e))?;
     }
 
     let bytes = encode_output(&output.file, to, compress)?;
@@ -121,11 +183,333 @@ pub fn run(
     Ok(())
 }
 
+/// Rejects merge strategies that are not implemented yet.
+///
+/// # Errors
+///
+/// Returns [`CliError::MergeConflict`] for `intersect`.
+pub(crate) fn ensure_supported(strategy: &MergeStrategy) -> Result<(), CliError> {
+    if matches!(strategy, MergeStrategy::Intersect) {
+        return Err(CliError::MergeConflict {
+            detail: "intersect strategy is not yet implemented".to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Maps `--primary-perspective` to the position of that file among the inputs.
+///
+/// # Errors
+///
+/// Returns [`CliError::InvalidArgument`] if `primary` is not one of `files`.
+fn perspective_index(files: &[PathOrStdin], primary: &PathOrStdin) -> Result<usize, CliError> {
+    files
+        .iter()
+        .position(|file| file == primary)
+        .ok_or_else(|| CliError::InvalidArgument {
+            detail: format!(
+                "--primary-perspective {} is not one of the merged files",
+                match primary {
+                    PathOrStdin::Stdin => "-".to_owned(),
+                    PathOrStdin::Path(path) => path.display().to_string(),
+                }
+            ),
+        })
+}
+
+/// Maps a `--resolve` policy to the engine's [`ConflictPolicy`]; `priority`
+/// ranks the inputs in command-line order.
+fn conflict_policy(policy: ResolvePolicy, file_count: usize) -> ConflictPolicy {
+    match policy {
+        ResolvePolicy::Priority => ConflictPolicy::SourcePriority((0..file_count).collect()),
+        ResolvePolicy::LastVerified => ConflictPolicy::MostRecentlyVerified,
+        ResolvePolicy::Confidence => ConflictPolicy::HighestConfidence,
+        ResolvePolicy::Snapshot => ConflictPolicy::NewestSnapshot,
+        ResolvePolicy::Max => ConflictPolicy::Max,
+        ResolvePolicy::Min => ConflictPolicy::Min,
+        ResolvePolicy::Mean => ConflictPolicy::Mean,
+    }
+}
+
+/// Runs L1 validation on one merge input, writing any errors to `log`.
+///
+/// # Errors
+///
+/// - [`CliError::ValidationErrors`] — the input fails L1 validation.
+/// - [`CliError::IoError`] — writing to `log` failed.
+pub(crate) fn check_l1<W: std::io::Write>(file: &OmtsFile, log: &mut W) -> Result<(), CliError> {
+    let l1_config = ValidationConfig {
+        run_l1: true,
+        run_l2: false,
+        run_l3: false,
+    };
+    let validation_result = validate(file, &l1_config, None);
+    if !validation_result.has_errors() {
+        return Ok(());
+    }
+    for diag in validation_result.errors() {
+        writeln!(
+            log,
+            "error: {} {}: {}",
+            diag.rule_id, diag.location, diag.message
+        )
+        .map_err(|e| log_error(&e))?;
+    }
+    Err(CliError::ValidationErrors)
+}
+
+/// Merges inputs that passed [`check_l1`], writing warnings and the conflict
+/// count to `log`.
+///
+/// Shared by [`run`] and `omts serve`.
+///
+/// # Errors
+///
+/// - [`CliError::InvalidArgument`] — a conflict policy does not apply to its
+///   property.
+/// - [`CliError::MergeConflict`] — the merge engine reports an internal error.
+/// - [`CliError::IoError`] — writing to `log` failed.
+pub(crate) fn merge_checked<W: std::io::Write>(
+    parsed: &[OmtsFile],
+    config: &MergeConfig,
+    log: &mut W,
+) -> Result<MergeOutput, CliError> {
+    let output = merge_with_config(parsed, config).map_err(|e| merge_error(&e))?;
+
+    for warning in &output.warnings {
+        writeln!(log, "warning: {warning}").map_err(|e| log_error(&e))?;
+    }
+
+    if output.conflict_count > 0 {
+        writeln!(
+            log,
+            "merge complete: {} conflict(s) recorded",
+            output.conflict_count
+        )
+        .map_err(|e| log_error(&e))?;
+    }
+
+    if output.proposed_same_as > 0 {
+        writeln!(
+            log,
+            "merge complete: {} same_as edge(s) proposed for review (enrichment matches)",
+            output.proposed_same_as
+        )
+        .map_err(|e| log_error(&e))?;
+    }
+
+    Ok(output)
+}
+
+/// Maps an engine error: an inapplicable conflict policy or an unusable
+/// enrichment base is a bad argument, anything else a merge failure.
+fn merge_error(e: &MergeError) -> CliError {
+    match e {
+        MergeError::InvalidConflictPolicy(_) => CliError::InvalidArgument {
+            detail: e.to_string(),
+        },
+        MergeError::InvalidEnrichmentBase(_) => CliError::InvalidArgument {
+            detail: format!("{e} (merge the base with --provenance)"),
+        },
+        MergeError::NoInputFiles
+        | MergeError::PostMergeValidationFailed(_)
+        | MergeError::SaltGenerationFailed(_)
+        | MergeError::InternalDataError(_)
+        | MergeError::InvalidPrimaryPerspective(_) => CliError::MergeConflict {
+            detail: e.to_string(),
+        },
+    }
+}
+
+/// Writes the `--dry-run` report: totals, group-size histogram, oversized
+/// groups with their members, and conflict counts per property.
+fn write_preview_human<W: std::io::Write>(
+    w: &mut W,
+    preview: &MergePreview,
+) -> std::io::Result<()> {
+    writeln!(
+        w,
+        "would merge {} node(s) from {} file(s) into {} node(s)",
+        preview.node_count, preview.file_count, preview.merged_node_count
+    )?;
+    let sizes: Vec<String> = preview
+        .group_sizes
+        .iter()
+        .map(|(size, count)| format!("{size} x {count}"))
+        .collect();
+    writeln!(w, "group sizes: {}", sizes.join(", "))?;
+    for group in preview.groups.iter().filter(|g| g.oversized) {
+        let members: Vec<String> = group.members.iter().map(member_label).collect();
+        writeln!(
+            w,
+            "oversized group ({} > limit {}): {}",
+            group.size,
+            preview.group_size_limit,
+            members.join(", ")
+        )?;
+    }
+    if preview.conflicts.is_empty() {
+        writeln!(w, "expected conflicts: none")?;
+    } else {
+        let conflicts: Vec<String> = preview
+            .conflicts
+            .iter()
+            .map(|(field, count)| format!("{field} {count}"))
+            .collect();
+        writeln!(w, "expected conflicts: {}", conflicts.join(", "))?;
+    }
+    writeln!(w, "singletons: {}", preview.singletons.len())
+}
+
+/// Writes the `--dry-run` report as a single JSON object.
+fn write_preview_json<W: std::io::Write>(w: &mut W, preview: &MergePreview) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(preview).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+/// Writes the merge explanation as one line per group, link, and rejection.
+fn write_explanation_human<W: std::io::Write>(
+    w: &mut W,
+    explanation: &MergeExplanation,
+) -> std::io::Result<()> {
+    for group in &explanation.groups {
+        let members: Vec<String> = group.members.iter().map(member_label).collect();
+        writeln!(w, "merge group {}: {}", group.merged_id, members.join(", "))?;
+        for link in &group.links {
+            writeln!(
+                w,
+                "  {} -- {}: {}",
+                member_label(&link.a),
+                member_label(&link.b),
+                link_basis_text(&link.basis)
+            )?;
+        }
+    }
+    for rejected in &explanation.rejected {
+        let reason = match &rejected.reason {
+            RejectionReason::TemporalIncompatibility { identifier } => {
+                format!("{identifier} validity periods do not overlap")
+            }
+            RejectionReason::AuthorityMismatch { identifier } => {
+                format!("{identifier} authorities differ")
+            }
+            RejectionReason::AnnulledLei { identifier } => format!("{identifier} is annulled"),
+            RejectionReason::BelowSameAsThreshold {
+                edge_id,
+                confidence,
+            } => format!(
+                "same_as edge {edge_id} ({}) is below the threshold",
+                confidence.as_deref().unwrap_or("no confidence")
+            ),
+            RejectionReason::EnrichmentMatch { link } => format!(
+                "{} extends a group of the enrichment base",
+                link_basis_text(link)
+            ),
+        };
+        writeln!(
+            w,
+            "rejected {} -- {}: {reason}",
+            member_label(&rejected.a),
+            member_label(&rejected.b)
+        )?;
+    }
+    writeln!(
+        w,
+        "explain: {} merge group(s), {} rejected candidate(s)",
+        explanation.groups.len(),
+        explanation.rejected.len()
+    )
+}
+
+/// Writes the merge explanation as a single-line JSON object.
+fn write_explanation_json<W: std::io::Write>(
+    w: &mut W,
+    explanation: &MergeExplanation,
+) -> std::io::Result<()> {
+    let json = serde_json::to_string(explanation).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+/// Writes the `--previous` ID changes as one line per changed ID and a count
+/// line.
+fn write_id_changes_human<W: std::io::Write>(
+    w: &mut W,
+    changes: &IdChanges,
+) -> std::io::Result<()> {
+    let join = |ids: &[omts_core::NodeId]| {
+        ids.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    for split in &changes.split {
+        writeln!(w, "split {} into {}", split.previous, join(&split.into))?;
+    }
+    for coalesced in &changes.coalesced {
+        writeln!(
+            w,
+            "coalesced {} into {}",
+            join(&coalesced.from),
+            coalesced.merged
+        )?;
+    }
+    for id in &changes.minted {
+        writeln!(w, "minted {id}")?;
+    }
+    for id in &changes.retired {
+        writeln!(w, "retired {id}")?;
+    }
+    writeln!(
+        w,
+        "ids: {} reused, {} minted, {} split, {} coalesced, {} retired",
+        changes.reused,
+        changes.minted.len(),
+        changes.split.len(),
+        changes.coalesced.len(),
+        changes.retired.len()
+    )
+}
+
+/// Writes the `--previous` ID changes as a single-line JSON object.
+fn write_id_changes_json<W: std::io::Write>(w: &mut W, changes: &IdChanges) -> std::io::Result<()> {
+    let json = serde_json::to_string(changes).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+/// `shared identifier lei:...` or `same_as edge e-1 (probable)` — a link as
+/// shown in explanation lines.
+fn link_basis_text(basis: &LinkBasis) -> String {
+    match basis {
+        LinkBasis::Identifier { identifier } => format!("shared identifier {identifier}"),
+        LinkBasis::SameAs {
+            edge_id,
+            confidence,
+        } => format!(
+            "same_as edge {edge_id} ({})",
+            confidence.as_deref().unwrap_or("no confidence")
+        ),
+    }
+}
+
+/// `a.omts:org-1` — a source node as shown in explanation lines, named by
+/// its input path (or its `file_{i}` label when the path is unknown).
+fn member_label(member: &GroupMember) -> String {
+    let source = member.source_path.as_deref().unwrap_or(&member.source_file);
+    format!("{source}:{}", member.node_id)
+}
+
+fn log_error(e: &std::io::Error) -> CliError {
+    CliError::IoError {
+        source: "stderr".to_owned(),
+        detail: e.to_string(),
+    }
+}
+
 /// Serializes `file` to the requested encoding, optionally compressing with zstd.
 ///
 /// Pretty-printed JSON is the default for `--to json`. CBOR uses the
 /// self-describing tag 55799 prepended per SPEC-007 Section 4.1.
-fn encode_output(
+pub(crate) fn encode_output(
     file: &OmtsFile,
     to: &TargetEncoding,
     compress: bool,
@@ -157,6 +541,7 @@ mod tests {
     #![allow(clippy::panic)]
 
     use super::*;
+    use omts_core::merge;
     const MINIMAL_A: &str = r#"{
         "omts_version": "1.0.0",
         "snapshot_date": "2026-02-19",
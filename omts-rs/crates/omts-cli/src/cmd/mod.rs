@@ -3,10 +3,14 @@
 /// Each submodule implements one subcommand. The `run` function in each
 /// module takes the parsed arguments and returns `Ok(())` on success or
 /// a [`crate::error::CliError`] on failure.
+pub mod audit_disclosure;
+pub mod conflicts;
 pub mod convert;
+pub mod dedup;
 pub mod diff;
 pub mod export;
 pub mod import;
+pub mod index;
 pub mod init;
 pub mod inspect;
 pub mod merge;
@@ -14,6 +18,11 @@ pub mod path;
 pub mod query;
 pub mod reach;
 pub mod redact;
+pub mod resolve_boundary;
+pub mod segment;
 pub mod selectors;
+pub mod serve;
+pub mod shell;
 pub mod subgraph;
+pub mod unmerge;
 pub mod validate;
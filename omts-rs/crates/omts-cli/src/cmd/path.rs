@@ -1,129 +1,299 @@
 //! Implementation of `omts path <file> <from> <to>`.
 //!
-//! Parses an `.omts` file, builds the directed graph, finds paths from `from`
-//! to `to`, and writes them to stdout.
+//! Loads the graph of an `.omts` file, finds the cheapest
+//! loopless paths from `from` to `to` ([`omts_core::k_shortest_paths`]), and
+//! writes them to stdout with the edges each path traverses.
 //!
 //! Flags:
 //! - `--max-paths <n>` (default 10): maximum number of paths to report.
 //! - `--max-depth <n>` (default 20): maximum path length in edges.
+//! - `--cost <hops|share-of-buyer-demand|annual-value>` (default hops): edge
+//!   cost. The value-based costs make high-value relationships cheap.
+//! - `--edge-cost <type>=<n>` (repeatable): multiply the cost of one edge type.
+//! - `--pattern <pattern>`: constrain the edge-type sequence, e.g.
+//!   `"supplies+ operates"`.
+//! - `--edge-type <type>` (repeatable): traverse only edges of these types.
+//! - `--subgraph`: instead of listing paths, emit the union of the found
+//!   paths (their nodes and exactly the edges they traverse) as a valid
+//!   `.omts` file. `--to` and `--compress` select the encoding.
 //!
-//! Output (human mode): each path on one line with node IDs separated by
-//! ` -> `, shortest first.
-//! Output (JSON mode): a JSON object `{"paths": [[...], ...], "count": N}`,
-//! with paths ordered shortest-first.
+//! Output (human mode): each path on one line, cheapest first, as
+//! `a -[edge-id:type]-> b`. A non-hop cost is appended in parentheses.
+//! Output (JSON mode): a JSON object
+//! `{"paths": [[...], ...], "routes": [...], "count": N}`, where `paths`
+//! holds the node IDs of each path and `routes` adds the edges and cost.
 //!
 //! Exit codes: 0 = at least one path found, 1 = no path / node not found,
-//! 2 = parse/build failure.
-use omts_core::OmtsFile;
+//! 2 = parse/build failure or invalid flag value.
+use std::collections::HashMap;
+
+use omts_core::graph::OmtsGraph;
 use omts_core::graph::queries::Direction as CoreDirection;
-use omts_core::graph::{QueryError, all_paths, build_graph};
+use omts_core::graph::{QueryError, path_subgraph};
+use omts_core::{
+    EdgePattern, EdgeTypeTag, OmtsFile, PathCost as CorePathCost, PathOptions, WeightedPath,
+    k_shortest_paths,
+};
 
-use crate::OutputFormat;
+use crate::cmd::index::IndexedInput;
+use crate::cmd::selectors::{parse_edge_filter, parse_edge_type_tag};
+use crate::cmd::subgraph::write_subgraph;
 use crate::error::CliError;
+use crate::{OutputFormat, PathCost, TargetEncoding};
+
+/// One traversed edge of a reported path.
+pub(crate) struct HopEdge {
+    id: String,
+    edge_type: String,
+    source: String,
+    target: String,
+    /// `true` when the edge was traversed from its target to its source.
+    reversed: bool,
+}
+
+/// A path resolved to IDs for output.
+pub(crate) struct Route {
+    nodes: Vec<String>,
+    edges: Vec<HopEdge>,
+    cost: f64,
+}
 
 /// Runs the `path` command.
 ///
-/// Builds the graph from the pre-parsed `file` and finds up to `max_paths`
-/// paths from `from` to `to` with a maximum length of `max_depth` edges.
-/// Paths are ordered shortest-first.
+/// Loads the graph from `input` (from its index sidecar when fresh) and finds up to `max_paths`
+/// paths from `from` to `to` with a maximum length of `max_depth` edges. The
+/// file is parsed only for a value-based `cost` or for `subgraph` output.
+/// Paths are ordered cheapest-first and only traverse edges of `edge_types`
+/// (all types when empty). When `subgraph` is `true`, the union of the paths
+/// is written as an `.omts` file in `encoding` instead.
 ///
 /// # Errors
 ///
-/// - [`CliError`] exit code 2 if the graph cannot be built.
+/// - [`CliError`] exit code 2 if the graph cannot be built, an
+///   `--edge-cost` or `--pattern` value is invalid, or the subgraph cannot be
+///   serialized.
 /// - [`CliError`] exit code 1 if either node ID is not found, or no path
 ///   exists.
-pub fn run(
-    file: &OmtsFile,
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run(
+    input: &IndexedInput<'_>,
     from: &str,
     to: &str,
     max_paths: usize,
     max_depth: u32,
+    cost: PathCost,
+    edge_costs: &[String],
+    pattern: Option<&str>,
+    edge_types: &[String],
+    subgraph: bool,
+    encoding: &TargetEncoding,
+    compress: bool,
     format: &OutputFormat,
 ) -> Result<(), CliError> {
-    let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
+    let show_cost = !matches!(cost, PathCost::Hops) || !edge_costs.is_empty();
+
+    // Hop costs need only the graph, which a fresh index provides unparsed.
+    let graph = input.graph()?;
+    let file = match cost {
+        PathCost::Hops => None,
+        PathCost::ShareOfBuyerDemand | PathCost::AnnualValue => Some(input.file()?),
+    };
+
+    let raw_paths = find_paths(
+        graph, file, from, to, max_paths, max_depth, cost, edge_costs, pattern, edge_types,
+    )?;
+
+    if subgraph {
+        let file = input.file()?;
+        return write_subgraph(path_subgraph(graph, file, &raw_paths), encoding, compress);
+    }
+
+    let routes: Vec<Route> = raw_paths.iter().map(|p| resolve(graph, p)).collect();
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    match format {
+        OutputFormat::Human => print_human(&mut out, &routes, show_cost),
+        OutputFormat::Json => print_json(&mut out, &routes),
+    }
+    .map_err(|e| CliError::IoError {
+        source: "stdout".to_owned(),
         detail: e.to_string(),
-    })?;
-
-    let mut raw_paths = all_paths(
-        &graph,
-        from,
-        to,
-        max_depth as usize,
-        CoreDirection::Forward,
-        None,
-    )
-    .map_err(query_error_to_cli)?;
+    })
+}
+
+/// Finds up to `max_paths` cheapest paths from `from` to `to`.
+///
+/// `file` is needed only for value-based costs ([`k_shortest_paths`]).
+/// Shared by [`run`], `omts shell`, and `omts serve`.
+///
+/// # Errors
+///
+/// - [`CliError`] exit code 2 if an `--edge-cost`, `--pattern`, or
+///   `--edge-type` value is invalid.
+/// - [`CliError`] exit code 1 if either node ID is not found, or no path
+///   exists.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn find_paths(
+    graph: &OmtsGraph,
+    file: Option<&OmtsFile>,
+    from: &str,
+    to: &str,
+    max_paths: usize,
+    max_depth: u32,
+    cost: PathCost,
+    edge_costs: &[String],
+    pattern: Option<&str>,
+    edge_types: &[String],
+) -> Result<Vec<WeightedPath>, CliError> {
+    let options = PathOptions {
+        cost: to_core_cost(cost),
+        edge_type_costs: parse_edge_costs(edge_costs)?,
+        pattern: pattern
+            .map(|p| {
+                EdgePattern::parse(p).map_err(|e| CliError::InvalidArgument {
+                    detail: format!("--pattern {p:?}: {e}"),
+                })
+            })
+            .transpose()?,
+        direction: CoreDirection::Forward,
+        edge_filter: parse_edge_filter(edge_types)?,
+        max_depth: max_depth as usize,
+    };
+
+    let raw_paths =
+        k_shortest_paths(graph, file, from, to, max_paths, &options).map_err(query_error_to_cli)?;
 
     if raw_paths.is_empty() {
         return Err(CliError::NoResults {
             detail: format!("no path from {from:?} to {to:?}"),
         });
     }
+    Ok(raw_paths)
+}
 
-    raw_paths.sort_by(|a, b| {
-        a.len().cmp(&b.len()).then_with(|| {
-            let ids_a: Vec<&str> = a
-                .iter()
-                .filter_map(|&idx| graph.node_weight(idx).map(|w| w.local_id.as_str()))
-                .collect();
-            let ids_b: Vec<&str> = b
-                .iter()
-                .filter_map(|&idx| graph.node_weight(idx).map(|w| w.local_id.as_str()))
-                .collect();
-            ids_a.cmp(&ids_b)
+/// Maps the CLI cost to the core [`CorePathCost`].
+fn to_core_cost(cost: PathCost) -> CorePathCost {
+    match cost {
+        PathCost::Hops => CorePathCost::Hops,
+        PathCost::ShareOfBuyerDemand => CorePathCost::InverseShareOfBuyerDemand,
+        PathCost::AnnualValue => CorePathCost::InverseAnnualValue,
+    }
+}
+
+/// Parses `--edge-cost TYPE=N` values into per-type multipliers.
+fn parse_edge_costs(values: &[String]) -> Result<HashMap<EdgeTypeTag, f64>, CliError> {
+    values
+        .iter()
+        .map(|value| {
+            let invalid = || CliError::InvalidArgument {
+                detail: format!(
+                    "--edge-cost {value:?}: expected TYPE=N with N a non-negative number"
+                ),
+            };
+            let (edge_type, n) = value.split_once('=').ok_or_else(invalid)?;
+            let n: f64 = n.parse().map_err(|_| invalid())?;
+            if !n.is_finite() || n < 0.0 {
+                return Err(invalid());
+            }
+            Ok((parse_edge_type_tag(edge_type)?, n))
         })
-    });
+        .collect()
+}
 
-    let paths: Vec<Vec<String>> = raw_paths
+/// Resolves a core path to node and edge IDs.
+pub(crate) fn resolve(graph: &OmtsGraph, path: &WeightedPath) -> Route {
+    let id = |idx| {
+        graph
+            .node_weight(idx)
+            .map(|w| w.local_id.clone())
+            .unwrap_or_default()
+    };
+    let nodes: Vec<String> = path.nodes.iter().map(|&idx| id(idx)).collect();
+    let edges = path
+        .edges
         .iter()
-        .take(max_paths)
-        .map(|path| {
-            path.iter()
-                .filter_map(|&idx| graph.node_weight(idx).map(|w| w.local_id.clone()))
-                .collect()
+        .enumerate()
+        .filter_map(|(i, &edge)| {
+            let weight = graph.edge_weight(edge)?;
+            let (source, target) = graph.graph().edge_endpoints(edge)?;
+            Some(HopEdge {
+                id: weight.local_id.clone(),
+                edge_type: edge_type_display(&weight.edge_type),
+                source: id(source),
+                target: id(target),
+                reversed: path.nodes.get(i) != Some(&source),
+            })
         })
         .collect();
-
-    let stdout = std::io::stdout();
-    let mut out = stdout.lock();
-
-    match format {
-        OutputFormat::Human => print_human(&mut out, &paths),
-        OutputFormat::Json => print_json(&mut out, &paths),
+    Route {
+        nodes,
+        edges,
+        cost: path.cost,
     }
-    .map_err(|e| CliError::IoError {
-        source: "stdout".to_owned(),
-        detail: e.to_string(),
-    })
 }
 
-/// Writes paths in human-readable format: each path on one line, node IDs
-/// separated by ` -> `.
-fn print_human<W: std::io::Write>(w: &mut W, paths: &[Vec<String>]) -> std::io::Result<()> {
-    for path in paths {
-        writeln!(w, "{}", path.join(" -> "))?;
+/// Writes paths in human-readable format: each path on one line, with each
+/// hop shown as `-[edge-id:type]->` (or `<-[edge-id:type]-` when traversed
+/// against the edge direction).
+fn print_human<W: std::io::Write>(
+    w: &mut W,
+    routes: &[Route],
+    show_cost: bool,
+) -> std::io::Result<()> {
+    for route in routes {
+        let mut line = route.nodes.first().cloned().unwrap_or_default();
+        for (edge, node) in route.edges.iter().zip(route.nodes.iter().skip(1)) {
+            if edge.reversed {
+                line.push_str(&format!(" <-[{}:{}]- {node}", edge.id, edge.edge_type));
+            } else {
+                line.push_str(&format!(" -[{}:{}]-> {node}", edge.id, edge.edge_type));
+            }
+        }
+        if show_cost {
+            line.push_str(&format!("  (cost {:.3})", route.cost));
+        }
+        writeln!(w, "{line}")?;
     }
     Ok(())
 }
 
-/// Writes paths as a JSON object `{"paths": [[...], ...], "count": N}`.
-fn print_json<W: std::io::Write>(w: &mut W, paths: &[Vec<String>]) -> std::io::Result<()> {
-    let paths_array: Vec<serde_json::Value> = paths
+/// Writes paths as a JSON object `{"paths": [[...], ...], "routes": [...], "count": N}`.
+pub(crate) fn print_json<W: std::io::Write>(w: &mut W, routes: &[Route]) -> std::io::Result<()> {
+    let paths_array: Vec<serde_json::Value> = routes
         .iter()
-        .map(|path| {
-            serde_json::Value::Array(
-                path.iter()
-                    .map(|id| serde_json::Value::String(id.clone()))
-                    .collect(),
-            )
+        .map(|route| serde_json::json!(route.nodes))
+        .collect();
+    let routes_array: Vec<serde_json::Value> = routes
+        .iter()
+        .map(|route| {
+            let edges: Vec<serde_json::Value> = route
+                .edges
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "id": e.id,
+                        "type": e.edge_type,
+                        "source": e.source,
+                        "target": e.target,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "nodes": route.nodes,
+                "edges": edges,
+                "cost": route.cost,
+            })
         })
         .collect();
 
     let mut obj = serde_json::Map::new();
     obj.insert("paths".to_owned(), serde_json::Value::Array(paths_array));
+    obj.insert("routes".to_owned(), serde_json::Value::Array(routes_array));
     obj.insert(
         "count".to_owned(),
-        serde_json::Value::Number(paths.len().into()),
+        serde_json::Value::Number(routes.len().into()),
     );
 
     let json = serde_json::to_string_pretty(&serde_json::Value::Object(obj))
@@ -131,6 +301,17 @@ fn print_json<W: std::io::Write>(w: &mut W, paths: &[Vec<String>]) -> std::io::R
     writeln!(w, "{json}")
 }
 
+/// Returns a display string for an [`EdgeTypeTag`].
+fn edge_type_display(tag: &EdgeTypeTag) -> String {
+    match tag {
+        EdgeTypeTag::Known(t) => serde_json::to_value(t)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_owned))
+            .unwrap_or_else(|| format!("{t:?}")),
+        EdgeTypeTag::Extension(s) => s.clone(),
+    }
+}
+
 /// Converts a [`QueryError`] to the appropriate [`CliError`].
 fn query_error_to_cli(e: QueryError) -> CliError {
     match e {
@@ -6,6 +6,7 @@
 //! Flags:
 //! - `--node-type`, `--edge-type`, `--label`, `--identifier`, `--jurisdiction`,
 //!   `--name` (repeatable, see [`crate::cmd::selectors`] for parsing rules)
+//! - `--where <EXPR>`: boolean selector expression, combined with the flags by AND.
 //! - `--count`: print only match counts, not individual results.
 //!
 //! Output (human mode, default): tab-separated table with columns
@@ -16,10 +17,11 @@
 //! Diagnostic match counts are always emitted to stderr.
 //!
 //! Exit codes: 0 = at least one match found, 1 = no matches, 2 = parse error.
-use omts_core::OmtsFile;
-use omts_core::graph::selector_match;
+use omts_core::graph::{IndexedEdge, IndexedNode, SelectorSet, selector_match};
+use omts_core::{GraphIndex, OmtsFile};
 
 use crate::OutputFormat;
+use crate::cmd::index::IndexedInput;
 use crate::cmd::selectors::build_selector_set;
 use crate::error::CliError;
 
@@ -37,7 +39,8 @@ use crate::error::CliError;
 ///
 /// # Errors
 ///
-/// - [`CliError`] exit code 2 if no selector flags were provided.
+/// - [`CliError`] exit code 2 if no selector flags were provided or the
+///   `--where` expression is malformed.
 /// - [`CliError`] exit code 1 if no elements match the selectors.
 #[allow(clippy::too_many_arguments)]
 pub fn run(
@@ -48,6 +51,7 @@ pub fn run(
     identifiers: &[String],
     jurisdictions: &[String],
     names: &[String],
+    where_expr: Option<&str>,
     count: bool,
     format: &OutputFormat,
 ) -> Result<(), CliError> {
@@ -58,9 +62,77 @@ pub fn run(
         identifiers,
         jurisdictions,
         names,
+        where_expr,
     )?;
+    run_on_file(file, &selector_set, count, format)
+}
 
-    let result = selector_match(file, &selector_set);
+/// Runs the `query` command against an [`IndexedInput`].
+///
+/// When the input has a fresh index, the output needs no full element bodies
+/// (human table or `--count`), and the selectors are answerable from the
+/// index, the query is served from the index without parsing the file.
+/// Otherwise this behaves exactly like [`run`].
+///
+/// # Errors
+///
+/// Same as [`run`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_indexed(
+    input: &IndexedInput<'_>,
+    node_types: &[String],
+    edge_types: &[String],
+    labels: &[String],
+    identifiers: &[String],
+    jurisdictions: &[String],
+    names: &[String],
+    where_expr: Option<&str>,
+    count: bool,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let selector_set = build_selector_set(
+        node_types,
+        edge_types,
+        labels,
+        identifiers,
+        jurisdictions,
+        names,
+        where_expr,
+    )?;
+
+    let table_only = match format {
+        OutputFormat::Human => true,
+        OutputFormat::Json => count,
+    };
+    if let Some(index) = input.index().filter(|_| table_only) {
+        if let Some(result) = index.select(&selector_set) {
+            let nodes: Vec<Row> = result
+                .node_indices
+                .iter()
+                .map(|&i| Row::indexed_node(&index.nodes[i]))
+                .collect();
+            let edges: Vec<Row> = result
+                .edge_indices
+                .iter()
+                .map(|&i| Row::indexed_edge(index, &index.edges[i]))
+                .collect();
+            return report(nodes.len(), edges.len(), count, |out| {
+                print_human(out, &nodes, &edges)
+            });
+        }
+    }
+
+    run_on_file(input.file()?, &selector_set, count, format)
+}
+
+/// Matches `selector_set` against a parsed file and prints the result.
+fn run_on_file(
+    file: &OmtsFile,
+    selector_set: &SelectorSet,
+    count: bool,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let result = selector_match(file, selector_set);
 
     let matched_nodes: Vec<&omts_core::Node> = result
         .node_indices
@@ -74,9 +146,31 @@ pub fn run(
         .map(|&i| &file.edges[i])
         .collect();
 
-    let node_count = matched_nodes.len();
-    let edge_count = matched_edges.len();
+    report(
+        matched_nodes.len(),
+        matched_edges.len(),
+        count,
+        |out| match format {
+            OutputFormat::Human => {
+                let nodes: Vec<Row> = matched_nodes.iter().map(|n| Row::node(n)).collect();
+                let edges: Vec<Row> = matched_edges.iter().map(|e| Row::edge(e)).collect();
+                print_human(out, &nodes, &edges)
+            }
+            OutputFormat::Json => print_json(out, &matched_nodes, &matched_edges),
+        },
+    )
+}
 
+/// Reports match counts and writes the result body.
+///
+/// Returns `NoResults` when nothing matched, emits the diagnostic count line
+/// on stderr, and then writes either the `--count` lines or `body` to stdout.
+fn report(
+    node_count: usize,
+    edge_count: usize,
+    count: bool,
+    body: impl FnOnce(&mut std::io::StdoutLock<'static>) -> std::io::Result<()>,
+) -> Result<(), CliError> {
     if node_count == 0 && edge_count == 0 {
         return Err(CliError::NoResults {
             detail: "no nodes or edges matched the given selectors".to_owned(),
@@ -85,8 +179,7 @@ pub fn run(
 
     eprintln!("matched: {node_count} node(s), {edge_count} edge(s)");
 
-    let stdout = std::io::stdout();
-    let mut out = stdout.lock();
+    let mut out = std::io::stdout().lock();
 
     if count {
         use std::io::Write as _;
@@ -101,40 +194,64 @@ pub fn run(
         return Ok(());
     }
 
-    match format {
-        OutputFormat::Human => print_human(&mut out, &matched_nodes, &matched_edges),
-        OutputFormat::Json => print_json(&mut out, &matched_nodes, &matched_edges),
-    }
-    .map_err(|e| CliError::IoError {
+    body(&mut out).map_err(|e| CliError::IoError {
         source: "stdout".to_owned(),
         detail: e.to_string(),
     })
 }
 
+/// One row of the human-readable table: `ID`, `TYPE`, `NAME/ENDPOINT`.
+struct Row {
+    id: String,
+    type_str: String,
+    detail: String,
+}
+
+impl Row {
+    fn node(node: &omts_core::Node) -> Self {
+        Row {
+            id: node.id.to_string(),
+            type_str: node_type_display(&node.node_type),
+            detail: node.name.clone().unwrap_or_else(|| "-".to_owned()),
+        }
+    }
+
+    fn edge(edge: &omts_core::Edge) -> Self {
+        Row {
+            id: edge.id.to_string(),
+            type_str: edge_type_display(&edge.edge_type),
+            detail: format!("{}→{}", edge.source, edge.target),
+        }
+    }
+
+    fn indexed_node(node: &IndexedNode) -> Self {
+        Row {
+            id: node.id.clone(),
+            type_str: node_type_display(&node.node_type),
+            detail: node.name.clone().unwrap_or_else(|| "-".to_owned()),
+        }
+    }
+
+    fn indexed_edge(index: &GraphIndex, edge: &IndexedEdge) -> Self {
+        let endpoint = |pos: u32| index.nodes.get(pos as usize).map_or("?", |n| n.id.as_str());
+        Row {
+            id: edge.id.clone(),
+            type_str: edge_type_display(&edge.edge_type),
+            detail: format!("{}→{}", endpoint(edge.source), endpoint(edge.target)),
+        }
+    }
+}
+
 /// Writes matched nodes and edges as a tab-separated table.
 ///
 /// Columns: `KIND`, `ID`, `TYPE`, `NAME/ENDPOINT`
-fn print_human<W: std::io::Write>(
-    w: &mut W,
-    nodes: &[&omts_core::Node],
-    edges: &[&omts_core::Edge],
-) -> std::io::Result<()> {
+fn print_human<W: std::io::Write>(w: &mut W, nodes: &[Row], edges: &[Row]) -> std::io::Result<()> {
     writeln!(w, "KIND\tID\tTYPE\tNAME/ENDPOINT")?;
 
-    for node in nodes {
-        let kind = "node";
-        let id = node.id.to_string();
-        let type_str = node_type_display(&node.node_type);
-        let name = node.name.as_deref().unwrap_or("-");
-        writeln!(w, "{kind}\t{id}\t{type_str}\t{name}")?;
-    }
-
-    for edge in edges {
-        let kind = "edge";
-        let id = edge.id.to_string();
-        let type_str = edge_type_display(&edge.edge_type);
-        let endpoint = format!("{}→{}", edge.source, edge.target);
-        writeln!(w, "{kind}\t{id}\t{type_str}\t{endpoint}")?;
+    for (kind, rows) in [("node", nodes), ("edge", edges)] {
+        for row in rows {
+            writeln!(w, "{kind}\t{}\t{}\t{}", row.id, row.type_str, row.detail)?;
+        }
     }
 
     Ok(())
@@ -144,7 +261,7 @@ fn print_human<W: std::io::Write>(
 ///
 /// Output: `{"nodes": [...], "edges": [...]}` where each element is the full
 /// serialized JSON of the matching node or edge.
-fn print_json<W: std::io::Write>(
+pub(crate) fn print_json<W: std::io::Write>(
     w: &mut W,
     nodes: &[&omts_core::Node],
     edges: &[&omts_core::Edge],
@@ -236,6 +353,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             false,
             &OutputFormat::Human,
         );
@@ -257,6 +375,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             false,
             &OutputFormat::Human,
         );
@@ -275,6 +394,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             false,
             &OutputFormat::Human,
         );
@@ -294,6 +414,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             true,
             &OutputFormat::Human,
         );
@@ -312,6 +433,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             false,
             &OutputFormat::Json,
         );
@@ -330,6 +452,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             false,
             &OutputFormat::Human,
         );
@@ -342,8 +465,8 @@ mod tests {
     fn test_human_output_contains_header() {
         let file: OmtsFile = serde_json::from_str(SAMPLE_FILE).expect("parse");
         let mut buf = Vec::new();
-        let nodes: Vec<&omts_core::Node> = file.nodes.iter().collect();
-        let edges: Vec<&omts_core::Edge> = vec![];
+        let nodes: Vec<Row> = file.nodes.iter().map(Row::node).collect();
+        let edges: Vec<Row> = vec![];
         print_human(&mut buf, &nodes, &edges).expect("write");
         let output = String::from_utf8(buf).expect("utf8");
         assert!(output.contains("KIND"), "should contain KIND header");
@@ -356,8 +479,8 @@ mod tests {
     fn test_human_output_contains_node_data() {
         let file: OmtsFile = serde_json::from_str(SAMPLE_FILE).expect("parse");
         let mut buf = Vec::new();
-        let nodes: Vec<&omts_core::Node> = file.nodes.iter().collect();
-        let edges: Vec<&omts_core::Edge> = vec![];
+        let nodes: Vec<Row> = file.nodes.iter().map(Row::node).collect();
+        let edges: Vec<Row> = vec![];
         print_human(&mut buf, &nodes, &edges).expect("write");
         let output = String::from_utf8(buf).expect("utf8");
         assert!(output.contains("org-1"), "should contain node ID org-1");
@@ -373,8 +496,8 @@ mod tests {
     fn test_human_output_contains_edge_data() {
         let file: OmtsFile = serde_json::from_str(SAMPLE_FILE).expect("parse");
         let mut buf = Vec::new();
-        let nodes: Vec<&omts_core::Node> = vec![];
-        let edges: Vec<&omts_core::Edge> = file.edges.iter().collect();
+        let nodes: Vec<Row> = vec![];
+        let edges: Vec<Row> = file.edges.iter().map(Row::edge).collect();
         print_human(&mut buf, &nodes, &edges).expect("write");
         let output = String::from_utf8(buf).expect("utf8");
         assert!(output.contains("e-1"), "should contain edge ID");
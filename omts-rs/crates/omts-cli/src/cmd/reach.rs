@@ -7,55 +7,84 @@
 //! - `--depth <n>` (optional): maximum traversal depth.
 //! - `--direction <d>`: traversal direction (`outgoing` (default), `incoming`,
 //!   or `both`).
+//! - `--edge-type <type>` (repeatable): traverse only edges of these types.
+//! - `--subgraph`: instead of listing IDs, emit the reached region (the start
+//!   node, every reached node, and the traversable edges between them) as a
+//!   valid `.omts` file. `--to` and `--compress` select the encoding.
+//!
+//! With a fresh index sidecar (see [`crate::cmd::index`]) the graph comes from
+//! the index, and a plain listing never parses the file.
 //!
 //! Output (human mode): one node ID per line, sorted for determinism.
 //! Output (JSON mode): a JSON object `{"node_ids": [...], "count": N}`.
 //!
 //! Exit codes: 0 = success, 1 = source node not found, 2 = parse/build failure.
-use omts_core::OmtsFile;
+use std::collections::HashSet;
+
+use omts_core::EdgeTypeTag;
 use omts_core::graph::queries::Direction as CoreDirection;
-use omts_core::graph::{QueryError, build_graph, reachable_from};
+use omts_core::graph::{OmtsGraph, QueryError, reachable_subgraph, reachable_within};
 
-use crate::Direction;
-use crate::OutputFormat;
+use crate::cmd::index::IndexedInput;
+use crate::cmd::selectors::parse_edge_filter;
+use crate::cmd::subgraph::write_subgraph;
 use crate::error::CliError;
+use crate::{Direction, OutputFormat, TargetEncoding};
 
 /// Runs the `reach` command.
 ///
-/// Builds the graph from the pre-parsed `file`, finds all nodes reachable from
-/// `node_id`, and writes them to stdout in the requested format.
+/// Loads the graph from `input`, finds all nodes reachable from
+/// `node_id` along edges of the `edge_types` (all types when empty), and
+/// writes them to stdout in the requested format — or, when `subgraph` is
+/// `true`, writes the reached region as an `.omts` file encoded with `to`.
 ///
 /// `depth` is an optional maximum traversal depth (in hops).  When `None`,
 /// the traversal is unbounded (limited only by graph size).
 ///
 /// # Errors
 ///
-/// - [`CliError`] exit code 2 if the graph cannot be built.
+/// - [`CliError`] exit code 2 if the graph cannot be built or the subgraph
+///   cannot be serialized.
 /// - [`CliError`] exit code 1 if `node_id` is not found in the graph.
-pub fn run(
-    file: &OmtsFile,
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run(
+    input: &IndexedInput<'_>,
     node_id: &str,
     depth: Option<u32>,
     direction: &Direction,
+    edge_types: &[String],
+    subgraph: bool,
+    to: &TargetEncoding,
+    compress: bool,
     format: &OutputFormat,
 ) -> Result<(), CliError> {
-    let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
-        detail: e.to_string(),
-    })?;
+    let edge_filter = parse_edge_filter(edge_types)?;
+
+    let graph = input.graph()?;
 
     let core_direction = to_core_direction(direction);
+    let max_depth = depth.map(|d| d as usize);
+
+    if subgraph {
+        let region = reachable_subgraph(
+            graph,
+            input.file()?,
+            node_id,
+            max_depth,
+            core_direction,
+            edge_filter.as_ref(),
+        )
+        .map_err(query_error_to_cli)?;
+        return write_subgraph(region, to, compress);
+    }
 
-    let reachable = if let Some(max_depth) = depth {
-        reachable_from_bounded(&graph, node_id, max_depth, core_direction)?
-    } else {
-        reachable_from(&graph, node_id, core_direction, None).map_err(query_error_to_cli)?
-    };
-
-    let mut node_ids: Vec<String> = reachable
-        .into_iter()
-        .filter_map(|idx| graph.node_weight(idx).map(|w| w.local_id.clone()))
-        .collect();
-    node_ids.sort();
+    let node_ids = reachable_ids(
+        graph,
+        node_id,
+        max_depth,
+        core_direction,
+        edge_filter.as_ref(),
+    )?;
 
     let stdout = std::io::stdout();
     let mut out = stdout.lock();
@@ -70,81 +99,29 @@ pub fn run(
     })
 }
 
-/// Performs a depth-bounded BFS from `start`, returning all reachable
-/// [`petgraph::stable_graph::NodeIndex`] values within `max_depth` hops.
+/// Returns the sorted IDs of the nodes reachable from `node_id`.
 ///
-/// The start node itself is excluded from the result, consistent with the
-/// unbounded [`reachable_from`] behaviour.
-fn reachable_from_bounded(
-    graph: &omts_core::graph::OmtsGraph,
-    start: &str,
-    max_depth: u32,
-    direction: CoreDirection,
-) -> Result<std::collections::HashSet<petgraph::stable_graph::NodeIndex>, CliError> {
-    use std::collections::{HashSet, VecDeque};
-
-    let start_idx = *graph
-        .node_index(start)
-        .ok_or_else(|| CliError::NodeNotFound {
-            node_id: start.to_owned(),
-        })?;
-
-    let mut visited: HashSet<petgraph::stable_graph::NodeIndex> = HashSet::new();
-    let mut queue: VecDeque<(petgraph::stable_graph::NodeIndex, u32)> = VecDeque::new();
-
-    visited.insert(start_idx);
-    queue.push_back((start_idx, 0));
-
-    while let Some((current, depth)) = queue.pop_front() {
-        if depth >= max_depth {
-            continue;
-        }
-        let next_depth = depth + 1;
-        for neighbour in neighbours(graph, current, direction) {
-            if !visited.contains(&neighbour) {
-                visited.insert(neighbour);
-                queue.push_back((neighbour, next_depth));
-            }
-        }
-    }
-
-    visited.remove(&start_idx);
-    Ok(visited)
-}
-
-/// Returns the immediate neighbours of `node` in `direction`.
-fn neighbours(
-    graph: &omts_core::graph::OmtsGraph,
-    node: petgraph::stable_graph::NodeIndex,
+/// Shared by [`run`] and `omts serve`.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 1 if `node_id` is not found in the graph.
+pub(crate) fn reachable_ids(
+    graph: &OmtsGraph,
+    node_id: &str,
+    max_depth: Option<usize>,
     direction: CoreDirection,
-) -> Vec<petgraph::stable_graph::NodeIndex> {
-    use petgraph::visit::EdgeRef as _;
-
-    let g = graph.graph();
-    let mut result = Vec::new();
-
-    match direction {
-        CoreDirection::Forward => {
-            for edge_ref in g.edges(node) {
-                result.push(edge_ref.target());
-            }
-        }
-        CoreDirection::Backward => {
-            for edge_ref in g.edges_directed(node, petgraph::Direction::Incoming) {
-                result.push(edge_ref.source());
-            }
-        }
-        CoreDirection::Both => {
-            for edge_ref in g.edges(node) {
-                result.push(edge_ref.target());
-            }
-            for edge_ref in g.edges_directed(node, petgraph::Direction::Incoming) {
-                result.push(edge_ref.source());
-            }
-        }
-    }
+    edge_filter: Option<&HashSet<EdgeTypeTag>>,
+) -> Result<Vec<String>, CliError> {
+    let reachable = reachable_within(graph, node_id, max_depth, direction, edge_filter)
+        .map_err(query_error_to_cli)?;
 
-    result
+    let mut node_ids: Vec<String> = reachable
+        .into_iter()
+        .filter_map(|idx| graph.node_weight(idx).map(|w| w.local_id.clone()))
+        .collect();
+    node_ids.sort();
+    Ok(node_ids)
 }
 
 /// Writes reachable node IDs in human-readable format (one per line).
@@ -156,7 +133,7 @@ fn print_human<W: std::io::Write>(w: &mut W, node_ids: &[String]) -> std::io::Re
 }
 
 /// Writes reachable node IDs as a JSON object.
-fn print_json<W: std::io::Write>(w: &mut W, node_ids: &[String]) -> std::io::Result<()> {
+pub(crate) fn print_json<W: std::io::Write>(w: &mut W, node_ids: &[String]) -> std::io::Result<()> {
     let ids_array: Vec<serde_json::Value> = node_ids
         .iter()
         .map(|s| serde_json::Value::String(s.clone()))
@@ -175,7 +152,7 @@ fn print_json<W: std::io::Write>(w: &mut W, node_ids: &[String]) -> std::io::Res
 }
 
 /// Converts a CLI [`Direction`] to the core [`CoreDirection`].
-fn to_core_direction(d: &Direction) -> CoreDirection {
+pub(crate) fn to_core_direction(d: &Direction) -> CoreDirection {
     match d {
         Direction::Outgoing => CoreDirection::Forward,
         Direction::Incoming => CoreDirection::Backward,
@@ -184,7 +161,7 @@ fn to_core_direction(d: &Direction) -> CoreDirection {
 }
 
 /// Converts a [`QueryError`] to the appropriate [`CliError`].
-fn query_error_to_cli(e: QueryError) -> CliError {
+pub(crate) fn query_error_to_cli(e: QueryError) -> CliError {
     match e {
         QueryError::NodeNotFound(id) => CliError::NodeNotFound { node_id: id },
         QueryError::EmptyResult => CliError::NoResults {
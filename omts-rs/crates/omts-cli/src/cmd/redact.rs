@@ -4,25 +4,52 @@
 //! disclosure scope, and writes the redacted output to stdout. Redaction
 //! statistics (nodes redacted, boundary refs generated) are written to stderr.
 //!
+//! Positional node IDs, the selector flags (`--node-type`, `--edge-type`,
+//! `--label`, `--identifier`, `--jurisdiction`, `--name`, `--where`), and
+//! `--expand <n>` with `--direction` choose the nodes to retain; every other
+//! retain-eligible node is replaced with a `boundary_ref` stub. Selection
+//! follows `omts subgraph`, except that expansion follows `--direction`.
+//!
+//! `--policy <FILE>` layers a redaction policy on top of the scope
+//! (redaction.md Section 7.1). The policy is read as TOML when the file name
+//! ends in `.toml` and as JSON otherwise.
+//!
+//! `--report <FILE>` writes an audit report of the redaction (redaction.md
+//! Section 7.2): every node retained, replaced (with its opaque value), or
+//! omitted, and every identifier, edge, and edge property removed, each with
+//! its reason. Human format prints one line per item and a summary line;
+//! `--format json` writes the report as a JSON object.
+//!
 //! Exit codes:
 //! - 0 = success
 //! - 1 = redaction error (scope less restrictive than existing `disclosure_scope`,
-//!        or the engine produces an invalid output)
-//! - 2 = parse/validation failure
+//!        or the engine produces an invalid output), a node ID not found, or
+//!        no selector matches
+//! - 2 = parse/validation failure, an unreadable or invalid policy file, or a
+//!        report write error
 use std::collections::HashSet;
 use std::io::Write as _;
+use std::path::Path;
 
-use omts_core::redact;
-use omts_core::{DisclosureScope as CoreScope, OmtsFile, enums::NodeType, enums::NodeTypeTag};
+use omts_core::graph::build_graph;
+use omts_core::newtypes::NodeId;
+use omts_core::{
+    DisclosureScope as CoreScope, DropReason, EdgeOmitReason, GeneralizeMethod, NodeAction,
+    NodeReason, OmtsFile, RedactOutput, RedactionPolicy, RedactionReport, Sensitivity,
+    enums::NodeType, enums::NodeTypeTag, redact_with_report,
+};
 
 use crate::DisclosureScope as CliScope;
-use crate::TargetEncoding;
+use crate::cmd::reach::to_core_direction;
+use crate::cmd::subgraph::{expand_ids, seed_ids};
 use crate::error::CliError;
+use crate::{Direction, OutputFormat, TargetEncoding};
 
 /// Runs the `redact` command.
 ///
 /// Checks that the target scope is at least as restrictive as the pre-parsed
-/// `file`'s existing `disclosure_scope`, then applies the redaction engine.
+/// `file`'s existing `disclosure_scope`, then applies the redaction engine,
+/// retaining the nodes in `retain_ids` (see [`retain_set`]).
 /// The redacted file is written to stdout in the requested encoding;
 /// statistics go to stderr.
 ///
@@ -30,12 +57,76 @@ use crate::error::CliError;
 ///
 /// - [`CliError::RedactionError`] — target scope is less restrictive than
 ///   the existing scope, or the engine produces an invalid output.
+/// - [`CliError::IoError`] — the policy file cannot be read, or the report
+///   cannot be written.
+/// - [`CliError::InvalidArgument`] — the policy file is not a valid policy.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     file: &OmtsFile,
     scope: &CliScope,
+    retain_ids: &HashSet<NodeId>,
+    policy: Option<&Path>,
+    report: Option<&Path>,
+    format: &OutputFormat,
     to: &TargetEncoding,
     compress: bool,
 ) -> Result<(), CliError> {
+    let policy = match policy {
+        Some(path) => load_policy(path)?,
+        None => RedactionPolicy::default(),
+    };
+    let stderr = std::io::stderr();
+    let redacted = redact_file(file, scope, retain_ids, &policy, &mut stderr.lock())?;
+
+    if let Some(path) = report {
+        let mut text = Vec::new();
+        match format {
+            OutputFormat::Human => write_report_human(&mut text, &redacted.report),
+            OutputFormat::Json => write_report_json(&mut text, &redacted.report),
+        }
+        .and_then(|()| std::fs::write(path, text))
+        .map_err(|e| CliError::IoError {
+            source: path.display().to_string(),
+            detail: e.to_string(),
+        })?;
+    }
+
+    let bytes = encode_output(&redacted.file, to, compress)?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    out.write_all(&bytes).map_err(|e| CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    })?;
+
+    // Append a trailing newline for uncompressed JSON so the shell prompt
+    // appears on a new line. Binary outputs must not have an appended newline.
+    if matches!(to, TargetEncoding::Json) && !compress {
+        out.write_all(b"\n").map_err(|e| CliError::IoError {
+            source: "stdout".to_owned(),
+            detail: e.to_string(),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Applies the redaction engine for `scope`, `retain_ids`, and `policy` and
+/// writes the statistics line to `log`.
+///
+/// Shared by [`run`] and `omts serve`.
+///
+/// # Errors
+///
+/// Same as [`run`], plus [`CliError::IoError`] if writing to `log` fails.
+pub(crate) fn redact_file<W: std::io::Write>(
+    file: &OmtsFile,
+    scope: &CliScope,
+    retain_ids: &HashSet<NodeId>,
+    policy: &RedactionPolicy,
+    log: &mut W,
+) -> Result<RedactOutput, CliError> {
     let target_core = cli_scope_to_core(scope);
     if let Some(existing) = &file.disclosure_scope {
         if scope_is_less_restrictive(&target_core, existing) {
@@ -55,17 +146,15 @@ pub fn run(
         .filter(|n| matches!(&n.node_type, NodeTypeTag::Known(NodeType::Person)))
         .count();
 
-    let retain_ids = HashSet::new();
-    let redacted =
-        redact(file, target_core, &retain_ids).map_err(|e| CliError::RedactionError {
+    let redacted = redact_with_report(file, target_core, retain_ids, policy).map_err(|e| {
+        CliError::RedactionError {
             detail: e.to_string(),
-        })?;
-
-    let stderr = std::io::stderr();
-    let mut err_out = stderr.lock();
+        }
+    })?;
 
-    let nodes_after = redacted.nodes.len();
+    let nodes_after = redacted.file.nodes.len();
     let boundary_refs = redacted
+        .file
         .nodes
         .iter()
         .filter(|n| matches!(&n.node_type, NodeTypeTag::Known(NodeType::BoundaryRef)))
@@ -73,7 +162,7 @@ pub fn run(
     let nodes_redacted = total_nodes_before.saturating_sub(nodes_after - boundary_refs);
 
     writeln!(
-        err_out,
+        log,
         "redaction complete: scope={scope:?}, nodes_before={total_nodes_before}, \
          nodes_redacted={nodes_redacted}, person_nodes_dropped={person_nodes_before}, \
          boundary_refs={boundary_refs}"
@@ -83,32 +172,249 @@ pub fn run(
         detail: e.to_string(),
     })?;
 
-    let bytes = encode_output(&redacted, to, compress)?;
+    Ok(redacted)
+}
 
-    let stdout = std::io::stdout();
-    let mut out = stdout.lock();
-    out.write_all(&bytes).map_err(|e| CliError::IoError {
-        source: "stdout".to_owned(),
+/// Collects the IDs of the nodes to retain.
+///
+/// Seeds are the explicit `node_ids` plus the nodes matched by the selector
+/// flags, as for `omts subgraph`; with `expand > 0`, nodes within `expand`
+/// hops of a seed along `direction` are added. Returns an empty set (every
+/// eligible node replaced) when no node IDs or selectors are given.
+///
+/// # Errors
+///
+/// - [`CliError::GraphBuildError`] — the graph cannot be built.
+/// - [`CliError::NodeNotFound`] — an explicit node ID is not in the file.
+/// - [`CliError::NoResults`] — the selectors matched nothing.
+/// - [`CliError::InvalidArgument`] — a selector flag is malformed.
+#[allow(clippy::too_many_arguments)]
+pub fn retain_set(
+    file: &OmtsFile,
+    node_ids: &[String],
+    node_types: &[String],
+    edge_types: &[String],
+    labels: &[String],
+    identifiers: &[String],
+    jurisdictions: &[String],
+    names: &[String],
+    where_expr: Option<&str>,
+    expand: u32,
+    direction: &Direction,
+) -> Result<HashSet<NodeId>, CliError> {
+    let has_selection = !node_ids.is_empty()
+        || !node_types.is_empty()
+        || !edge_types.is_empty()
+        || !labels.is_empty()
+        || !identifiers.is_empty()
+        || !jurisdictions.is_empty()
+        || !names.is_empty()
+        || where_expr.is_some();
+    if !has_selection {
+        return Ok(HashSet::new());
+    }
+
+    let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
         detail: e.to_string(),
     })?;
+    let seeds = seed_ids(
+        file,
+        &graph,
+        node_ids,
+        node_types,
+        edge_types,
+        labels,
+        identifiers,
+        jurisdictions,
+        names,
+        where_expr,
+    )?;
+    let ids = if expand == 0 {
+        seeds
+    } else {
+        expand_ids(&graph, file, &seeds, expand, to_core_direction(direction))?
+    };
+
+    Ok(file
+        .nodes
+        .iter()
+        .filter(|node| ids.contains(&node.id.to_string()))
+        .map(|node| node.id.clone())
+        .collect())
+}
 
-    // Append a trailing newline for uncompressed JSON so the shell prompt
-    // appears on a new line. Binary outputs must not have an appended newline.
-    if matches!(to, TargetEncoding::Json) && !compress {
-        out.write_all(b"\n").map_err(|e| CliError::IoError {
-            source: "stdout".to_owned(),
-            detail: e.to_string(),
-        })?;
+/// Writes the audit report as one line per node and removal, then a summary
+/// line.
+fn write_report_human<W: std::io::Write>(
+    w: &mut W,
+    report: &RedactionReport,
+) -> std::io::Result<()> {
+    for node in &report.nodes {
+        let reason = node_reason_text(node.reason);
+        let (id, node_type) = (&node.id, node.node_type.as_str());
+        match (&node.action, &node.opaque_value) {
+            (NodeAction::Retain, _) => writeln!(w, "retained {id} ({node_type}): {reason}")?,
+            (NodeAction::Replace, Some(opaque)) => writeln!(
+                w,
+                "replaced {id} ({node_type}) with boundary_ref {opaque}: {reason}"
+            )?,
+            (NodeAction::Replace, None) => writeln!(w, "replaced {id} ({node_type}): {reason}")?,
+            (NodeAction::Omit, _) => writeln!(w, "omitted {id} ({node_type}): {reason}")?,
+        }
+    }
+    for dropped in &report.identifiers_dropped {
+        writeln!(
+            w,
+            "dropped identifier {}:{} from {}: {}",
+            dropped.scheme,
+            dropped.value,
+            dropped.node_id,
+            drop_reason_text(&dropped.reason, &report.scope)
+        )?;
+    }
+    for edge in &report.edges_omitted {
+        let reason = match edge.reason {
+            EdgeOmitReason::BeneficialOwnership => "beneficial_ownership edges are public-omitted",
+            EdgeOmitReason::EndpointOmitted => "an endpoint was omitted",
+            EdgeOmitReason::BothEndpointsReplaced => "both endpoints were replaced",
+        };
+        writeln!(
+            w,
+            "omitted edge {} ({} {} -> {}): {reason}",
+            edge.id,
+            edge.edge_type.as_str(),
+            edge.source,
+            edge.target
+        )?;
+    }
+    for stripped in &report.properties_stripped {
+        writeln!(
+            w,
+            "stripped {} from edge {}: {}",
+            stripped.property,
+            stripped.edge_id,
+            drop_reason_text(&stripped.reason, &report.scope)
+        )?;
     }
+    for generalized in &report.properties_generalized {
+        writeln!(
+            w,
+            "generalised {} on edge {}: {}",
+            generalized.property,
+            generalized.edge_id,
+            method_text(&generalized.method)
+        )?;
+    }
+    for generalized in &report.geo_generalized {
+        let verb = if generalized.removed {
+            "removed unreadable"
+        } else {
+            "generalised"
+        };
+        writeln!(
+            w,
+            "{verb} geo on node {}: {}",
+            generalized.node_id,
+            method_text(&generalized.method)
+        )?;
+    }
+    writeln!(
+        w,
+        "report: scope={}, {} retained, {} replaced, {} omitted, {} identifier(s) dropped, \
+         {} edge(s) omitted, {} edge propert(ies) stripped, {} value(s) generalised",
+        scope_text(&report.scope),
+        report.count(&NodeAction::Retain),
+        report.count(&NodeAction::Replace),
+        report.count(&NodeAction::Omit),
+        report.identifiers_dropped.len(),
+        report.edges_omitted.len(),
+        report.properties_stripped.len(),
+        report.properties_generalized.len() + report.geo_generalized.len()
+    )
+}
 
-    Ok(())
+/// Writes the audit report as a pretty-printed JSON object.
+fn write_report_json<W: std::io::Write>(
+    w: &mut W,
+    report: &RedactionReport,
+) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(report).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+fn node_reason_text(reason: NodeReason) -> &'static str {
+    match reason {
+        NodeReason::Scope => "decided by the scope",
+        NodeReason::Policy => "policy rule",
+        NodeReason::RetainSet => "in the retain set",
+        NodeReason::BoundaryRef => "already a boundary_ref",
+        NodeReason::NotRetained => "not in the retain set",
+    }
+}
+
+fn drop_reason_text(reason: &DropReason, scope: &CoreScope) -> String {
+    match reason {
+        DropReason::Sensitivity { sensitivity } => {
+            let level = match sensitivity {
+                Sensitivity::Public => "public",
+                Sensitivity::Restricted => "restricted",
+                Sensitivity::Confidential => "confidential",
+            };
+            format!("sensitivity {level} exceeds {} scope", scope_text(scope))
+        }
+        DropReason::Policy => "policy rule".to_owned(),
+        DropReason::PublicScope => "removed at public scope".to_owned(),
+    }
+}
+
+fn method_text(method: &GeneralizeMethod) -> String {
+    match method {
+        GeneralizeMethod::Bands { bands } => {
+            let cuts: Vec<String> = bands.iter().map(f64::to_string).collect();
+            format!("bands {}", cuts.join(", "))
+        }
+        GeneralizeMethod::Magnitude => "order of magnitude".to_owned(),
+        GeneralizeMethod::Round { decimals } => format!("rounded to {decimals} decimal(s)"),
+        GeneralizeMethod::Centroid => "centroid".to_owned(),
+        GeneralizeMethod::Bbox => "bounding box".to_owned(),
+    }
+}
+
+fn scope_text(scope: &CoreScope) -> &'static str {
+    match scope {
+        CoreScope::Internal => "internal",
+        CoreScope::Partner => "partner",
+        CoreScope::Public => "public",
+    }
+}
+
+/// Reads a redaction policy: TOML for a `.toml` file, JSON otherwise.
+///
+/// # Errors
+///
+/// - [`CliError::IoError`] — the file cannot be read.
+/// - [`CliError::InvalidArgument`] — the file does not parse as a policy.
+pub(crate) fn load_policy(path: &Path) -> Result<RedactionPolicy, CliError> {
+    let bytes = std::fs::read(path).map_err(|e| CliError::IoError {
+        source: path.display().to_string(),
+        detail: e.to_string(),
+    })?;
+    let invalid = |e: &dyn std::fmt::Display| CliError::InvalidArgument {
+        detail: format!("invalid redaction policy {}: {e}", path.display()),
+    };
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        let text = std::str::from_utf8(&bytes).map_err(|e| invalid(&e))?;
+        toml::from_str(text).map_err(|e| invalid(&e))
+    } else {
+        serde_json::from_slice(&bytes).map_err(|e| invalid(&e))
+    }
 }
 
 /// Serializes `file` to the requested encoding, optionally compressing with zstd.
 ///
 /// Pretty-printed JSON is the default for `--to json`. CBOR uses the
 /// self-describing tag 55799 prepended per SPEC-007 Section 4.1.
-fn encode_output(
+pub(crate) fn encode_output(
     file: &OmtsFile,
     to: &TargetEncoding,
     compress: bool,
@@ -135,7 +441,7 @@ fn encode_output(
 }
 
 /// Converts a CLI [`crate::DisclosureScope`] to the core library [`CoreScope`].
-fn cli_scope_to_core(scope: &CliScope) -> CoreScope {
+pub(crate) fn cli_scope_to_core(scope: &CliScope) -> CoreScope {
     match scope {
         CliScope::Public => CoreScope::Public,
         CliScope::Partner => CoreScope::Partner,
@@ -275,7 +581,16 @@ mod tests {
     #[test]
     fn run_less_restrictive_scope_returns_error() {
         let file = parse(ALREADY_PUBLIC);
-        let result = run(&file, &CliScope::Partner, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Partner,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         match result {
             Err(CliError::RedactionError { .. }) => {}
             other => panic!("expected RedactionError, got {other:?}"),
@@ -285,7 +600,16 @@ mod tests {
     #[test]
     fn run_less_restrictive_scope_exit_code_is_1() {
         let file = parse(ALREADY_PUBLIC);
-        let result = run(&file, &CliScope::Partner, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Partner,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         let err = result.expect_err("should fail");
         assert_eq!(err.exit_code(), 1);
     }
@@ -294,7 +618,16 @@ mod tests {
     #[test]
     fn run_partner_to_internal_returns_error() {
         let file = parse(ALREADY_PARTNER);
-        let result = run(&file, &CliScope::Internal, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Internal,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         match result {
             Err(CliError::RedactionError { .. }) => {}
             other => panic!("expected RedactionError, got {other:?}"),
@@ -305,7 +638,16 @@ mod tests {
     #[test]
     fn run_same_scope_is_ok() {
         let file = parse(ALREADY_PUBLIC);
-        let result = run(&file, &CliScope::Public, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Public,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         assert!(result.is_ok(), "same scope should succeed: {result:?}");
     }
 
@@ -313,7 +655,16 @@ mod tests {
     #[test]
     fn run_minimal_to_public_succeeds() {
         let file = parse(MINIMAL);
-        let result = run(&file, &CliScope::Public, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Public,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         assert!(result.is_ok(), "expected Ok for minimal file: {result:?}");
     }
 
@@ -321,7 +672,16 @@ mod tests {
     #[test]
     fn run_minimal_to_partner_succeeds() {
         let file = parse(MINIMAL);
-        let result = run(&file, &CliScope::Partner, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Partner,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         assert!(result.is_ok(), "expected Ok for minimal file: {result:?}");
     }
 
@@ -329,7 +689,16 @@ mod tests {
     #[test]
     fn run_minimal_to_internal_succeeds() {
         let file = parse(MINIMAL);
-        let result = run(&file, &CliScope::Internal, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Internal,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         assert!(result.is_ok(), "expected Ok for minimal file: {result:?}");
     }
 
@@ -348,7 +717,16 @@ mod tests {
             "edges": []
         }"#;
         let file = parse(content);
-        let result = run(&file, &CliScope::Public, &TargetEncoding::Json, false);
+        let result = run(
+            &file,
+            &CliScope::Public,
+            &HashSet::new(),
+            None,
+            None,
+            &OutputFormat::Human,
+            &TargetEncoding::Json,
+            false,
+        );
         assert!(result.is_ok(), "expected Ok: {result:?}");
     }
 
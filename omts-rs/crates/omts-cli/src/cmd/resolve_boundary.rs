@@ -0,0 +1,109 @@
+//! Implementation of `omts resolve-boundary <file> <candidates>`.
+//!
+//! Maps the `boundary_ref` nodes of a redacted file back to known nodes. The
+//! candidates file is any `.omts` file holding nodes the caller already knows:
+//! the unredacted original, or an export of the caller's own master data.
+//! Each candidate's public identifiers are hashed with the redacted file's
+//! salt ([`omts_core::resolve_boundary_refs`], redaction.md Section 7.3) and
+//! compared with the opaque values.
+//!
+//! Output:
+//! - Human: one line per `boundary_ref` (`<id> -> <candidate>` for each
+//!   match, `<id>: unresolved` otherwise), followed by a summary line.
+//! - JSON: `{ "boundary_refs", "resolved", "resolutions": [...] }`.
+//!
+//! Exit codes: 0 = at least one `boundary_ref` resolved, 1 = none resolved
+//! (or the file has no `boundary_ref` nodes), 2 = parse failure.
+use omts_core::{BoundaryResolution, OmtsFile, resolve_boundary_refs};
+
+use crate::OutputFormat;
+use crate::error::CliError;
+
+/// Runs the `resolve-boundary` command.
+///
+/// # Errors
+///
+/// - [`CliError`] exit code 1 if no `boundary_ref` node matches a candidate.
+/// - [`CliError`] exit code 2 if the file salt cannot be decoded or a stdout
+///   write fails.
+pub fn run(
+    redacted: &OmtsFile,
+    candidates: &OmtsFile,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let resolutions = resolve_boundary_refs(redacted, &candidates.nodes).map_err(|e| {
+        CliError::InvalidArgument {
+            detail: e.to_string(),
+        }
+    })?;
+    if resolutions.is_empty() {
+        return Err(CliError::NoResults {
+            detail: "the file has no boundary_ref nodes".to_owned(),
+        });
+    }
+    if resolutions.iter().all(|r| r.matches.is_empty()) {
+        return Err(CliError::NoResults {
+            detail: format!(
+                "none of {} boundary_ref nodes matched a candidate",
+                resolutions.len()
+            ),
+        });
+    }
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        OutputFormat::Human => print_human(&mut out, &resolutions),
+        OutputFormat::Json => print_json(&mut out, &resolutions),
+    }
+    .map_err(|e| CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    })
+}
+
+/// Writes one line per match or unresolved reference, then a summary line.
+fn print_human<W: std::io::Write>(
+    w: &mut W,
+    resolutions: &[BoundaryResolution],
+) -> std::io::Result<()> {
+    for resolution in resolutions {
+        if resolution.matches.is_empty() {
+            writeln!(w, "{}: unresolved", resolution.node_id)?;
+        }
+        for m in &resolution.matches {
+            let kind = if m.complete { "complete" } else { "partial" };
+            writeln!(
+                w,
+                "{} -> {} ({kind}: {})",
+                resolution.node_id,
+                m.candidate_id,
+                m.identifiers.join(", ")
+            )?;
+        }
+    }
+    writeln!(
+        w,
+        "{} of {} boundary refs resolved",
+        resolved_count(resolutions),
+        resolutions.len()
+    )
+}
+
+/// Writes the resolutions as a JSON object with summary counts.
+fn print_json<W: std::io::Write>(
+    w: &mut W,
+    resolutions: &[BoundaryResolution],
+) -> std::io::Result<()> {
+    let value = serde_json::json!({
+        "boundary_refs": resolutions.len(),
+        "resolved": resolved_count(resolutions),
+        "resolutions": resolutions,
+    });
+    let json = serde_json::to_string_pretty(&value).map_err(std::io::Error::other)?;
+    writeln!(w, "{json}")
+}
+
+fn resolved_count(resolutions: &[BoundaryResolution]) -> usize {
+    resolutions.iter().filter(|r| !r.matches.is_empty()).count()
+}
@@ -0,0 +1,181 @@
+//! Implementation of `omts segment <file>`.
+//!
+//! Parses an `.omts` file, builds the directed graph, and clusters the supply
+//! subgraph into segments using Louvain community detection
+//! ([`omts_core::louvain`]).
+//!
+//! Flags:
+//! - `--edge-type <type>` (repeatable): edge types to cluster over. Defaults
+//!   to the supply relationships (`supplies`, `subcontracts`, `tolls`,
+//!   `distributes`, `brokers`, `sells_to`).
+//! - `--weight <uniform|annual-value|volume>`: edge weight property.
+//! - `--write-labels`: instead of printing the mapping, emit the input file
+//!   with a `<label-key>=<segment>` label on every clustered node.
+//! - `--label-key <key>` (default `omts.segment`), `--to`, `--compress`: only
+//!   valid with `--write-labels`.
+//!
+//! Output (mapping mode):
+//! - Human: one block per segment, largest first, followed by the modularity.
+//! - JSON: `{ "segment_count", "modularity", "levels", "segments": [...] }`.
+//!
+//! Exit codes: 0 = success, 1 = no edges of the selected types, 2 = parse or
+//! graph build failure.
+use std::collections::HashSet;
+use std::io::Write as _;
+
+use omts_core::{
+    EdgeTypeTag, EdgeWeighting, OmtsFile, Segmentation, apply_segment_labels, build_graph, louvain,
+    supply_edge_types,
+};
+
+use crate::cmd::selectors::parse_edge_type_tag;
+use crate::error::CliError;
+use crate::{OutputFormat, SegmentWeight, TargetEncoding};
+
+/// Runs the `segment` command.
+///
+/// # Errors
+///
+/// - [`CliError`] exit code 1 if the selected edge types yield no edges.
+/// - [`CliError`] exit code 2 if the graph cannot be built, serialization
+///   fails, or a stdout write fails.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    file: &OmtsFile,
+    edge_types: &[String],
+    weight: SegmentWeight,
+    write_labels: bool,
+    label_key: &str,
+    to: &TargetEncoding,
+    compress: bool,
+    format: &OutputFormat,
+) -> Result<(), CliError> {
+    let edge_filter: HashSet<EdgeTypeTag> = if edge_types.is_empty() {
+        supply_edge_types()
+    } else {
+        edge_types
+            .iter()
+            .map(|s| parse_edge_type_tag(s))
+            .collect::<Result<_, _>>()?
+    };
+
+    let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
+        detail: e.to_string(),
+    })?;
+
+    let segmentation = louvain(&graph, file, Some(&edge_filter), to_core_weighting(weight));
+    if segmentation.segments.is_empty() {
+        return Err(CliError::NoResults {
+            detail: "no edges of the selected types to segment".to_owned(),
+        });
+    }
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    if write_labels {
+        let labelled = apply_segment_labels(file, &segmentation, label_key);
+        let bytes = encode_output(&labelled, to, compress)?;
+        out.write_all(&bytes).map_err(|e| stdout_error(&e))?;
+        // Binary outputs must not have an appended newline.
+        if matches!(to, TargetEncoding::Json) && !compress {
+            out.write_all(b"\n").map_err(|e| stdout_error(&e))?;
+        }
+        return Ok(());
+    }
+
+    match format {
+        OutputFormat::Human => print_human(&mut out, &segmentation),
+        OutputFormat::Json => print_json(&mut out, &segmentation),
+    }
+    .map_err(|e| stdout_error(&e))
+}
+
+/// Maps the CLI weight option to the core [`EdgeWeighting`].
+fn to_core_weighting(weight: SegmentWeight) -> EdgeWeighting {
+    match weight {
+        SegmentWeight::Uniform => EdgeWeighting::Uniform,
+        SegmentWeight::AnnualValue => EdgeWeighting::AnnualValue,
+        SegmentWeight::Volume => EdgeWeighting::Volume,
+    }
+}
+
+/// Writes the segment mapping in human-readable form.
+fn print_human<W: std::io::Write>(w: &mut W, seg: &Segmentation) -> std::io::Result<()> {
+    for segment in &seg.segments {
+        writeln!(
+            w,
+            "segment {} ({} nodes):",
+            segment.id,
+            segment.members.len()
+        )?;
+        for member in &segment.members {
+            writeln!(w, "  {member}")?;
+        }
+    }
+    writeln!(
+        w,
+        "{} segments, modularity {:.4}",
+        seg.segments.len(),
+        seg.modularity
+    )
+}
+
+/// Writes the segment mapping as a single JSON object.
+fn print_json<W: std::io::Write>(w: &mut W, seg: &Segmentation) -> std::io::Result<()> {
+    let segments: Vec<serde_json::Value> = seg
+        .segments
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "segment": s.id,
+                "size": s.members.len(),
+                "members": s.members,
+            })
+        })
+        .collect();
+    let value = serde_json::json!({
+        "segment_count": seg.segments.len(),
+        "modularity": seg.modularity,
+        "levels": seg.levels,
+        "segments": segments,
+    });
+    let json = serde_json::to_string_pretty(&value)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    writeln!(w, "{json}")
+}
+
+/// Serializes `file` to the requested encoding, optionally compressing with zstd.
+fn encode_output(
+    file: &OmtsFile,
+    to: &TargetEncoding,
+    compress: bool,
+) -> Result<Vec<u8>, CliError> {
+    match to {
+        TargetEncoding::Cbor => omts_core::convert(file, omts_core::Encoding::Cbor, compress)
+            .map_err(|e| CliError::InternalError {
+                detail: e.to_string(),
+            }),
+        TargetEncoding::Json => {
+            let json_bytes =
+                serde_json::to_vec_pretty(file).map_err(|e| CliError::InternalError {
+                    detail: format!("JSON serialization of labelled output failed: {e}"),
+                })?;
+            if compress {
+                omts_core::compress_zstd(&json_bytes).map_err(|e| CliError::InternalError {
+                    detail: format!("zstd compression failed: {e}"),
+                })
+            } else {
+                Ok(json_bytes)
+            }
+        }
+    }
+}
+
+/// Wraps a stdout write failure.
+fn stdout_error(e: &std::io::Error) -> CliError {
+    CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    }
+}
@@ -1,4 +1,5 @@
-//! Shared selector flag parsing for `query` and `subgraph` commands.
+//! Shared selector flag parsing for `query`, `subgraph`, and `diff` commands,
+//! and `--edge-type` traversal filters for `reach` and `path`.
 //!
 //! Provides [`build_selector_set`], which converts the raw string vectors
 //! collected by clap into a `SelectorSet` ready for use with
@@ -18,17 +19,21 @@
 //! | `--edge-type`    | `supplies`          | `Selector::EdgeType(EdgeTypeTag::Known(…))` |
 //! | `--jurisdiction` | `DE`                | `Selector::Jurisdiction(CountryCode)` |
 //! | `--name`         | `acme`              | `Selector::Name("acme")`             |
+//! | `--where`        | `type=good AND NOT label:eol` | `SelectorSet::expr` (see [`SelectorExpr`]) |
 
-use omts_core::graph::selectors::{Selector, SelectorSet};
+use std::collections::HashSet;
+
+use omts_core::graph::selectors::{Selector, SelectorExpr, SelectorSet};
 use omts_core::{CountryCode, EdgeTypeTag, NodeTypeTag};
 
 use crate::error::CliError;
 
 /// Builds a `SelectorSet` from the raw flag vectors collected by clap.
 ///
-/// Each argument vector corresponds to one repeatable CLI flag. The function
-/// returns an error if the resulting `SelectorSet` would be empty (i.e. no
-/// selector flags were provided at all).
+/// Each argument vector corresponds to one repeatable CLI flag; `where_expr`
+/// is the optional `--where` expression, combined with the flags by AND. The
+/// function returns an error if the resulting `SelectorSet` would be empty
+/// (i.e. no selector flags were provided at all).
 ///
 /// # Errors
 ///
@@ -36,6 +41,7 @@ use crate::error::CliError;
 /// - No selector flags were provided (empty result would be a universal match,
 ///   which is not the intended behaviour for an explicit query command).
 /// - A `--jurisdiction` value is not a valid ISO 3166-1 alpha-2 country code.
+/// - The `--where` expression does not parse.
 pub fn build_selector_set(
     node_types: &[String],
     edge_types: &[String],
@@ -43,6 +49,7 @@ pub fn build_selector_set(
     identifiers: &[String],
     jurisdictions: &[String],
     names: &[String],
+    where_expr: Option<&str>,
 ) -> Result<SelectorSet, CliError> {
     let mut selectors: Vec<Selector> = Vec::new();
 
@@ -86,12 +93,14 @@ pub fn build_selector_set(
         selectors.push(Selector::Name(s.clone()));
     }
 
-    let set = SelectorSet::from_selectors(selectors);
+    let mut set = SelectorSet::from_selectors(selectors);
+    set.expr = where_expr.map(parse_where).transpose()?;
 
     if set.is_empty() {
         return Err(CliError::InvalidArgument {
             detail: "at least one selector flag is required \
-                     (--node-type, --edge-type, --label, --identifier, --jurisdiction, --name)"
+                     (--node-type, --edge-type, --label, --identifier, --jurisdiction, --name, \
+                     --where)"
                 .to_owned(),
         });
     }
@@ -99,6 +108,17 @@ pub fn build_selector_set(
     Ok(set)
 }
 
+/// Parses a `--where` expression into a [`SelectorExpr`].
+///
+/// # Errors
+///
+/// Returns [`CliError`] with exit code 2 if the expression is malformed.
+pub fn parse_where(s: &str) -> Result<SelectorExpr, CliError> {
+    SelectorExpr::parse(s).map_err(|e| CliError::InvalidArgument {
+        detail: format!("--where {s:?}: {e}"),
+    })
+}
+
 /// Parses a string into a [`NodeTypeTag`] using serde deserialization.
 ///
 /// Known `snake_case` node type strings (e.g. `"organization"`, `"facility"`)
@@ -123,7 +143,7 @@ fn parse_node_type_tag(s: &str) -> Result<NodeTypeTag, CliError> {
 ///
 /// Because `EdgeTypeTag`'s serde impl always succeeds (unknown strings become
 /// `Extension`), this function is infallible in practice.
-fn parse_edge_type_tag(s: &str) -> Result<EdgeTypeTag, CliError> {
+pub fn parse_edge_type_tag(s: &str) -> Result<EdgeTypeTag, CliError> {
     serde_json::from_value(serde_json::Value::String(s.to_owned())).map_err(|e| {
         CliError::InvalidArgument {
             detail: format!("--edge-type {s:?}: {e}"),
@@ -131,6 +151,21 @@ fn parse_edge_type_tag(s: &str) -> Result<EdgeTypeTag, CliError> {
     })
 }
 
+/// Parses repeatable `--edge-type` traversal flags into an edge filter.
+///
+/// Returns `None` when no types were given, meaning every edge type is
+/// traversed.
+pub fn parse_edge_filter(values: &[String]) -> Result<Option<HashSet<EdgeTypeTag>>, CliError> {
+    if values.is_empty() {
+        return Ok(None);
+    }
+    values
+        .iter()
+        .map(|s| parse_edge_type_tag(s))
+        .collect::<Result<HashSet<_>, _>>()
+        .map(Some)
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
@@ -151,8 +186,16 @@ mod tests {
     /// No selector flags → error with exit code 2.
     #[test]
     fn test_empty_selectors_returns_error() {
-        let err = build_selector_set(&empty(), &empty(), &empty(), &empty(), &empty(), &empty())
-            .expect_err("should error on empty selector set");
+        let err = build_selector_set(
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            None,
+        )
+        .expect_err("should error on empty selector set");
         assert_eq!(err.exit_code(), 2);
         assert!(
             err.message().contains("selector"),
@@ -171,6 +214,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse --label key");
         assert_eq!(ss.label_keys, vec!["certified"]);
@@ -187,6 +231,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse --label key=value");
         assert!(ss.label_keys.is_empty());
@@ -206,6 +251,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse");
         assert_eq!(
@@ -224,6 +270,7 @@ mod tests {
             &strs(&["lei"]),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse --identifier scheme");
         assert_eq!(ss.identifier_schemes, vec!["lei"]);
@@ -240,6 +287,7 @@ mod tests {
             &strs(&["duns:123456789"]),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse --identifier scheme:value");
         assert!(ss.identifier_schemes.is_empty());
@@ -259,6 +307,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse organization");
         assert_eq!(
@@ -277,6 +326,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse extension node type");
         assert_eq!(
@@ -304,6 +354,7 @@ mod tests {
                 &empty(),
                 &empty(),
                 &empty(),
+                None,
             )
             .unwrap_or_else(|e| panic!("--node-type {type_str} should parse: {e}"));
         }
@@ -319,6 +370,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse supplies");
         assert_eq!(ss.edge_types, vec![EdgeTypeTag::Known(EdgeType::Supplies)]);
@@ -334,6 +386,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should parse extension edge type");
         assert_eq!(
@@ -352,6 +405,7 @@ mod tests {
             &empty(),
             &strs(&["DE"]),
             &empty(),
+            None,
         )
         .expect("should parse DE");
         assert_eq!(ss.jurisdictions.len(), 1);
@@ -368,6 +422,7 @@ mod tests {
             &empty(),
             &strs(&["123"]),
             &empty(),
+            None,
         )
         .expect_err("123 is not a valid country code");
         assert_eq!(err.exit_code(), 2);
@@ -383,6 +438,7 @@ mod tests {
             &empty(),
             &empty(),
             &strs(&["acme"]),
+            None,
         )
         .expect("should parse --name");
         assert_eq!(ss.names, vec!["acme"]);
@@ -398,6 +454,7 @@ mod tests {
             &empty(),
             &strs(&["US"]),
             &empty(),
+            None,
         )
         .expect("should combine multiple selector types");
         assert_eq!(ss.node_types.len(), 1);
@@ -415,11 +472,51 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("should accept multiple --node-type values");
         assert_eq!(ss.node_types.len(), 2);
     }
 
+    /// `--where` alone produces a non-empty set carrying the expression.
+    #[test]
+    fn test_where_only() {
+        let ss = build_selector_set(
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            Some("type=organization AND NOT label:risk.cleared"),
+        )
+        .expect("should parse --where");
+        assert!(!ss.is_empty());
+        assert!(ss.expr.is_some());
+        assert!(ss.node_types.is_empty());
+    }
+
+    /// A malformed `--where` expression returns error with exit code 2.
+    #[test]
+    fn test_where_invalid() {
+        let err = build_selector_set(
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            &empty(),
+            Some("type=organization AND ("),
+        )
+        .expect_err("unbalanced parenthesis");
+        assert_eq!(err.exit_code(), 2);
+        assert!(
+            err.message().contains("--where"),
+            "message should mention --where: {}",
+            err.message()
+        );
+    }
+
     /// The returned `SelectorSet` is never empty (function errors before that).
     #[test]
     fn test_non_empty_selector_set_is_never_empty() {
@@ -430,6 +527,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
         )
         .expect("valid selector");
         assert!(!ss.is_empty());
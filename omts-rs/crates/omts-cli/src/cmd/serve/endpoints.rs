@@ -0,0 +1,364 @@
+//! Endpoint handlers for `omts serve`.
+//!
+//! Each endpoint mirrors one subcommand run with `--format json`: it takes
+//! the same flags as query parameters and returns the bytes that command
+//! would write to stdout (for `validate`, the NDJSON it writes to stderr).
+use std::collections::HashSet;
+
+use omts_core::graph::selector_match;
+use omts_core::{MergeConfig, OmtsFile, RedactionPolicy, diff_filtered, validate};
+
+use super::Reply;
+use super::params::Params;
+use super::workspace::Input;
+use crate::cmd::selectors::{build_selector_set, parse_edge_filter};
+use crate::cmd::{diff, merge, path, query, reach, redact, subgraph, validate as validate_cmd};
+use crate::error::CliError;
+use crate::format::{FormatMode, FormatterConfig, write_diagnostic, write_summary};
+use crate::{Direction, DisclosureScope, MergeStrategy, PathCost, TargetEncoding};
+
+/// Selector parameters shared by `/query` and `/subgraph`.
+const SELECTOR_PARAMS: &[&str] = &[
+    "node-type",
+    "edge-type",
+    "label",
+    "identifier",
+    "jurisdiction",
+    "name",
+    "where",
+];
+
+/// The served endpoints.
+#[derive(Clone, Copy)]
+pub(crate) enum Endpoint {
+    Validate,
+    Query,
+    Reach,
+    Path,
+    Subgraph,
+    Diff,
+    Merge,
+    Redact,
+}
+
+impl Endpoint {
+    /// Every endpoint, in the order they are listed to clients.
+    pub(crate) const ALL: [Endpoint; 8] = [
+        Endpoint::Validate,
+        Endpoint::Query,
+        Endpoint::Reach,
+        Endpoint::Path,
+        Endpoint::Subgraph,
+        Endpoint::Diff,
+        Endpoint::Merge,
+        Endpoint::Redact,
+    ];
+
+    /// Looks up the endpoint served at `path`.
+    pub(crate) fn from_path(path: &str) -> Option<Endpoint> {
+        Endpoint::ALL.into_iter().find(|e| e.path() == path)
+    }
+
+    /// The URL path of this endpoint.
+    pub(crate) fn path(self) -> &'static str {
+        match self {
+            Endpoint::Validate => "/validate",
+            Endpoint::Query => "/query",
+            Endpoint::Reach => "/reach",
+            Endpoint::Path => "/path",
+            Endpoint::Subgraph => "/subgraph",
+            Endpoint::Diff => "/diff",
+            Endpoint::Merge => "/merge",
+            Endpoint::Redact => "/redact",
+        }
+    }
+
+    /// Query parameters accepted besides `file`.
+    fn params(self) -> Vec<&'static str> {
+        let own: &[&str] = match self {
+            Endpoint::Validate => &["level"],
+            Endpoint::Query => &["count"],
+            Endpoint::Reach => &["node", "depth", "direction", "edge-type"],
+            Endpoint::Path => &[
+                "from",
+                "to",
+                "max-paths",
+                "max-depth",
+                "cost",
+                "edge-cost",
+                "pattern",
+                "edge-type",
+            ],
+            Endpoint::Subgraph => &["node", "expand"],
+            Endpoint::Diff => &["node-type", "edge-type", "ignore-field", "where"],
+            Endpoint::Merge => &["strategy"],
+            Endpoint::Redact => &["scope"],
+        };
+        let mut known = vec!["file"];
+        if matches!(self, Endpoint::Query | Endpoint::Subgraph) {
+            known.extend_from_slice(SELECTOR_PARAMS);
+        }
+        known.extend_from_slice(own);
+        known
+    }
+
+    /// Runs the endpoint on `inputs`. Messages the command would print to
+    /// stderr (merge warnings, redaction statistics) are written to `log`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`CliError`] the matching command would exit with.
+    pub(crate) fn run(
+        self,
+        inputs: &[Input<'_>],
+        params: &Params,
+        log: &mut Vec<u8>,
+    ) -> Result<Reply, CliError> {
+        params.check_known(self.path(), &self.params())?;
+        match self {
+            Endpoint::Validate => validate_file(&single(self, inputs)?.file, params),
+            Endpoint::Query => run_query(&single(self, inputs)?.file, params),
+            Endpoint::Reach => run_reach(single(self, inputs)?, params),
+            Endpoint::Path => run_path(single(self, inputs)?, params),
+            Endpoint::Subgraph => run_subgraph(single(self, inputs)?, params),
+            Endpoint::Diff => match inputs {
+                [a, b] => run_diff(&a.file, &b.file, params),
+                _ => Err(arity_error(self, "2 input files", inputs.len())),
+            },
+            Endpoint::Merge => {
+                if inputs.len() < 2 {
+                    return Err(arity_error(self, "at least 2 input files", inputs.len()));
+                }
+                run_merge(inputs, params, log)
+            }
+            Endpoint::Redact => run_redact(&single(self, inputs)?.file, params, log),
+        }
+    }
+}
+
+/// Returns the only input of a single-file endpoint.
+fn single<'a>(endpoint: Endpoint, inputs: &'a [Input<'a>]) -> Result<&'a Input<'a>, CliError> {
+    match inputs {
+        [input] => Ok(input),
+        _ => Err(arity_error(endpoint, "1 input file", inputs.len())),
+    }
+}
+
+fn arity_error(endpoint: Endpoint, expected: &str, got: usize) -> CliError {
+    CliError::InvalidArgument {
+        detail: format!(
+            "{} takes {expected}, got {got}; select served files with file=<name> \
+             or POST an .omts document",
+            endpoint.path()
+        ),
+    }
+}
+
+/// `/validate?level=N`: NDJSON diagnostics followed by the summary object.
+fn validate_file(file: &OmtsFile, params: &Params) -> Result<Reply, CliError> {
+    let level = params.number::<u8>("level")?.unwrap_or(2);
+    if !(1..=3).contains(&level) {
+        return Err(CliError::InvalidArgument {
+            detail: format!("level={level}: expected 1, 2, or 3"),
+        });
+    }
+    let result = validate(file, &validate_cmd::config_for_level(level), None);
+
+    let config = FormatterConfig::from_flags(true, false, false);
+    let mut body = Vec::new();
+    for diag in &result.diagnostics {
+        write_diagnostic(&mut body, diag, FormatMode::Json, &config)
+            .map_err(|e| buffer_error(&e))?;
+    }
+    write_summary(
+        &mut body,
+        result.errors().count(),
+        result.warnings().count(),
+        result.infos().count(),
+        FormatMode::Json,
+        &config,
+    )
+    .map_err(|e| buffer_error(&e))?;
+
+    let reply = Reply::ndjson(body);
+    Ok(if result.has_errors() {
+        reply.with_exit_code(CliError::ValidationErrors.exit_code())
+    } else {
+        reply
+    })
+}
+
+/// `/query`: `{"nodes": [...], "edges": [...]}`, or the two counts with `count`.
+fn run_query(file: &OmtsFile, params: &Params) -> Result<Reply, CliError> {
+    let selector_set = build_selector_set(
+        &params.all("node-type"),
+        &params.all("edge-type"),
+        &params.all("label"),
+        &params.all("identifier"),
+        &params.all("jurisdiction"),
+        &params.all("name"),
+        params.get("where"),
+    )?;
+    let result = selector_match(file, &selector_set);
+    if result.node_indices.is_empty() && result.edge_indices.is_empty() {
+        return Err(CliError::NoResults {
+            detail: "no nodes or edges matched the given selectors".to_owned(),
+        });
+    }
+
+    if params.flag("count")? {
+        let counts = serde_json::json!({
+            "nodes": result.node_indices.len(),
+            "edges": result.edge_indices.len(),
+        });
+        return json_value(&counts);
+    }
+
+    let nodes: Vec<&omts_core::Node> = result
+        .node_indices
+        .iter()
+        .map(|&i| &file.nodes[i])
+        .collect();
+    let edges: Vec<&omts_core::Edge> = result
+        .edge_indices
+        .iter()
+        .map(|&i| &file.edges[i])
+        .collect();
+    let mut body = Vec::new();
+    query::print_json(&mut body, &nodes, &edges).map_err(|e| buffer_error(&e))?;
+    Ok(Reply::json(body))
+}
+
+/// `/reach?node=ID`: `{"node_ids": [...], "count": N}`.
+fn run_reach(input: &Input<'_>, params: &Params) -> Result<Reply, CliError> {
+    let edge_filter = parse_edge_filter(&params.all("edge-type"))?;
+    let direction = params
+        .value_enum::<Direction>("direction")?
+        .unwrap_or(Direction::Outgoing);
+    let node_ids = reach::reachable_ids(
+        input.graph()?,
+        params.required("node")?,
+        params.number::<usize>("depth")?,
+        reach::to_core_direction(&direction),
+        edge_filter.as_ref(),
+    )?;
+
+    let mut body = Vec::new();
+    reach::print_json(&mut body, &node_ids).map_err(|e| buffer_error(&e))?;
+    Ok(Reply::json(body))
+}
+
+/// `/path?from=A&to=B`: `{"paths": [...], "routes": [...], "count": N}`.
+fn run_path(input: &Input<'_>, params: &Params) -> Result<Reply, CliError> {
+    let graph = input.graph()?;
+    let paths = path::find_paths(
+        graph,
+        Some(&input.file),
+        params.required("from")?,
+        params.required("to")?,
+        params.number("max-paths")?.unwrap_or(10),
+        params.number("max-depth")?.unwrap_or(20),
+        params.value_enum("cost")?.unwrap_or(PathCost::Hops),
+        &params.all("edge-cost"),
+        params.get("pattern"),
+        &params.all("edge-type"),
+    )?;
+    let routes: Vec<path::Route> = paths.iter().map(|p| path::resolve(graph, p)).collect();
+
+    let mut body = Vec::new();
+    path::print_json(&mut body, &routes).map_err(|e| buffer_error(&e))?;
+    Ok(Reply::json(body))
+}
+
+/// `/subgraph?node=ID&...`: the extracted `.omts` file.
+fn run_subgraph(input: &Input<'_>, params: &Params) -> Result<Reply, CliError> {
+    let extracted = subgraph::extract(
+        &input.file,
+        input.graph()?,
+        &params.all("node"),
+        &params.all("node-type"),
+        &params.all("edge-type"),
+        &params.all("label"),
+        &params.all("identifier"),
+        &params.all("jurisdiction"),
+        &params.all("name"),
+        params.get("where"),
+        params.number("expand")?.unwrap_or(0),
+    )?;
+    let body = subgraph::encode_subgraph(extracted, &TargetEncoding::Json, false)?;
+    Ok(Reply::json(body))
+}
+
+/// `/diff`: the diff JSON of the two inputs, in order.
+fn run_diff(a: &OmtsFile, b: &OmtsFile, params: &Params) -> Result<Reply, CliError> {
+    let filter = diff::build_filter(
+        &params.all("node-type"),
+        &params.all("edge-type"),
+        &params.all("ignore-field"),
+        params.get("where"),
+    )?;
+    let result = diff_filtered(a, b, Some(&filter));
+
+    let mut body = Vec::new();
+    diff::json::write_json(&mut body, &result).map_err(|e| buffer_error(&e))?;
+    let reply = Reply::json(body);
+    Ok(if result.is_empty() {
+        reply
+    } else {
+        reply.with_exit_code(CliError::DiffHasDifferences.exit_code())
+    })
+}
+
+/// `/merge`: the merged `.omts` file of all inputs.
+fn run_merge(inputs: &[Input<'_>], params: &Params, log: &mut Vec<u8>) -> Result<Reply, CliError> {
+    let strategy = params
+        .value_enum::<MergeStrategy>("strategy")?
+        .unwrap_or(MergeStrategy::Union);
+    merge::ensure_supported(&strategy)?;
+
+    for input in inputs {
+        merge::check_l1(&input.file, log)?;
+    }
+    let files: Vec<OmtsFile> = inputs.iter().map(|input| input.file.clone()).collect();
+    let merged = merge::merge_checked(&files, &MergeConfig::default(), log)?.file;
+
+    let mut body = merge::encode_output(&merged, &TargetEncoding::Json, false)?;
+    body.push(b'\n');
+    Ok(Reply::json(body))
+}
+
+/// `/redact?scope=S`: the redacted `.omts` file.
+fn run_redact(file: &OmtsFile, params: &Params, log: &mut Vec<u8>) -> Result<Reply, CliError> {
+    let scope = params
+        .value_enum::<DisclosureScope>("scope")?
+        .ok_or_else(|| CliError::InvalidArgument {
+            detail: "missing required parameter \"scope\"".to_owned(),
+        })?;
+    let redacted = redact::redact_file(
+        file,
+        &scope,
+        &HashSet::new(),
+        &RedactionPolicy::default(),
+        log,
+    )?
+    .file;
+
+    let mut body = redact::encode_output(&redacted, &TargetEncoding::Json, false)?;
+    body.push(b'\n');
+    Ok(Reply::json(body))
+}
+
+/// Pretty-prints `value` as a JSON reply body.
+fn json_value(value: &serde_json::Value) -> Result<Reply, CliError> {
+    let mut body = serde_json::to_vec_pretty(value).map_err(|e| CliError::InternalError {
+        detail: format!("JSON serialization failed: {e}"),
+    })?;
+    body.push(b'\n');
+    Ok(Reply::json(body))
+}
+
+fn buffer_error(e: &std::io::Error) -> CliError {
+    CliError::InternalError {
+        detail: format!("writing response body failed: {e}"),
+    }
+}
@@ -0,0 +1,245 @@
+//! Implementation of `omts serve <file>...`.
+//!
+//! Loads one or more `.omts` files and serves the OMTS commands as JSON
+//! endpoints over HTTP on localhost, so dashboards and notebooks can call
+//! them without shelling out:
+//!
+//! | Endpoint    | Command                   | Inputs |
+//! |-------------|---------------------------|--------|
+//! | `/validate` | `validate`                | 1      |
+//! | `/query`    | `query`                   | 1      |
+//! | `/reach`    | `reach <node>`            | 1      |
+//! | `/path`     | `path <from> <to>`        | 1      |
+//! | `/subgraph` | `subgraph [node...]`      | 1      |
+//! | `/diff`     | `diff <a> <b>`            | 2      |
+//! | `/merge`    | `merge <file>...`         | 2+     |
+//! | `/redact`   | `redact --scope <scope>`  | 1      |
+//!
+//! Query parameters carry the command's long flags (`?node=org-1&depth=2`);
+//! the positional node IDs become `node`, `from`, and `to`. Inputs are the
+//! served files named by repeated `file` parameters (path as given, or file
+//! name), followed by the request body when a POST uploads an `.omts`
+//! document; with neither, every served file is an input. Served files are
+//! reloaded when their modification time or length changes. Request bodies
+//! larger than `--max-file-size` are rejected with 413.
+//!
+//! A successful response carries exactly what the command prints with
+//! `--format json` (`validate` answers with its NDJSON diagnostics). The
+//! `X-Omts-Exit-Code` header holds the exit code the command would return:
+//! `1` for a file with L1 errors or a non-empty diff, which are still
+//! answered with 200. Failures are answered with
+//! `{"error": ..., "exit_code": N, "messages": [...]}`, where `messages`
+//! holds what the command would have printed to stderr.
+//!
+//! Requests are handled one at a time. The command runs until killed.
+//!
+//! Exit codes: 2 = a file could not be loaded or the address could not be bound.
+mod endpoints;
+mod params;
+mod workspace;
+
+use std::io::Read as _;
+use std::path::PathBuf;
+
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::Cli;
+use crate::error::CliError;
+use endpoints::Endpoint;
+use params::{Params, split_url};
+use workspace::Workspace;
+
+/// Header carrying the exit code the equivalent command would return.
+const EXIT_CODE_HEADER: &str = "X-Omts-Exit-Code";
+
+/// Runs the `serve` command.
+///
+/// Loads `files`, binds `host:port` (port 0 picks a free port), prints the
+/// bound address to stderr, and answers requests until the process is killed.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 2 if a file cannot be read or parsed, or the
+/// address cannot be bound.
+pub fn run(files: &[PathBuf], host: &str, port: u16, cli: &Cli) -> Result<(), CliError> {
+    let mut workspace = Workspace::load(files, cli.max_file_size, cli.verbose)?;
+
+    let server = Server::http((host, port)).map_err(|e| CliError::IoError {
+        source: format!("{host}:{port}"),
+        detail: e.to_string(),
+    })?;
+
+    for (path, nodes, edges) in workspace.summary() {
+        eprintln!("loaded {path}: {nodes} node(s), {edges} edge(s)");
+    }
+    eprintln!("listening on http://{}", server.server_addr());
+
+    for mut request in server.incoming_requests() {
+        let reply = respond(&mut workspace, &mut request, cli.max_file_size);
+        if cli.verbose {
+            eprintln!("{} {} -> {}", request.method(), request.url(), reply.status);
+        }
+        if let Err(e) = request.respond(reply.into_response()) {
+            eprintln!("warning: failed to send response: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// A response about to be sent.
+pub(crate) struct Reply {
+    status: u16,
+    content_type: &'static str,
+    exit_code: i32,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    /// A 200 response with a JSON body.
+    pub(crate) fn json(body: Vec<u8>) -> Self {
+        Reply {
+            status: 200,
+            content_type: "application/json",
+            exit_code: 0,
+            body,
+        }
+    }
+
+    /// A 200 response with a newline-delimited JSON body.
+    pub(crate) fn ndjson(body: Vec<u8>) -> Self {
+        Reply {
+            content_type: "application/x-ndjson",
+            ..Reply::json(body)
+        }
+    }
+
+    /// Sets the exit code reported in the `X-Omts-Exit-Code` header.
+    pub(crate) fn with_exit_code(self, exit_code: i32) -> Self {
+        Reply { exit_code, ..self }
+    }
+
+    /// An error response: `{"error": ..., "exit_code": N, "messages": [...]}`.
+    fn failure(status: u16, error: &str, exit_code: i32, log: &[u8]) -> Self {
+        let messages: Vec<&str> = std::str::from_utf8(log)
+            .unwrap_or_default()
+            .lines()
+            .collect();
+        let value = serde_json::json!({
+            "error": error,
+            "exit_code": exit_code,
+            "messages": messages,
+        });
+        let mut body = serde_json::to_vec_pretty(&value).unwrap_or_default();
+        body.push(b'\n');
+        Reply {
+            status,
+            content_type: "application/json",
+            exit_code,
+            body,
+        }
+    }
+
+    /// The error response for `e`.
+    fn from_error(e: &CliError, log: &[u8]) -> Self {
+        Reply::failure(http_status(e), &e.message(), e.exit_code(), log)
+    }
+
+    fn into_response(self) -> Response<std::io::Cursor<Vec<u8>>> {
+        let mut response = Response::from_data(self.body).with_status_code(self.status);
+        for (name, value) in [
+            ("Content-Type", self.content_type.to_owned()),
+            (EXIT_CODE_HEADER, self.exit_code.to_string()),
+        ] {
+            if let Ok(header) = Header::from_bytes(name, value) {
+                response = response.with_header(header);
+            }
+        }
+        response
+    }
+}
+
+/// Answers one request.
+fn respond(workspace: &mut Workspace, request: &mut Request, max_file_size: u64) -> Reply {
+    let (path, query) = split_url(request.url());
+    let query = query.to_owned();
+    let Some(endpoint) = Endpoint::from_path(path) else {
+        let endpoints: Vec<&str> = Endpoint::ALL.iter().map(|e| e.path()).collect();
+        let error = format!(
+            "error: unknown endpoint {path:?}; expected one of: {}",
+            endpoints.join(", ")
+        );
+        return Reply::failure(404, &error, 2, &[]);
+    };
+    if !matches!(request.method(), Method::Get | Method::Post) {
+        let error = format!("error: {} supports GET and POST only", endpoint.path());
+        return Reply::failure(405, &error, 2, &[]);
+    }
+
+    let mut log = Vec::new();
+    let result = Params::parse(&query).and_then(|params| {
+        let body = read_body(request, max_file_size)?;
+        let inputs = workspace.inputs(&params.all("file"), body.as_deref())?;
+        endpoint.run(&inputs, &params, &mut log)
+    });
+    match result {
+        Ok(reply) => {
+            eprint!("{}", String::from_utf8_lossy(&log));
+            reply
+        }
+        Err(e) => Reply::from_error(&e, &log),
+    }
+}
+
+/// Reads the request body, or `None` when it is empty.
+///
+/// # Errors
+///
+/// Returns [`CliError::FileTooLarge`] when the body exceeds `max_file_size`.
+fn read_body(request: &mut Request, max_file_size: u64) -> Result<Option<Vec<u8>>, CliError> {
+    let too_large = |actual: Option<u64>| CliError::FileTooLarge {
+        source: "request body".to_owned(),
+        limit: max_file_size,
+        actual,
+    };
+    if let Some(len) = request.body_length() {
+        if len as u64 > max_file_size {
+            return Err(too_large(Some(len as u64)));
+        }
+    }
+
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_file_size.saturating_add(1))
+        .read_to_end(&mut body)
+        .map_err(|e| CliError::IoError {
+            source: "request body".to_owned(),
+            detail: e.to_string(),
+        })?;
+    if body.len() as u64 > max_file_size {
+        return Err(too_large(None));
+    }
+    Ok(if body.is_empty() { None } else { Some(body) })
+}
+
+/// Maps a command error to an HTTP status code.
+fn http_status(e: &CliError) -> u16 {
+    match e {
+        CliError::InvalidArgument { .. } => 400,
+        CliError::NodeNotFound { .. } | CliError::NoResults { .. } => 404,
+        CliError::FileTooLarge { .. } | CliError::DecompressedTooLarge { .. } => 413,
+        CliError::InvalidUtf8 { .. }
+        | CliError::EncodingDetectionFailed { .. }
+        | CliError::ParseFailed { .. }
+        | CliError::GraphBuildError { .. }
+        | CliError::ValidationErrors
+        | CliError::MergeConflict { .. }
+        | CliError::RedactionError { .. } => 422,
+        CliError::DiffHasDifferences => 200,
+        CliError::FileNotFound { .. }
+        | CliError::PermissionDenied { .. }
+        | CliError::StdinReadError { .. }
+        | CliError::IoError { .. }
+        | CliError::InternalError { .. } => 500,
+    }
+}
@@ -0,0 +1,232 @@
+//! Query-string parsing for `omts serve`.
+//!
+//! Endpoint parameters reuse the long flag names of the matching `omts`
+//! subcommands (`node-type`, `max-paths`, ...). Repeatable flags are given by
+//! repeating the parameter; boolean flags accept an empty value, `true`, or
+//! `false`.
+use std::str::FromStr;
+
+use clap::ValueEnum;
+
+use crate::error::CliError;
+
+/// Decoded `key=value` pairs of a request's query string, in order.
+pub(crate) struct Params {
+    pairs: Vec<(String, String)>,
+}
+
+impl Params {
+    /// Parses a query string (without the leading `?`).
+    ///
+    /// `+` decodes to a space and `%XX` to the byte `XX`; the decoded bytes
+    /// must be valid UTF-8.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::InvalidArgument`] for a malformed escape or
+    /// non-UTF-8 value.
+    pub(crate) fn parse(query: &str) -> Result<Self, CliError> {
+        let pairs = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                Ok((decode(key)?, decode(value)?))
+            })
+            .collect::<Result<_, CliError>>()?;
+        Ok(Params { pairs })
+    }
+
+    /// Rejects parameters outside `known`, as clap rejects unknown flags.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::InvalidArgument`] naming the first unknown key.
+    pub(crate) fn check_known(&self, endpoint: &str, known: &[&str]) -> Result<(), CliError> {
+        match self
+            .pairs
+            .iter()
+            .find(|(key, _)| !known.contains(&key.as_str()))
+        {
+            Some((key, _)) => Err(CliError::InvalidArgument {
+                detail: format!(
+                    "unknown parameter {key:?} for {endpoint} (expected one of: {})",
+                    known.join(", ")
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns every value of the repeatable parameter `key`.
+    pub(crate) fn all(&self, key: &str) -> Vec<String> {
+        self.pairs
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+            .collect()
+    }
+
+    /// Returns the value of `key`; the last one wins when repeated.
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the value of the required parameter `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::InvalidArgument`] when `key` is absent.
+    pub(crate) fn required(&self, key: &str) -> Result<&str, CliError> {
+        self.get(key).ok_or_else(|| CliError::InvalidArgument {
+            detail: format!("missing required parameter {key:?}"),
+        })
+    }
+
+    /// Returns the boolean flag `key` (`false` when absent).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::InvalidArgument`] for a value other than empty,
+    /// `true`, or `false`.
+    pub(crate) fn flag(&self, key: &str) -> Result<bool, CliError> {
+        match self.get(key) {
+            None | Some("false") => Ok(false),
+            Some("" | "true") => Ok(true),
+            Some(other) => Err(CliError::InvalidArgument {
+                detail: format!("{key}={other:?}: expected true or false"),
+            }),
+        }
+    }
+
+    /// Parses the numeric parameter `key`, if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::InvalidArgument`] when the value does not parse.
+    pub(crate) fn number<T: FromStr>(&self, key: &str) -> Result<Option<T>, CliError> {
+        self.get(key)
+            .map(|value| {
+                value.parse().map_err(|_| CliError::InvalidArgument {
+                    detail: format!("{key}={value:?}: expected a non-negative integer"),
+                })
+            })
+            .transpose()
+    }
+
+    /// Parses the parameter `key` as one of the CLI's value enums, if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::InvalidArgument`] listing the accepted values when
+    /// the value is not one of them.
+    pub(crate) fn value_enum<T: ValueEnum>(&self, key: &str) -> Result<Option<T>, CliError> {
+        self.get(key)
+            .map(|value| {
+                T::from_str(value, false).map_err(|_| {
+                    let accepted: Vec<String> = T::value_variants()
+                        .iter()
+                        .filter_map(ValueEnum::to_possible_value)
+                        .map(|v| v.get_name().to_owned())
+                        .collect();
+                    CliError::InvalidArgument {
+                        detail: format!(
+                            "{key}={value:?}: expected one of: {}",
+                            accepted.join(", ")
+                        ),
+                    }
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Splits a request target into its path and query string.
+pub(crate) fn split_url(url: &str) -> (&str, &str) {
+    url.split_once('?').unwrap_or((url, ""))
+}
+
+/// Decodes one `application/x-www-form-urlencoded` component.
+fn decode(s: &str) -> Result<String, CliError> {
+    let invalid = || CliError::InvalidArgument {
+        detail: format!("malformed query string component {s:?}"),
+    };
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = tail.get(..2).ok_or_else(invalid)?;
+                let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                rest = &tail[2..];
+                continue;
+            }
+            other => bytes.push(other),
+        }
+        rest = tail;
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+    use crate::Direction;
+
+    fn params(query: &str) -> Params {
+        Params::parse(query).expect("valid query string")
+    }
+
+    #[test]
+    fn decodes_plus_and_percent_escapes() {
+        let p = params("name=Acme+Corp&where=type%3Dorganization%20AND%20x");
+        assert_eq!(p.get("name"), Some("Acme Corp"));
+        assert_eq!(p.get("where"), Some("type=organization AND x"));
+    }
+
+    #[test]
+    fn rejects_malformed_escapes() {
+        assert!(Params::parse("name=%4").is_err());
+        assert!(Params::parse("name=%zz").is_err());
+        assert!(Params::parse("name=%ff").is_err());
+    }
+
+    #[test]
+    fn repeated_keys_collect_in_order() {
+        let p = params("node-type=organization&depth=1&node-type=facility&depth=2");
+        assert_eq!(p.all("node-type"), ["organization", "facility"]);
+        assert_eq!(p.number::<u32>("depth").expect("number"), Some(2));
+    }
+
+    #[test]
+    fn flags_and_enums_parse_like_the_cli() {
+        let p = params("count&ids-only=false&direction=both");
+        assert!(p.flag("count").expect("flag"));
+        assert!(!p.flag("ids-only").expect("flag"));
+        assert!(!p.flag("absent").expect("flag"));
+        assert!(matches!(
+            p.value_enum::<Direction>("direction").expect("enum"),
+            Some(Direction::Both)
+        ));
+        assert!(
+            params("direction=up")
+                .value_enum::<Direction>("direction")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn unknown_parameters_are_rejected() {
+        let p = params("node=a&dpeth=2");
+        assert!(p.check_known("/reach", &["node", "depth"]).is_err());
+        assert!(params("node=a").check_known("/reach", &["node"]).is_ok());
+    }
+}
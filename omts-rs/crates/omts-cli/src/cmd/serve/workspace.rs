@@ -0,0 +1,223 @@
+//! Files served by `omts serve`, reloaded when they change on disk.
+//!
+//! Each loaded file is identified by its path as given on the command line,
+//! or by its file name when that is unambiguous. Before a request uses a
+//! file, its modification time and length are compared with the values seen
+//! at the last load; a change triggers a re-read. A reload that fails leaves
+//! the previous contents in place and is retried on the next request.
+use std::cell::OnceCell;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use omts_core::OmtsFile;
+use omts_core::graph::{OmtsGraph, build_graph};
+
+use crate::error::CliError;
+use crate::{PathOrStdin, io};
+
+/// A parsed `.omts` file and its lazily built graph.
+pub(crate) struct Document {
+    pub(crate) file: OmtsFile,
+    graph: OnceCell<OmtsGraph>,
+}
+
+impl Document {
+    fn new(file: OmtsFile) -> Self {
+        Document {
+            file,
+            graph: OnceCell::new(),
+        }
+    }
+
+    /// Returns the graph, building it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::GraphBuildError`] if the graph cannot be built.
+    pub(crate) fn graph(&self) -> Result<&OmtsGraph, CliError> {
+        if let Some(graph) = self.graph.get() {
+            return Ok(graph);
+        }
+        let graph = build_graph(&self.file).map_err(|e| CliError::GraphBuildError {
+            detail: e.to_string(),
+        })?;
+        Ok(self.graph.get_or_init(|| graph))
+    }
+}
+
+/// One input of a request: a loaded file or the uploaded request body.
+pub(crate) enum Input<'a> {
+    Loaded(&'a Document),
+    Body(Document),
+}
+
+impl Deref for Input<'_> {
+    type Target = Document;
+
+    fn deref(&self) -> &Document {
+        match self {
+            Input::Loaded(doc) => doc,
+            Input::Body(doc) => doc,
+        }
+    }
+}
+
+/// Modification time and length of a file when it was last loaded.
+type Stamp = (Option<SystemTime>, u64);
+
+struct LoadedFile {
+    path: PathBuf,
+    stamp: Stamp,
+    doc: Document,
+}
+
+/// The set of files loaded at startup.
+pub(crate) struct Workspace {
+    files: Vec<LoadedFile>,
+    max_file_size: u64,
+    verbose: bool,
+}
+
+impl Workspace {
+    /// Loads every file in `paths`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError`] exit code 2 if any file cannot be read or parsed.
+    pub(crate) fn load(
+        paths: &[PathBuf],
+        max_file_size: u64,
+        verbose: bool,
+    ) -> Result<Self, CliError> {
+        let mut workspace = Workspace {
+            files: Vec::with_capacity(paths.len()),
+            max_file_size,
+            verbose,
+        };
+        for path in paths {
+            let (stamp, doc) = workspace.read(path)?;
+            workspace.files.push(LoadedFile {
+                path: path.clone(),
+                stamp,
+                doc,
+            });
+        }
+        Ok(workspace)
+    }
+
+    /// Returns `(path, nodes, edges)` for every loaded file.
+    pub(crate) fn summary(&self) -> Vec<(String, usize, usize)> {
+        self.files
+            .iter()
+            .map(|f| {
+                (
+                    f.path.display().to_string(),
+                    f.doc.file.nodes.len(),
+                    f.doc.file.edges.len(),
+                )
+            })
+            .collect()
+    }
+
+    /// Resolves the inputs of one request.
+    ///
+    /// `names` selects loaded files in order; `body` (an uploaded `.omts`
+    /// document) is appended after them. With neither, every loaded file is
+    /// an input. Selected files are reloaded first if they changed on disk.
+    ///
+    /// # Errors
+    ///
+    /// - [`CliError::InvalidArgument`] if a name matches no loaded file, or
+    ///   matches several by file name.
+    /// - [`CliError`] exit code 2 if a reload or the body fails to parse.
+    pub(crate) fn inputs(
+        &mut self,
+        names: &[String],
+        body: Option<&[u8]>,
+    ) -> Result<Vec<Input<'_>>, CliError> {
+        let selected: Vec<usize> = if names.is_empty() && body.is_none() {
+            (0..self.files.len()).collect()
+        } else {
+            names
+                .iter()
+                .map(|name| self.find(name))
+                .collect::<Result<_, _>>()?
+        };
+
+        for &i in &selected {
+            self.refresh(i)?;
+        }
+
+        let mut inputs: Vec<Input<'_>> = selected
+            .iter()
+            .map(|&i| Input::Loaded(&self.files[i].doc))
+            .collect();
+        if let Some(bytes) = body {
+            let (file, _encoding) =
+                io::parse_labelled_bytes(bytes, "request body", self.max_file_size, self.verbose)?;
+            inputs.push(Input::Body(Document::new(file)));
+        }
+        Ok(inputs)
+    }
+
+    /// Finds a loaded file by its path as given, or by its file name.
+    fn find(&self, name: &str) -> Result<usize, CliError> {
+        if let Some(i) = self.files.iter().position(|f| f.path.as_os_str() == name) {
+            return Ok(i);
+        }
+        let by_name: Vec<usize> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.path.file_name().is_some_and(|n| n == name))
+            .map(|(i, _)| i)
+            .collect();
+        match by_name.as_slice() {
+            [i] => Ok(*i),
+            [] => Err(CliError::InvalidArgument {
+                detail: format!("file={name:?} is not one of the served files"),
+            }),
+            [..] => Err(CliError::InvalidArgument {
+                detail: format!("file={name:?} matches several served files; use the full path"),
+            }),
+        }
+    }
+
+    /// Reloads file `i` if its modification time or length changed.
+    fn refresh(&mut self, i: usize) -> Result<(), CliError> {
+        let path = self.files[i].path.clone();
+        if stamp(&path)? == self.files[i].stamp {
+            return Ok(());
+        }
+        let (stamp, doc) = self.read(&path)?;
+        eprintln!(
+            "reloaded {}: {} node(s), {} edge(s)",
+            path.display(),
+            doc.file.nodes.len(),
+            doc.file.edges.len()
+        );
+        let loaded = &mut self.files[i];
+        loaded.stamp = stamp;
+        loaded.doc = doc;
+        Ok(())
+    }
+
+    /// Reads and parses `path`, returning it with the stamp taken before the
+    /// read (so a write racing the read is picked up next time).
+    fn read(&self, path: &std::path::Path) -> Result<(Stamp, Document), CliError> {
+        let stamp = stamp(path)?;
+        let (file, _encoding) = io::read_and_parse(
+            &PathOrStdin::Path(path.to_path_buf()),
+            self.max_file_size,
+            self.verbose,
+        )?;
+        Ok((stamp, Document::new(file)))
+    }
+}
+
+/// Returns the current [`Stamp`] of `path`.
+fn stamp(path: &std::path::Path) -> Result<Stamp, CliError> {
+    let metadata = std::fs::metadata(path).map_err(|e| io::io_error_to_cli(&e, path))?;
+    Ok((metadata.modified().ok(), metadata.len()))
+}
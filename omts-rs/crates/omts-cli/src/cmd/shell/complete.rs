@@ -0,0 +1,216 @@
+//! Tab completion for `omts shell`.
+//!
+//! Completes command names in the first word, node names after `--name`, and
+//! node IDs everywhere else. A word that is a prefix of a node *name* also
+//! completes to that node's ID, so `cd acm<TAB>` offers `org-7 (Acme Corp)`.
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+use omts_core::OmtsFile;
+
+use super::grammar::COMMAND_NAMES;
+
+/// Completion candidates for one shell session.
+pub(crate) struct ShellHelper {
+    /// Node IDs, sorted.
+    ids: Vec<String>,
+    /// `(lowercased name, name, node ID)` triples, sorted.
+    names: Vec<(String, String, String)>,
+}
+
+impl ShellHelper {
+    /// Collects node IDs and names from `file`.
+    pub(crate) fn new(file: &OmtsFile) -> Self {
+        let mut ids: Vec<String> = file.nodes.iter().map(|n| n.id.to_string()).collect();
+        ids.sort();
+        let mut names: Vec<(String, String, String)> = file
+            .nodes
+            .iter()
+            .filter_map(|n| {
+                let name = n.name.as_deref()?;
+                Some((name.to_lowercase(), name.to_owned(), n.id.to_string()))
+            })
+            .collect();
+        names.sort();
+        ShellHelper { ids, names }
+    }
+
+    /// Returns the start of the word being completed and its candidates.
+    pub(crate) fn candidates(&self, line: &str) -> (usize, Vec<Pair>) {
+        let start = line
+            .rfind(char::is_whitespace)
+            .map_or(0, |i| i + char_len(line, i));
+        let word = &line[start..];
+        let previous = line[..start].split_whitespace().last();
+
+        let candidates = match previous {
+            None => COMMAND_NAMES
+                .iter()
+                .filter(|name| name.starts_with(word))
+                .map(|name| pair((*name).to_owned(), (*name).to_owned()))
+                .collect(),
+            Some("--name") => self
+                .names_with_prefix(word)
+                .map(|(_, name, _)| pair(name.clone(), quote(name)))
+                .collect(),
+            Some(_) if word.starts_with('-') => Vec::new(),
+            Some(_) => {
+                let mut found: Vec<Pair> = self
+                    .ids_with_prefix(word)
+                    .map(|id| pair(id.clone(), id.clone()))
+                    .collect();
+                found.extend(
+                    self.names_with_prefix(word)
+                        .filter(|(_, _, id)| !id.starts_with(word))
+                        .map(|(_, name, id)| pair(format!("{id} ({name})"), id.clone())),
+                );
+                found
+            }
+        };
+        (start, candidates)
+    }
+
+    fn ids_with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a String> {
+        let from = self.ids.partition_point(|id| id.as_str() < prefix);
+        self.ids[from..]
+            .iter()
+            .take_while(move |id| id.starts_with(prefix))
+    }
+
+    fn names_with_prefix<'a>(
+        &'a self,
+        prefix: &str,
+    ) -> impl Iterator<Item = &'a (String, String, String)> {
+        let prefix = prefix.to_lowercase();
+        let from = self
+            .names
+            .partition_point(|(lower, _, _)| lower.as_str() < prefix.as_str());
+        self.names[from..]
+            .iter()
+            .take_while(move |(lower, _, _)| lower.starts_with(&prefix))
+    }
+}
+
+/// Byte length of the character starting at byte `i` of `s`.
+fn char_len(s: &str, i: usize) -> usize {
+    s[i..].chars().next().map_or(1, char::len_utf8)
+}
+
+fn pair(display: String, replacement: String) -> Pair {
+    Pair {
+        display,
+        replacement,
+    }
+}
+
+/// Quotes `s` for the shell tokenizer when it contains whitespace or quotes.
+fn quote(s: &str) -> String {
+    if s.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'' || c == '\\') {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        Ok(self.candidates(&line[..pos]))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    fn helper() -> ShellHelper {
+        let file: OmtsFile = serde_json::from_str(
+            r#"{
+                "omts_version": "1.0.0",
+                "snapshot_date": "2026-02-19",
+                "file_salt": "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+                "nodes": [
+                    {"id": "org-1", "type": "organization", "name": "Acme Corp"},
+                    {"id": "org-2", "type": "organization", "name": "Beta GmbH"},
+                    {"id": "fac-1", "type": "facility"}
+                ],
+                "edges": []
+            }"#,
+        )
+        .expect("valid OMTS JSON");
+        ShellHelper::new(&file)
+    }
+
+    fn replacements(line: &str) -> (usize, Vec<String>) {
+        let (start, pairs) = helper().candidates(line);
+        (start, pairs.into_iter().map(|p| p.replacement).collect())
+    }
+
+    #[test]
+    fn completes_command_names() {
+        assert_eq!(
+            replacements("s"),
+            (
+                0,
+                vec![
+                    "save".to_owned(),
+                    "selection".to_owned(),
+                    "show".to_owned(),
+                    "subgraph".to_owned()
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn completes_node_ids() {
+        assert_eq!(
+            replacements("cd org"),
+            (3, vec!["org-1".to_owned(), "org-2".to_owned()])
+        );
+        assert_eq!(replacements("path org-1 f"), (11, vec!["fac-1".to_owned()]));
+    }
+
+    #[test]
+    fn name_prefix_completes_to_node_id() {
+        let (start, pairs) = helper().candidates("show acm");
+        assert_eq!(start, 5);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].replacement, "org-1");
+        assert_eq!(pairs[0].display, "org-1 (Acme Corp)");
+    }
+
+    #[test]
+    fn completes_quoted_names_after_name_flag() {
+        assert_eq!(
+            replacements("query --name ac"),
+            (13, vec!["\"Acme Corp\"".to_owned()])
+        );
+    }
+
+    #[test]
+    fn flags_are_not_completed() {
+        assert_eq!(replacements("reach --dep"), (6, Vec::new()));
+    }
+}
@@ -0,0 +1,356 @@
+//! Line grammar for `omts shell`: tokenizer and clap command definitions.
+//!
+//! Each input line is split into words with [`tokenize`] and parsed with
+//! [`ShellLine`]. The commands reuse the flag names of the matching `omts`
+//! subcommands, minus the `FILE` argument (the shell's file is implicit).
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::{Direction, PathCost, TargetEncoding};
+
+/// Node ID argument that stands for the current node (set with `cd`).
+pub(crate) const CURRENT_NODE: &str = ".";
+
+/// Names of all shell commands, for tab completion.
+pub(crate) const COMMAND_NAMES: &[&str] = &[
+    "cd",
+    "diff",
+    "exit",
+    "help",
+    "inspect",
+    "neighbours",
+    "path",
+    "query",
+    "reach",
+    "save",
+    "selection",
+    "show",
+    "subgraph",
+];
+
+/// One parsed shell input line.
+#[derive(Parser)]
+#[command(
+    name = "omts>",
+    no_binary_name = true,
+    override_usage = "<COMMAND> [ARGS]",
+    disable_version_flag = true,
+    help_template = "{subcommands}"
+)]
+pub(crate) struct ShellLine {
+    #[command(subcommand)]
+    pub(crate) command: ShellCommand,
+}
+
+/// Property selector flags shared by `query` and `subgraph`.
+#[derive(Args)]
+pub(crate) struct SelectorArgs {
+    /// Match nodes of this type (repeatable).
+    #[arg(long, value_name = "TYPE")]
+    pub(crate) node_type: Vec<String>,
+    /// Match edges of this type (repeatable).
+    #[arg(long, value_name = "TYPE")]
+    pub(crate) edge_type: Vec<String>,
+    /// Match elements with this label key, or key=value pair (repeatable).
+    #[arg(long, value_name = "KEY[=VALUE]")]
+    pub(crate) label: Vec<String>,
+    /// Match nodes with this identifier scheme, or scheme:value pair (repeatable).
+    #[arg(long, value_name = "SCHEME[:VALUE]")]
+    pub(crate) identifier: Vec<String>,
+    /// Match nodes whose jurisdiction equals this country code (repeatable).
+    #[arg(long, value_name = "CC")]
+    pub(crate) jurisdiction: Vec<String>,
+    /// Match nodes whose name contains this pattern (repeatable).
+    #[arg(long, value_name = "PATTERN")]
+    pub(crate) name: Vec<String>,
+    /// Boolean selector expression, combined with the other flags by AND.
+    #[arg(long = "where", value_name = "EXPR")]
+    pub(crate) where_expr: Option<String>,
+}
+
+/// Shell commands. Node ID arguments accept `.` for the current node.
+#[derive(Subcommand)]
+pub(crate) enum ShellCommand {
+    /// Query nodes and edges by property predicates (as `omts query`).
+    Query {
+        #[command(flatten)]
+        selectors: SelectorArgs,
+        /// Print only match counts.
+        #[arg(long)]
+        count: bool,
+    },
+
+    /// List nodes reachable from a node (as `omts reach`; default: current node).
+    Reach {
+        /// The starting node ID.
+        #[arg(value_name = "NODE_ID", default_value = CURRENT_NODE)]
+        node_id: String,
+        /// Maximum traversal depth (default: unlimited).
+        #[arg(long)]
+        depth: Option<u32>,
+        /// Traversal direction: outgoing (default), incoming, or both.
+        #[arg(long, default_value = "outgoing", value_enum)]
+        direction: Direction,
+        /// Traverse only edges of this type (repeatable).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Make the reached region the selection instead of listing it.
+        #[arg(long)]
+        select: bool,
+    },
+
+    /// Find paths between two nodes (as `omts path`).
+    Path {
+        /// Source node ID.
+        #[arg(value_name = "FROM")]
+        from: String,
+        /// Target node ID.
+        #[arg(value_name = "TO")]
+        to: String,
+        /// Maximum number of paths to report.
+        #[arg(long, default_value = "10")]
+        max_paths: usize,
+        /// Maximum path length in edges.
+        #[arg(long, default_value = "20")]
+        max_depth: u32,
+        /// Edge cost: hops (default), share-of-buyer-demand, or annual-value.
+        #[arg(long, default_value = "hops", value_enum)]
+        cost: PathCost,
+        /// Multiply the cost of edges of this type (repeatable).
+        #[arg(long, value_name = "TYPE=N")]
+        edge_cost: Vec<String>,
+        /// Constrain the edge-type sequence, e.g. "supplies+ operates".
+        #[arg(long, value_name = "PATTERN")]
+        pattern: Option<String>,
+        /// Traverse only edges of this type (repeatable).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Make the union of the found paths the selection instead of listing them.
+        #[arg(long)]
+        select: bool,
+    },
+
+    /// Select the induced subgraph for nodes and/or selector matches (as `omts subgraph`).
+    Subgraph {
+        /// Node IDs to include.
+        #[arg(value_name = "NODE_ID", num_args = 0..)]
+        node_ids: Vec<String>,
+        #[command(flatten)]
+        selectors: SelectorArgs,
+        /// Include neighbours up to N hops from the seed nodes.
+        #[arg(long, default_value = "0")]
+        expand: u32,
+    },
+
+    /// Print summary statistics (as `omts inspect`).
+    Inspect {
+        /// Also compute network metrics.
+        #[arg(long)]
+        metrics: bool,
+        /// Number of organizations to list in the `PageRank` ranking.
+        #[arg(long, value_name = "N", default_value_t = 10, requires = "metrics")]
+        top: usize,
+    },
+
+    /// Diff the loaded file against another file (as `omts diff`).
+    Diff {
+        /// Path to the comparison file.
+        #[arg(value_name = "OTHER")]
+        other: PathBuf,
+        /// Only report added/removed/changed IDs.
+        #[arg(long)]
+        ids_only: bool,
+        /// Only print the summary statistics line.
+        #[arg(long)]
+        summary_only: bool,
+        /// Restrict diff to nodes of this type (repeatable).
+        #[arg(long, value_name = "TYPE")]
+        node_type: Vec<String>,
+        /// Restrict diff to edges of this type (repeatable).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+        /// Exclude this property from comparison (repeatable).
+        #[arg(long, value_name = "FIELD")]
+        ignore_field: Vec<String>,
+        /// Restrict diff to elements matching a boolean selector expression.
+        #[arg(long = "where", value_name = "EXPR")]
+        where_expr: Option<String>,
+    },
+
+    /// Set the current node (no argument clears it).
+    Cd {
+        /// Node ID to make current.
+        #[arg(value_name = "NODE_ID")]
+        node_id: Option<String>,
+    },
+
+    /// List the edges and adjacent nodes of a node (default: current node).
+    #[command(alias = "neighbors", alias = "ls")]
+    Neighbours {
+        /// Node ID to list.
+        #[arg(value_name = "NODE_ID", default_value = CURRENT_NODE)]
+        node_id: String,
+        /// Direction: outgoing, incoming, or both (default).
+        #[arg(long, default_value = "both", value_enum)]
+        direction: Direction,
+        /// Only list edges of this type (repeatable).
+        #[arg(long, value_name = "TYPE")]
+        edge_type: Vec<String>,
+    },
+
+    /// Print a node or edge as JSON (default: current node).
+    Show {
+        /// Node or edge ID to print.
+        #[arg(value_name = "ID", default_value = CURRENT_NODE)]
+        id: String,
+    },
+
+    /// List the node IDs in the current selection.
+    Selection,
+
+    /// Write the current selection as an .omts file.
+    Save {
+        /// Output path.
+        #[arg(value_name = "OUTPUT")]
+        output: PathBuf,
+        /// Target output encoding: json (default) or cbor.
+        #[arg(long, default_value = "json", value_enum)]
+        to: TargetEncoding,
+        /// Compress output with zstd after serialization.
+        #[arg(long)]
+        compress: bool,
+    },
+
+    /// Leave the shell.
+    #[command(alias = "quit")]
+    Exit,
+}
+
+/// Splits a shell input line into words.
+///
+/// Words are separated by whitespace. Single quotes preserve their contents
+/// literally; double quotes allow `\"` and `\\` escapes; outside quotes a
+/// backslash escapes the next character. Returns an error message for an
+/// unterminated quote.
+pub(crate) fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => word.push(ch),
+                        None => return Err("unterminated single quote".to_owned()),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\')) => word.push(ch),
+                            Some(ch) => {
+                                word.push('\\');
+                                word.push(ch);
+                            }
+                            None => return Err("unterminated double quote".to_owned()),
+                        },
+                        Some(ch) => word.push(ch),
+                        None => return Err("unterminated double quote".to_owned()),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                if let Some(ch) = chars.next() {
+                    word.push(ch);
+                }
+            }
+            ch if ch.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            ch => {
+                in_word = true;
+                word.push(ch);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+    #![allow(clippy::panic)]
+
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        tokenize(line).expect("tokenize")
+    }
+
+    #[test]
+    fn tokenize_splits_on_whitespace() {
+        assert_eq!(
+            words("  reach   org-a --depth 2 "),
+            ["reach", "org-a", "--depth", "2"]
+        );
+    }
+
+    #[test]
+    fn tokenize_honours_quotes() {
+        assert_eq!(
+            words(r#"query --name "Acme Corp""#),
+            ["query", "--name", "Acme Corp"]
+        );
+        assert_eq!(
+            words("query --where 'type=organization AND x'"),
+            ["query", "--where", "type=organization AND x"]
+        );
+        assert_eq!(words(r#"show "a \"b\"""#), ["show", r#"a "b""#]);
+        assert_eq!(words(r"show a\ b ''"), ["show", "a b", ""]);
+    }
+
+    #[test]
+    fn tokenize_rejects_unterminated_quote() {
+        assert!(tokenize("query --name 'Acme").is_err());
+        assert!(tokenize("query --name \"Acme").is_err());
+    }
+
+    #[test]
+    fn command_names_cover_every_command() {
+        for name in COMMAND_NAMES {
+            let parsed = ShellLine::try_parse_from([*name]);
+            let known = match parsed {
+                Ok(_) => true,
+                Err(e) => !matches!(e.kind(), clap::error::ErrorKind::InvalidSubcommand),
+            };
+            assert!(known, "{name} should be a shell command");
+        }
+    }
+
+    #[test]
+    fn reach_defaults_to_current_node() {
+        let line = ShellLine::try_parse_from(["reach", "--depth", "1"]).expect("parse");
+        let ShellCommand::Reach { node_id, depth, .. } = line.command else {
+            panic!("expected reach");
+        };
+        assert_eq!(node_id, CURRENT_NODE);
+        assert_eq!(depth, Some(1));
+    }
+}
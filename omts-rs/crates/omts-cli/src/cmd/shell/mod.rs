@@ -0,0 +1,626 @@
+//! Implementation of `omts shell <file>`: an interactive exploration shell.
+//!
+//! The file is read, parsed, and built into an [`omts_core::graph::OmtsGraph`]
+//! once (from its index sidecar when fresh, see [`crate::cmd::index`]); every
+//! command then runs against the loaded graph.
+//!
+//! Commands mirror the `omts` subcommands without the `FILE` argument:
+//! - `query`, `reach`, `path`, `inspect`, `diff OTHER`: print exactly what the
+//!   matching subcommand prints, honouring the global `--format`.
+//! - `subgraph`, `reach --select`, `path --select`: replace the **selection**,
+//!   an extracted `.omts` subgraph that `save OUTPUT` writes to disk and
+//!   `selection` lists.
+//!
+//! Navigation: `cd <node>` sets the current node, shown in the prompt and
+//! accepted as `.` wherever a node ID is expected; `neighbours` lists the
+//! edges around a node; `show` prints a node or edge as JSON.
+//!
+//! On a terminal, input is read with line editing, history, and tab completion
+//! of command names, node IDs, and node names. Otherwise commands are read from
+//! stdin one per line without a prompt, so the shell can run scripts. Blank
+//! lines and lines starting with `#` are ignored.
+//!
+//! A failing command prints its error to stderr and the shell continues.
+//!
+//! Exit codes: 0 = shell exited normally, 2 = the file could not be loaded or
+//! stdin could not be read.
+mod complete;
+mod grammar;
+
+use std::io::{BufRead as _, IsTerminal as _, Write as _};
+use std::path::Path;
+
+use clap::Parser as _;
+use omts_core::OmtsFile;
+use omts_core::graph::{QueryError, path_subgraph, reachable_subgraph};
+use petgraph::Direction as PetDirection;
+use petgraph::visit::EdgeRef as _;
+use rustyline::Editor;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+
+use crate::cmd::index::IndexedInput;
+use crate::cmd::selectors::parse_edge_filter;
+use crate::cmd::{diff, inspect, path, query, reach, subgraph};
+use crate::error::CliError;
+use crate::{Cli, Direction, OutputFormat, PathOrStdin, TargetEncoding, io};
+
+use self::complete::ShellHelper;
+use self::grammar::{CURRENT_NODE, SelectorArgs, ShellCommand, ShellLine, tokenize};
+
+/// Whether the shell keeps reading input after a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Exit,
+}
+
+/// Runs the `shell` command.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 2 if the file cannot be read, parsed, or
+/// built into a graph, or if reading commands fails.
+pub fn run(path: &Path, cli: &Cli) -> Result<(), CliError> {
+    let source = PathOrStdin::Path(path.to_path_buf());
+    let input = IndexedInput::open(&source, cli)?;
+    let file = input.file()?;
+    let graph = input.graph()?;
+
+    if !cli.quiet {
+        eprintln!(
+            "loaded {} node(s), {} edge(s) from {}; type `help` for commands",
+            graph.node_count(),
+            graph.edge_count(),
+            path.display()
+        );
+    }
+
+    let mut session = Session {
+        input: &input,
+        cli,
+        current: None,
+        selection: None,
+    };
+
+    if std::io::stdin().is_terminal() {
+        interactive(&mut session, file)
+    } else {
+        scripted(&mut session)
+    }
+}
+
+/// Reads commands from a terminal with line editing and tab completion.
+fn interactive(session: &mut Session<'_>, file: &OmtsFile) -> Result<(), CliError> {
+    let mut editor: Editor<ShellHelper, DefaultHistory> =
+        Editor::new().map_err(|e| readline_error(&e))?;
+    editor.set_helper(Some(ShellHelper::new(file)));
+
+    loop {
+        match editor.readline(&session.prompt()) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    editor
+                        .add_history_entry(line.as_str())
+                        .map_err(|e| readline_error(&e))?;
+                }
+                if session.execute(&line) == Flow::Exit {
+                    return Ok(());
+                }
+            }
+            Err(ReadlineError::Interrupted) => {}
+            Err(ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(readline_error(&e)),
+        }
+    }
+}
+
+/// Reads commands from non-terminal stdin, one per line.
+fn scripted(session: &mut Session<'_>) -> Result<(), CliError> {
+    for line in std::io::stdin().lock().lines() {
+        let line = line.map_err(|e| CliError::IoError {
+            source: "<stdin>".to_owned(),
+            detail: e.to_string(),
+        })?;
+        if session.execute(&line) == Flow::Exit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn readline_error(e: &ReadlineError) -> CliError {
+    CliError::IoError {
+        source: "terminal".to_owned(),
+        detail: e.to_string(),
+    }
+}
+
+/// State of one shell session.
+struct Session<'a> {
+    input: &'a IndexedInput<'a>,
+    cli: &'a Cli,
+    /// Node set with `cd`.
+    current: Option<String>,
+    /// Last extracted subgraph, written by `save`.
+    selection: Option<OmtsFile>,
+}
+
+impl Session<'_> {
+    fn prompt(&self) -> String {
+        match &self.current {
+            Some(id) => format!("omts:{id}> "),
+            None => "omts> ".to_owned(),
+        }
+    }
+
+    /// Parses and runs one input line, reporting any error on stderr.
+    fn execute(&mut self, line: &str) -> Flow {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Flow::Continue;
+        }
+
+        let words = match tokenize(line) {
+            Ok(words) => words,
+            Err(detail) => {
+                eprintln!("error: {detail}");
+                return Flow::Continue;
+            }
+        };
+        let command = match ShellLine::try_parse_from(words) {
+            Ok(parsed) => parsed.command,
+            Err(e) => {
+                // Help and usage errors; clap routes each to stdout or stderr.
+                if e.print().is_err() {
+                    eprintln!("error: could not print usage");
+                }
+                return Flow::Continue;
+            }
+        };
+
+        match self.dispatch(command) {
+            Ok(flow) => flow,
+            Err(e) => {
+                eprintln!("{e}");
+                Flow::Continue
+            }
+        }
+    }
+
+    fn dispatch(&mut self, command: ShellCommand) -> Result<Flow, CliError> {
+        let format = &self.cli.format;
+        match command {
+            ShellCommand::Query { selectors, count } => query::run_indexed(
+                self.input,
+                &selectors.node_type,
+                &selectors.edge_type,
+                &selectors.label,
+                &selectors.identifier,
+                &selectors.jurisdiction,
+                &selectors.name,
+                selectors.where_expr.as_deref(),
+                count,
+                format,
+            )?,
+
+            ShellCommand::Reach {
+                node_id,
+                depth,
+                direction,
+                edge_type,
+                select,
+            } => {
+                let node_id = self.resolve(&node_id)?;
+                if select {
+                    let edge_filter = parse_edge_filter(&edge_type)?;
+                    let region = reachable_subgraph(
+                        self.input.graph()?,
+                        self.input.file()?,
+                        &node_id,
+                        depth.map(|d| d as usize),
+                        reach::to_core_direction(&direction),
+                        edge_filter.as_ref(),
+                    )
+                    .map_err(query_error_to_cli)?;
+                    self.select(region);
+                } else {
+                    reach::run(
+                        self.input,
+                        &node_id,
+                        depth,
+                        &direction,
+                        &edge_type,
+                        false,
+                        &TargetEncoding::Json,
+                        false,
+                        format,
+                    )?;
+                }
+            }
+
+            ShellCommand::Path {
+                from,
+                to,
+                max_paths,
+                max_depth,
+                cost,
+                edge_cost,
+                pattern,
+                edge_type,
+                select,
+            } => {
+                let from = self.resolve(&from)?;
+                let to = self.resolve(&to)?;
+                if select {
+                    let graph = self.input.graph()?;
+                    let file = self.input.file()?;
+                    let paths = path::find_paths(
+                        graph,
+                        Some(file),
+                        &from,
+                        &to,
+                        max_paths,
+                        max_depth,
+                        cost,
+                        &edge_cost,
+                        pattern.as_deref(),
+                        &edge_type,
+                    )?;
+                    self.select(path_subgraph(graph, file, &paths));
+                } else {
+                    path::run(
+                        self.input,
+                        &from,
+                        &to,
+                        max_paths,
+                        max_depth,
+                        cost,
+                        &edge_cost,
+                        pattern.as_deref(),
+                        &edge_type,
+                        false,
+                        &TargetEncoding::Json,
+                        false,
+                        format,
+                    )?;
+                }
+            }
+
+            ShellCommand::Subgraph {
+                node_ids,
+                selectors,
+                expand,
+            } => {
+                let node_ids = node_ids
+                    .iter()
+                    .map(|id| self.resolve(id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let SelectorArgs {
+                    node_type,
+                    edge_type,
+                    label,
+                    identifier,
+                    jurisdiction,
+                    name,
+                    where_expr,
+                } = selectors;
+                let extracted = subgraph::extract(
+                    self.input.file()?,
+                    self.input.graph()?,
+                    &node_ids,
+                    &node_type,
+                    &edge_type,
+                    &label,
+                    &identifier,
+                    &jurisdiction,
+                    &name,
+                    where_expr.as_deref(),
+                    expand,
+                )?;
+                self.select(extracted);
+            }
+
+            ShellCommand::Inspect { metrics, top } => {
+                inspect::run(self.input.file()?, metrics, top, format)?;
+            }
+
+            ShellCommand::Diff {
+                other,
+                ids_only,
+                summary_only,
+                node_type,
+                edge_type,
+                ignore_field,
+                where_expr,
+            } => {
+                let (other_file, _encoding) = io::read_and_parse(
+                    &PathOrStdin::Path(other),
+                    self.cli.max_file_size,
+                    self.cli.verbose,
+                )?;
+                let result = diff::run(
+                    self.input.file()?,
+                    &other_file,
+                    ids_only,
+                    summary_only,
+                    &node_type,
+                    &edge_type,
+                    &ignore_field,
+                    where_expr.as_deref(),
+                    format,
+                    self.cli.verbose,
+                    self.cli.no_color,
+                );
+                // A non-empty diff is the expected outcome here, not an error.
+                match result {
+                    Ok(()) | Err(CliError::DiffHasDifferences) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            ShellCommand::Cd { node_id } => {
+                self.current = match node_id {
+                    Some(id) => {
+                        let id = self.resolve(&id)?;
+                        self.node(&id)?;
+                        Some(id)
+                    }
+                    None => None,
+                };
+            }
+
+            ShellCommand::Neighbours {
+                node_id,
+                direction,
+                edge_type,
+            } => {
+                let node_id = self.resolve(&node_id)?;
+                self.neighbours(&node_id, &direction, &edge_type)?;
+            }
+
+            ShellCommand::Show { id } => {
+                let id = self.resolve(&id)?;
+                self.show(&id)?;
+            }
+
+            ShellCommand::Selection => self.list_selection()?,
+
+            ShellCommand::Save {
+                output,
+                to,
+                compress,
+            } => {
+                let selection = self.selection.clone().ok_or_else(no_selection)?;
+                let counts = (selection.nodes.len(), selection.edges.len());
+                let bytes = subgraph::encode_subgraph(selection, &to, compress)?;
+                std::fs::write(&output, bytes).map_err(|e| CliError::IoError {
+                    source: output.display().to_string(),
+                    detail: e.to_string(),
+                })?;
+                if !self.cli.quiet {
+                    eprintln!(
+                        "saved {} node(s), {} edge(s) to {}",
+                        counts.0,
+                        counts.1,
+                        output.display()
+                    );
+                }
+            }
+
+            ShellCommand::Exit => return Ok(Flow::Exit),
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Replaces `.` with the current node ID.
+    fn resolve(&self, id: &str) -> Result<String, CliError> {
+        if id != CURRENT_NODE {
+            return Ok(id.to_owned());
+        }
+        self.current
+            .clone()
+            .ok_or_else(|| CliError::InvalidArgument {
+                detail: "no current node; use `cd <node>` first".to_owned(),
+            })
+    }
+
+    /// Returns the graph index of node `id`.
+    fn node(&self, id: &str) -> Result<petgraph::stable_graph::NodeIndex, CliError> {
+        self.input
+            .graph()?
+            .node_index(id)
+            .copied()
+            .ok_or_else(|| CliError::NodeNotFound {
+                node_id: id.to_owned(),
+            })
+    }
+
+    fn select(&mut self, extracted: OmtsFile) {
+        if !self.cli.quiet {
+            eprintln!(
+                "selected {} node(s), {} edge(s)",
+                extracted.nodes.len(),
+                extracted.edges.len()
+            );
+        }
+        self.selection = Some(extracted);
+    }
+
+    /// Lists the edges incident to `id` and the node at their other end.
+    fn neighbours(
+        &self,
+        id: &str,
+        direction: &Direction,
+        edge_types: &[String],
+    ) -> Result<(), CliError> {
+        let graph = self.input.graph()?;
+        let file = self.input.file()?;
+        let idx = self.node(id)?;
+        let edge_filter = parse_edge_filter(edge_types)?;
+
+        let directions: &[(PetDirection, &str)] = match direction {
+            Direction::Outgoing => &[(PetDirection::Outgoing, "out")],
+            Direction::Incoming => &[(PetDirection::Incoming, "in")],
+            Direction::Both => &[
+                (PetDirection::Outgoing, "out"),
+                (PetDirection::Incoming, "in"),
+            ],
+        };
+
+        let mut rows: Vec<Neighbour> = Vec::new();
+        for &(dir, label) in directions {
+            for edge in graph.graph().edges_directed(idx, dir) {
+                let weight = edge.weight();
+                if let Some(filter) = &edge_filter {
+                    if !filter.contains(&weight.edge_type) {
+                        continue;
+                    }
+                }
+                let other = match dir {
+                    PetDirection::Outgoing => edge.target(),
+                    PetDirection::Incoming => edge.source(),
+                };
+                let Some(other) = graph.node_weight(other) else {
+                    continue;
+                };
+                rows.push(Neighbour {
+                    direction: label,
+                    edge_id: weight.local_id.clone(),
+                    edge_type: weight.edge_type.as_str().to_owned(),
+                    node_id: other.local_id.clone(),
+                    name: file
+                        .nodes
+                        .get(other.data_index)
+                        .and_then(|n| n.name.clone()),
+                });
+            }
+        }
+        rows.sort_by(|a, b| {
+            (a.direction, &a.edge_type, &a.node_id).cmp(&(b.direction, &b.edge_type, &b.node_id))
+        });
+
+        let mut out = std::io::stdout().lock();
+        match &self.cli.format {
+            OutputFormat::Human => {
+                let mut result = writeln!(out, "DIR\tEDGE\tTYPE\tNODE\tNAME");
+                for row in &rows {
+                    if result.is_err() {
+                        break;
+                    }
+                    result = writeln!(
+                        out,
+                        "{}\t{}\t{}\t{}\t{}",
+                        row.direction,
+                        row.edge_id,
+                        row.edge_type,
+                        row.node_id,
+                        row.name.as_deref().unwrap_or("-")
+                    );
+                }
+                result
+            }
+            OutputFormat::Json => {
+                let neighbours: Vec<serde_json::Value> = rows
+                    .iter()
+                    .map(|row| {
+                        serde_json::json!({
+                            "direction": row.direction,
+                            "edge": row.edge_id,
+                            "type": row.edge_type,
+                            "node": row.node_id,
+                            "name": row.name,
+                        })
+                    })
+                    .collect();
+                let value = serde_json::json!({ "node": id, "neighbours": neighbours });
+                write_json(&mut out, &value)
+            }
+        }
+        .map_err(|e| stdout_error(&e))
+    }
+
+    /// Prints the node, or else the edge, with ID `id` as JSON.
+    fn show(&self, id: &str) -> Result<(), CliError> {
+        let graph = self.input.graph()?;
+        let file = self.input.file()?;
+        let value = match graph.node_index(id) {
+            Some(&idx) => graph
+                .node_weight(idx)
+                .and_then(|w| file.nodes.get(w.data_index))
+                .map(serde_json::to_value),
+            None => file
+                .edges
+                .iter()
+                .find(|e| e.id.to_string() == id)
+                .map(serde_json::to_value),
+        };
+        let value = value
+            .ok_or_else(|| CliError::NodeNotFound {
+                node_id: id.to_owned(),
+            })?
+            .map_err(|e| CliError::InternalError {
+                detail: e.to_string(),
+            })?;
+        write_json(&mut std::io::stdout().lock(), &value).map_err(|e| stdout_error(&e))
+    }
+
+    /// Prints the node IDs of the selection.
+    fn list_selection(&self) -> Result<(), CliError> {
+        let selection = self.selection.as_ref().ok_or_else(no_selection)?;
+        let mut node_ids: Vec<String> = selection.nodes.iter().map(|n| n.id.to_string()).collect();
+        node_ids.sort();
+
+        let mut out = std::io::stdout().lock();
+        match &self.cli.format {
+            OutputFormat::Human => node_ids.iter().try_for_each(|id| writeln!(out, "{id}")),
+            OutputFormat::Json => write_json(
+                &mut out,
+                &serde_json::json!({
+                    "node_ids": node_ids,
+                    "count": node_ids.len(),
+                    "edges": selection.edges.len(),
+                }),
+            ),
+        }
+        .map_err(|e| stdout_error(&e))
+    }
+}
+
+/// One row of `neighbours` output.
+struct Neighbour {
+    direction: &'static str,
+    edge_id: String,
+    edge_type: String,
+    node_id: String,
+    name: Option<String>,
+}
+
+fn write_json<W: std::io::Write>(w: &mut W, value: &serde_json::Value) -> std::io::Result<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    writeln!(w, "{json}")
+}
+
+fn stdout_error(e: &std::io::Error) -> CliError {
+    CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    }
+}
+
+fn no_selection() -> CliError {
+    CliError::NoResults {
+        detail: "nothing selected; use `subgraph`, `reach --select`, or `path --select`".to_owned(),
+    }
+}
+
+/// Converts a [`QueryError`] to the appropriate [`CliError`].
+fn query_error_to_cli(e: QueryError) -> CliError {
+    match e {
+        QueryError::NodeNotFound(id) => CliError::NodeNotFound { node_id: id },
+        QueryError::EmptyResult => CliError::NoResults {
+            detail: "no elements matched the given selectors".to_owned(),
+        },
+    }
+}
@@ -8,6 +8,7 @@
 //! Flags:
 //! - `--node-type`, `--edge-type`, `--label`, `--identifier`, `--jurisdiction`,
 //!   `--name` (repeatable selector flags)
+//! - `--where <EXPR>`: boolean selector expression, combined with the flags by AND.
 //! - `--expand <n>` (default 0): include neighbours up to `n` hops from the
 //!   seed nodes before computing the induced subgraph.
 //! - `--to <encoding>` (default json): output encoding (`json` or `cbor`).
@@ -24,10 +25,13 @@ use std::io::Write as _;
 
 use omts_core::OmtsFile;
 use omts_core::graph::queries::Direction as CoreDirection;
-use omts_core::graph::{QueryError, build_graph, ego_graph, induced_subgraph, selector_subgraph};
+use omts_core::graph::{
+    OmtsGraph, QueryError, build_graph, ego_graph, induced_subgraph, selector_subgraph,
+};
 use omts_core::newtypes::CalendarDate;
 
 use crate::TargetEncoding;
+use crate::cmd::index::IndexedInput;
 use crate::cmd::init::today_string;
 use crate::cmd::selectors::build_selector_set;
 use crate::error::CliError;
@@ -64,28 +68,162 @@ pub fn run(
     identifiers: &[String],
     jurisdictions: &[String],
     names: &[String],
+    where_expr: Option<&str>,
     expand: u32,
     to: &TargetEncoding,
     compress: bool,
 ) -> Result<(), CliError> {
+    let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
+        detail: e.to_string(),
+    })?;
+    let subgraph_file = extract(
+        file,
+        &graph,
+        node_ids,
+        node_types,
+        edge_types,
+        labels,
+        identifiers,
+        jurisdictions,
+        names,
+        where_expr,
+        expand,
+    )?;
+    write_subgraph(subgraph_file, to, compress)
+}
+
+/// Runs the `subgraph` command against an [`IndexedInput`].
+///
+/// The graph is expanded from a fresh index sidecar when one exists instead
+/// of being rebuilt from the parsed file. Otherwise identical to [`run`].
+///
+/// # Errors
+///
+/// Same as [`run`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_indexed(
+    input: &IndexedInput<'_>,
+    node_ids: &[String],
+    node_types: &[String],
+    edge_types: &[String],
+    labels: &[String],
+    identifiers: &[String],
+    jurisdictions: &[String],
+    names: &[String],
+    where_expr: Option<&str>,
+    expand: u32,
+    to: &TargetEncoding,
+    compress: bool,
+) -> Result<(), CliError> {
+    let subgraph_file = extract(
+        input.file()?,
+        input.graph()?,
+        node_ids,
+        node_types,
+        edge_types,
+        labels,
+        identifiers,
+        jurisdictions,
+        names,
+        where_expr,
+        expand,
+    )?;
+    write_subgraph(subgraph_file, to, compress)
+}
+
+/// Extracts the subgraph selected by `node_ids` and the selector flags.
+///
+/// Shared by [`run`], [`run_indexed`], and `omts shell`. See [`run`] for the
+/// seeding and expansion rules.
+///
+/// # Errors
+///
+/// Same as [`run`], except that nothing is serialized.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extract(
+    file: &OmtsFile,
+    graph: &OmtsGraph,
+    node_ids: &[String],
+    node_types: &[String],
+    edge_types: &[String],
+    labels: &[String],
+    identifiers: &[String],
+    jurisdictions: &[String],
+    names: &[String],
+    where_expr: Option<&str>,
+    expand: u32,
+) -> Result<OmtsFile, CliError> {
     let has_selectors = !node_types.is_empty()
         || !edge_types.is_empty()
         || !labels.is_empty()
         || !identifiers.is_empty()
         || !jurisdictions.is_empty()
-        || !names.is_empty();
+        || !names.is_empty()
+        || where_expr.is_some();
 
     if node_ids.is_empty() && !has_selectors {
         return Err(CliError::InvalidArgument {
             detail: "at least one node ID or selector flag is required \
-                     (--node-type, --edge-type, --label, --identifier, --jurisdiction, --name)"
+                     (--node-type, --edge-type, --label, --identifier, --jurisdiction, --name, \
+                     --where)"
                 .to_owned(),
         });
     }
 
-    let graph = build_graph(file).map_err(|e| CliError::GraphBuildError {
-        detail: e.to_string(),
-    })?;
+    let seed_ids = seed_ids(
+        file,
+        graph,
+        node_ids,
+        node_types,
+        edge_types,
+        labels,
+        identifiers,
+        jurisdictions,
+        names,
+        where_expr,
+    )?;
+
+    // Expand and extract induced subgraph.
+    let ids = if expand == 0 {
+        seed_ids
+    } else {
+        expand_ids(graph, file, &seed_ids, expand, CoreDirection::Both)?
+    };
+    let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+    induced_subgraph(graph, file, &id_refs).map_err(query_error_to_cli)
+}
+
+/// Collects the IDs of the nodes selected by explicit `node_ids` and the
+/// selector flags (nodes matched directly, and endpoints of matched edges).
+///
+/// Returns an empty set when neither is given. Shared by [`extract`] and
+/// `omts redact`.
+///
+/// # Errors
+///
+/// - [`CliError`] exit code 2 if a selector flag is malformed.
+/// - [`CliError`] exit code 1 if any explicit node ID is not found in the
+///   graph, or if the selectors matched no elements.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn seed_ids(
+    file: &OmtsFile,
+    graph: &OmtsGraph,
+    node_ids: &[String],
+    node_types: &[String],
+    edge_types: &[String],
+    labels: &[String],
+    identifiers: &[String],
+    jurisdictions: &[String],
+    names: &[String],
+    where_expr: Option<&str>,
+) -> Result<HashSet<String>, CliError> {
+    let has_selectors = !node_types.is_empty()
+        || !edge_types.is_empty()
+        || !labels.is_empty()
+        || !identifiers.is_empty()
+        || !jurisdictions.is_empty()
+        || !names.is_empty()
+        || where_expr.is_some();
 
     // Collect seed node IDs from selectors (expand=0 to get just the seeds).
     let mut seed_ids: HashSet<String> = HashSet::new();
@@ -98,9 +236,10 @@ pub fn run(
             identifiers,
             jurisdictions,
             names,
+            where_expr,
         )?;
         let selector_result =
-            selector_subgraph(&graph, file, &selector_set, 0).map_err(|e| match e {
+            selector_subgraph(graph, file, &selector_set, 0).map_err(|e| match e {
                 QueryError::EmptyResult => CliError::NoResults {
                     detail: "no nodes or edges matched the given selectors".to_owned(),
                 },
@@ -121,14 +260,46 @@ pub fn run(
         seed_ids.insert(id.clone());
     }
 
-    // Expand and extract induced subgraph.
-    let mut subgraph_file = if expand == 0 {
-        let id_refs: Vec<&str> = seed_ids.iter().map(String::as_str).collect();
-        induced_subgraph(&graph, file, &id_refs).map_err(query_error_to_cli)?
-    } else {
-        compute_expanded_subgraph(&graph, file, &seed_ids, expand)?
-    };
+    Ok(seed_ids)
+}
 
+/// Writes an extracted subgraph to stdout as a valid `.omts` file.
+///
+/// Encodes with [`encode_subgraph`]. Shared with `reach --subgraph` and
+/// `path --subgraph`.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 2 if encoding or the stdout write fails.
+pub(crate) fn write_subgraph(
+    subgraph_file: OmtsFile,
+    to: &TargetEncoding,
+    compress: bool,
+) -> Result<(), CliError> {
+    let bytes = encode_subgraph(subgraph_file, to, compress)?;
+
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+
+    out.write_all(&bytes).map_err(|e| CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    })
+}
+
+/// Encodes an extracted subgraph as the bytes of a valid `.omts` file.
+///
+/// Stamps `snapshot_date` with today's date, serializes with `to` (and zstd
+/// when `compress` is `true`), and appends a newline to uncompressed JSON.
+///
+/// # Errors
+///
+/// Returns [`CliError`] exit code 2 if the clock or serialization fails.
+pub(crate) fn encode_subgraph(
+    mut subgraph_file: OmtsFile,
+    to: &TargetEncoding,
+    compress: bool,
+) -> Result<Vec<u8>, CliError> {
     let today = today_string().map_err(|e| CliError::IoError {
         source: "system clock".to_owned(),
         detail: e,
@@ -139,28 +310,17 @@ pub fn run(
             detail: format!("generated date is invalid: {e}"),
         })?;
 
-    let bytes = serialize(&subgraph_file, to, compress)?;
-
-    let stdout = std::io::stdout();
-    let mut out = stdout.lock();
-
-    out.write_all(&bytes).map_err(|e| CliError::IoError {
-        source: "stdout".to_owned(),
-        detail: e.to_string(),
-    })?;
+    let mut bytes = serialize(&subgraph_file, to, compress)?;
 
     // Append a trailing newline for uncompressed JSON so the shell prompt
     // appears on a new line.  Binary outputs (CBOR, any compressed payload)
     // must not have an appended newline because that would corrupt the stream.
     let is_text_output = matches!(to, TargetEncoding::Json) && !compress;
     if is_text_output {
-        out.write_all(b"\n").map_err(|e| CliError::IoError {
-            source: "stdout".to_owned(),
-            detail: e.to_string(),
-        })?;
+        bytes.push(b'\n');
     }
 
-    Ok(())
+    Ok(bytes)
 }
 
 /// Serializes `file` to bytes using the requested encoding and optional
@@ -191,32 +351,32 @@ fn serialize(file: &OmtsFile, to: &TargetEncoding, compress: bool) -> Result<Vec
     }
 }
 
-/// Computes the induced subgraph after expanding each node in `seed_ids` by
-/// `expand` hops in both directions.
+/// Expands `seed_ids` by up to `expand` hops along `direction`.
+///
+/// Returns the union of the seeds' ego-graph node sets. Shared by
+/// [`extract`] (which expands in both directions) and `omts redact`.
+///
+/// # Errors
 ///
-/// Algorithm:
-/// 1. For each node in `seed_ids`, compute the ego-graph with radius `expand`
-///    and direction `Both`.
-/// 2. Union all ego-graph node sets.
-/// 3. Extract the induced subgraph of the union.
-fn compute_expanded_subgraph(
-    graph: &omts_core::graph::OmtsGraph,
+/// Returns [`CliError`] exit code 1 if a seed is not found in the graph.
+pub(crate) fn expand_ids(
+    graph: &OmtsGraph,
     file: &OmtsFile,
     seed_ids: &HashSet<String>,
     expand: u32,
-) -> Result<OmtsFile, CliError> {
+    direction: CoreDirection,
+) -> Result<HashSet<String>, CliError> {
     let mut expanded_ids: HashSet<String> = HashSet::new();
 
     for id in seed_ids {
-        let ego = ego_graph(graph, file, id, expand as usize, CoreDirection::Both)
-            .map_err(query_error_to_cli)?;
+        let ego =
+            ego_graph(graph, file, id, expand as usize, direction).map_err(query_error_to_cli)?;
         for node in &ego.nodes {
             expanded_ids.insert(node.id.to_string());
         }
     }
 
-    let id_refs: Vec<&str> = expanded_ids.iter().map(String::as_str).collect();
-    induced_subgraph(graph, file, &id_refs).map_err(query_error_to_cli)
+    Ok(expanded_ids)
 }
 
 /// Converts a [`QueryError`] to the appropriate [`CliError`].
@@ -275,6 +435,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             0,
             &TargetEncoding::Json,
             false,
@@ -295,6 +456,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             1,
             &TargetEncoding::Json,
             false,
@@ -315,6 +477,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             0,
             &TargetEncoding::Json,
             false,
@@ -338,6 +501,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             0,
             &TargetEncoding::Json,
             false,
@@ -359,6 +523,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             0,
             &TargetEncoding::Json,
             false,
@@ -380,6 +545,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
         )
         .expect("build selector set");
 
@@ -406,6 +572,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
         )
         .expect("build selector set");
 
@@ -434,6 +601,7 @@ mod tests {
             &[],
             &[],
             &[],
+            None,
         )
         .expect("build selector set");
 
@@ -461,6 +629,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             0,
             &TargetEncoding::Json,
             false,
@@ -481,6 +650,7 @@ mod tests {
             &empty(),
             &empty(),
             &empty(),
+            None,
             0,
             &TargetEncoding::Json,
             false,
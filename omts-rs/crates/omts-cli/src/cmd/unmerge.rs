@@ -0,0 +1,127 @@
+//! Implementation of `omts unmerge <file>`.
+//!
+//! Undoes a merge using the `_provenance` records written by
+//! `omts merge --provenance` (merge-semantics Section 4.6):
+//!
+//! - `--node <ID>` (repeatable): split each merged node back into the source
+//!   nodes it was built from. Each constituent gets its own identifiers and
+//!   its own values for conflicting properties; edges are rewired to the
+//!   constituent that contributed them.
+//! - `--source <SOURCE>`: rebuild one input's contribution with its original
+//!   node and edge IDs. The input is named by its label (`file_0`, `file_1`,
+//!   ...) or by the file name `merge` recorded for it.
+//!
+//! Output: the rewritten file on stdout and a one-line summary on stderr.
+//!
+//! Exit codes: 0 = success, 1 = `--node` ID not in the file, 2 = parse
+//! failure, missing or malformed provenance, unknown or ambiguous source, or
+//! write error.
+use std::io::Write as _;
+
+use omts_core::merge::MergeMetadata;
+use omts_core::newtypes::NodeId;
+use omts_core::{OmtsFile, UnmergeError, extract_source, split_nodes};
+
+use crate::TargetEncoding;
+use crate::cmd::merge::encode_output;
+use crate::error::CliError;
+
+/// Runs the `unmerge` command.
+///
+/// # Errors
+///
+/// - [`CliError::NodeNotFound`] — a `--node` ID is not in the file.
+/// - [`CliError::InvalidArgument`] — provenance is missing or malformed, or
+///   `source` names no input, or several inputs, of the merge. The message
+///   lists the inputs by label and file name.
+/// - [`CliError::IoError`] — serialization or a stdout write fails.
+pub fn run(
+    file: &OmtsFile,
+    nodes: &[String],
+    source: Option<&str>,
+    to: &TargetEncoding,
+    compress: bool,
+) -> Result<(), CliError> {
+    let output = match source {
+        Some(label) => extract_source(file, label),
+        None => {
+            let ids = nodes
+                .iter()
+                .map(|id| {
+                    NodeId::try_from(id.as_str()).map_err(|e| CliError::InvalidArgument {
+                        detail: format!("invalid node ID `{id}`: {e}"),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            split_nodes(file, &ids)
+        }
+    }
+    .map_err(|e| unmerge_error(e, file))?;
+
+    let bytes = encode_output(&output.file, to, compress)?;
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    out.write_all(&bytes).map_err(|e| stdout_error(&e))?;
+    // Binary outputs must not have an appended newline.
+    if matches!(to, TargetEncoding::Json) && !compress {
+        out.write_all(b"\n").map_err(|e| stdout_error(&e))?;
+    }
+
+    eprintln!(
+        "unmerge: restored {} node(s), wrote {} edge(s) from provenance",
+        output.nodes_restored, output.edges_rewired
+    );
+    Ok(())
+}
+
+/// Maps an engine error to its CLI error.
+fn unmerge_error(e: UnmergeError, file: &OmtsFile) -> CliError {
+    match e {
+        UnmergeError::NodeNotFound(node_id) => CliError::NodeNotFound { node_id },
+        UnmergeError::MissingProvenance(_) => CliError::InvalidArgument {
+            detail: format!("{e} (re-run `omts merge` with --provenance)"),
+        },
+        UnmergeError::UnknownSource(_) | UnmergeError::AmbiguousSource { .. } => {
+            CliError::InvalidArgument {
+                detail: format!("{e}; inputs: {}", input_list(file)),
+            }
+        }
+        UnmergeError::InvalidProvenance(_) => CliError::InvalidArgument {
+            detail: e.to_string(),
+        },
+    }
+}
+
+/// `file_0 (a.omts), file_1 (b.omts)` — the inputs recorded in
+/// `merge_metadata`, with their file names where known.
+fn input_list(file: &OmtsFile) -> String {
+    let metadata = file.extra.get("merge_metadata").and_then(|m| {
+        serde_json::from_value::<MergeMetadata>(serde_json::Value::from(m.clone())).ok()
+    });
+    let Some(metadata) = metadata else {
+        return "unknown (no merge_metadata)".to_owned();
+    };
+    let inputs: Vec<String> = metadata
+        .source_files
+        .iter()
+        .map(|label| {
+            let name = label
+                .strip_prefix("file_")
+                .and_then(|i| i.parse::<usize>().ok())
+                .and_then(|i| metadata.source_names.get(i));
+            match name {
+                Some(name) => format!("{label} ({name})"),
+                None => label.clone(),
+            }
+        })
+        .collect();
+    inputs.join(", ")
+}
+
+/// Wraps a stdout write failure.
+fn stdout_error(e: &std::io::Error) -> CliError {
+    CliError::IoError {
+        source: "stdout".to_owned(),
+        detail: e.to_string(),
+    }
+}
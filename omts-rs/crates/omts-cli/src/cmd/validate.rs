@@ -93,7 +93,7 @@ pub fn run(
 ///
 /// Level 1 runs L1 rules only; level 2 adds L2; level 3 adds L3.
 /// Callers guarantee that `level` is in the range `1..=3`.
-fn config_for_level(level: u8) -> ValidationConfig {
+pub(crate) fn config_for_level(level: u8) -> ValidationConfig {
     match level {
         1 => ValidationConfig {
             run_l1: true,
@@ -81,13 +81,43 @@ pub fn read_and_parse(
     max_file_size: u64,
     verbose: bool,
 ) -> Result<(OmtsFile, Encoding), CliError> {
-    let source_label = source_label(source);
     let bytes = read_input_bytes(source, max_file_size)?;
+    parse_bytes(&bytes, source, max_file_size, verbose)
+}
 
+/// Parses bytes already read from `source` as an OMTS file.
+///
+/// Steps 2 and 3 of [`read_and_parse`], for callers that need the raw bytes
+/// as well (e.g. to hash them for an index sidecar).
+///
+/// # Errors
+///
+/// Returns [`CliError`] (exit code 2) for any parse failure.
+pub fn parse_bytes(
+    bytes: &[u8],
+    source: &PathOrStdin,
+    max_file_size: u64,
+    verbose: bool,
+) -> Result<(OmtsFile, Encoding), CliError> {
+    parse_labelled_bytes(bytes, &source_label(source), max_file_size, verbose)
+}
+
+/// Parses bytes that did not come from a [`PathOrStdin`] source, such as an
+/// HTTP request body; `label` names the source in error messages.
+///
+/// # Errors
+///
+/// Returns [`CliError`] (exit code 2) for any parse failure.
+pub fn parse_labelled_bytes(
+    bytes: &[u8],
+    label: &str,
+    max_file_size: u64,
+    verbose: bool,
+) -> Result<(OmtsFile, Encoding), CliError> {
     let max_decompressed = max_decompressed_limit(max_file_size);
 
     let (file, encoding) =
-        parse_omts(&bytes, max_decompressed).map_err(|e| decode_error_to_cli(e, &source_label))?;
+        parse_omts(bytes, max_decompressed).map_err(|e| decode_error_to_cli(e, label))?;
 
     if verbose {
         let enc_name = match encoding {
@@ -115,7 +145,7 @@ fn max_decompressed_limit(max_file_size: u64) -> usize {
 }
 
 /// Returns a human-readable label for the source.
-fn source_label(source: &PathOrStdin) -> String {
+pub(crate) fn source_label(source: &PathOrStdin) -> String {
     match source {
         PathOrStdin::Path(path) => path.display().to_string(),
         PathOrStdin::Stdin => "-".to_owned(),
@@ -188,7 +218,7 @@ fn read_file_bytes(path: &PathBuf, max_size: u64) -> Result<Vec<u8>, CliError> {
 }
 
 /// Maps a `std::io::Error` arising from a disk-file operation to a [`CliError`].
-fn io_error_to_cli(e: &std::io::Error, path: &Path) -> CliError {
+pub(crate) fn io_error_to_cli(e: &std::io::Error, path: &Path) -> CliError {
     match e.kind() {
         std::io::ErrorKind::NotFound => CliError::FileNotFound {
             path: path.to_path_buf(),
@@ -5,8 +5,9 @@ pub mod format;
 pub mod io;
 
 pub use cli::{
-    Cli, Command, Direction, DisclosureScope, ExportFormat, ImportFormat, MergeStrategy,
-    OutputFormat, PathOrStdin, TargetEncoding,
+    Cli, Command, ConflictsCommand, Direction, DisclosureScope, ExportFormat, ImportFormat,
+    MergeStrategy, OutputFormat, PathCost, PathOrStdin, ResolvePolicy, SegmentWeight,
+    TargetEncoding,
 };
 
 use clap::Parser;
@@ -31,6 +32,14 @@ fn main() {
 /// caller is responsible for printing the error message and exiting with the
 /// appropriate exit code.
 fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
+    if cli.as_of.is_some() && !cli.command.supports_as_of() {
+        return Err(error::CliError::InvalidArgument {
+            detail: "--as-of is only supported by query, reach, path, subgraph, inspect, \
+                     segment, and export"
+                .to_owned(),
+        });
+    }
+
     match &cli.command {
         Command::Validate { file, level } => {
             let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
@@ -44,9 +53,9 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
             )
         }
 
-        Command::Inspect { file } => {
-            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
-            cmd::inspect::run(&omts_file, &cli.format)
+        Command::Inspect { file, metrics, top } => {
+            let omts_file = read_view(file, cli)?;
+            cmd::inspect::run(&omts_file, *metrics, *top, &cli.format)
         }
 
         Command::Convert {
@@ -62,14 +71,36 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
 
         Command::Init { example } => cmd::init::run(*example),
 
+        Command::Index { file } => {
+            cmd::index::run(file, cli.max_file_size, cli.verbose, &cli.format)
+        }
+
+        Command::Shell { file } => cmd::shell::run(file, cli),
+
+        Command::Serve { files, host, port } => cmd::serve::run(files, host, *port, cli),
+
         Command::Reach {
             file,
             node_id,
             depth,
             direction,
+            edge_type,
+            subgraph,
+            to,
+            compress,
         } => {
-            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
-            cmd::reach::run(&omts_file, node_id, *depth, direction, &cli.format)
+            let input = cmd::index::IndexedInput::open(file, cli)?;
+            cmd::reach::run(
+                &input,
+                node_id,
+                *depth,
+                direction,
+                edge_type,
+                *subgraph,
+                to,
+                *compress,
+                &cli.format,
+            )
         }
 
         Command::Path {
@@ -78,9 +109,30 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
             to,
             max_paths,
             max_depth,
+            cost,
+            edge_cost,
+            pattern,
+            edge_type,
+            subgraph,
+            encoding,
+            compress,
         } => {
-            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
-            cmd::path::run(&omts_file, from, to, *max_paths, *max_depth, &cli.format)
+            let input = cmd::index::IndexedInput::open(file, cli)?;
+            cmd::path::run(
+                &input,
+                from,
+                to,
+                *max_paths,
+                *max_depth,
+                *cost,
+                edge_cost,
+                pattern.as_deref(),
+                edge_type,
+                *subgraph,
+                encoding,
+                *compress,
+                &cli.format,
+            )
         }
 
         Command::Subgraph {
@@ -92,13 +144,14 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
             identifier,
             jurisdiction,
             name,
+            where_expr,
             expand,
             to,
             compress,
         } => {
-            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
-            cmd::subgraph::run(
-                &omts_file,
+            let input = cmd::index::IndexedInput::open(file, cli)?;
+            cmd::subgraph::run_indexed(
+                &input,
                 node_ids,
                 node_type,
                 edge_type,
@@ -106,6 +159,7 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
                 identifier,
                 jurisdiction,
                 name,
+                where_expr.as_deref(),
                 *expand,
                 to,
                 *compress,
@@ -115,25 +169,151 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
         Command::Merge {
             files,
             strategy,
+            primary_perspective,
+            resolve,
+            explain,
+            provenance,
+            previous,
+            enrichment_base,
+            pre_enrichment,
+            dry_run,
             to,
             compress,
         } => cmd::merge::run(
             files,
             strategy,
+            primary_perspective.as_ref(),
+            resolve,
+            *explain,
+            *provenance,
+            previous.as_ref(),
+            enrichment_base.as_ref(),
+            *pre_enrichment,
+            *dry_run,
             to,
             *compress,
             cli.max_file_size,
             cli.verbose,
+            &cli.format,
         ),
 
+        Command::Dedup {
+            file,
+            apply,
+            suggest,
+            threshold,
+            to,
+            compress,
+        } => {
+            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+            let config = omts_core::DedupConfig {
+                threshold: *threshold,
+                apply: *apply,
+                suggest: *suggest,
+            };
+            cmd::dedup::run(&omts_file, &config, to, *compress, &cli.format)
+        }
+
+        Command::Unmerge {
+            file,
+            node,
+            source,
+            to,
+            compress,
+        } => {
+            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+            cmd::unmerge::run(&omts_file, node, source.as_deref(), to, *compress)
+        }
+
+        Command::Conflicts { command } => match command {
+            ConflictsCommand::List {
+                file,
+                field,
+                node_type,
+                source,
+                csv,
+            } => {
+                let (omts_file, _encoding) =
+                    io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+                let filter = omts_core::ConflictFilter {
+                    fields: field.clone(),
+                    node_types: node_type.clone(),
+                    sources: source.clone(),
+                };
+                cmd::conflicts::list(&omts_file, &filter, *csv, &cli.format)
+            }
+            ConflictsCommand::Resolve {
+                file,
+                decisions,
+                to,
+                compress,
+            } => {
+                let (omts_file, _encoding) =
+                    io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+                cmd::conflicts::resolve(&omts_file, decisions, to, *compress)
+            }
+        },
+
         Command::Redact {
             file,
+            node_ids,
             scope,
+            node_type,
+            edge_type,
+            label,
+            identifier,
+            jurisdiction,
+            name,
+            where_expr,
+            expand,
+            direction,
+            policy,
+            report,
             to,
             compress,
         } => {
             let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
-            cmd::redact::run(&omts_file, scope, to, *compress)
+            let retain_ids = cmd::redact::retain_set(
+                &omts_file,
+                node_ids,
+                node_type,
+                edge_type,
+                label,
+                identifier,
+                jurisdiction,
+                name,
+                where_expr.as_deref(),
+                *expand,
+                direction,
+            )?;
+            cmd::redact::run(
+                &omts_file,
+                scope,
+                &retain_ids,
+                policy.as_deref(),
+                report.as_deref(),
+                &cli.format,
+                to,
+                *compress,
+            )
+        }
+
+        Command::ResolveBoundary { file, candidates } => {
+            let (redacted, _enc) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+            let (candidates, _enc) =
+                io::read_and_parse(candidates, cli.max_file_size, cli.verbose)?;
+            cmd::resolve_boundary::run(&redacted, &candidates, &cli.format)
+        }
+
+        Command::AuditDisclosure { file, scope } => {
+            let (omts_file, _enc) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+            cmd::audit_disclosure::run(
+                &omts_file,
+                scope.as_ref(),
+                &cli.format,
+                cli.quiet,
+                cli.no_color,
+            )
         }
 
         Command::Diff {
@@ -144,6 +324,7 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
             node_type,
             edge_type,
             ignore_field,
+            where_expr,
         } => {
             let (file_a, _enc_a) = io::read_and_parse(a, cli.max_file_size, cli.verbose)?;
             let (file_b, _enc_b) = io::read_and_parse(b, cli.max_file_size, cli.verbose)?;
@@ -155,6 +336,7 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
                 node_type,
                 edge_type,
                 ignore_field,
+                where_expr.as_deref(),
                 &cli.format,
                 cli.verbose,
                 cli.no_color,
@@ -169,22 +351,46 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
             identifier,
             jurisdiction,
             name,
+            where_expr,
             count,
         } => {
-            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
-            cmd::query::run(
-                &omts_file,
+            let input = cmd::index::IndexedInput::open(file, cli)?;
+            cmd::query::run_indexed(
+                &input,
                 node_type,
                 edge_type,
                 label,
                 identifier,
                 jurisdiction,
                 name,
+                where_expr.as_deref(),
                 *count,
                 &cli.format,
             )
         }
 
+        Command::Segment {
+            file,
+            edge_type,
+            weight,
+            write_labels,
+            label_key,
+            to,
+            compress,
+        } => {
+            let omts_file = read_view(file, cli)?;
+            cmd::segment::run(
+                &omts_file,
+                edge_type,
+                *weight,
+                *write_labels,
+                label_key,
+                to,
+                *compress,
+                &cli.format,
+            )
+        }
+
         Command::Import {
             file,
             input_format,
@@ -197,12 +403,22 @@ fn dispatch(cli: &Cli) -> Result<(), error::CliError> {
             output_format,
             output,
         } => {
-            let (omts_file, _encoding) = io::read_and_parse(file, cli.max_file_size, cli.verbose)?;
+            let omts_file = read_view(file, cli)?;
             cmd::export::run(&omts_file, output_format, output.as_deref())
         }
     }
 }
 
+/// Reads and parses `source`, then applies the global `--as-of` projection
+/// when one was requested.
+fn read_view(source: &PathOrStdin, cli: &Cli) -> Result<omts_core::OmtsFile, error::CliError> {
+    let (omts_file, _encoding) = io::read_and_parse(source, cli.max_file_size, cli.verbose)?;
+    Ok(match &cli.as_of {
+        Some(date) => omts_core::as_of(&omts_file, date),
+        None => omts_file,
+    })
+}
+
 /// Resets `SIGPIPE` to its default disposition (`SIG_DFL`).
 ///
 /// Rust's runtime ignores `SIGPIPE` by default, which causes programs that
@@ -0,0 +1,153 @@
+//! Integration tests for `omts audit-disclosure`.
+#![allow(clippy::expect_used)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+/// A merged internal file: the edge recorded a conflict on `annual_value`
+/// and the merge metadata names the input paths.
+const MERGED: &str = r#"{
+    "omts_version": "1.0.0",
+    "snapshot_date": "2026-02-19",
+    "file_salt": "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+    "disclosure_scope": "internal",
+    "merge_metadata": {
+        "source_files": ["/srv/exports/erp.omts", "/srv/exports/crm.omts"],
+        "reporting_entities": [],
+        "timestamp": "2026-02-19T00:00:00Z",
+        "merged_node_count": 2,
+        "merged_edge_count": 1,
+        "conflict_count": 1
+    },
+    "nodes": [
+        { "id": "org-a", "type": "organization", "name": "Buyer",
+          "identifiers": [{ "scheme": "lei", "value": "5493006MHB84DD0ZWV18" }] },
+        { "id": "org-b", "type": "organization", "name": "Supplier",
+          "identifiers": [{ "scheme": "duns", "value": "081466849" }] }
+    ],
+    "edges": [
+        { "id": "e-1", "type": "supplies", "source": "org-b", "target": "org-a",
+          "properties": {
+              "_conflicts": [
+                  { "field": "annual_value", "values": [
+                      { "value": 1000000, "source_file": "file_0" },
+                      { "value": 1250000, "source_file": "file_1" }
+                  ] }
+              ]
+          } }
+    ]
+}"#;
+
+fn audit(args: &[&str], file: &Path) -> std::process::Output {
+    Command::new(omts_bin())
+        .args(args)
+        .arg("audit-disclosure")
+        .arg(file)
+        .output()
+        .expect("run omts audit-disclosure")
+}
+
+/// Redacts `input` to `scope`, writing the result into `dir`.
+fn redact(dir: &Path, input: &Path, scope: &str, retain: &str) -> PathBuf {
+    let out = Command::new(omts_bin())
+        .args(["redact", "--scope", scope])
+        .arg(input)
+        .arg(retain)
+        .output()
+        .expect("run omts redact");
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let redacted = dir.join("redacted.omts");
+    std::fs::write(&redacted, &out.stdout).expect("write redacted");
+    redacted
+}
+
+#[test]
+fn audit_disclosure_passes_a_clean_redaction() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let redacted = redact(
+        dir.path(),
+        &fixture("redact-internal.omts"),
+        "public",
+        "org-001",
+    );
+    let out = audit(&[], &redacted);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("0 errors, 0 warnings"), "stderr: {stderr}");
+}
+
+#[test]
+fn audit_disclosure_finds_leaks_that_survive_redaction() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let merged = dir.path().join("merged.omts");
+    std::fs::write(&merged, MERGED).expect("write merged");
+    let redacted = redact(dir.path(), &merged, "public", "org-a");
+
+    let out = audit(&["--format", "json"], &redacted);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let codes: Vec<String> = stderr
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|v| v["rule_id"].as_str().map(str::to_owned))
+        .collect();
+    assert_eq!(codes, ["SDA-05", "SDA-05", "SDA-03"], "stderr: {stderr}");
+}
+
+#[test]
+fn audit_disclosure_scope_flag_sets_the_audited_scope() {
+    let input = fixture("redact-internal.omts");
+    let run = |scope: &str| {
+        Command::new(omts_bin())
+            .args(["audit-disclosure", "--scope", scope])
+            .arg(&input)
+            .output()
+            .expect("run omts audit-disclosure")
+    };
+
+    // Person nodes may go to partners.
+    assert_eq!(run("partner").status.code(), Some(0));
+
+    let out = run("public");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("SDA-01"), "stderr: {stderr}");
+    assert!(stderr.contains("person-001"), "stderr: {stderr}");
+}
+
+#[test]
+fn audit_disclosure_without_any_scope_exits_2() {
+    let out = audit(&[], &fixture("minimal.omts"));
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--scope"), "stderr: {stderr}");
+}
@@ -0,0 +1,168 @@
+//! Integration tests for `omts conflicts`.
+#![allow(clippy::expect_used)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Writes a one-supplier file; both copies share a DUNS number but disagree
+/// on `name`.
+fn supplier_file(dir: &Path, file_name: &str, salt: char, node_id: &str, name: &str) -> PathBuf {
+    let file = serde_json::json!({
+        "omts_version": "1.0.0",
+        "snapshot_date": "2026-02-20",
+        "file_salt": salt.to_string().repeat(64),
+        "nodes": [{
+            "id": node_id,
+            "type": "organization",
+            "name": name,
+            "identifiers": [{ "scheme": "duns", "value": "081466849" }]
+        }],
+        "edges": []
+    });
+    let path = dir.join(file_name);
+    std::fs::write(&path, file.to_string()).expect("write input");
+    path
+}
+
+/// Merges two suppliers with conflicting names and returns the merged path.
+fn merged(dir: &Path) -> PathBuf {
+    let a = supplier_file(dir, "a.omts", 'a', "sup-a", "Acme Metals");
+    let b = supplier_file(dir, "b.omts", 'b', "sup-b", "Acme Metals, Inc.");
+    let out = Command::new(omts_bin())
+        .arg("merge")
+        .arg(&a)
+        .arg(&b)
+        .output()
+        .expect("run omts merge");
+    assert_eq!(out.status.code(), Some(0));
+    let path = dir.join("merged.omts");
+    std::fs::write(&path, &out.stdout).expect("write merged");
+    path
+}
+
+fn conflicts(args: &[&str], file: &Path) -> std::process::Output {
+    Command::new(omts_bin())
+        .arg("conflicts")
+        .args(args)
+        .arg(file)
+        .output()
+        .expect("run omts conflicts")
+}
+
+#[test]
+fn conflicts_list_prints_table_csv_and_json() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = merged(dir.path());
+
+    let table = conflicts(&["list"], &path);
+    assert_eq!(table.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&table.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines[0],
+        "ELEMENT\tID\tTYPE\tFIELD\tSOURCE_FILE\tVALUE\tRESOLUTION"
+    );
+    assert_eq!(
+        lines[1],
+        "node\tn-0\torganization\tname\tfile_0\tAcme Metals\t"
+    );
+    assert_eq!(lines.len(), 3);
+
+    let csv = conflicts(&["list", "--csv"], &path);
+    let stdout = String::from_utf8_lossy(&csv.stdout);
+    assert!(
+        stdout.contains("node,n-0,organization,name,file_1,\"Acme Metals, Inc.\","),
+        "stdout: {stdout}"
+    );
+
+    let json = Command::new(omts_bin())
+        .args(["--format", "json", "conflicts", "list", "--field", "name"])
+        .arg(&path)
+        .output()
+        .expect("run omts conflicts");
+    let records: serde_json::Value = serde_json::from_slice(&json.stdout).expect("JSON");
+    assert_eq!(records[0]["type"], "organization");
+    assert_eq!(records[0]["values"].as_array().map(Vec::len), Some(2));
+
+    let none = conflicts(&["list", "--source", "file_5"], &path);
+    assert_eq!(String::from_utf8_lossy(&none.stdout).lines().count(), 1);
+}
+
+#[test]
+fn conflicts_resolve_applies_decisions() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = merged(dir.path());
+    let decisions = dir.path().join("decisions.json");
+    std::fs::write(
+        &decisions,
+        r#"[{ "id": "n-0", "field": "name", "source": "file_1", "note": "legal name" }]"#,
+    )
+    .expect("write decisions");
+
+    let out = conflicts(
+        &["resolve", "--decisions", decisions.to_str().expect("path")],
+        &path,
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("resolved 1, 0 remaining"));
+
+    let file: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON");
+    let node = &file["nodes"][0];
+    assert_eq!(node["name"], "Acme Metals, Inc.");
+    assert!(node.get("_conflicts").is_none());
+    assert_eq!(node["_resolutions"][0]["source_file"], "file_1");
+    assert_eq!(node["_resolutions"][0]["note"], "legal name");
+    assert_eq!(file["merge_metadata"]["conflict_count"], 0);
+
+    let resolved = dir.path().join("resolved.omts");
+    std::fs::write(&resolved, &out.stdout).expect("write resolved");
+    let validate = Command::new(omts_bin())
+        .args(["validate", "--level", "1"])
+        .arg(&resolved)
+        .output()
+        .expect("run omts validate");
+    assert_eq!(validate.status.code(), Some(0));
+}
+
+#[test]
+fn conflicts_resolve_errors() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = merged(dir.path());
+    let run = |body: &str| {
+        let decisions = dir.path().join("decisions.json");
+        std::fs::write(&decisions, body).expect("write decisions");
+        conflicts(
+            &["resolve", "--decisions", decisions.to_str().expect("path")],
+            &path,
+        )
+        .status
+        .code()
+    };
+
+    assert_eq!(
+        run(r#"[{ "id": "n-9", "field": "name", "source": "file_0" }]"#),
+        Some(1)
+    );
+    assert_eq!(
+        run(r#"[{ "id": "n-0", "field": "status", "source": "file_0" }]"#),
+        Some(2)
+    );
+    assert_eq!(run(r#"[{ "id": "n-0", "field": "name" }]"#), Some(2));
+    assert_eq!(run(r#"{ "not": "an array" }"#), Some(2));
+}
@@ -0,0 +1,152 @@
+//! Integration tests for `omts dedup`.
+#![allow(clippy::expect_used)]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+fn run(args: &[&str]) -> std::process::Output {
+    Command::new(omts_bin())
+        .args(args)
+        .arg(fixture("dedup-vendors.omts"))
+        .output()
+        .expect("run omts dedup")
+}
+
+fn stdout_json(out: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&out.stdout).expect("JSON stdout")
+}
+
+#[test]
+fn dedup_reports_identifier_groups_and_fuzzy_matches() {
+    let out = run(&["--format", "json", "dedup"]);
+    assert_eq!(out.status.code(), Some(0));
+    let report = stdout_json(&out);
+
+    assert_eq!(
+        report["identifier_groups"],
+        serde_json::json!([{
+            "nodes": ["v-100", "v-200"],
+            "shared_identifiers": ["duns:081466849"],
+        }])
+    );
+    let matches = report["fuzzy_matches"].as_array().expect("fuzzy matches");
+    let pairs: Vec<(&str, &str)> = matches
+        .iter()
+        .map(|m| {
+            (
+                m["source"].as_str().expect("source"),
+                m["target"].as_str().expect("target"),
+            )
+        })
+        .collect();
+    // "Acme Ltd" (GB) is blocked by jurisdiction.
+    assert_eq!(pairs, [("v-100", "v-300"), ("v-200", "v-300")]);
+    assert_eq!(matches[0]["basis"], "name_address_match");
+    assert_eq!(matches[1]["basis"], "name_match");
+}
+
+#[test]
+fn dedup_human_report_ends_with_counts() {
+    let out = run(&["dedup"]);
+    assert_eq!(out.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("identifier group: v-100, v-200"),
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout.lines().last(),
+        Some("1 identifier group(s), 2 fuzzy match(es)")
+    );
+}
+
+#[test]
+fn dedup_apply_collapses_shared_identifiers() {
+    let out = run(&["dedup", "--apply"]);
+    assert_eq!(out.status.code(), Some(0));
+    let file = stdout_json(&out);
+
+    let nodes = file["nodes"].as_array().expect("nodes");
+    assert_eq!(nodes.len(), 5);
+    let acme = nodes.iter().find(|n| n["id"] == "v-100").expect("survivor");
+    let internal: Vec<&str> = acme["identifiers"]
+        .as_array()
+        .expect("identifiers")
+        .iter()
+        .filter(|id| id["scheme"] == "internal")
+        .filter_map(|id| id["value"].as_str())
+        .collect();
+    assert_eq!(internal, ["V-100", "V-200"]);
+    assert!(
+        file["edges"]
+            .as_array()
+            .expect("edges")
+            .iter()
+            .all(|e| e["source"] != "v-200")
+    );
+    assert!(String::from_utf8_lossy(&out.stderr).contains("removing 1 node(s) and 1 edge(s)"));
+}
+
+#[test]
+fn dedup_suggest_emits_probable_same_as_edges() {
+    let out = run(&["dedup", "--apply", "--suggest"]);
+    assert_eq!(out.status.code(), Some(0));
+    let file = stdout_json(&out);
+    let same_as: Vec<&serde_json::Value> = file["edges"]
+        .as_array()
+        .expect("edges")
+        .iter()
+        .filter(|e| e["type"] == "same_as")
+        .collect();
+    assert_eq!(same_as.len(), 1);
+    assert_eq!(same_as[0]["source"], "v-100");
+    assert_eq!(same_as[0]["target"], "v-300");
+    assert_eq!(same_as[0]["properties"]["confidence"], "probable");
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("deduped.omts");
+    std::fs::write(&path, &out.stdout).expect("write output");
+    let validate = Command::new(omts_bin())
+        .args(["validate", "--level", "1"])
+        .arg(&path)
+        .output()
+        .expect("run omts validate");
+    assert_eq!(validate.status.code(), Some(0));
+}
+
+#[test]
+fn dedup_threshold_is_configurable_and_checked() {
+    let strict = run(&["--format", "json", "dedup", "--threshold", "1.0"]);
+    assert_eq!(
+        stdout_json(&strict)["fuzzy_matches"]
+            .as_array()
+            .map(Vec::len),
+        Some(2)
+    );
+
+    let loose = run(&["--format", "json", "dedup", "--threshold", "0.3"]);
+    let matches = stdout_json(&loose)["fuzzy_matches"].clone();
+    assert!(matches.as_array().is_some_and(|m| m.len() >= 2));
+
+    let invalid = run(&["dedup", "--threshold", "1.5"]);
+    assert_eq!(invalid.status.code(), Some(2));
+}
@@ -608,3 +608,24 @@ fn diff_json_property_changes_contain_name_field() {
         "property_changes should include the 'name' field; fields: {fields:?}; obj: {obj}"
     );
 }
+
+/// `--where` excluding the only changed node leaves nothing to diff (exit 0).
+#[test]
+fn diff_where_excludes_changed_node_exits_0() {
+    let out = Command::new(omts_bin())
+        .args([
+            "diff",
+            fixture("diff-base.omts").to_str().expect("path"),
+            fixture("diff-modified.omts").to_str().expect("path"),
+            "--where",
+            "NOT name=acme",
+        ])
+        .output()
+        .expect("run omts diff --where");
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "expected exit 0 when --where filters out all changes; stdout: {}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+}
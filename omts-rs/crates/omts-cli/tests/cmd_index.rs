@@ -0,0 +1,211 @@
+//! Integration tests for `omts index` and index-aware query commands.
+#![allow(clippy::expect_used)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+/// Copies a fixture into `dir` so the sidecar is written next to the copy.
+fn copy_fixture(dir: &Path, name: &str) -> PathBuf {
+    let dest = dir.join(name);
+    std::fs::copy(fixture(name), &dest).expect("copy fixture");
+    dest
+}
+
+/// Sidecar path for `file`.
+fn sidecar(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(".idx");
+    PathBuf::from(name)
+}
+
+fn omts(args: &[&str]) -> Output {
+    Command::new(omts_bin())
+        .args(args)
+        .output()
+        .expect("run omts")
+}
+
+fn index(file: &Path) -> Output {
+    omts(&["index", file.to_str().expect("path")])
+}
+
+#[test]
+fn index_writes_sidecar_with_magic() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-query.omts");
+
+    let out = index(&file);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("indexed 5 node(s), 4 edge(s)"),
+        "summary: {stdout}"
+    );
+
+    let bytes = std::fs::read(sidecar(&file)).expect("sidecar exists");
+    assert!(
+        bytes.starts_with(b"OMTSIDX"),
+        "sidecar should start with magic"
+    );
+}
+
+#[test]
+fn index_json_reports_content_hash() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-query.omts");
+
+    let out = omts(&["--format", "json", "index", file.to_str().expect("path")]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    assert_eq!(value["nodes"], 5);
+    assert_eq!(value["edges"], 4);
+    assert_eq!(value["edge_types"], 1);
+    assert_eq!(value["content_hash"].as_str().map(str::len), Some(64));
+}
+
+#[test]
+fn index_invalid_graph_exits_2() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "invalid-edge.omts");
+
+    let out = index(&file);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(!sidecar(&file).exists(), "no sidecar for an invalid graph");
+}
+
+#[test]
+fn indexed_query_reach_and_path_match_unindexed_output() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-paths.omts");
+    let path = file.to_str().expect("path");
+
+    let cases: [&[&str]; 5] = [
+        &["query", path, "--node-type", "organization"],
+        &["query", path, "--edge-type", "supplies", "--count"],
+        &["--format", "json", "query", path, "--name", "plant"],
+        &["reach", path, "org-a", "--edge-type", "supplies"],
+        &["path", path, "org-a", "fac-d"],
+    ];
+    let before: Vec<Output> = cases.iter().map(|args| omts(args)).collect();
+
+    assert!(index(&file).status.success());
+
+    for (args, expected) in cases.iter().zip(&before) {
+        let actual = omts(args);
+        assert_eq!(actual.status.code(), expected.status.code(), "{args:?}");
+        assert_eq!(
+            String::from_utf8_lossy(&actual.stdout),
+            String::from_utf8_lossy(&expected.stdout),
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn verbose_reports_fresh_sidecar_use() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-query.omts");
+    assert!(index(&file).status.success());
+
+    let out = omts(&["--verbose", "reach", file.to_str().expect("path"), "org-a"]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("index: using"), "stderr: {stderr}");
+}
+
+#[test]
+fn indexed_hop_path_does_not_parse_the_file() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-paths.omts");
+    let path = file.to_str().expect("path");
+    assert!(index(&file).status.success());
+
+    // `--verbose` reports the detected encoding whenever the file is parsed.
+    let out = omts(&["--verbose", "path", path, "org-a", "fac-d"]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("index: using"), "stderr: {stderr}");
+    assert!(!stderr.contains("encoding:"), "stderr: {stderr}");
+
+    for extra in [&["--cost", "annual-value"][..], &["--subgraph"][..]] {
+        let mut args = vec!["--verbose", "path", path, "org-a", "fac-d"];
+        args.extend_from_slice(extra);
+        let out = omts(&args);
+        assert!(out.status.success(), "{extra:?}: {:?}", out.status.code());
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(stderr.contains("encoding:"), "{extra:?}: {stderr}");
+    }
+}
+
+#[test]
+fn stale_sidecar_is_rebuilt() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-query.omts");
+    assert!(index(&file).status.success());
+    let original = std::fs::read(sidecar(&file)).expect("sidecar");
+
+    let content = std::fs::read_to_string(&file).expect("read fixture");
+    let edited = content.replace("\"Org E\"", "\"Org Echo\"");
+    std::fs::write(&file, edited).expect("write fixture");
+
+    let out = omts(&["query", file.to_str().expect("path"), "--name", "echo"]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Org Echo"), "stdout: {stdout}");
+
+    let rebuilt = std::fs::read(sidecar(&file)).expect("sidecar");
+    assert_ne!(rebuilt, original, "stale sidecar should be rewritten");
+}
+
+#[test]
+fn corrupt_sidecar_is_rebuilt() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-query.omts");
+    std::fs::write(sidecar(&file), b"not an index").expect("write sidecar");
+
+    let out = omts(&["reach", file.to_str().expect("path"), "org-a"]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    assert!(String::from_utf8_lossy(&out.stdout).contains("org-d"));
+
+    let bytes = std::fs::read(sidecar(&file)).expect("sidecar");
+    assert!(bytes.starts_with(b"OMTSIDX"), "corrupt sidecar rewritten");
+}
+
+#[test]
+fn as_of_ignores_sidecar() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = copy_fixture(dir.path(), "graph-query.omts");
+    assert!(index(&file).status.success());
+
+    let out = omts(&[
+        "--verbose",
+        "--as-of",
+        "2026-01-01",
+        "query",
+        file.to_str().expect("path"),
+        "--node-type",
+        "organization",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!stderr.contains("index:"), "stderr: {stderr}");
+}
@@ -216,3 +216,82 @@ fn inspect_invalid_json_exits_2() {
         "expected exit 2 for invalid JSON"
     );
 }
+
+#[test]
+fn inspect_metrics_human_shows_section() {
+    let out = Command::new(omts_bin())
+        .args([
+            "inspect",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "--metrics",
+        ])
+        .output()
+        .expect("run omts inspect --metrics");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("metrics:"), "stdout: {stdout}");
+    assert!(stdout.contains("weak_components:    1"), "stdout: {stdout}");
+    assert!(stdout.contains("top_pagerank:"), "stdout: {stdout}");
+}
+
+#[test]
+fn inspect_metrics_json_fields() {
+    let out = Command::new(omts_bin())
+        .args([
+            "inspect",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "--metrics",
+            "--top",
+            "2",
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("run omts inspect --metrics");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    let metrics = &value["metrics"];
+    assert_eq!(metrics["weak_component_count"], 1);
+    assert_eq!(metrics["largest_weak_component"], 5);
+    assert_eq!(metrics["strong_component_count"], 5);
+    assert_eq!(
+        metrics["degree_distributions"]["supplies"]["max_out_degree"],
+        2
+    );
+    assert!(metrics["diameter_from_reporting_entity"].is_null());
+    let top = metrics["top_pagerank"].as_array().expect("array");
+    assert_eq!(top.len(), 2);
+    assert_eq!(
+        top[0]["node_id"], "org-d",
+        "sink of the longest chain ranks first"
+    );
+}
+
+#[test]
+fn inspect_without_metrics_omits_section() {
+    let out = Command::new(omts_bin())
+        .args([
+            "inspect",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("run omts inspect");
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    assert!(value.get("metrics").is_none());
+}
+
+#[test]
+fn inspect_top_requires_metrics() {
+    let out = Command::new(omts_bin())
+        .args([
+            "inspect",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "--top",
+            "3",
+        ])
+        .output()
+        .expect("run omts inspect");
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -379,3 +379,380 @@ fn merge_cbor_compress_passes_validate() {
         String::from_utf8_lossy(&validate_out.stderr)
     );
 }
+
+/// Runs `omts merge` on the two tier fixtures with extra arguments.
+fn merge_tier_fixtures(extra: &[&str]) -> std::process::Output {
+    Command::new(omts_bin())
+        .arg("merge")
+        .arg(fixture("merge-tier-a.omts"))
+        .arg(fixture("merge-tier-b.omts"))
+        .args(extra)
+        .output()
+        .expect("run omts merge")
+}
+
+/// The merged `supplies` edge from `omts merge` stdout.
+fn supplies_edge(stdout: &[u8]) -> serde_json::Value {
+    let value: serde_json::Value = serde_json::from_slice(stdout).expect("merged JSON");
+    value["edges"]
+        .as_array()
+        .and_then(|edges| edges.iter().find(|e| e["type"] == "supplies"))
+        .cloned()
+        .expect("supplies edge")
+}
+
+#[test]
+fn merge_mixed_perspectives_moves_tiers_to_conflicts() {
+    let out = merge_tier_fixtures(&[]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let edge = supplies_edge(&out.stdout);
+    assert!(edge["properties"].get("tier").is_none(), "{edge}");
+    let values = &edge["properties"]["_conflicts"][0]["values"];
+    assert_eq!(values.as_array().map(Vec::len), Some(2), "{edge}");
+    assert!(values[0]["reporting_entity"].is_string());
+    assert!(values[1]["reporting_entity"].is_string());
+}
+
+#[test]
+fn merge_primary_perspective_keeps_its_tier() {
+    let primary = fixture("merge-tier-b.omts");
+    let out = merge_tier_fixtures(&["--primary-perspective", primary.to_str().expect("path")]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let edge = supplies_edge(&out.stdout);
+    assert_eq!(edge["properties"]["tier"], 1, "{edge}");
+    let values = &edge["properties"]["_conflicts"][0]["values"];
+    assert_eq!(values.as_array().map(Vec::len), Some(1), "{edge}");
+    assert_eq!(values[0]["value"], 2);
+}
+
+#[test]
+fn merge_primary_perspective_not_an_input_exits_2() {
+    let other = fixture("merge-a.omts");
+    let out = merge_tier_fixtures(&["--primary-perspective", other.to_str().expect("path")]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--primary-perspective"), "stderr: {stderr}");
+}
+
+/// Runs `omts merge` on the full-overlap fixtures, whose `Epsilon Corp` node
+/// carries a different name in each file.
+fn merge_overlap_fixtures(extra: &[&str]) -> std::process::Output {
+    Command::new(omts_bin())
+        .arg("merge")
+        .args(extra)
+        .arg(fixture("merge-full-overlap-a.omts"))
+        .arg(fixture("merge-full-overlap-b.omts"))
+        .output()
+        .expect("run omts merge")
+}
+
+/// The merged node whose name conflicted, from `omts merge` stdout.
+fn epsilon_node(stdout: &[u8]) -> serde_json::Value {
+    let value: serde_json::Value = serde_json::from_slice(stdout).expect("merged JSON");
+    value["nodes"]
+        .as_array()
+        .and_then(|nodes| {
+            nodes
+                .iter()
+                .find(|n| n["_conflicts"].as_array().is_some_and(|c| !c.is_empty()))
+        })
+        .cloned()
+        .expect("node with conflicts")
+}
+
+#[test]
+fn merge_resolve_priority_fills_conflicting_name() {
+    let out = merge_overlap_fixtures(&["--resolve", "name=priority"]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let node = epsilon_node(&out.stdout);
+    assert_eq!(node["name"], "Epsilon Corp", "{node}");
+    assert_eq!(node["_conflicts"][0]["resolution"], "source_priority");
+    assert_eq!(
+        node["_conflicts"][0]["values"].as_array().map(Vec::len),
+        Some(2)
+    );
+}
+
+#[test]
+fn merge_without_resolve_leaves_conflicting_name_empty() {
+    let out = merge_overlap_fixtures(&[]);
+    assert_eq!(out.status.code(), Some(0));
+    let node = epsilon_node(&out.stdout);
+    assert!(node.get("name").is_none(), "{node}");
+}
+
+#[test]
+fn merge_resolve_numeric_policy_on_name_exits_2() {
+    let out = merge_overlap_fixtures(&["--resolve", "name=mean"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("numeric"), "stderr: {stderr}");
+}
+
+#[test]
+fn merge_resolve_malformed_rule_is_clap_error() {
+    let out = merge_overlap_fixtures(&["--resolve", "name"]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+#[test]
+fn merge_explain_reports_linking_identifier_on_stderr() {
+    let out = Command::new(omts_bin())
+        .args([
+            "merge",
+            "--explain",
+            fixture("merge-a.omts").to_str().expect("path"),
+            fixture("merge-b.omts").to_str().expect("path"),
+        ])
+        .output()
+        .expect("run omts merge");
+    assert_eq!(out.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("merge group n-"), "stderr: {stderr}");
+    assert!(
+        stderr.contains("merge-a.omts:org-acme -- ")
+            && stderr.contains("merge-b.omts:")
+            && stderr.contains("shared identifier lei:"),
+        "stderr: {stderr}"
+    );
+    // stdout is still the merged file.
+    let merged: serde_json::Value = serde_json::from_slice(&out.stdout).expect("merged JSON");
+    assert!(merged["nodes"].is_array());
+}
+
+#[test]
+fn merge_explain_json_lists_annulled_lei_rejection() {
+    let out = Command::new(omts_bin())
+        .args([
+            "--format",
+            "json",
+            "merge",
+            "--explain",
+            fixture("merge-annulled-lei-a.omts").to_str().expect("path"),
+            fixture("merge-annulled-lei-b.omts").to_str().expect("path"),
+        ])
+        .output()
+        .expect("run omts merge");
+    assert_eq!(out.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let report: serde_json::Value = stderr
+        .lines()
+        .find_map(|line| serde_json::from_str(line).ok())
+        .expect("JSON explanation on stderr");
+    let rejected = report["rejected"].as_array().expect("rejected array");
+    assert!(
+        rejected.iter().any(|r| r["reason"] == "annulled_lei"),
+        "report: {report}"
+    );
+    assert!(report["groups"].as_array().is_some_and(|g| !g.is_empty()));
+    let member = &report["groups"][0]["members"][0];
+    assert!(
+        member["source_file"]
+            .as_str()
+            .is_some_and(|s| s.starts_with("file_"))
+    );
+    assert!(
+        member["source_path"]
+            .as_str()
+            .is_some_and(|p| p.ends_with("merge-annulled-lei-a.omts")
+                || p.ends_with("merge-annulled-lei-b.omts")),
+        "report: {report}"
+    );
+}
+
+#[test]
+fn merge_previous_keeps_matching_ids_and_reports_changes() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let first = Command::new(omts_bin())
+        .args([
+            "merge",
+            fixture("merge-b.omts").to_str().expect("path"),
+            fixture("merge-b.omts").to_str().expect("path"),
+        ])
+        .output()
+        .expect("run omts merge");
+    assert_eq!(first.status.code(), Some(0));
+    let previous_path = dir.path().join("previous.omts");
+    std::fs::write(&previous_path, &first.stdout).expect("write previous");
+    let previous: serde_json::Value = serde_json::from_slice(&first.stdout).expect("JSON");
+
+    let out = Command::new(omts_bin())
+        .args([
+            "--format",
+            "json",
+            "merge",
+            "--previous",
+            previous_path.to_str().expect("path"),
+            fixture("merge-a.omts").to_str().expect("path"),
+            fixture("merge-b.omts").to_str().expect("path"),
+        ])
+        .output()
+        .expect("run omts merge");
+    assert_eq!(out.status.code(), Some(0));
+
+    let lei_node = |file: &serde_json::Value| -> String {
+        file["nodes"]
+            .as_array()
+            .expect("nodes")
+            .iter()
+            .find(|n| n["identifiers"][0]["scheme"] == "lei")
+            .and_then(|n| n["id"].as_str())
+            .expect("lei node")
+            .to_owned()
+    };
+    let merged: serde_json::Value = serde_json::from_slice(&out.stdout).expect("merged JSON");
+    assert_eq!(lei_node(&merged), lei_node(&previous));
+
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    let changes: serde_json::Value = stderr
+        .lines()
+        .find_map(|line| serde_json::from_str(line).ok())
+        .expect("JSON id changes on stderr");
+    assert_eq!(changes["reused"], 1, "changes: {changes}");
+    assert_eq!(changes["minted"].as_array().map(Vec::len), Some(2));
+    // The factory has no identifiers to match on; both copies are retired.
+    assert_eq!(changes["retired"].as_array().map(Vec::len), Some(2));
+}
+
+#[test]
+fn merge_dry_run_previews_groups_without_writing_a_file() {
+    let run = |format: &str| {
+        Command::new(omts_bin())
+            .args([
+                "--format",
+                format,
+                "merge",
+                "--dry-run",
+                fixture("merge-a.omts").to_str().expect("path"),
+                fixture("merge-b.omts").to_str().expect("path"),
+            ])
+            .output()
+            .expect("run omts merge")
+    };
+
+    let human = run("human");
+    assert_eq!(human.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&human.stdout);
+    assert!(
+        stdout.contains("would merge 4 node(s) from 2 file(s) into 3 node(s)"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        stdout.contains("group sizes: 1 x 2, 2 x 1"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("singletons: 2"), "stdout: {stdout}");
+
+    let json = run("json");
+    assert_eq!(json.status.code(), Some(0));
+    let preview: serde_json::Value = serde_json::from_slice(&json.stdout).expect("JSON preview");
+    assert_eq!(preview["merged_node_count"], 3);
+    assert_eq!(preview["groups"][0]["size"], 2);
+    assert_eq!(preview["groups"][0]["members"][0]["node_id"], "org-acme");
+    assert_eq!(preview["singletons"].as_array().map(Vec::len), Some(2));
+    assert!(preview.get("nodes").is_none());
+}
+
+#[test]
+fn merge_enrichment_base_proposes_same_as_for_new_links() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let merge_a = fixture("merge-a.omts");
+    let merge_b = fixture("merge-b.omts");
+    let merge_base = |extra: &[&str]| {
+        Command::new(omts_bin())
+            .arg("merge")
+            .args(extra)
+            .arg(&merge_a)
+            .arg(&merge_b)
+            .output()
+            .expect("run omts merge")
+    };
+    let base_path = dir.path().join("base.omts");
+    let base = merge_base(&["--pre-enrichment"]);
+    assert_eq!(base.status.code(), Some(0));
+    std::fs::write(&base_path, &base.stdout).expect("write base");
+    let base: serde_json::Value = serde_json::from_slice(&base.stdout).expect("base JSON");
+    assert_eq!(base["merge_metadata"]["enrichment"], "pre_enrichment");
+
+    // Enrichment found the supplier's DUNS for the factory.
+    let mut enriched: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&merge_b).expect("read fixture")).expect("JSON");
+    enriched["nodes"][1]["identifiers"] =
+        serde_json::json!([{ "scheme": "duns", "value": "111111111" }]);
+    let enriched_path = dir.path().join("merge-b-enriched.omts");
+    std::fs::write(&enriched_path, enriched.to_string()).expect("write enriched");
+
+    let out = Command::new(omts_bin())
+        .arg("merge")
+        .arg("--enrichment-base")
+        .arg(&base_path)
+        .arg(&merge_a)
+        .arg(&enriched_path)
+        .output()
+        .expect("run omts merge");
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("1 same_as edge(s) proposed for review"),
+        "stderr: {stderr}"
+    );
+
+    let merged: serde_json::Value = serde_json::from_slice(&out.stdout).expect("merged JSON");
+    assert_eq!(merged["nodes"].as_array().map(Vec::len), Some(3));
+    let proposed: Vec<&serde_json::Value> = merged["edges"]
+        .as_array()
+        .expect("edges")
+        .iter()
+        .filter(|e| e["type"] == "same_as")
+        .collect();
+    assert_eq!(proposed.len(), 1);
+    assert_eq!(proposed[0]["properties"]["confidence"], "probable");
+    assert_eq!(proposed[0]["properties"]["basis"], "enrichment_match");
+    assert_eq!(merged["merge_metadata"]["enrichment"], "post_enrichment");
+
+    // Inputs in a different order from the base merge are rejected.
+    let out = Command::new(omts_bin())
+        .arg("merge")
+        .arg("--enrichment-base")
+        .arg(&base_path)
+        .arg(&enriched_path)
+        .arg(&merge_a)
+        .output()
+        .expect("run omts merge");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("order of the base merge"));
+
+    // A base without provenance cannot be used.
+    let plain_path = dir.path().join("plain.omts");
+    std::fs::write(&plain_path, merge_base(&[]).stdout).expect("write plain");
+    let out = Command::new(omts_bin())
+        .arg("merge")
+        .arg("--enrichment-base")
+        .arg(&plain_path)
+        .arg(&merge_a)
+        .arg(&enriched_path)
+        .output()
+        .expect("run omts merge");
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--provenance"));
+}
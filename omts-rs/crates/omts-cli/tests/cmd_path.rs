@@ -38,7 +38,7 @@ fn path_exists_exits_0() {
 }
 
 #[test]
-fn path_human_output_uses_arrow_separator() {
+fn path_human_output_shows_edges_between_nodes() {
     let out = Command::new(omts_bin())
         .args([
             "path",
@@ -50,8 +50,8 @@ fn path_human_output_uses_arrow_separator() {
         .expect("run omts path");
     let stdout = String::from_utf8_lossy(&out.stdout);
     assert!(
-        stdout.contains(" -> "),
-        "human output should use ' -> ' separator: {stdout}"
+        stdout.contains("org-a -[e-ab:supplies]-> org-b"),
+        "human output should show each hop as -[edge:type]->: {stdout}"
     );
     assert!(
         stdout.contains("org-a"),
@@ -277,3 +277,213 @@ fn path_invalid_json_exits_2() {
         "expected exit 2 for invalid JSON"
     );
 }
+
+#[test]
+fn path_json_routes_include_edge_ids_and_types() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "-f",
+            "json",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path -f json");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value =
+        serde_json::from_str(String::from_utf8_lossy(&out.stdout).trim()).expect("valid JSON");
+    let edges = value["routes"][0]["edges"]
+        .as_array()
+        .expect("routes[0].edges should be an array");
+    let hops: Vec<(&str, &str)> = edges
+        .iter()
+        .map(|e| {
+            (
+                e["id"].as_str().expect("id"),
+                e["type"].as_str().expect("type"),
+            )
+        })
+        .collect();
+    assert_eq!(hops, vec![("e-ad", "supplies"), ("e-op", "operates")]);
+}
+
+#[test]
+fn path_cost_share_of_buyer_demand_prefers_critical_route() {
+    // graph-paths.omts: the direct e-ad edge is 5% of demand, the route via
+    // org-b is 80% per hop, so it is cheaper despite being longer.
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--cost",
+            "share-of-buyer-demand",
+            "--max-paths",
+            "1",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --cost");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("e-ab"), "stdout: {stdout}");
+    assert!(!stdout.contains("e-ad"), "stdout: {stdout}");
+    assert!(stdout.contains("(cost "), "stdout: {stdout}");
+}
+
+#[test]
+fn path_pattern_constrains_edge_types() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--pattern",
+            "supplies supplies operates",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --pattern");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(stdout.trim().lines().count(), 1, "stdout: {stdout}");
+    assert!(stdout.contains("org-b"), "stdout: {stdout}");
+}
+
+#[test]
+fn path_edge_cost_changes_route() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--edge-cost",
+            "supplies=10",
+            "--edge-cost",
+            "operates=0",
+            "--max-paths",
+            "1",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --edge-cost");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("e-ad"), "stdout: {stdout}");
+    assert!(stdout.contains("(cost 10.000)"), "stdout: {stdout}");
+}
+
+#[test]
+fn path_invalid_pattern_exits_2() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--pattern",
+            "supplies||",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --pattern");
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "expected exit 2 for bad pattern"
+    );
+}
+
+#[test]
+fn path_invalid_edge_cost_exits_2() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--edge-cost",
+            "supplies=-1",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --edge-cost");
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "expected exit 2 for negative cost"
+    );
+}
+
+#[test]
+fn path_edge_type_excludes_other_edges() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--edge-type",
+            "supplies",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --edge-type");
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "fac-d is only reachable via an operates edge"
+    );
+}
+
+#[test]
+fn path_subgraph_emits_union_of_paths() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--subgraph",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts path --subgraph");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    let ids = |key: &str| {
+        let mut ids: Vec<String> = value[key]
+            .as_array()
+            .expect("array")
+            .iter()
+            .map(|v| v["id"].as_str().expect("id").to_owned())
+            .collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids("nodes"), vec!["fac-d", "org-a", "org-b", "org-d"]);
+    assert_eq!(ids("edges"), vec!["e-ab", "e-ad", "e-bd", "e-op"]);
+}
+
+#[test]
+fn path_subgraph_keeps_only_traversed_edges() {
+    let out = Command::new(omts_bin())
+        .args([
+            "path",
+            "--subgraph",
+            "--max-paths",
+            "1",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-a",
+            "org-d",
+        ])
+        .output()
+        .expect("run omts path --subgraph");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    let edges: Vec<&str> = value["edges"]
+        .as_array()
+        .expect("edges")
+        .iter()
+        .map(|e| e["id"].as_str().expect("id"))
+        .collect();
+    assert_eq!(edges, vec!["e-ad"], "the direct route only");
+}
@@ -719,3 +719,129 @@ fn query_label_no_match_exits_1() {
         "expected exit 1 when no node has label key 'region'"
     );
 }
+
+#[test]
+fn query_where_excludes_with_not() {
+    // graph-labels.omts: org-tier1 carries "certified", org-tier2 does not
+    let out = Command::new(omts_bin())
+        .args([
+            "query",
+            fixture("graph-labels.omts").to_str().expect("path"),
+            "--where",
+            "label:tier AND NOT label:certified",
+        ])
+        .output()
+        .expect("run omts query --where with NOT");
+    assert!(
+        out.status.success(),
+        "expected exit 0 for --where; exit: {:?}",
+        out.status.code()
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("org-tier2"),
+        "stdout should contain org-tier2; stdout: {stdout}"
+    );
+    assert!(
+        !stdout.contains("org-tier1"),
+        "stdout should not contain excluded org-tier1; stdout: {stdout}"
+    );
+}
+
+#[test]
+fn query_where_anded_with_flags() {
+    // --node-type alone matches all three orgs; --where narrows to tier=1 or tier=2
+    let out = Command::new(omts_bin())
+        .args([
+            "query",
+            fixture("graph-labels.omts").to_str().expect("path"),
+            "--node-type",
+            "organization",
+            "--where",
+            "label:tier IN (1, 2)",
+        ])
+        .output()
+        .expect("run omts query --node-type with --where");
+    assert!(out.status.success(), "exit: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("org-tier1"), "stdout: {stdout}");
+    assert!(stdout.contains("org-tier2"), "stdout: {stdout}");
+    assert!(!stdout.contains("org-unlabeled"), "stdout: {stdout}");
+}
+
+#[test]
+fn query_where_invalid_expression_exits_2() {
+    let out = Command::new(omts_bin())
+        .args([
+            "query",
+            fixture("graph-labels.omts").to_str().expect("path"),
+            "--where",
+            "label:tier AND (",
+        ])
+        .output()
+        .expect("run omts query with malformed --where");
+    assert_eq!(
+        out.status.code(),
+        Some(2),
+        "expected exit 2 for malformed --where"
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("--where"),
+        "stderr should name the flag; stderr: {stderr}"
+    );
+}
+
+#[test]
+fn query_where_numeric_property_comparison() {
+    // full-featured.omts: edge-ownership-01 is 51%, edge-bo-01 is 60%
+    let out = Command::new(omts_bin())
+        .args([
+            "query",
+            fixture("full-featured.omts").to_str().expect("path"),
+            "--where",
+            "percentage > 55",
+        ])
+        .output()
+        .expect("run omts query --where with numeric comparison");
+    assert!(out.status.success(), "exit: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("edge-bo-01"), "stdout: {stdout}");
+    assert!(!stdout.contains("edge-ownership-01"), "stdout: {stdout}");
+}
+
+#[test]
+fn query_where_date_property_comparison() {
+    // full-featured.omts: att-sa8000 is valid to 2028-05-31
+    let matched = Command::new(omts_bin())
+        .args([
+            "query",
+            fixture("full-featured.omts").to_str().expect("path"),
+            "--where",
+            "type=attestation AND valid_to < 2028-07-01",
+        ])
+        .output()
+        .expect("run omts query --where with date comparison");
+    assert!(
+        matched.status.success(),
+        "exit: {:?}",
+        matched.status.code()
+    );
+    let stdout = String::from_utf8_lossy(&matched.stdout);
+    assert!(stdout.contains("att-sa8000"), "stdout: {stdout}");
+
+    let unmatched = Command::new(omts_bin())
+        .args([
+            "query",
+            fixture("full-featured.omts").to_str().expect("path"),
+            "--where",
+            "type=attestation AND valid_to < 2028-01-01",
+        ])
+        .output()
+        .expect("run omts query --where with date comparison");
+    assert_eq!(
+        unmatched.status.code(),
+        Some(1),
+        "expected exit 1 for no match"
+    );
+}
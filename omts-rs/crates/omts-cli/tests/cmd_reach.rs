@@ -1,6 +1,7 @@
 //! Integration tests for `omts reach`.
 #![allow(clippy::expect_used)]
 
+use std::io::Write as _;
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -268,3 +269,138 @@ fn reach_invalid_json_exits_2() {
         "expected exit 2 for invalid JSON"
     );
 }
+
+/// Parses `--subgraph` JSON output into sorted node and edge IDs.
+fn subgraph_ids(stdout: &[u8]) -> (Vec<String>, Vec<String>) {
+    let value: serde_json::Value = serde_json::from_slice(stdout).expect("valid .omts JSON");
+    let ids = |key: &str| {
+        let mut ids: Vec<String> = value[key]
+            .as_array()
+            .expect("array")
+            .iter()
+            .map(|v| v["id"].as_str().expect("id").to_owned())
+            .collect();
+        ids.sort();
+        ids
+    };
+    (ids("nodes"), ids("edges"))
+}
+
+#[test]
+fn reach_edge_type_restricts_traversal() {
+    let out = Command::new(omts_bin())
+        .args([
+            "reach",
+            "--direction",
+            "incoming",
+            "--edge-type",
+            "supplies",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts reach --edge-type");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    assert!(
+        out.stdout.is_empty(),
+        "fac-d is only attached by an operates edge: {}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+
+    let out = Command::new(omts_bin())
+        .args([
+            "reach",
+            "--direction",
+            "incoming",
+            "--edge-type",
+            "supplies",
+            "--edge-type",
+            "operates",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "fac-d",
+        ])
+        .output()
+        .expect("run omts reach --edge-type");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let ids: Vec<&str> = stdout.lines().collect();
+    assert_eq!(ids, vec!["org-a", "org-b", "org-d"]);
+}
+
+#[test]
+fn reach_subgraph_emits_region_with_filtered_edges() {
+    let out = Command::new(omts_bin())
+        .args([
+            "reach",
+            "--direction",
+            "incoming",
+            "--edge-type",
+            "supplies",
+            "--subgraph",
+            fixture("graph-paths.omts").to_str().expect("path"),
+            "org-d",
+        ])
+        .output()
+        .expect("run omts reach --subgraph");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let (nodes, edges) = subgraph_ids(&out.stdout);
+    assert_eq!(nodes, vec!["org-a", "org-b", "org-d"]);
+    assert_eq!(edges, vec!["e-ab", "e-ad", "e-bd"]);
+}
+
+#[test]
+fn reach_subgraph_output_validates() {
+    let out = Command::new(omts_bin())
+        .args([
+            "reach",
+            "--subgraph",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "org-b",
+        ])
+        .output()
+        .expect("run omts reach --subgraph");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+
+    let mut tmp = tempfile::NamedTempFile::new().expect("temp file");
+    tmp.write_all(&out.stdout).expect("write");
+    let validate = Command::new(omts_bin())
+        .args(["validate", tmp.path().to_str().expect("path")])
+        .output()
+        .expect("run omts validate");
+    assert!(
+        validate.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&validate.stderr)
+    );
+}
+
+#[test]
+fn reach_subgraph_to_cbor_starts_with_cbor_tag() {
+    let out = Command::new(omts_bin())
+        .args([
+            "reach",
+            "--subgraph",
+            "--to",
+            "cbor",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "org-a",
+        ])
+        .output()
+        .expect("run omts reach --subgraph --to cbor");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    assert_eq!(&out.stdout[..3], &[0xD9, 0xD9, 0xF7]);
+}
+
+#[test]
+fn reach_to_without_subgraph_exits_2() {
+    let out = Command::new(omts_bin())
+        .args([
+            "reach",
+            "--to",
+            "cbor",
+            fixture("graph-query.omts").to_str().expect("path"),
+            "org-a",
+        ])
+        .output()
+        .expect("run omts reach --to");
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -515,3 +515,333 @@ fn redact_cbor_compress_passes_validate() {
         String::from_utf8_lossy(&validate_out.stderr)
     );
 }
+
+/// A TOML policy retains the organization and drops its `nat-reg` identifier.
+#[test]
+fn redact_with_toml_policy_applies_rules() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let policy = dir.path().join("partner-a.toml");
+    std::fs::write(
+        &policy,
+        "[[nodes]]\n\
+         where = \"type=organization\"\n\
+         action = \"retain\"\n\
+         \n\
+         [[drop_identifiers]]\n\
+         scheme = \"nat-reg\"\n",
+    )
+    .expect("write policy");
+
+    let out = Command::new(omts_bin())
+        .args([
+            "redact",
+            "--scope",
+            "partner",
+            "--policy",
+            policy.to_str().expect("path"),
+            fixture("redact-internal.omts").to_str().expect("path"),
+        ])
+        .output()
+        .expect("run omts redact --policy");
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "expected exit 0; stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON output");
+    let org = value["nodes"]
+        .as_array()
+        .expect("nodes array")
+        .iter()
+        .find(|n| n["id"] == "org-001")
+        .expect("org-001 present");
+    assert_eq!(org["type"], "organization");
+    let schemes: Vec<&str> = org["identifiers"]
+        .as_array()
+        .expect("identifiers array")
+        .iter()
+        .filter_map(|id| id["scheme"].as_str())
+        .collect();
+    assert_eq!(schemes, ["lei"]);
+}
+
+#[test]
+fn redact_with_invalid_policy_exits_2() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let policy = dir.path().join("policy.json");
+    std::fs::write(
+        &policy,
+        r#"{ "nodes": [{ "where": "edge.type=supplies", "action": "omit" }] }"#,
+    )
+    .expect("write policy");
+
+    let out = Command::new(omts_bin())
+        .args([
+            "redact",
+            "--scope",
+            "partner",
+            "--policy",
+            policy.to_str().expect("path"),
+            fixture("redact-internal.omts").to_str().expect("path"),
+        ])
+        .output()
+        .expect("run omts redact --policy");
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("never matches a node"), "stderr: {stderr}");
+}
+
+/// A buyer, its tier-1 and tier-2 suppliers, and an unrelated organization.
+const CHAIN: &str = r#"{
+    "omts_version": "1.0.0",
+    "snapshot_date": "2026-02-19",
+    "file_salt": "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+    "nodes": [
+        { "id": "buyer", "type": "organization", "name": "Buyer" },
+        { "id": "tier-1", "type": "organization", "name": "Tier One" },
+        { "id": "tier-2", "type": "organization", "name": "Tier Two" },
+        { "id": "other", "type": "organization", "name": "Other" }
+    ],
+    "edges": [
+        { "id": "e-1", "type": "supplies", "source": "tier-1", "target": "buyer" },
+        { "id": "e-2", "type": "supplies", "source": "tier-2", "target": "tier-1" },
+        { "id": "e-3", "type": "supplies", "source": "other", "target": "buyer" }
+    ]
+}"#;
+
+/// Runs `omts redact --scope partner` on [`CHAIN`] with extra arguments.
+fn redact_chain(args: &[&str]) -> std::process::Output {
+    let mut tmp = tempfile::NamedTempFile::new().expect("temp file");
+    tmp.write_all(CHAIN.as_bytes()).expect("write chain");
+    Command::new(omts_bin())
+        .args(["redact", "--scope", "partner"])
+        .arg(tmp.path())
+        .args(args)
+        .output()
+        .expect("run omts redact")
+}
+
+/// Node types in the redacted output, keyed by node ID.
+fn node_types(stdout: &[u8]) -> Vec<(String, String)> {
+    let value: serde_json::Value = serde_json::from_slice(stdout).expect("JSON output");
+    value["nodes"]
+        .as_array()
+        .expect("nodes array")
+        .iter()
+        .map(|n| {
+            (
+                n["id"].as_str().expect("id").to_owned(),
+                n["type"].as_str().expect("type").to_owned(),
+            )
+        })
+        .collect()
+}
+
+/// `--where` picks the tier-1 supplier and `--expand 1 --direction incoming`
+/// adds its own supplier; everything else becomes a boundary ref.
+#[test]
+fn redact_retains_selected_nodes_and_upstream_hops() {
+    let out = redact_chain(&[
+        "--where",
+        "name=\"Tier One\"",
+        "--expand",
+        "1",
+        "--direction",
+        "incoming",
+    ]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "expected exit 0; stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let types = node_types(&out.stdout);
+    let type_of = |id: &str| {
+        types
+            .iter()
+            .find(|(node, _)| node == id)
+            .map(|(_, t)| t.as_str())
+            .expect("node present")
+    };
+    assert_eq!(type_of("tier-1"), "organization");
+    assert_eq!(type_of("tier-2"), "organization");
+    assert_eq!(type_of("buyer"), "boundary_ref");
+    assert_eq!(type_of("other"), "boundary_ref");
+
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON output");
+    let edge_ids: Vec<&str> = value["edges"]
+        .as_array()
+        .expect("edges array")
+        .iter()
+        .filter_map(|e| e["id"].as_str())
+        .collect();
+    assert_eq!(
+        edge_ids,
+        ["e-1", "e-2"],
+        "edges between boundary refs are omitted"
+    );
+}
+
+/// Without node IDs or selectors, every eligible node is replaced.
+#[test]
+fn redact_without_selection_replaces_every_node() {
+    let out = redact_chain(&[]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(
+        node_types(&out.stdout)
+            .iter()
+            .all(|(_, t)| t == "boundary_ref")
+    );
+}
+
+#[test]
+fn redact_with_unknown_node_id_exits_1() {
+    let out = redact_chain(&["missing-node"]);
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+/// `--report` with `--format json` writes one disposition per node, with the
+/// opaque value of each replacement, and lists the omitted edge.
+#[test]
+fn redact_writes_json_report() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let report_path = dir.path().join("report.json");
+    let report_arg = report_path.to_str().expect("utf-8 path");
+    let out = redact_chain(&["tier-1", "--format", "json", "--report", report_arg]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    // stdout still carries the redacted file.
+    assert_eq!(node_types(&out.stdout).len(), 4);
+
+    let text = std::fs::read_to_string(&report_path).expect("read report");
+    let report: serde_json::Value = serde_json::from_str(&text).expect("JSON report");
+    assert_eq!(report["scope"], "partner");
+    let nodes = report["nodes"].as_array().expect("nodes array");
+    assert_eq!(nodes.len(), 4);
+    for node in nodes {
+        if node["id"] == "tier-1" {
+            assert_eq!(node["action"], "retain");
+            assert_eq!(node["reason"], "retain_set");
+            assert!(node.get("opaque_value").is_none());
+        } else {
+            assert_eq!(node["action"], "replace");
+            assert_eq!(node["reason"], "not_retained");
+            assert!(node["opaque_value"].is_string(), "node: {node}");
+        }
+    }
+    let omitted = report["edges_omitted"].as_array().expect("edges_omitted");
+    assert_eq!(omitted.len(), 1);
+    assert_eq!(omitted[0]["id"], "e-3");
+    assert_eq!(omitted[0]["reason"], "both_endpoints_replaced");
+}
+
+/// The human report has one line per node and a summary line.
+#[test]
+fn redact_writes_human_report() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let report_path = dir.path().join("report.txt");
+    let report_arg = report_path.to_str().expect("utf-8 path");
+    let out = redact_chain(&["tier-1", "--report", report_arg]);
+    assert_eq!(out.status.code(), Some(0));
+
+    let text = std::fs::read_to_string(&report_path).expect("read report");
+    assert!(
+        text.contains("retained tier-1 (organization): in the retain set"),
+        "report: {text}"
+    );
+    assert!(
+        text.contains("replaced buyer (organization) with boundary_ref "),
+        "report: {text}"
+    );
+    assert!(
+        text.contains("omitted edge e-3 (supplies other -> buyer)"),
+        "report: {text}"
+    );
+    assert!(
+        text.contains("report: scope=partner, 1 retained, 3 replaced, 0 omitted"),
+        "report: {text}"
+    );
+}
+
+#[test]
+fn redact_report_to_unwritable_path_exits_2() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let report_path = dir.path().join("missing").join("report.json");
+    let out = redact_chain(&["--report", report_path.to_str().expect("utf-8 path")]);
+    assert_eq!(out.status.code(), Some(2));
+}
+
+/// A buyer and supplier trading a known annual value.
+const VALUED: &str = r#"{
+    "omts_version": "1.0.0",
+    "snapshot_date": "2026-02-19",
+    "file_salt": "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+    "nodes": [
+        { "id": "buyer", "type": "organization", "name": "Buyer" },
+        { "id": "supplier", "type": "organization", "name": "Supplier" }
+    ],
+    "edges": [
+        { "id": "e-1", "type": "supplies", "source": "supplier", "target": "buyer",
+          "properties": { "annual_value": 2500000, "value_currency": "EUR" } }
+    ]
+}"#;
+
+/// A `generalize` rule discloses `annual_value` as a band at public scope
+/// and the human report lists it.
+#[test]
+fn redact_with_generalize_policy_writes_bands() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let input = dir.path().join("valued.omts");
+    std::fs::write(&input, VALUED).expect("write input");
+    let policy = dir.path().join("public.toml");
+    std::fs::write(
+        &policy,
+        "[[generalize]]\n\
+         property = \"annual_value\"\n\
+         method = \"bands\"\n\
+         bands = [1e5, 1e6, 1e7]\n",
+    )
+    .expect("write policy");
+    let report = dir.path().join("report.txt");
+
+    let out = Command::new(omts_bin())
+        .args(["redact", "--scope", "public", "--policy"])
+        .arg(&policy)
+        .arg("--report")
+        .arg(&report)
+        .arg(&input)
+        .args(["buyer", "supplier"])
+        .output()
+        .expect("run omts redact --policy");
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON output");
+    let props = &value["edges"][0]["properties"];
+    assert!(props.get("annual_value").is_none(), "props: {props}");
+    assert_eq!(
+        props["_generalized"]["annual_value"],
+        serde_json::json!({ "min": 1_000_000, "max": 10_000_000, "unit": "EUR" })
+    );
+
+    let text = std::fs::read_to_string(&report).expect("read report");
+    assert!(
+        text.contains("generalised annual_value on edge e-1: bands 100000, 1000000, 10000000"),
+        "report: {text}"
+    );
+}
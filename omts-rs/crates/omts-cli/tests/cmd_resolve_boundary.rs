@@ -0,0 +1,144 @@
+//! Integration tests for `omts resolve-boundary`.
+#![allow(clippy::expect_used)]
+
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+/// A buyer and two suppliers; `org-c` has no public identifiers.
+const ORIGINAL: &str = r#"{
+    "omts_version": "1.0.0",
+    "snapshot_date": "2026-02-19",
+    "file_salt": "deadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef",
+    "nodes": [
+        { "id": "org-a", "type": "organization", "name": "Buyer",
+          "identifiers": [{ "scheme": "lei", "value": "5493006MHB84DD0ZWV18" }] },
+        { "id": "org-b", "type": "organization", "name": "Supplier B",
+          "identifiers": [
+              { "scheme": "lei", "value": "529900T8BM49AURSDO55" },
+              { "scheme": "duns", "value": "081466849" }
+          ] },
+        { "id": "org-c", "type": "organization", "name": "Supplier C",
+          "identifiers": [{ "scheme": "vat", "value": "FR12345678901", "authority": "FR" }] }
+    ],
+    "edges": [
+        { "id": "e-1", "type": "supplies", "source": "org-b", "target": "org-a" },
+        { "id": "e-2", "type": "supplies", "source": "org-c", "target": "org-a" }
+    ]
+}"#;
+
+/// Writes [`ORIGINAL`] and its partner-scope redaction retaining `org-a`
+/// into `dir`, returning `(original, redacted)`.
+fn original_and_redacted(dir: &Path) -> (PathBuf, PathBuf) {
+    let original = dir.join("original.omts");
+    std::fs::File::create(&original)
+        .and_then(|mut f| f.write_all(ORIGINAL.as_bytes()))
+        .expect("write original");
+    let out = Command::new(omts_bin())
+        .args(["redact", "--scope", "partner"])
+        .arg(&original)
+        .arg("org-a")
+        .output()
+        .expect("run omts redact");
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let redacted = dir.join("redacted.omts");
+    std::fs::write(&redacted, &out.stdout).expect("write redacted");
+    (original, redacted)
+}
+
+fn resolve(args: &[&str], redacted: &Path, candidates: &Path) -> std::process::Output {
+    Command::new(omts_bin())
+        .arg("resolve-boundary")
+        .arg(redacted)
+        .arg(candidates)
+        .args(args)
+        .output()
+        .expect("run omts resolve-boundary")
+}
+
+#[test]
+fn resolve_boundary_json_maps_refs_to_original_nodes() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let (original, redacted) = original_and_redacted(dir.path());
+    let out = resolve(&["--format", "json"], &redacted, &original);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON output");
+    assert_eq!(value["boundary_refs"], 2);
+    assert_eq!(value["resolved"], 1);
+    let resolutions = value["resolutions"].as_array().expect("resolutions array");
+    assert_eq!(resolutions[0]["node_id"], "org-b");
+    let matches = resolutions[0]["matches"].as_array().expect("matches array");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0]["candidate_id"], "org-b");
+    assert_eq!(matches[0]["complete"], true);
+    assert_eq!(
+        matches[0]["identifiers"],
+        serde_json::json!(["duns:081466849", "lei:529900T8BM49AURSDO55"])
+    );
+    assert_eq!(resolutions[1]["node_id"], "org-c");
+    assert_eq!(resolutions[1]["matches"], serde_json::json!([]));
+}
+
+#[test]
+fn resolve_boundary_human_lists_matches_and_unresolved_refs() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let (original, redacted) = original_and_redacted(dir.path());
+    let out = resolve(&[], &redacted, &original);
+    assert_eq!(out.status.code(), Some(0));
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("org-b -> org-b (complete: duns:081466849, lei:529900T8BM49AURSDO55)"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("org-c: unresolved"), "stdout: {stdout}");
+    assert!(
+        stdout.contains("1 of 2 boundary refs resolved"),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn resolve_boundary_without_matches_exits_1() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let (_original, redacted) = original_and_redacted(dir.path());
+    let out = resolve(&[], &redacted, &fixture("minimal.omts"));
+    assert_eq!(
+        out.status.code(),
+        Some(1),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("matched a candidate"), "stderr: {stderr}");
+}
@@ -0,0 +1,145 @@
+//! Integration tests for `omts segment`.
+#![allow(clippy::expect_used)]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+fn run(args: &[&str]) -> std::process::Output {
+    Command::new(omts_bin())
+        .args(args)
+        .output()
+        .expect("run omts segment")
+}
+
+/// Returns the segment number of `id` from a JSON mapping.
+fn segment_of(value: &serde_json::Value, id: &str) -> u64 {
+    value["segments"]
+        .as_array()
+        .expect("segments array")
+        .iter()
+        .find(|s| {
+            s["members"]
+                .as_array()
+                .is_some_and(|m| m.iter().any(|v| v == id))
+        })
+        .and_then(|s| s["segment"].as_u64())
+        .expect("node is segmented")
+}
+
+#[test]
+fn segment_human_lists_two_clusters() {
+    let out = run(&["segment", fixture("segment.omts").to_str().expect("path")]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("segment 1 (3 nodes):"), "stdout: {stdout}");
+    assert!(stdout.contains("segment 2 (3 nodes):"), "stdout: {stdout}");
+    assert!(stdout.contains("2 segments"), "stdout: {stdout}");
+}
+
+#[test]
+fn segment_json_mapping_excludes_ownership() {
+    let out = run(&[
+        "segment",
+        fixture("segment.omts").to_str().expect("path"),
+        "--format",
+        "json",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    assert_eq!(value["segment_count"], 2);
+    assert_eq!(segment_of(&value, "metal-1"), segment_of(&value, "metal-3"));
+    assert_eq!(segment_of(&value, "poly-1"), segment_of(&value, "poly-3"));
+    assert_ne!(segment_of(&value, "metal-1"), segment_of(&value, "poly-1"));
+    let all_members: Vec<&serde_json::Value> = value["segments"]
+        .as_array()
+        .expect("segments")
+        .iter()
+        .flat_map(|s| s["members"].as_array().expect("members"))
+        .collect();
+    assert!(
+        !all_members.iter().any(|m| *m == "holding"),
+        "ownership edges are not part of the supply subgraph"
+    );
+}
+
+#[test]
+fn segment_weighted_by_annual_value_follows_heavy_bridge() {
+    let out = run(&[
+        "segment",
+        fixture("segment.omts").to_str().expect("path"),
+        "--weight",
+        "annual-value",
+        "--format",
+        "json",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    assert_eq!(segment_of(&value, "metal-3"), segment_of(&value, "poly-1"));
+}
+
+#[test]
+fn segment_write_labels_emits_labelled_file() {
+    let out = run(&[
+        "segment",
+        fixture("segment.omts").to_str().expect("path"),
+        "--write-labels",
+        "--label-key",
+        "com.acme.segment",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    let nodes = value["nodes"].as_array().expect("nodes");
+    let label_of = |id: &str| -> Option<String> {
+        nodes
+            .iter()
+            .find(|n| n["id"] == id)
+            .and_then(|n| n["labels"].as_array())
+            .and_then(|labels| labels.iter().find(|l| l["key"] == "com.acme.segment"))
+            .and_then(|l| l["value"].as_str().map(str::to_owned))
+    };
+    assert!(label_of("metal-1").is_some());
+    assert_eq!(label_of("metal-1"), label_of("metal-2"));
+    assert!(label_of("holding").is_none());
+    assert_eq!(value["edges"].as_array().expect("edges").len(), 8);
+}
+
+#[test]
+fn segment_no_matching_edges_exits_1() {
+    let out = run(&[
+        "segment",
+        fixture("segment.omts").to_str().expect("path"),
+        "--edge-type",
+        "same_as",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn segment_label_key_requires_write_labels() {
+    let out = run(&[
+        "segment",
+        fixture("segment.omts").to_str().expect("path"),
+        "--label-key",
+        "x",
+    ]);
+    assert_eq!(out.status.code(), Some(2));
+}
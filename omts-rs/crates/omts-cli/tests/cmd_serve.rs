@@ -0,0 +1,360 @@
+//! Integration tests for `omts serve`, driven over a loopback socket.
+#![allow(clippy::expect_used)]
+
+use std::io::{BufRead as _, BufReader, Read as _, Write as _};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+/// A running `omts serve` process, killed on drop.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    /// Starts `omts <global args> serve --port 0 <files>` and waits until it
+    /// reports the bound address.
+    fn start(global: &[&str], files: &[&Path]) -> Server {
+        let mut child = Command::new(omts_bin())
+            .args(global)
+            .args(["serve", "--port", "0"])
+            .args(files)
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("spawn omts serve");
+        let mut stderr = BufReader::new(child.stderr.take().expect("stderr"));
+        let mut addr = None;
+        let mut line = String::new();
+        while addr.is_none() {
+            line.clear();
+            let read = stderr.read_line(&mut line).expect("read stderr");
+            assert!(read > 0, "omts serve exited before listening");
+            addr = line
+                .trim_end()
+                .strip_prefix("listening on http://")
+                .map(str::to_owned);
+        }
+        // Keep draining stderr so reload messages never block the server.
+        std::thread::spawn(move || std::io::copy(&mut stderr, &mut std::io::sink()));
+        Server {
+            child,
+            addr: addr.expect("listening address"),
+        }
+    }
+
+    fn get(&self, target: &str) -> Reply {
+        self.request("GET", target, b"")
+    }
+
+    fn post(&self, target: &str, body: &[u8]) -> Reply {
+        self.request("POST", target, body)
+    }
+
+    fn request(&self, method: &str, target: &str, body: &[u8]) -> Reply {
+        let mut stream = TcpStream::connect(&self.addr).expect("connect");
+        write!(
+            stream,
+            "{method} {target} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\
+             Content-Length: {}\r\n\r\n",
+            self.addr,
+            body.len()
+        )
+        .expect("write request head");
+        stream.write_all(body).expect("write request body");
+
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw).expect("read response");
+        let split = raw
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .expect("end of response head");
+        let head = String::from_utf8_lossy(&raw[..split]).into_owned();
+        let mut lines = head.lines();
+        let status = lines
+            .next()
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|s| s.parse().ok())
+            .expect("status code");
+        let exit_code = lines
+            .filter_map(|l| l.split_once(':'))
+            .find(|(name, _)| name.eq_ignore_ascii_case("x-omts-exit-code"))
+            .and_then(|(_, value)| value.trim().parse().ok());
+        Reply {
+            status,
+            exit_code,
+            body: raw[split + 4..].to_vec(),
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        if self.child.kill().is_ok() {
+            self.child.wait().ok();
+        }
+    }
+}
+
+struct Reply {
+    status: u16,
+    exit_code: Option<i32>,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("JSON response body")
+    }
+}
+
+/// Runs `omts --format json <args>` and returns its stdout.
+fn cli_json(args: &[&str]) -> Vec<u8> {
+    Command::new(omts_bin())
+        .args(["--format", "json"])
+        .args(args)
+        .output()
+        .expect("run omts")
+        .stdout
+}
+
+#[test]
+fn serve_query_reach_and_path_match_cli_json() {
+    let file = fixture("graph-paths.omts");
+    let path = file.to_str().expect("path");
+    let server = Server::start(&[], &[&file]);
+
+    let cases: [(&str, &[&str]); 3] = [
+        (
+            "/query?node-type=organization&name=org",
+            &[
+                "query",
+                path,
+                "--node-type",
+                "organization",
+                "--name",
+                "org",
+            ],
+        ),
+        (
+            "/reach?node=org-a&edge-type=supplies",
+            &["reach", path, "org-a", "--edge-type", "supplies"],
+        ),
+        (
+            "/path?from=org-a&to=fac-d",
+            &["path", path, "org-a", "fac-d"],
+        ),
+    ];
+    for (target, args) in cases {
+        let reply = server.get(target);
+        assert_eq!(reply.status, 200, "{target}");
+        assert_eq!(reply.exit_code, Some(0), "{target}");
+        assert_eq!(
+            String::from_utf8_lossy(&reply.body),
+            String::from_utf8_lossy(&cli_json(args)),
+            "{target}"
+        );
+    }
+}
+
+#[test]
+fn serve_query_count_and_url_encoding() {
+    let server = Server::start(&[], &[&fixture("graph-query.omts")]);
+    let reply = server.get("/query?name=org+b&count");
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.json(), serde_json::json!({"nodes": 1, "edges": 0}));
+
+    let reply = server.get("/query?where=type%3Dorganization%20AND%20name%3D%22Org%20C%22");
+    assert_eq!(
+        reply.status,
+        200,
+        "{}",
+        String::from_utf8_lossy(&reply.body)
+    );
+    assert_eq!(reply.json()["nodes"][0]["id"], "org-c");
+}
+
+#[test]
+fn serve_subgraph_returns_valid_omts() {
+    let server = Server::start(&[], &[&fixture("graph-query.omts")]);
+    let reply = server.get("/subgraph?node=org-b&expand=1");
+    assert_eq!(reply.status, 200);
+    let mut ids: Vec<String> = reply.json()["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .filter_map(|n| n["id"].as_str().map(str::to_owned))
+        .collect();
+    ids.sort();
+    assert_eq!(ids, ["org-a", "org-b", "org-c"]);
+}
+
+#[test]
+fn serve_diff_reports_differences_in_exit_code_header() {
+    let base = fixture("diff-base.omts");
+    let modified = fixture("diff-modified.omts");
+    let server = Server::start(&[], &[&base, &modified]);
+
+    let reply = server.get("/diff");
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.exit_code, Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&reply.body),
+        String::from_utf8_lossy(&cli_json(&[
+            "diff",
+            base.to_str().expect("path"),
+            modified.to_str().expect("path"),
+        ]))
+    );
+
+    let same = server.get("/diff?file=diff-base.omts&file=diff-base.omts");
+    assert_eq!(same.status, 200);
+    assert_eq!(same.exit_code, Some(0));
+}
+
+#[test]
+fn serve_validates_posted_document() {
+    let server = Server::start(&[], &[&fixture("graph-query.omts")]);
+    let body = std::fs::read(fixture("invalid-edge.omts")).expect("read fixture");
+
+    let reply = server.post("/validate?level=1", &body);
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.exit_code, Some(1));
+    let text = String::from_utf8_lossy(&reply.body);
+    let summary: serde_json::Value =
+        serde_json::from_str(text.lines().last().expect("summary line")).expect("NDJSON");
+    assert!(
+        summary["summary"]["errors"].as_u64().expect("error count") > 0,
+        "{text}"
+    );
+
+    let served = server.get("/validate");
+    assert_eq!(served.exit_code, Some(0));
+}
+
+#[test]
+fn serve_merges_and_redacts() {
+    let server = Server::start(
+        &[],
+        &[
+            &fixture("merge-a.omts"),
+            &fixture("merge-b.omts"),
+            &fixture("redact-internal.omts"),
+        ],
+    );
+
+    let merged = server.get("/merge?file=merge-a.omts&file=merge-b.omts");
+    assert_eq!(
+        merged.status,
+        200,
+        "{}",
+        String::from_utf8_lossy(&merged.body)
+    );
+    assert!(
+        merged.json()["nodes"]
+            .as_array()
+            .is_some_and(|n| !n.is_empty())
+    );
+
+    let redacted = server.get("/redact?file=redact-internal.omts&scope=public");
+    assert_eq!(redacted.status, 200);
+    let nodes = redacted.json()["nodes"].clone();
+    assert!(
+        nodes
+            .as_array()
+            .expect("nodes")
+            .iter()
+            .all(|n| n["type"] != "person"),
+        "public redaction drops person nodes"
+    );
+}
+
+#[test]
+fn serve_errors_are_json_with_cli_exit_codes() {
+    let server = Server::start(
+        &[],
+        &[&fixture("graph-query.omts"), &fixture("graph-paths.omts")],
+    );
+
+    let missing = server.get("/reach?file=graph-query.omts&node=nope");
+    assert_eq!(missing.status, 404);
+    assert_eq!(missing.json()["exit_code"], 1);
+    assert!(
+        missing.json()["error"]
+            .as_str()
+            .expect("error")
+            .contains("nope")
+    );
+
+    let ambiguous = server.get("/reach?node=org-a");
+    assert_eq!(ambiguous.status, 400);
+    assert_eq!(ambiguous.json()["exit_code"], 2);
+
+    let unknown_param = server.get("/reach?file=graph-query.omts&node=org-a&dpeth=1");
+    assert_eq!(unknown_param.status, 400);
+
+    let unknown_file = server.get("/query?file=other.omts&name=org");
+    assert_eq!(unknown_file.status, 400);
+
+    let no_scope = server.get("/redact?file=graph-query.omts");
+    assert_eq!(no_scope.status, 400);
+
+    assert_eq!(server.get("/nowhere").status, 404);
+}
+
+#[test]
+fn serve_reloads_changed_files() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let file = dir.path().join("graph.omts");
+    std::fs::copy(fixture("graph-query.omts"), &file).expect("copy fixture");
+    let server = Server::start(&[], &[&file]);
+
+    assert_eq!(server.get("/query?name=echo").status, 404);
+
+    let content = std::fs::read_to_string(&file).expect("read copy");
+    std::fs::write(&file, content.replace("\"Org E\"", "\"Org Echo\"")).expect("edit copy");
+
+    let reply = server.get("/query?name=echo");
+    assert_eq!(reply.status, 200);
+    assert_eq!(reply.json()["nodes"][0]["name"], "Org Echo");
+}
+
+#[test]
+fn serve_rejects_bodies_over_max_file_size() {
+    let server = Server::start(
+        &["--max-file-size", "100000"],
+        &[&fixture("graph-query.omts")],
+    );
+    let reply = server.post("/validate", &vec![b' '; 100_001]);
+    assert_eq!(reply.status, 413);
+    assert_eq!(reply.json()["exit_code"], 2);
+}
+
+#[test]
+fn serve_missing_file_exits_2() {
+    let out = Command::new(omts_bin())
+        .args(["serve", "--port", "0"])
+        .arg(fixture("does-not-exist.omts"))
+        .output()
+        .expect("run omts serve");
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -0,0 +1,173 @@
+//! Integration tests for `omts shell` driven by a script on stdin.
+#![allow(clippy::expect_used)]
+
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+/// Runs `omts <global args> shell <fixture>` with `script` on stdin.
+fn shell(global: &[&str], name: &str, script: &str) -> Output {
+    let mut child = Command::new(omts_bin())
+        .args(global)
+        .arg("shell")
+        .arg(fixture(name))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn omts shell");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(script.as_bytes())
+        .expect("write script");
+    child.wait_with_output().expect("wait for omts shell")
+}
+
+#[test]
+fn shell_runs_query_and_reach() {
+    let out = shell(
+        &[],
+        "graph-query.omts",
+        "query --name \"org b\"\nreach org-c\n",
+    );
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("node\torg-b\torganization\tOrg B"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("org-d"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("loaded 5 node(s), 4 edge(s)"), "{stderr}");
+}
+
+#[test]
+fn shell_cd_sets_current_node_for_navigation() {
+    let out = shell(
+        &[],
+        "graph-query.omts",
+        "cd org-b\nneighbours\nreach .\nshow\n",
+    );
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("in\te-ab\tsupplies\torg-a\tOrg A"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("out\te-bc\tsupplies\torg-c\tOrg C"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("\"name\": \"Org B\""), "{stdout}");
+}
+
+#[test]
+fn shell_dot_without_current_node_is_an_error() {
+    let out = shell(&[], "graph-query.omts", "reach\n");
+    assert!(out.status.success(), "shell itself should exit 0");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("no current node"), "{stderr}");
+}
+
+#[test]
+fn shell_errors_do_not_end_the_session() {
+    let out = shell(
+        &[],
+        "graph-query.omts",
+        "cd missing\nbogus\nquery --name 'unterminated\nreach org-c\n",
+    );
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("node not found"), "{stderr}");
+    assert!(stderr.contains("unrecognized subcommand"), "{stderr}");
+    assert!(stderr.contains("unterminated single quote"), "{stderr}");
+    assert!(String::from_utf8_lossy(&out.stdout).contains("org-d"));
+}
+
+#[test]
+fn shell_exit_stops_reading_commands() {
+    let out = shell(&[], "graph-query.omts", "exit\nreach org-c\n");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    assert!(out.stdout.is_empty(), "nothing should run after exit");
+}
+
+#[test]
+fn shell_saves_selection_as_omts() {
+    let tmp = tempfile::NamedTempFile::new().expect("temp file");
+    let target = tmp.path().to_str().expect("path");
+    let script = format!("subgraph org-b --expand 1\nselection\nsave {target}\n");
+    let out = shell(&[], "graph-query.omts", &script);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(stdout, "org-a\norg-b\norg-c\n");
+
+    let saved: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(tmp.path()).expect("read saved")).expect("JSON");
+    let mut ids: Vec<&str> = saved["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .filter_map(|n| n["id"].as_str())
+        .collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["org-a", "org-b", "org-c"]);
+
+    let validate = Command::new(omts_bin())
+        .args(["validate", target])
+        .output()
+        .expect("run omts validate");
+    assert!(validate.status.success(), "saved selection should validate");
+}
+
+#[test]
+fn shell_path_select_keeps_traversed_edges() {
+    let out = shell(
+        &["--format", "json"],
+        "graph-paths.omts",
+        "path org-a fac-d --select --max-paths 1\nselection\n",
+    );
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON");
+    assert_eq!(
+        value["node_ids"],
+        serde_json::json!(["fac-d", "org-a", "org-d"])
+    );
+    assert_eq!(value["edges"], 2);
+}
+
+#[test]
+fn shell_save_without_selection_reports_error() {
+    let tmp = tempfile::NamedTempFile::new().expect("temp file");
+    let script = format!("save {}\n", tmp.path().display());
+    let out = shell(&[], "graph-query.omts", &script);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("nothing selected"));
+}
+
+#[test]
+fn shell_missing_file_exits_2() {
+    let out = shell(&[], "does-not-exist.omts", "");
+    assert_eq!(out.status.code(), Some(2));
+}
@@ -523,3 +523,27 @@ fn subgraph_selector_no_match_exits_1() {
         "expected exit 1 when selectors match nothing"
     );
 }
+
+#[test]
+fn subgraph_where_expression_selects_seeds() {
+    let out = Command::new(omts_bin())
+        .args([
+            "subgraph",
+            fixture("graph-labels.omts").to_str().expect("path"),
+            "--where",
+            "node.label:tier AND NOT label:tier=1",
+        ])
+        .output()
+        .expect("run omts subgraph --where");
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let value: serde_json::Value =
+        serde_json::from_str(stdout.trim()).expect("valid JSON from subgraph");
+    let ids: Vec<&str> = value["nodes"]
+        .as_array()
+        .expect("nodes array")
+        .iter()
+        .filter_map(|n| n["id"].as_str())
+        .collect();
+    assert_eq!(ids, vec!["org-tier2"]);
+}
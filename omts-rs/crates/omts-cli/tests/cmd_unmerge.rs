@@ -0,0 +1,205 @@
+//! Integration tests for `omts unmerge`.
+#![allow(clippy::expect_used)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+/// Merges `merge-a.omts` and `merge-b.omts` into `dir`, with or without
+/// provenance, and returns the merged file's path.
+fn merged(dir: &Path, provenance: bool) -> PathBuf {
+    let mut cmd = Command::new(omts_bin());
+    cmd.arg("merge")
+        .arg(fixture("merge-a.omts"))
+        .arg(fixture("merge-b.omts"));
+    if provenance {
+        cmd.arg("--provenance");
+    }
+    let out = cmd.output().expect("run omts merge");
+    assert_eq!(out.status.code(), Some(0));
+    let path = dir.join(if provenance {
+        "merged-prov.omts"
+    } else {
+        "merged.omts"
+    });
+    std::fs::write(&path, &out.stdout).expect("write merged file");
+    path
+}
+
+fn unmerge(file: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(omts_bin())
+        .arg("unmerge")
+        .arg(file)
+        .args(args)
+        .output()
+        .expect("run omts unmerge")
+}
+
+fn stdout_json(out: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&out.stdout).expect("JSON stdout")
+}
+
+/// ID of the merged node carrying the shared LEI.
+fn acme_id(file: &serde_json::Value) -> String {
+    file["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .find(|n| n["_provenance"].as_array().is_some_and(|p| p.len() == 2))
+        .and_then(|n| n["id"].as_str())
+        .expect("merged acme node")
+        .to_owned()
+}
+
+#[test]
+fn merge_provenance_records_source_elements() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let text = std::fs::read(merged(dir.path(), true)).expect("read merged");
+    let file: serde_json::Value = serde_json::from_slice(&text).expect("JSON");
+
+    let acme = file["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .find(|n| n["id"] == acme_id(&file).as_str())
+        .expect("acme node");
+    let sources: Vec<(&str, &str)> = acme["_provenance"]
+        .as_array()
+        .expect("provenance")
+        .iter()
+        .map(|p| {
+            (
+                p["source_file"].as_str().expect("source_file"),
+                p["node_id"].as_str().expect("node_id"),
+            )
+        })
+        .collect();
+    assert_eq!(sources, [("file_0", "org-acme"), ("file_1", "org-acme-2")]);
+
+    let plain = std::fs::read_to_string(merged(dir.path(), false)).expect("read merged");
+    assert!(!plain.contains("_provenance"));
+}
+
+#[test]
+fn unmerge_node_splits_and_rewires() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = merged(dir.path(), true);
+    let file: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&path).expect("read")).expect("JSON");
+    let acme = acme_id(&file);
+
+    let out = unmerge(&path, &["--node", &acme]);
+    assert_eq!(out.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("restored 2 node(s)"));
+
+    let split = stdout_json(&out);
+    let ids: Vec<&str> = split["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .filter_map(|n| n["id"].as_str())
+        .collect();
+    assert!(ids.contains(&"org-acme"));
+    assert!(ids.contains(&"org-acme-2"));
+    assert!(!ids.contains(&acme.as_str()));
+
+    let edges = split["edges"].as_array().expect("edges");
+    let supplies = edges
+        .iter()
+        .find(|e| e["type"] == "supplies")
+        .expect("supplies edge");
+    assert_eq!(supplies["target"], "org-acme");
+    let operates = edges
+        .iter()
+        .find(|e| e["type"] == "operates")
+        .expect("operates edge");
+    assert_eq!(operates["source"], "org-acme-2");
+
+    let split_path = dir.path().join("split.omts");
+    std::fs::write(&split_path, &out.stdout).expect("write split");
+    let validate = Command::new(omts_bin())
+        .args(["validate", "--level", "1"])
+        .arg(&split_path)
+        .output()
+        .expect("run omts validate");
+    assert_eq!(validate.status.code(), Some(0));
+}
+
+#[test]
+fn unmerge_source_rebuilds_one_input() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let out = unmerge(&merged(dir.path(), true), &["--source", "file_1"]);
+    assert_eq!(out.status.code(), Some(0));
+
+    let file = stdout_json(&out);
+    let mut ids: Vec<&str> = file["nodes"]
+        .as_array()
+        .expect("nodes")
+        .iter()
+        .filter_map(|n| n["id"].as_str())
+        .collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["org-acme-2", "org-factory"]);
+    assert_eq!(file["edges"][0]["id"], "e-operates");
+    assert!(file.get("merge_metadata").is_none());
+}
+
+#[test]
+fn unmerge_source_accepts_input_file_name() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let with = merged(dir.path(), true);
+    let by_label = unmerge(&with, &["--source", "file_1"]);
+    let by_name = unmerge(&with, &["--source", "merge-b.omts"]);
+    assert_eq!(by_name.status.code(), Some(0));
+    assert_eq!(stdout_json(&by_name), stdout_json(&by_label));
+
+    let out = unmerge(&with, &["--source", "merge-c.omts"]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("file_0 (merge-a.omts), file_1 (merge-b.omts)"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn unmerge_errors() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let with = merged(dir.path(), true);
+
+    assert_eq!(
+        unmerge(&with, &["--node", "no-such-node"]).status.code(),
+        Some(1)
+    );
+    assert_eq!(
+        unmerge(&with, &["--source", "file_9"]).status.code(),
+        Some(2)
+    );
+    assert_eq!(unmerge(&with, &[]).status.code(), Some(2));
+
+    let without = merged(dir.path(), false);
+    let file: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&without).expect("read")).expect("JSON");
+    let node = file["nodes"][0]["id"].as_str().expect("node id");
+    let out = unmerge(&without, &["--node", node]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--provenance"));
+}
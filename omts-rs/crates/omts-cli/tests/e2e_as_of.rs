@@ -0,0 +1,145 @@
+//! Integration tests for the global `--as-of` flag.
+#![allow(clippy::expect_used)]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Path to the compiled `omts` binary.
+fn omts_bin() -> PathBuf {
+    let mut path = std::env::current_exe().expect("current exe");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.push("omts");
+    path
+}
+
+/// Path to a shared fixture file.
+fn fixture(name: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../../tests/fixtures");
+    path.push(name);
+    path
+}
+
+fn run(args: &[&str]) -> std::process::Output {
+    Command::new(omts_bin())
+        .args(args)
+        .output()
+        .expect("run omts")
+}
+
+#[test]
+fn reach_as_of_follows_only_valid_ownership() {
+    let path = fixture("temporal.omts");
+    let path = path.to_str().expect("path");
+
+    let out = run(&["reach", path, "org-a", "--as-of", "2018-06-30"]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("org-b"), "stdout: {stdout}");
+    assert!(!stdout.contains("org-c"), "stdout: {stdout}");
+
+    let out = run(&["reach", path, "org-a", "--as-of", "2022-01-01"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(!stdout.contains("org-b"), "stdout: {stdout}");
+    assert!(stdout.contains("org-c"), "stdout: {stdout}");
+}
+
+#[test]
+fn query_as_of_drops_expired_edges() {
+    let out = run(&[
+        "query",
+        fixture("temporal.omts").to_str().expect("path"),
+        "--edge-type",
+        "ownership",
+        "--as-of",
+        "2022-01-01",
+        "--format",
+        "json",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    let edges = value["edges"].as_array().expect("edges array");
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["id"], "e-own-ac");
+}
+
+#[test]
+fn inspect_as_of_filters_identifiers_and_attestations() {
+    let out = run(&[
+        "inspect",
+        fixture("temporal.omts").to_str().expect("path"),
+        "--as-of",
+        "2023-01-01",
+        "--format",
+        "json",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    assert_eq!(value["node_count"], 4, "expired attestation dropped");
+    assert_eq!(value["edge_count"], 2);
+    assert_eq!(value["identifier_count"], 1, "retired DUNS dropped");
+}
+
+#[test]
+fn subgraph_as_of_emits_valid_file_without_expired_edges() {
+    let out = run(&[
+        "subgraph",
+        fixture("temporal.omts").to_str().expect("path"),
+        "org-a",
+        "--expand",
+        "2",
+        "--as-of",
+        "2019-06-30",
+    ]);
+    assert!(out.status.success(), "exit code: {:?}", out.status.code());
+    let value: serde_json::Value = serde_json::from_slice(&out.stdout).expect("valid JSON");
+    let edge_ids: Vec<&str> = value["edges"]
+        .as_array()
+        .expect("edges array")
+        .iter()
+        .filter_map(|e| e["id"].as_str())
+        .collect();
+    assert!(edge_ids.contains(&"e-own-ab"), "edges: {edge_ids:?}");
+    assert!(edge_ids.contains(&"e-att-b"), "edges: {edge_ids:?}");
+    assert!(!edge_ids.contains(&"e-own-ac"), "edges: {edge_ids:?}");
+}
+
+#[test]
+fn path_as_of_no_path_before_relationship_exists() {
+    let out = run(&[
+        "path",
+        fixture("temporal.omts").to_str().expect("path"),
+        "org-d",
+        "org-c",
+        "--as-of",
+        "2020-06-30",
+    ]);
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn as_of_rejected_by_unsupported_command() {
+    let out = run(&[
+        "validate",
+        fixture("temporal.omts").to_str().expect("path"),
+        "--as-of",
+        "2020-06-30",
+    ]);
+    assert_eq!(out.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("--as-of"), "stderr: {stderr}");
+}
+
+#[test]
+fn as_of_invalid_date_is_usage_error() {
+    let out = run(&[
+        "inspect",
+        fixture("temporal.omts").to_str().expect("path"),
+        "--as-of",
+        "June 2020",
+    ]);
+    assert_eq!(out.status.code(), Some(2));
+}
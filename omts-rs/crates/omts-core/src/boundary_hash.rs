@@ -37,7 +37,7 @@ impl fmt::Display for BoundaryHashError {
 impl std::error::Error for BoundaryHashError {}
 
 /// Encodes a byte slice as a lowercase hexadecimal string.
-fn hex_encode(bytes: &[u8]) -> String {
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
     const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
     let mut out = String::with_capacity(bytes.len() * 2);
     for &b in bytes {
@@ -110,13 +110,20 @@ pub fn boundary_ref_value(
     let mut canonicals: Vec<&str> = public_ids.iter().map(CanonicalId::as_str).collect();
     canonicals.sort_unstable();
 
-    let joined = canonicals.join("\n");
+    Ok(salted_hash(&canonicals, salt))
+}
+
+/// The deterministic path of [`boundary_ref_value`]: joins the already
+/// sorted canonical strings with `\n`, appends the salt, and returns the
+/// hex-encoded SHA-256 digest.
+pub(crate) fn salted_hash(sorted_canonicals: &[&str], salt: &[u8; 32]) -> String {
+    let joined = sorted_canonicals.join("\n");
 
     let mut hasher = Sha256::new();
     hasher.update(joined.as_bytes());
     hasher.update(salt.as_slice());
 
-    Ok(hex_encode(&hasher.finalize()))
+    hex_encode(&hasher.finalize())
 }
 
 /// Decodes a [`FileSalt`] from its 64-character hex representation to a
@@ -0,0 +1,24 @@
+/// Reviewing the conflicts a merge recorded (merge.md Section 4.9).
+///
+/// Merged nodes carry `_conflicts` in their extension fields and merged edges
+/// in their properties: one entry per property whose sources disagreed, with
+/// every value and the source file that contributed it. From those records:
+///
+/// - [`list_conflicts`] flattens them into [`ConflictRecord`]s, filtered by
+///   property, node type, or source file.
+/// - [`resolve_conflicts`] applies a [`ConflictDecision`] per conflict: the
+///   chosen value is written to the property, the entry is removed from
+///   `_conflicts`, and a [`ConflictResolution`] is appended to the element's
+///   `_resolutions` so the decision and the values it replaced stay on
+///   record.
+mod review;
+mod types;
+
+pub use review::{list_conflicts, resolve_conflicts};
+pub use types::{
+    ConflictDecision, ConflictFilter, ConflictRecord, ConflictResolution, ConflictReviewError,
+    ElementKind, ResolveOutput,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::dynvalue::{DynMap, DynValue};
+use crate::file::OmtsFile;
+use crate::merge::{Conflict, build_conflicts_value};
+
+use super::types::{
+    ConflictDecision, ConflictFilter, ConflictRecord, ConflictResolution, ConflictReviewError,
+    ElementKind, ResolveOutput,
+};
+
+/// Lists the conflicts recorded in `file` that match `filter`: node conflicts
+/// in node order, then edge conflicts in edge order, each element's by field.
+///
+/// # Errors
+///
+/// [`ConflictReviewError::InvalidConflicts`] — a `_conflicts` record is
+/// malformed.
+pub fn list_conflicts(
+    file: &OmtsFile,
+    filter: &ConflictFilter,
+) -> Result<Vec<ConflictRecord>, ConflictReviewError> {
+    let mut records = Vec::new();
+    let mut push = |element, id: String, element_type: &str, conflicts: Vec<Conflict>| {
+        for conflict in conflicts {
+            let field_matches =
+                filter.fields.is_empty() || filter.fields.iter().any(|f| *f == conflict.field);
+            let source_matches = filter.sources.is_empty()
+                || conflict
+                    .values
+                    .iter()
+                    .any(|entry| filter.sources.contains(&entry.source_file));
+            if field_matches && source_matches {
+                records.push(ConflictRecord {
+                    element,
+                    id: id.clone(),
+                    element_type: element_type.to_owned(),
+                    field: conflict.field,
+                    values: conflict.values,
+                    resolution: conflict.resolution,
+                });
+            }
+        }
+    };
+
+    for node in &file.nodes {
+        let node_type = node.node_type.as_str();
+        if !filter.node_types.is_empty() && !filter.node_types.iter().any(|t| t == node_type) {
+            continue;
+        }
+        let conflicts = read_records(&node.extra, "_conflicts")?;
+        push(ElementKind::Node, node.id.to_string(), node_type, conflicts);
+    }
+    if filter.node_types.is_empty() {
+        for edge in &file.edges {
+            let conflicts = read_records(&edge.properties.extra, "_conflicts")?;
+            push(
+                ElementKind::Edge,
+                edge.id.to_string(),
+                edge.edge_type.as_str(),
+                conflicts,
+            );
+        }
+    }
+    Ok(records)
+}
+
+/// Applies `decisions` to the conflicts recorded in `file`, in order.
+///
+/// Each decision writes its value to the property (on the node, or in the
+/// edge's properties), removes the conflict from `_conflicts`, and appends a
+/// [`ConflictResolution`] to the element's `_resolutions`. A
+/// `merge_metadata.conflict_count` in the header is updated to the number of
+/// conflicts left.
+///
+/// # Errors
+///
+/// - [`ConflictReviewError::ElementNotFound`] — a decision names an unknown
+///   node or edge.
+/// - [`ConflictReviewError::NoSuchConflict`] — the element has no conflict
+///   on the decision's field (including one resolved by an earlier decision).
+/// - [`ConflictReviewError::InvalidDecision`] — a decision gives both or
+///   neither of `value` and `source`, names a source that contributed no
+///   value, or its value does not fit the property.
+/// - [`ConflictReviewError::InvalidConflicts`] — a `_conflicts` or
+///   `_resolutions` record is malformed.
+pub fn resolve_conflicts(
+    file: &OmtsFile,
+    decisions: &[ConflictDecision],
+) -> Result<ResolveOutput, ConflictReviewError> {
+    let node_pos: HashMap<String, usize> = file
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(pos, node)| (node.id.to_string(), pos))
+        .collect();
+    let edge_pos: HashMap<String, usize> = file
+        .edges
+        .iter()
+        .enumerate()
+        .map(|(pos, edge)| (edge.id.to_string(), pos))
+        .collect();
+
+    let mut out = file.clone();
+    for decision in decisions {
+        if let Some(&pos) = node_pos.get(&decision.id) {
+            out.nodes[pos] = apply_decision(&out.nodes[pos], decision)?;
+        } else if let Some(&pos) = edge_pos.get(&decision.id) {
+            out.edges[pos].properties = apply_decision(&out.edges[pos].properties, decision)?;
+        } else {
+            return Err(ConflictReviewError::ElementNotFound(decision.id.clone()));
+        }
+    }
+
+    let mut remaining = 0;
+    for node in &out.nodes {
+        remaining += read_records::<Conflict>(&node.extra, "_conflicts")?.len();
+    }
+    for edge in &out.edges {
+        remaining += read_records::<Conflict>(&edge.properties.extra, "_conflicts")?.len();
+    }
+    if let Some(metadata) = out.extra.get_mut("merge_metadata") {
+        let mut json = serde_json::Value::from(metadata.clone());
+        if let Some(count) = json.get_mut("conflict_count") {
+            *count = serde_json::Value::from(remaining);
+            *metadata = DynValue::from(json);
+        }
+    }
+
+    Ok(ResolveOutput {
+        file: out,
+        resolved: decisions.len(),
+        remaining,
+    })
+}
+
+/// Resolves one conflict on `element`, a node or an edge's properties.
+fn apply_decision<T>(element: &T, decision: &ConflictDecision) -> Result<T, ConflictReviewError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let ConflictDecision {
+        id,
+        field,
+        value,
+        source,
+        note,
+    } = decision;
+    let mut json = serde_json::to_value(element).map_err(invalid)?;
+    let Some(map) = json.as_object_mut() else {
+        return Err(ConflictReviewError::InvalidConflicts(format!(
+            "`{id}` is not a JSON object"
+        )));
+    };
+
+    let mut conflicts: Vec<Conflict> = take_records(map, "_conflicts")?;
+    let Some(pos) = conflicts.iter().position(|c| c.field == *field) else {
+        return Err(ConflictReviewError::NoSuchConflict {
+            id: id.clone(),
+            field: field.clone(),
+        });
+    };
+    let conflict = conflicts.remove(pos);
+
+    let (value, source_file) = match (value, source) {
+        (Some(value), None) => {
+            let source_file = conflict
+                .values
+                .iter()
+                .find(|entry| entry.value == *value)
+                .map(|entry| entry.source_file.clone());
+            (value.clone(), source_file)
+        }
+        (None, Some(source)) => {
+            let entry = conflict
+                .values
+                .iter()
+                .find(|entry| entry.source_file == *source)
+                .ok_or_else(|| {
+                    ConflictReviewError::InvalidDecision(format!(
+                        "`{id}` `{field}`: no value came from `{source}`"
+                    ))
+                })?;
+            (entry.value.clone(), Some(source.clone()))
+        }
+        (Some(_), Some(_)) | (None, None) => {
+            return Err(ConflictReviewError::InvalidDecision(format!(
+                "`{id}` `{field}`: give exactly one of `value` and `source`"
+            )));
+        }
+    };
+
+    map.insert(field.clone(), value.clone());
+    if let Some(rest) = build_conflicts_value(conflicts) {
+        map.insert("_conflicts".to_owned(), rest);
+    }
+    let mut resolutions: Vec<ConflictResolution> = take_records(map, "_resolutions")?;
+    resolutions.push(ConflictResolution {
+        field: field.clone(),
+        value,
+        source_file,
+        values: conflict.values,
+        note: note.clone(),
+    });
+    map.insert(
+        "_resolutions".to_owned(),
+        serde_json::to_value(&resolutions).map_err(invalid)?,
+    );
+
+    serde_json::from_value(json)
+        .map_err(|e| ConflictReviewError::InvalidDecision(format!("`{id}` `{field}`: {e}")))
+}
+
+/// Reads a record array such as `_conflicts` from an element's extension
+/// fields; absent means empty.
+fn read_records<T: DeserializeOwned>(
+    extra: &DynMap,
+    key: &str,
+) -> Result<Vec<T>, ConflictReviewError> {
+    extra.get(key).map_or(Ok(Vec::new()), |value| {
+        serde_json::from_value(serde_json::Value::from(value.clone())).map_err(invalid)
+    })
+}
+
+/// Removes and parses a record array from an element's JSON form.
+fn take_records<T: DeserializeOwned>(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Result<Vec<T>, ConflictReviewError> {
+    map.remove(key).map_or(Ok(Vec::new()), |value| {
+        serde_json::from_value(value).map_err(invalid)
+    })
+}
+
+/// Wraps a serialization error.
+fn invalid(e: impl std::fmt::Display) -> ConflictReviewError {
+    ConflictReviewError::InvalidConflicts(e.to_string())
+}
@@ -0,0 +1,206 @@
+#![allow(clippy::expect_used)]
+
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::enums::{NodeType, NodeTypeTag};
+use crate::file::OmtsFile;
+use crate::merge_pipeline::{MergeConfig, merge_with_config};
+use crate::newtypes::CountryCode;
+use crate::structures::Node;
+use crate::test_helpers::{date, file_salt, node_id, semver};
+use crate::types::Identifier;
+use crate::unmerge::split_nodes;
+
+const SALT_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const SALT_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn supplier(id: &str, name: &str, jurisdiction: &str) -> Node {
+    Node {
+        id: node_id(id),
+        node_type: NodeTypeTag::Known(NodeType::Organization),
+        identifiers: Some(vec![Identifier {
+            scheme: "duns".to_owned(),
+            value: "081466849".to_owned(),
+            authority: None,
+            valid_from: None,
+            valid_to: None,
+            sensitivity: None,
+            verification_status: None,
+            verification_date: None,
+            extra: BTreeMap::new(),
+        }]),
+        name: Some(name.to_owned()),
+        jurisdiction: Some(CountryCode::try_from(jurisdiction).expect("country code")),
+        ..Node::default()
+    }
+}
+
+fn file(salt: &str, nodes: Vec<Node>) -> OmtsFile {
+    OmtsFile {
+        omts_version: semver("1.0.0"),
+        snapshot_date: date("2026-02-20"),
+        file_salt: file_salt(salt),
+        disclosure_scope: None,
+        previous_snapshot_ref: None,
+        snapshot_sequence: None,
+        reporting_entity: None,
+        nodes,
+        edges: vec![],
+        extra: BTreeMap::new(),
+    }
+}
+
+/// One merged supplier whose `name` and `jurisdiction` conflict.
+fn merged() -> OmtsFile {
+    let files = [
+        file(SALT_A, vec![supplier("sup-a", "Acme Metals", "DE")]),
+        file(SALT_B, vec![supplier("sup-b", "Acme Plastics", "FR")]),
+    ];
+    let config = MergeConfig {
+        record_provenance: true,
+        ..MergeConfig::default()
+    };
+    merge_with_config(&files, &config)
+        .expect("merge succeeds")
+        .file
+}
+
+fn decision(
+    field: &str,
+    value: Option<serde_json::Value>,
+    source: Option<&str>,
+) -> ConflictDecision {
+    ConflictDecision {
+        id: "n-0".to_owned(),
+        field: field.to_owned(),
+        value,
+        source: source.map(str::to_owned),
+        note: None,
+    }
+}
+
+#[test]
+fn list_filters_by_field_type_and_source() {
+    let file = merged();
+    let all = list_conflicts(&file, &ConflictFilter::default()).expect("list");
+    let fields: Vec<&str> = all.iter().map(|r| r.field.as_str()).collect();
+    assert_eq!(fields, ["jurisdiction", "name"]);
+    assert_eq!(all[1].element, ElementKind::Node);
+    assert_eq!(all[1].element_type, "organization");
+    assert_eq!(all[1].values.len(), 2);
+
+    let filtered = |filter: ConflictFilter| list_conflicts(&file, &filter).expect("list").len();
+    let by_field = ConflictFilter {
+        fields: vec!["name".to_owned()],
+        ..ConflictFilter::default()
+    };
+    assert_eq!(filtered(by_field), 1);
+    let by_source = ConflictFilter {
+        sources: vec!["file_1".to_owned()],
+        ..ConflictFilter::default()
+    };
+    assert_eq!(filtered(by_source), 2);
+    let other_source = ConflictFilter {
+        sources: vec!["file_7".to_owned()],
+        ..ConflictFilter::default()
+    };
+    assert_eq!(filtered(other_source), 0);
+    let other_type = ConflictFilter {
+        node_types: vec!["facility".to_owned()],
+        ..ConflictFilter::default()
+    };
+    assert_eq!(filtered(other_type), 0);
+}
+
+#[test]
+fn resolve_applies_values_and_records_resolutions() {
+    let file = merged();
+    let mut by_value = decision("jurisdiction", Some(serde_json::json!("DE")), None);
+    by_value.note = Some("registry extract".to_owned());
+    let decisions = [decision("name", None, Some("file_1")), by_value];
+    let output = resolve_conflicts(&file, &decisions).expect("resolve");
+    assert_eq!(output.resolved, 2);
+    assert_eq!(output.remaining, 0);
+
+    let node = &output.file.nodes[0];
+    assert_eq!(node.name.as_deref(), Some("Acme Plastics"));
+    assert_eq!(
+        node.jurisdiction.as_ref().map(ToString::to_string),
+        Some("DE".to_owned())
+    );
+    assert!(!node.extra.contains_key("_conflicts"));
+
+    let resolutions: Vec<ConflictResolution> = serde_json::from_value(serde_json::Value::from(
+        node.extra.get("_resolutions").expect("resolutions").clone(),
+    ))
+    .expect("resolution records");
+    assert_eq!(resolutions.len(), 2);
+    assert_eq!(resolutions[0].source_file.as_deref(), Some("file_1"));
+    assert_eq!(resolutions[1].source_file.as_deref(), Some("file_0"));
+    assert_eq!(resolutions[1].note.as_deref(), Some("registry extract"));
+    assert_eq!(resolutions[1].values.len(), 2);
+
+    let metadata = serde_json::Value::from(
+        output
+            .file
+            .extra
+            .get("merge_metadata")
+            .expect("metadata")
+            .clone(),
+    );
+    assert_eq!(metadata["conflict_count"], 0);
+    assert!(
+        list_conflicts(&output.file, &ConflictFilter::default())
+            .expect("list")
+            .is_empty()
+    );
+
+    // Unmerging still gives each constituent its own values.
+    let split = split_nodes(&output.file, &[node_id("n-0")]).expect("split");
+    let names: Vec<Option<&str>> = split.file.nodes.iter().map(|n| n.name.as_deref()).collect();
+    assert_eq!(names, [Some("Acme Metals"), Some("Acme Plastics")]);
+}
+
+#[test]
+fn resolve_rejects_bad_decisions() {
+    let file = merged();
+    let resolve = |d: ConflictDecision| resolve_conflicts(&file, &[d]).map(|o| o.resolved);
+
+    let mut unknown = decision("name", None, Some("file_0"));
+    unknown.id = "n-9".to_owned();
+    assert_eq!(
+        resolve(unknown),
+        Err(ConflictReviewError::ElementNotFound("n-9".to_owned()))
+    );
+    assert!(matches!(
+        resolve(decision("status", None, Some("file_0"))),
+        Err(ConflictReviewError::NoSuchConflict { .. })
+    ));
+    assert!(matches!(
+        resolve(decision(
+            "name",
+            Some(serde_json::json!("X")),
+            Some("file_0")
+        )),
+        Err(ConflictReviewError::InvalidDecision(_))
+    ));
+    assert!(matches!(
+        resolve(decision("name", None, Some("file_7"))),
+        Err(ConflictReviewError::InvalidDecision(_))
+    ));
+    assert!(matches!(
+        resolve(decision("jurisdiction", Some(serde_json::json!(42)), None)),
+        Err(ConflictReviewError::InvalidDecision(_))
+    ));
+
+    // A conflict resolved once cannot be resolved again.
+    let twice = [
+        decision("name", None, Some("file_0")),
+        decision("name", None, Some("file_1")),
+    ];
+    assert!(matches!(
+        resolve_conflicts(&file, &twice),
+        Err(ConflictReviewError::NoSuchConflict { .. })
+    ));
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+
+use crate::file::OmtsFile;
+use crate::merge::ConflictEntry;
+
+/// Errors from [`super::list_conflicts`] and [`super::resolve_conflicts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictReviewError {
+    /// A `_conflicts` or `_resolutions` record is malformed.
+    InvalidConflicts(String),
+    /// A decision names a node or edge that is not in the file.
+    ElementNotFound(String),
+    /// A decision names a property with no recorded conflict on its element.
+    NoSuchConflict {
+        /// The node or edge ID.
+        id: String,
+        /// The property name.
+        field: String,
+    },
+    /// A decision is malformed, or no value of the conflict matches it.
+    InvalidDecision(String),
+}
+
+impl std::fmt::Display for ConflictReviewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidConflicts(msg) => write!(f, "invalid conflict record: {msg}"),
+            Self::ElementNotFound(id) => write!(f, "no node or edge `{id}`"),
+            Self::NoSuchConflict { id, field } => {
+                write!(f, "`{id}` has no conflict on `{field}`")
+            }
+            Self::InvalidDecision(msg) => write!(f, "invalid decision: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ConflictReviewError {}
+
+/// Whether a conflict is on a node or an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementKind {
+    /// A node; the conflict is in its `_conflicts` extension field.
+    Node,
+    /// An edge; the conflict is in its properties' `_conflicts`.
+    Edge,
+}
+
+impl ElementKind {
+    /// `node` or `edge`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Node => "node",
+            Self::Edge => "edge",
+        }
+    }
+}
+
+/// Which conflicts [`super::list_conflicts`] returns.
+///
+/// Each list matches when it is empty or when any of its entries matches;
+/// a conflict must match all three.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictFilter {
+    /// Property names, e.g. `name`, `tier`.
+    pub fields: Vec<String>,
+    /// Node types, e.g. `organization`. When set, edge conflicts are
+    /// excluded.
+    pub node_types: Vec<String>,
+    /// Source file labels (`file_0`, ...); a conflict matches when one of its
+    /// values came from the source.
+    pub sources: Vec<String>,
+}
+
+/// One recorded conflict, as listed by [`super::list_conflicts`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConflictRecord {
+    /// Whether the element is a node or an edge.
+    pub element: ElementKind,
+    /// The node or edge ID.
+    pub id: String,
+    /// The node or edge type.
+    #[serde(rename = "type")]
+    pub element_type: String,
+    /// The conflicting property.
+    pub field: String,
+    /// Every value seen, with the source file that contributed it.
+    pub values: Vec<ConflictEntry>,
+    /// The merge policy that already chose a value, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+}
+
+/// A reviewer's choice for one conflict, read from a decisions file by
+/// [`super::resolve_conflicts`].
+///
+/// Exactly one of `value` and `source` must be given.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConflictDecision {
+    /// The node or edge ID.
+    pub id: String,
+    /// The conflicting property.
+    pub field: String,
+    /// The value to keep; it need not be one of the recorded values.
+    #[serde(default)]
+    pub value: Option<serde_json::Value>,
+    /// The source file whose recorded value to keep.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Free-text reason, copied to the resolution record.
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// How a conflict was resolved, appended to the element's `_resolutions`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictResolution {
+    /// The resolved property.
+    pub field: String,
+    /// The value written to the property.
+    pub value: serde_json::Value,
+    /// The source file whose value was kept; absent when the value matches
+    /// none of the recorded ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_file: Option<String>,
+    /// The conflict's values as recorded by the merge.
+    pub values: Vec<ConflictEntry>,
+    /// The reviewer's note, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// The result of [`super::resolve_conflicts`].
+#[derive(Debug, Clone)]
+pub struct ResolveOutput {
+    /// The rewritten file.
+    pub file: OmtsFile,
+    /// Number of conflicts resolved.
+    pub resolved: usize,
+    /// Number of conflicts still recorded in the file.
+    pub remaining: usize,
+}
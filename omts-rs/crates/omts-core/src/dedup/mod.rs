@@ -0,0 +1,30 @@
+/// Intra-file deduplication (merge-semantics Section 8).
+///
+/// ERP exports often carry several records for one legal entity. [`dedup`]
+/// finds them in two ways:
+///
+/// 1. **Identifier groups** — nodes of the same type that share an external
+///    identifier (the merge identity predicate; `internal` identifiers and
+///    annulled LEIs never match). With [`DedupConfig::apply`] each group is
+///    collapsed into its first node, which carries the union of the members'
+///    identifiers (including every `internal` one) and labels. Edges are
+///    re-pointed at the survivor, and edges that become duplicates under the
+///    edge identity predicate are folded together.
+/// 2. **Fuzzy matches** — organizations whose normalised legal names are
+///    similar. Names are lowercased, diacritics folded, and legal forms
+///    (`GmbH`, `Ltd`, `Inc`, `S.A.`, ...) stripped, then compared with a
+///    token-based similarity. Only names sharing a token prefix are compared,
+///    and pairs are discarded when their jurisdictions differ or when both
+///    carry clearly different addresses. With [`DedupConfig::suggest`] each
+///    match becomes a `same_as` edge with `confidence: "probable"`.
+///
+/// Fuzzy matches are never collapsed: a name match is evidence, not identity.
+mod normalize;
+mod pipeline;
+mod types;
+
+pub use pipeline::dedup;
+pub use types::{DedupConfig, DedupOutput, FuzzyMatch, IdentifierGroup};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+/// Legal-form tokens removed from organization names before comparison.
+///
+/// Dotted forms are matched after dots are removed (`S.A.` → `sa`,
+/// `Co.` → `co`), so each abbreviation needs only one entry.
+const LEGAL_FORMS: &[&str] = &[
+    "ab",
+    "ag",
+    "aps",
+    "as",
+    "asa",
+    "bv",
+    "bvba",
+    "co",
+    "company",
+    "corp",
+    "corporation",
+    "cv",
+    "eg",
+    "ev",
+    "gmbh",
+    "inc",
+    "incorporated",
+    "kft",
+    "kg",
+    "kgaa",
+    "kk",
+    "limited",
+    "llc",
+    "llp",
+    "lp",
+    "ltd",
+    "ltda",
+    "mbh",
+    "nv",
+    "ohg",
+    "oy",
+    "oyj",
+    "plc",
+    "pte",
+    "pty",
+    "sa",
+    "sarl",
+    "sas",
+    "se",
+    "sl",
+    "spa",
+    "sro",
+    "srl",
+    "ug",
+];
+
+/// Connective tokens that carry no identifying information.
+const STOP_WORDS: &[&str] = &["and", "the", "und"];
+
+/// Address abbreviations expanded before comparison.
+const ADDRESS_ABBREVIATIONS: &[(&str, &str)] = &[
+    ("ave", "avenue"),
+    ("blvd", "boulevard"),
+    ("rd", "road"),
+    ("st", "street"),
+    ("str", "strasse"),
+];
+
+/// Splits `s` into lowercase alphanumeric tokens.
+///
+/// Dots and apostrophes are dropped so that abbreviations stay whole
+/// (`S.A.` → `sa`); every other non-alphanumeric character separates tokens.
+/// Common Latin diacritics are folded to their base letter.
+fn tokenize(s: &str) -> Vec<String> {
+    let mut cleaned = String::with_capacity(s.len());
+    for c in s.chars().flat_map(char::to_lowercase) {
+        match c {
+            '.' | '\'' | '\u{2019}' => {}
+            'ß' => cleaned.push_str("ss"),
+            c if c.is_alphanumeric() => cleaned.push(fold_diacritic(c)),
+            _ => cleaned.push(' '),
+        }
+    }
+    cleaned.split_whitespace().map(str::to_owned).collect()
+}
+
+/// Maps common accented Latin letters to their unaccented base letter.
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        other => other,
+    }
+}
+
+/// Normalises a legal name into comparison tokens.
+///
+/// Legal-form suffixes (`GmbH`, `Ltd`, `Inc`, `S.A.`, ...) and connectives
+/// are removed. A name consisting only of such tokens keeps them, so that
+/// it still has something to compare.
+pub(crate) fn name_tokens(name: &str) -> Vec<String> {
+    let tokens = tokenize(name);
+    let kept: Vec<String> = tokens
+        .iter()
+        .filter(|t| !LEGAL_FORMS.contains(&t.as_str()) && !STOP_WORDS.contains(&t.as_str()))
+        .cloned()
+        .collect();
+    if kept.is_empty() { tokens } else { kept }
+}
+
+/// Normalises a free-text address into comparison tokens, expanding common
+/// street abbreviations.
+pub(crate) fn address_tokens(address: &str) -> Vec<String> {
+    tokenize(address)
+        .into_iter()
+        .flat_map(|t| {
+            let t = ADDRESS_ABBREVIATIONS
+                .iter()
+                .find(|(abbr, _)| *abbr == t)
+                .map_or(t.clone(), |(_, full)| (*full).to_owned());
+            // Split compound German street names: "hauptstr" and
+            // "hauptstrasse" both become "haupt strasse".
+            match t.strip_suffix("strasse").or_else(|| t.strip_suffix("str")) {
+                Some(stem) if !stem.is_empty() => vec![stem.to_owned(), "strasse".to_owned()],
+                _ => vec![t],
+            }
+        })
+        .collect()
+}
+
+/// Symmetric token-set similarity in `[0.0, 1.0]`.
+///
+/// Each token is paired with its most similar token on the other side
+/// (character-bigram Dice coefficient), and the best scores are averaged in
+/// both directions (a symmetric Monge-Elkan measure). Word order does not
+/// matter, and small spelling differences within a token cost only part of
+/// that token's weight.
+pub(crate) fn token_similarity(a: &[String], b: &[String]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    (directed_similarity(a, b) + directed_similarity(b, a)) / 2.0
+}
+
+fn directed_similarity(from: &[String], to: &[String]) -> f64 {
+    let total: f64 = from
+        .iter()
+        .map(|x| to.iter().map(|y| dice(x, y)).fold(0.0, f64::max))
+        .sum();
+    total / from.len() as f64
+}
+
+/// Character-bigram Dice coefficient of two tokens.
+fn dice(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let (ba, bb) = (bigrams(a), bigrams(b));
+    if ba.is_empty() || bb.is_empty() {
+        return 0.0;
+    }
+    let shared = ba.intersection(&bb).count();
+    2.0 * shared as f64 / (ba.len() + bb.len()) as f64
+}
+
+fn bigrams(s: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Blocking keys for a token list: the first three characters of each token.
+///
+/// Two names (or addresses) are only compared when they share a key, which
+/// keeps the comparison count near-linear while tolerating typos past the
+/// prefix.
+pub(crate) fn blocking_keys(tokens: &[String]) -> HashSet<String> {
+    tokens.iter().map(|t| t.chars().take(3).collect()).collect()
+}
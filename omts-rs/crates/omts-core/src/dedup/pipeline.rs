@@ -0,0 +1,545 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::canonical::CanonicalId;
+use crate::dynvalue::DynValue;
+use crate::enums::{EdgeType, EdgeTypeTag, NodeType, NodeTypeTag};
+use crate::file::OmtsFile;
+use crate::identity::{edge_composite_key, edges_match, identifiers_match, is_lei_annulled};
+use crate::merge::{
+    Conflict, ScalarMergeResult, build_conflicts_value, merge_identifiers, merge_labels,
+    merge_scalars,
+};
+use crate::newtypes::NodeId;
+use crate::structures::{Edge, EdgeProperties, Node};
+use crate::types::{Identifier, Label};
+use crate::union_find::UnionFind;
+
+use super::normalize::{address_tokens, blocking_keys, name_tokens, token_similarity};
+use super::types::{DedupConfig, DedupOutput, FuzzyMatch, IdentifierGroup};
+
+/// Minimum address similarity for two addressed nodes to stay a candidate
+/// pair. Deliberately lower than the name threshold: free-text addresses vary
+/// far more in formatting than legal names do.
+const ADDRESS_THRESHOLD: f64 = 0.6;
+
+/// Deduplicates nodes within a single file.
+///
+/// Always reports identifier groups and fuzzy matches; `config` decides
+/// whether groups are collapsed ([`DedupConfig::apply`]) and whether fuzzy
+/// matches are written back as `same_as` edges ([`DedupConfig::suggest`]).
+/// Fuzzy pairs already linked by a `same_as` edge, or (without `apply`)
+/// already in the same identifier group, are not reported.
+pub fn dedup(file: &OmtsFile, config: &DedupConfig) -> DedupOutput {
+    let identifier_groups = find_identifier_groups(file);
+
+    let mut out = file.clone();
+    let mut nodes_removed = 0;
+    let mut edges_removed = 0;
+    if config.apply {
+        (nodes_removed, edges_removed) = collapse_groups(&mut out, &identifier_groups);
+    }
+
+    let pending: &[IdentifierGroup] = if config.apply {
+        &[]
+    } else {
+        &identifier_groups
+    };
+    let fuzzy_matches = find_fuzzy_matches(&out, config.threshold, pending);
+
+    let edges_added = if config.suggest {
+        add_same_as_edges(&mut out, &fuzzy_matches)
+    } else {
+        0
+    };
+
+    DedupOutput {
+        file: out,
+        identifier_groups,
+        fuzzy_matches,
+        nodes_removed,
+        edges_removed,
+        edges_added,
+    }
+}
+
+/// Groups same-typed nodes connected by matching external identifiers.
+fn find_identifier_groups(file: &OmtsFile) -> Vec<IdentifierGroup> {
+    let nodes = &file.nodes;
+
+    let mut id_index: HashMap<CanonicalId, Vec<usize>> = HashMap::new();
+    for (ord, node) in nodes.iter().enumerate() {
+        for id in external_identifiers(node) {
+            id_index
+                .entry(CanonicalId::from_identifier(id))
+                .or_default()
+                .push(ord);
+        }
+    }
+
+    let mut uf = UnionFind::new(nodes.len());
+    for ords in id_index.values() {
+        for (i, &a) in ords.iter().enumerate() {
+            for &b in &ords[i + 1..] {
+                if nodes[a].node_type == nodes[b].node_type
+                    && share_identifier(&nodes[a], &nodes[b])
+                {
+                    uf.union(a, b);
+                }
+            }
+        }
+    }
+
+    // Keyed by the first member's ordinal, so groups come out in file order.
+    let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut first_of_rep: HashMap<usize, usize> = HashMap::new();
+    for ord in 0..nodes.len() {
+        let rep = uf.find(ord);
+        let first = *first_of_rep.entry(rep).or_insert(ord);
+        members.entry(first).or_default().push(ord);
+    }
+
+    members
+        .into_values()
+        .filter(|ords| ords.len() > 1)
+        .map(|ords| {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for &ord in &ords {
+                let distinct: BTreeSet<String> = external_identifiers(&nodes[ord])
+                    .map(|id| CanonicalId::from_identifier(id).into_string())
+                    .collect();
+                for cid in distinct {
+                    *counts.entry(cid).or_insert(0) += 1;
+                }
+            }
+            IdentifierGroup {
+                nodes: ords.iter().map(|&ord| nodes[ord].id.clone()).collect(),
+                shared_identifiers: counts
+                    .into_iter()
+                    .filter(|(_, n)| *n > 1)
+                    .map(|(cid, _)| cid)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Identifiers eligible for identity matching.
+fn external_identifiers(node: &Node) -> impl Iterator<Item = &Identifier> {
+    node.identifiers
+        .as_deref()
+        .unwrap_or(&[])
+        .iter()
+        .filter(|id| id.scheme != "internal" && !is_lei_annulled(id))
+}
+
+fn share_identifier(a: &Node, b: &Node) -> bool {
+    external_identifiers(a).any(|x| external_identifiers(b).any(|y| identifiers_match(x, y)))
+}
+
+/// Collapses each group into its first member and rewrites references.
+///
+/// Returns `(nodes_removed, edges_removed)`.
+fn collapse_groups(file: &mut OmtsFile, groups: &[IdentifierGroup]) -> (usize, usize) {
+    if groups.is_empty() {
+        return (0, 0);
+    }
+
+    let ordinal: HashMap<String, usize> = file
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(ord, node)| (node.id.to_string(), ord))
+        .collect();
+
+    let mut survivor_of: HashMap<String, NodeId> = HashMap::new();
+    let mut removed: HashSet<usize> = HashSet::new();
+    for group in groups {
+        let ords: Vec<usize> = group
+            .nodes
+            .iter()
+            .filter_map(|id| ordinal.get(&**id).copied())
+            .collect();
+        let Some((&first, rest)) = ords.split_first() else {
+            continue;
+        };
+        let merged = merge_group(&file.nodes, &ords);
+        for &ord in rest {
+            survivor_of.insert(file.nodes[ord].id.to_string(), merged.id.clone());
+            removed.insert(ord);
+        }
+        file.nodes[first] = merged;
+    }
+
+    let mut ord = 0;
+    file.nodes.retain(|_| {
+        let keep = !removed.contains(&ord);
+        ord += 1;
+        keep
+    });
+
+    let redirect = |id: &mut NodeId| {
+        if let Some(survivor) = survivor_of.get(&**id) {
+            *id = survivor.clone();
+        }
+    };
+    if let Some(entity) = file.reporting_entity.as_mut() {
+        redirect(entity);
+    }
+    for node in &mut file.nodes {
+        if let Some(operator) = node.operator.as_mut() {
+            redirect(operator);
+        }
+        if let Some(installation) = node.installation_id.as_mut() {
+            redirect(installation);
+        }
+    }
+    let mut rewritten: HashSet<usize> = HashSet::new();
+    for (idx, edge) in file.edges.iter_mut().enumerate() {
+        if survivor_of.contains_key(&*edge.source) || survivor_of.contains_key(&*edge.target) {
+            redirect(&mut edge.source);
+            redirect(&mut edge.target);
+            rewritten.insert(idx);
+        }
+    }
+
+    let edges_before = file.edges.len();
+    fold_duplicate_edges(file, &rewritten);
+    (removed.len(), edges_before - file.edges.len())
+}
+
+/// Builds the collapsed node for one group (`ords[0]` is the representative).
+///
+/// Identifiers and labels are unioned. For `name`, `jurisdiction`, `status`,
+/// and `address` the representative's value is kept (or the first member's
+/// value when the representative has none); disagreeing values are recorded
+/// in `_conflicts`, keyed by the contributing node's ID.
+fn merge_group(nodes: &[Node], ords: &[usize]) -> Node {
+    let members: Vec<&Node> = ords.iter().map(|&ord| &nodes[ord]).collect();
+    let mut node = members[0].clone();
+
+    let ids: Vec<Option<&[Identifier]>> =
+        members.iter().map(|n| n.identifiers.as_deref()).collect();
+    let ids = merge_identifiers(&ids);
+    node.identifiers = if ids.is_empty() { None } else { Some(ids) };
+
+    let labels: Vec<Option<&[Label]>> = members.iter().map(|n| n.labels.as_deref()).collect();
+    let labels = merge_labels(&labels);
+    node.labels = if labels.is_empty() {
+        None
+    } else {
+        Some(labels)
+    };
+
+    let mut conflicts = Vec::new();
+    node.name = reconcile(&members, "name", |n| n.name.clone(), &mut conflicts);
+    node.jurisdiction = reconcile(
+        &members,
+        "jurisdiction",
+        |n| n.jurisdiction.clone(),
+        &mut conflicts,
+    );
+    node.status = reconcile(&members, "status", |n| n.status.clone(), &mut conflicts);
+    node.address = reconcile(&members, "address", |n| n.address.clone(), &mut conflicts);
+    if let Some(value) = build_conflicts_value(conflicts) {
+        node.extra
+            .insert("_conflicts".to_owned(), DynValue::from(value));
+    }
+    node
+}
+
+/// Returns the first member's value of one property, recording a
+/// [`Conflict`] when members disagree.
+fn reconcile<T, F>(
+    members: &[&Node],
+    field: &str,
+    get: F,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<T>
+where
+    T: serde::Serialize + Clone,
+    F: Fn(&Node) -> Option<T>,
+{
+    let inputs: Vec<(Option<T>, &str)> = members.iter().map(|n| (get(n), &*n.id)).collect();
+    match merge_scalars(&inputs) {
+        ScalarMergeResult::Agreed(value) => value,
+        ScalarMergeResult::Conflict(values) => {
+            conflicts.push(Conflict {
+                field: field.to_owned(),
+                values,
+                resolution: None,
+            });
+            inputs.into_iter().find_map(|(value, _)| value)
+        }
+    }
+}
+
+/// Folds rewritten edges into earlier edges they now duplicate, and drops
+/// rewritten `same_as` edges that became self-loops.
+///
+/// Only buckets containing a rewritten edge are touched, so pre-existing
+/// parallel edges elsewhere in the file are left alone.
+fn fold_duplicate_edges(file: &mut OmtsFile, rewritten: &HashSet<usize>) {
+    let ordinal: HashMap<&str, usize> = file
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(ord, node)| (&*node.id, ord))
+        .collect();
+    let endpoints =
+        |edge: &Edge| Some((*ordinal.get(&*edge.source)?, *ordinal.get(&*edge.target)?));
+
+    let mut drop: HashSet<usize> = HashSet::new();
+    let mut absorbed: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut buckets: HashMap<_, Vec<usize>> = HashMap::new();
+    for (idx, edge) in file.edges.iter().enumerate() {
+        let is_same_as = matches!(edge.edge_type, EdgeTypeTag::Known(EdgeType::SameAs));
+        if is_same_as && rewritten.contains(&idx) && edge.source == edge.target {
+            drop.insert(idx);
+            continue;
+        }
+        let Some((src, tgt)) = endpoints(edge) else {
+            continue;
+        };
+        if let Some(key) = edge_composite_key(src, tgt, edge) {
+            buckets.entry(key).or_default().push(idx);
+        }
+    }
+
+    for bucket in buckets.values() {
+        if bucket.len() < 2 || !bucket.iter().any(|idx| rewritten.contains(idx)) {
+            continue;
+        }
+        let mut kept: Vec<usize> = Vec::new();
+        for &idx in bucket {
+            let edge = &file.edges[idx];
+            let Some((src, tgt)) = endpoints(edge) else {
+                continue;
+            };
+            let into = kept
+                .iter()
+                .copied()
+                .find(|&k| edges_match(src, tgt, src, tgt, &file.edges[k], edge));
+            match into {
+                Some(k) => {
+                    absorbed.entry(k).or_default().push(idx);
+                    drop.insert(idx);
+                }
+                None => kept.push(idx),
+            }
+        }
+    }
+
+    for (k, others) in absorbed {
+        let group: Vec<&Edge> = std::iter::once(k)
+            .chain(others.iter().copied())
+            .map(|idx| &file.edges[idx])
+            .collect();
+        let ids: Vec<Option<&[Identifier]>> =
+            group.iter().map(|e| e.identifiers.as_deref()).collect();
+        let ids = merge_identifiers(&ids);
+        let labels: Vec<Option<&[Label]>> = group
+            .iter()
+            .map(|e| e.properties.labels.as_deref())
+            .collect();
+        let labels = merge_labels(&labels);
+        let edge = &mut file.edges[k];
+        edge.identifiers = if ids.is_empty() { None } else { Some(ids) };
+        edge.properties.labels = if labels.is_empty() {
+            None
+        } else {
+            Some(labels)
+        };
+    }
+
+    let mut idx = 0;
+    file.edges.retain(|_| {
+        let keep = !drop.contains(&idx);
+        idx += 1;
+        keep
+    });
+}
+
+/// An organization prepared for fuzzy comparison.
+pub(super) struct Candidate<'a> {
+    pub(super) node: &'a Node,
+    name: Vec<String>,
+    address: Option<Vec<String>>,
+}
+
+/// Prepares every named organization in `file` for fuzzy comparison.
+pub(super) fn fuzzy_candidates(file: &OmtsFile) -> Vec<Candidate<'_>> {
+    file.nodes
+        .iter()
+        .filter(|n| matches!(n.node_type, NodeTypeTag::Known(NodeType::Organization)))
+        .filter_map(|node| {
+            let name = name_tokens(node.name.as_deref()?);
+            if name.is_empty() {
+                return None;
+            }
+            let address = node
+                .address
+                .as_deref()
+                .map(address_tokens)
+                .filter(|tokens| !tokens.is_empty());
+            Some(Candidate {
+                node,
+                name,
+                address,
+            })
+        })
+        .collect()
+}
+
+/// A blocking bucket within one name key: a jurisdiction and an address
+/// key, where `None` marks a candidate without one.
+type Bucket<'a> = (Option<&'a str>, Option<String>);
+
+/// Candidate index pairs `(i, j)`, `i < j`, that share a blocking key.
+///
+/// Candidates are blocked on name-token prefixes, then bucketed by
+/// jurisdiction and address-token prefix. Two buckets are compared only if
+/// their jurisdictions and address keys agree, where a missing jurisdiction
+/// or address agrees with any, so organizations in different jurisdictions
+/// are never paired.
+pub(super) fn candidate_pairs(candidates: &[Candidate<'_>]) -> BTreeSet<(usize, usize)> {
+    let mut blocks: BTreeMap<String, BTreeMap<Bucket<'_>, Vec<usize>>> = BTreeMap::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        let jurisdiction = candidate.node.jurisdiction.as_deref();
+        let address_keys: Vec<Option<String>> = match &candidate.address {
+            Some(tokens) => blocking_keys(tokens).into_iter().map(Some).collect(),
+            None => vec![None],
+        };
+        for name_key in blocking_keys(&candidate.name) {
+            let buckets = blocks.entry(name_key).or_default();
+            for address_key in &address_keys {
+                buckets
+                    .entry((jurisdiction, address_key.clone()))
+                    .or_default()
+                    .push(i);
+            }
+        }
+    }
+
+    let agree = |a: Option<&str>, b: Option<&str>| a.is_none() || b.is_none() || a == b;
+    let mut pairs = BTreeSet::new();
+    for buckets in blocks.values() {
+        let buckets: Vec<(&Bucket<'_>, &Vec<usize>)> = buckets.iter().collect();
+        for (n, &((ja, aa), members_a)) in buckets.iter().enumerate() {
+            for &((jb, ab), members_b) in &buckets[n..] {
+                if !agree(*ja, *jb) || !agree(aa.as_deref(), ab.as_deref()) {
+                    continue;
+                }
+                for &i in members_a {
+                    for &j in members_b {
+                        if i != j {
+                            pairs.insert((i.min(j), i.max(j)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Finds organizations with similar normalised names.
+///
+/// Pairs whose members already share an identifier group in `pending`, or
+/// that are already linked by a `same_as` edge, are skipped.
+fn find_fuzzy_matches(
+    file: &OmtsFile,
+    threshold: f64,
+    pending: &[IdentifierGroup],
+) -> Vec<FuzzyMatch> {
+    let candidates = fuzzy_candidates(file);
+    let pairs = candidate_pairs(&candidates);
+
+    let group_of: HashMap<&str, usize> = pending
+        .iter()
+        .enumerate()
+        .flat_map(|(g, group)| group.nodes.iter().map(move |id| (&**id, g)))
+        .collect();
+    let linked: HashSet<(&str, &str)> = file
+        .edges
+        .iter()
+        .filter(|e| matches!(e.edge_type, EdgeTypeTag::Known(EdgeType::SameAs)))
+        .flat_map(|e| [(&*e.source, &*e.target), (&*e.target, &*e.source)])
+        .collect();
+
+    let mut matches = Vec::new();
+    for (i, j) in pairs {
+        let (a, b) = (&candidates[i], &candidates[j]);
+        let (id_a, id_b) = (&*a.node.id, &*b.node.id);
+        if linked.contains(&(id_a, id_b)) {
+            continue;
+        }
+        if let (Some(ga), Some(gb)) = (group_of.get(id_a), group_of.get(id_b)) {
+            if ga == gb {
+                continue;
+            }
+        }
+        let basis = match (&a.address, &b.address) {
+            (Some(aa), Some(ab)) => {
+                if token_similarity(aa, ab) < ADDRESS_THRESHOLD {
+                    continue;
+                }
+                "name_address_match"
+            }
+            _ => "name_match",
+        };
+        let similarity = token_similarity(&a.name, &b.name);
+        if similarity < threshold {
+            continue;
+        }
+        matches.push(FuzzyMatch {
+            source: a.node.id.clone(),
+            target: b.node.id.clone(),
+            similarity,
+            basis,
+        });
+    }
+    matches
+}
+
+/// Appends one `same_as` edge per match and returns the number added.
+///
+/// Edge IDs are `same-as-<n>`, skipping any already used in the file.
+fn add_same_as_edges(file: &mut OmtsFile, matches: &[FuzzyMatch]) -> usize {
+    let mut used: HashSet<String> = file.edges.iter().map(|e| e.id.to_string()).collect();
+    let mut counter = 0usize;
+    let mut added = 0;
+    for m in matches {
+        let id = loop {
+            counter += 1;
+            let candidate = format!("same-as-{counter}");
+            if !used.contains(&candidate) {
+                break candidate;
+            }
+        };
+        let Ok(edge_id) = NodeId::try_from(id.as_str()) else {
+            continue;
+        };
+        used.insert(id);
+
+        let mut properties = EdgeProperties::default();
+        properties.extra.insert(
+            "confidence".to_owned(),
+            DynValue::from(serde_json::Value::from("probable")),
+        );
+        properties.extra.insert(
+            "basis".to_owned(),
+            DynValue::from(serde_json::Value::from(m.basis)),
+        );
+        file.edges.push(Edge {
+            id: edge_id,
+            edge_type: EdgeTypeTag::Known(EdgeType::SameAs),
+            source: m.source.clone(),
+            target: m.target.clone(),
+            identifiers: None,
+            properties,
+            extra: BTreeMap::new(),
+        });
+        added += 1;
+    }
+    added
+}
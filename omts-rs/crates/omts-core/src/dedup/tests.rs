@@ -0,0 +1,427 @@
+#![allow(clippy::expect_used)]
+
+use super::normalize::{address_tokens, name_tokens, token_similarity};
+use super::pipeline::{candidate_pairs, fuzzy_candidates};
+use super::*;
+use crate::enums::{EdgeType, EdgeTypeTag};
+use crate::newtypes::CountryCode;
+use crate::structures::{Edge, Node};
+use crate::test_helpers::{facility_node, minimal_file, org_node, supplies_edge, typed_edge};
+use crate::types::Identifier;
+use crate::validation::{ValidationConfig, validate};
+use std::collections::BTreeMap;
+
+fn ident(scheme: &str, value: &str, authority: Option<&str>) -> Identifier {
+    Identifier {
+        scheme: scheme.to_owned(),
+        value: value.to_owned(),
+        authority: authority.map(str::to_owned),
+        valid_from: None,
+        valid_to: None,
+        sensitivity: None,
+        verification_status: None,
+        verification_date: None,
+        extra: BTreeMap::new(),
+    }
+}
+
+fn vendor(id: &str, name: &str, identifiers: Vec<Identifier>) -> Node {
+    Node {
+        name: Some(name.to_owned()),
+        identifiers: Some(identifiers),
+        ..org_node(id)
+    }
+}
+
+fn located(mut node: Node, jurisdiction: &str, address: &str) -> Node {
+    node.jurisdiction = Some(CountryCode::try_from(jurisdiction).expect("valid country"));
+    node.address = Some(address.to_owned());
+    node
+}
+
+fn tokens(s: &[&str]) -> Vec<String> {
+    s.iter().map(|t| (*t).to_owned()).collect()
+}
+
+fn ids(edge_ids: &[&Edge]) -> Vec<String> {
+    edge_ids.iter().map(|e| e.id.to_string()).collect()
+}
+
+fn apply() -> DedupConfig {
+    DedupConfig {
+        apply: true,
+        ..DedupConfig::default()
+    }
+}
+
+fn suggest() -> DedupConfig {
+    DedupConfig {
+        suggest: true,
+        ..DedupConfig::default()
+    }
+}
+
+#[test]
+fn name_tokens_strip_legal_forms_and_punctuation() {
+    assert_eq!(name_tokens("ACME GmbH"), tokens(&["acme"]));
+    assert_eq!(name_tokens("Acme Ltd."), tokens(&["acme"]));
+    assert_eq!(name_tokens("Acme, Inc"), tokens(&["acme"]));
+    assert_eq!(
+        name_tokens("Müller & Söhne S.A."),
+        tokens(&["muller", "sohne"])
+    );
+    assert_eq!(
+        name_tokens("Acme Holding GmbH & Co. KG"),
+        tokens(&["acme", "holding"])
+    );
+    // A name made only of legal-form tokens keeps them.
+    assert_eq!(name_tokens("AG"), tokens(&["ag"]));
+}
+
+#[test]
+fn address_tokens_expand_street_abbreviations() {
+    assert_eq!(
+        address_tokens("Hauptstr. 5, 80331 München"),
+        address_tokens("Hauptstrasse 5 80331 Munchen")
+    );
+    assert_eq!(
+        address_tokens("12 Main St"),
+        tokens(&["12", "main", "street"])
+    );
+}
+
+#[test]
+fn token_similarity_tolerates_order_and_small_spelling_differences() {
+    let a = name_tokens("Acme Industrial Supplies Ltd");
+    let b = name_tokens("ACME Industrial Supply Limited");
+    assert!(token_similarity(&a, &b) >= 0.85);
+
+    let reordered = name_tokens("Industrial Supplies Acme");
+    assert!((token_similarity(&a, &reordered) - 1.0).abs() < f64::EPSILON);
+
+    let broader = name_tokens("Acme Holdings");
+    assert!(token_similarity(&name_tokens("Acme"), &broader) < 0.85);
+    assert!(token_similarity(&[], &broader).abs() < f64::EPSILON);
+}
+
+#[test]
+fn shared_external_identifier_forms_a_group() {
+    let file = minimal_file(
+        vec![
+            vendor(
+                "v-100",
+                "Acme GmbH",
+                vec![
+                    ident("internal", "V-100", Some("sap-prod-100")),
+                    ident("duns", "081466849", None),
+                ],
+            ),
+            vendor("other", "Beta AG", vec![ident("duns", "111111111", None)]),
+            vendor(
+                "v-200",
+                "ACME GmbH",
+                vec![
+                    ident("internal", "V-200", Some("sap-prod-200")),
+                    ident("duns", "081466849", None),
+                ],
+            ),
+        ],
+        vec![],
+    );
+
+    let out = dedup(&file, &DedupConfig::default());
+    assert_eq!(out.identifier_groups.len(), 1);
+    let group = &out.identifier_groups[0];
+    assert_eq!(
+        group
+            .nodes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["v-100", "v-200"]
+    );
+    assert_eq!(group.shared_identifiers, ["duns:081466849"]);
+    // Report-only mode leaves the file alone and does not repeat the pair as
+    // a fuzzy match.
+    assert_eq!(out.file, file);
+    assert!(out.fuzzy_matches.is_empty());
+}
+
+#[test]
+fn internal_identifiers_and_differing_types_never_group() {
+    let mut facility = facility_node("fac");
+    facility.identifiers = Some(vec![ident("duns", "081466849", None)]);
+    let file = minimal_file(
+        vec![
+            vendor(
+                "a",
+                "Acme",
+                vec![
+                    ident("internal", "V-1", Some("sap")),
+                    ident("duns", "081466849", None),
+                ],
+            ),
+            vendor("b", "Other", vec![ident("internal", "V-1", Some("sap"))]),
+            facility,
+        ],
+        vec![],
+    );
+    assert!(dedup(&file, &apply()).identifier_groups.is_empty());
+}
+
+#[test]
+fn apply_collapses_group_into_first_member() {
+    let file = minimal_file(
+        vec![
+            vendor(
+                "v-100",
+                "Acme GmbH",
+                vec![
+                    ident("internal", "V-100", Some("sap-prod-100")),
+                    ident("duns", "081466849", None),
+                ],
+            ),
+            vendor(
+                "v-200",
+                "ACME GmbH",
+                vec![
+                    ident("internal", "V-200", Some("sap-prod-200")),
+                    ident("duns", "081466849", None),
+                ],
+            ),
+            org_node("buyer"),
+        ],
+        vec![
+            supplies_edge("e-1", "v-100", "buyer"),
+            supplies_edge("e-2", "v-200", "buyer"),
+            typed_edge("e-3", EdgeType::SameAs, "v-100", "v-200"),
+        ],
+    );
+
+    let out = dedup(&file, &apply());
+    assert_eq!(out.nodes_removed, 1);
+    assert_eq!(out.edges_removed, 2);
+
+    let nodes: Vec<String> = out.file.nodes.iter().map(|n| n.id.to_string()).collect();
+    assert_eq!(nodes, ["v-100", "buyer"]);
+    let acme = &out.file.nodes[0];
+    assert_eq!(acme.identifiers.as_ref().map(Vec::len), Some(3));
+    assert_eq!(acme.name.as_deref(), Some("Acme GmbH"));
+    let conflicts = serde_json::Value::from(
+        acme.extra
+            .get("_conflicts")
+            .cloned()
+            .expect("name conflict recorded"),
+    );
+    assert_eq!(conflicts[0]["field"], "name");
+    assert_eq!(conflicts[0]["values"][1]["source_file"], "v-200");
+
+    let edges: Vec<&Edge> = out.file.edges.iter().collect();
+    assert_eq!(ids(&edges), ["e-1"]);
+
+    let result = validate(
+        &out.file,
+        &ValidationConfig {
+            run_l1: true,
+            run_l2: false,
+            run_l3: false,
+        },
+        None,
+    );
+    assert!(!result.has_errors(), "{:?}", result.errors().next());
+}
+
+#[test]
+fn apply_keeps_edges_that_stay_distinct() {
+    let mut commodity = supplies_edge("e-2", "v-200", "buyer");
+    commodity.properties.commodity = Some("steel".to_owned());
+    let file = minimal_file(
+        vec![
+            vendor(
+                "v-100",
+                "Acme",
+                vec![ident("lei", "5493006MHB84DD0ZWV18", None)],
+            ),
+            vendor(
+                "v-200",
+                "Acme",
+                vec![ident("lei", "5493006MHB84DD0ZWV18", None)],
+            ),
+            org_node("buyer"),
+        ],
+        vec![supplies_edge("e-1", "v-100", "buyer"), commodity],
+    );
+
+    let out = dedup(&file, &apply());
+    assert_eq!(out.edges_removed, 0);
+    assert!(
+        out.file
+            .edges
+            .iter()
+            .all(|e| &*e.source == "v-100" && &*e.target == "buyer")
+    );
+}
+
+#[test]
+fn suggest_links_fuzzy_matches_with_probable_same_as() {
+    let file = minimal_file(
+        vec![
+            located(
+                vendor("v-1", "Acme Industrial Supplies Ltd", vec![]),
+                "GB",
+                "12 Main St, Leeds",
+            ),
+            located(
+                vendor("v-2", "ACME Industrial Supply Limited", vec![]),
+                "GB",
+                "12 Main Street Leeds",
+            ),
+            vendor("v-3", "Acme Holdings plc", vec![]),
+        ],
+        vec![],
+    );
+
+    let out = dedup(&file, &suggest());
+    assert_eq!(out.fuzzy_matches.len(), 1);
+    let m = &out.fuzzy_matches[0];
+    assert_eq!((&*m.source, &*m.target), ("v-1", "v-2"));
+    assert_eq!(m.basis, "name_address_match");
+
+    assert_eq!(out.edges_added, 1);
+    let edge = out.file.edges.last().expect("same_as edge");
+    assert_eq!(edge.edge_type, EdgeTypeTag::Known(EdgeType::SameAs));
+    assert_eq!(&*edge.id, "same-as-1");
+    let confidence = edge.properties.extra.get("confidence").cloned();
+    assert_eq!(
+        confidence.map(serde_json::Value::from),
+        Some(serde_json::json!("probable"))
+    );
+}
+
+#[test]
+fn jurisdiction_and_address_block_fuzzy_matches() {
+    let file = minimal_file(
+        vec![
+            located(
+                vendor("de", "Acme GmbH", vec![]),
+                "DE",
+                "Hauptstr. 5, Berlin",
+            ),
+            located(
+                vendor("at", "Acme GmbH", vec![]),
+                "AT",
+                "Hauptstr. 5, Berlin",
+            ),
+            located(
+                vendor("de-far", "Acme GmbH", vec![]),
+                "DE",
+                "Rue de Rivoli 99, Paris",
+            ),
+            vendor("unlocated", "Acme", vec![]),
+        ],
+        vec![],
+    );
+
+    let pairs: Vec<(String, String)> = dedup(&file, &DedupConfig::default())
+        .fuzzy_matches
+        .iter()
+        .map(|m| (m.source.to_string(), m.target.to_string()))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("de".to_owned(), "unlocated".to_owned()),
+            ("at".to_owned(), "unlocated".to_owned()),
+            ("de-far".to_owned(), "unlocated".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn blocking_never_pairs_different_jurisdictions_or_distant_addresses() {
+    let file = minimal_file(
+        vec![
+            located(vendor("de", "Acme GmbH", vec![]), "DE", "Hauptstr. 5"),
+            located(vendor("at", "Acme GmbH", vec![]), "AT", "Hauptstr. 5"),
+            located(vendor("de-far", "Acme GmbH", vec![]), "DE", "Rue de Rivoli"),
+            vendor("unlocated", "Acme", vec![]),
+        ],
+        vec![],
+    );
+
+    let candidates = fuzzy_candidates(&file);
+    let pairs: Vec<(&str, &str)> = candidate_pairs(&candidates)
+        .into_iter()
+        .map(|(i, j)| (&*candidates[i].node.id, &*candidates[j].node.id))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("de", "unlocated"),
+            ("at", "unlocated"),
+            ("de-far", "unlocated")
+        ]
+    );
+}
+
+#[test]
+fn threshold_and_existing_same_as_edges_limit_suggestions() {
+    let file = minimal_file(
+        vec![
+            vendor("a", "Acme Industrial Supplies", vec![]),
+            vendor("b", "Acme Industrial Supply", vec![]),
+            vendor("c", "Acme Industrial Supplies", vec![]),
+        ],
+        vec![typed_edge("same-as-1", EdgeType::SameAs, "a", "c")],
+    );
+
+    let out = dedup(&file, &suggest());
+    let pairs: Vec<(String, String)> = out
+        .fuzzy_matches
+        .iter()
+        .map(|m| (m.source.to_string(), m.target.to_string()))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("a".to_owned(), "b".to_owned()),
+            ("b".to_owned(), "c".to_owned())
+        ]
+    );
+    // Generated IDs skip the one already in use.
+    let added: Vec<&Edge> = out.file.edges.iter().skip(1).collect();
+    assert_eq!(ids(&added), ["same-as-2", "same-as-3"]);
+
+    let strict = DedupConfig {
+        threshold: 0.99,
+        ..suggest()
+    };
+    assert_eq!(dedup(&file, &strict).edges_added, 0);
+}
+
+#[test]
+fn apply_then_suggest_matches_against_collapsed_nodes() {
+    let file = minimal_file(
+        vec![
+            vendor("v-1", "Acme GmbH", vec![ident("duns", "081466849", None)]),
+            vendor("v-2", "Acme GmbH", vec![ident("duns", "081466849", None)]),
+            vendor("v-3", "ACME G.m.b.H.", vec![]),
+        ],
+        vec![],
+    );
+    let out = dedup(
+        &file,
+        &DedupConfig {
+            apply: true,
+            suggest: true,
+            ..DedupConfig::default()
+        },
+    );
+    assert_eq!(out.nodes_removed, 1);
+    let pairs: Vec<(String, String)> = out
+        .fuzzy_matches
+        .iter()
+        .map(|m| (m.source.to_string(), m.target.to_string()))
+        .collect();
+    assert_eq!(pairs, [("v-1".to_owned(), "v-3".to_owned())]);
+}
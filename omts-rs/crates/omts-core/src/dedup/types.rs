@@ -0,0 +1,80 @@
+use crate::file::OmtsFile;
+use crate::newtypes::NodeId;
+
+/// Configuration for [`super::dedup`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DedupConfig {
+    /// Minimum normalised-name similarity (`0.0`–`1.0`) for two organizations
+    /// to be reported as a fuzzy match.
+    ///
+    /// Default: 0.85.
+    pub threshold: f64,
+
+    /// Collapse nodes that share an external identifier into one node.
+    ///
+    /// Default: `false`.
+    pub apply: bool,
+
+    /// Add a `same_as` edge with `confidence: "probable"` for every fuzzy
+    /// match.
+    ///
+    /// Default: `false`.
+    pub suggest: bool,
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.85,
+            apply: false,
+            suggest: false,
+        }
+    }
+}
+
+/// Nodes of one type that share at least one external identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifierGroup {
+    /// Member node IDs in file order. The first is the representative that
+    /// survives a collapse.
+    pub nodes: Vec<NodeId>,
+    /// Sorted canonical identifiers carried by two or more members.
+    pub shared_identifiers: Vec<String>,
+}
+
+/// Two organizations whose normalised names are similar enough to be the same
+/// legal entity, but which share no external identifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    /// The node that appears first in the file.
+    pub source: NodeId,
+    /// The node that appears later in the file.
+    pub target: NodeId,
+    /// Normalised-name similarity in `[threshold, 1.0]`.
+    pub similarity: f64,
+    /// `"name_address_match"` when both nodes carry a compatible address,
+    /// otherwise `"name_match"`. Written as the `same_as` edge's `basis`.
+    pub basis: &'static str,
+}
+
+/// The result of [`super::dedup`].
+#[derive(Debug, Clone)]
+pub struct DedupOutput {
+    /// The input file with the configured changes applied; identical to the
+    /// input when neither [`DedupConfig::apply`] nor [`DedupConfig::suggest`]
+    /// is set.
+    pub file: OmtsFile,
+    /// Identifier-sharing groups found in the input, in file order.
+    pub identifier_groups: Vec<IdentifierGroup>,
+    /// Fuzzy matches, in file order of `(source, target)`.
+    ///
+    /// With [`DedupConfig::apply`], matching runs on the collapsed file.
+    pub fuzzy_matches: Vec<FuzzyMatch>,
+    /// Nodes removed by collapsing identifier groups.
+    pub nodes_removed: usize,
+    /// Edges removed because a collapse made them duplicates of another edge
+    /// (or a `same_as` self-loop).
+    pub edges_removed: usize,
+    /// `same_as` edges added for fuzzy matches.
+    pub edges_added: usize,
+}
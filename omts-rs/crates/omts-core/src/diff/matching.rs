@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::canonical::{CanonicalId, build_identifier_index};
+use crate::graph::selectors::SelectorExpr;
 use crate::identity::{edges_match, identifiers_match};
 use crate::newtypes::NodeId;
 use crate::structures::{Edge, Node};
@@ -9,6 +10,13 @@ use crate::union_find::UnionFind;
 use super::helpers::{edge_type_str, node_type_str};
 use super::types::{DiffFilter, NodeMatchResult};
 
+/// Returns `true` unless the filter's `expr` applies to nodes and rejects `node`.
+fn node_expr_allowed(node: &Node, filter: Option<&DiffFilter>) -> bool {
+    filter
+        .and_then(|f| f.expr.as_ref())
+        .is_none_or(|e| !e.applies_to_nodes() || e.matches_node(node))
+}
+
 /// Performs node matching for a diff.
 ///
 /// Builds canonical identifier indices for both files, finds matching pairs
@@ -21,10 +29,11 @@ pub(super) fn match_nodes(
     filter: Option<&DiffFilter>,
 ) -> NodeMatchResult {
     let node_type_allowed = |node: &Node| -> bool {
-        match filter.and_then(|f| f.node_types.as_ref()) {
+        let type_ok = match filter.and_then(|f| f.node_types.as_ref()) {
             None => true,
             Some(allowed) => allowed.contains(node_type_str(&node.node_type)),
-        }
+        };
+        type_ok && node_expr_allowed(node, filter)
     };
 
     let active_a: HashSet<usize> = (0..nodes_a.len())
@@ -236,29 +245,40 @@ pub(super) fn match_edges(
     let (node_rep_map, _) = build_node_rep_map(nodes_a, nodes_b, matched_node_pairs);
 
     let edge_type_allowed = |edge: &Edge| -> bool {
-        match filter.and_then(|f| f.edge_types.as_ref()) {
+        let type_ok = match filter.and_then(|f| f.edge_types.as_ref()) {
             None => true,
             Some(allowed) => allowed.contains(edge_type_str(&edge.edge_type)),
-        }
+        };
+        type_ok
+            && filter
+                .and_then(|f| f.expr.as_ref())
+                .is_none_or(|e| !e.applies_to_edges() || e.matches_edge(edge))
     };
 
     let node_map_a: HashMap<&str, &Node> = nodes_a.iter().map(|n| (&*n.id as &str, n)).collect();
     let node_map_b: HashMap<&str, &Node> = nodes_b.iter().map(|n| (&*n.id as &str, n)).collect();
 
     let node_type_allowed_for_id = |node_id: &NodeId| -> bool {
-        match filter.and_then(|f| f.node_types.as_ref()) {
-            None => true,
-            Some(allowed) => {
-                let id_str: &str = node_id;
-                if let Some(node) = node_map_a.get(id_str) {
-                    return allowed.contains(node_type_str(&node.node_type));
-                }
-                if let Some(node) = node_map_b.get(id_str) {
-                    return allowed.contains(node_type_str(&node.node_type));
-                }
-                false
-            }
+        let node_filtered = filter.is_some_and(|f| {
+            f.node_types.is_some() || f.expr.as_ref().is_some_and(SelectorExpr::applies_to_nodes)
+        });
+        if !node_filtered {
+            return true;
+        }
+        let id_str: &str = node_id;
+        let allowed = |node: &Node| -> bool {
+            filter
+                .and_then(|f| f.node_types.as_ref())
+                .is_none_or(|allowed| allowed.contains(node_type_str(&node.node_type)))
+                && node_expr_allowed(node, filter)
+        };
+        if let Some(node) = node_map_a.get(id_str) {
+            return allowed(node);
+        }
+        if let Some(node) = node_map_b.get(id_str) {
+            return allowed(node);
         }
+        false
     };
 
     let edge_is_active = |edge: &Edge| -> bool {
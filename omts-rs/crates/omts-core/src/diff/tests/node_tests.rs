@@ -259,6 +259,31 @@ fn diff_filtered_by_node_type() {
     assert_eq!(total_matched, 1);
 }
 
+/// diff_filtered with a `--where` expression excludes non-matching nodes.
+#[test]
+fn diff_filtered_by_expr_excludes_nodes() {
+    use crate::graph::selectors::SelectorExpr;
+    let kept_a = with_lei(org_node("org-a"), "LEI_KEPT");
+    let kept_b = with_lei(org_node("org-b"), "LEI_KEPT");
+    let mut excluded = org_node("org-new");
+    excluded.name = Some("Cleared Holdings".to_owned());
+
+    let a = make_file(vec![kept_a], vec![]);
+    let b = make_file_b(vec![kept_b, excluded], vec![]);
+
+    let mut filter = DiffFilter::default();
+    filter.expr = Some(SelectorExpr::parse("NOT name=cleared").expect("valid expression"));
+
+    let result = diff_filtered(&a, &b, Some(&filter));
+    // org-new would be an addition, but the expression excludes it.
+    assert!(result.nodes.added.is_empty());
+    assert!(result.nodes.removed.is_empty());
+    assert_eq!(
+        result.nodes.unchanged.len() + result.nodes.modified.len(),
+        1
+    );
+}
+
 /// Two files with identical content produce an empty diff.
 #[test]
 fn diff_identical_files_empty_diff() {
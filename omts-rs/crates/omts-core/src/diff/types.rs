@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use crate::canonical::CanonicalId;
+use crate::graph::selectors::SelectorExpr;
 use crate::newtypes::{EdgeId, NodeId};
 use crate::structures::{Edge, Node};
 use crate::types::{Identifier, Label};
@@ -158,7 +159,8 @@ pub struct EdgesDiff {
 /// Optional filter to restrict which nodes and edges are compared.
 ///
 /// Filtering by node type also filters edges: edges whose source or target
-/// has a filtered-out node type are excluded from the diff.
+/// has a filtered-out node type are excluded from the diff. The same applies
+/// to nodes excluded by `expr`.
 #[derive(Debug, Clone, Default)]
 pub struct DiffFilter {
     /// If set, only diff nodes of these types; `None` means all types.
@@ -167,6 +169,11 @@ pub struct DiffFilter {
     pub edge_types: Option<HashSet<String>>,
     /// Property names to exclude from comparison.
     pub ignore_fields: HashSet<String>,
+    /// If set, only diff elements matching this expression (`--where`).
+    ///
+    /// Nodes are tested only when the expression applies to nodes, and edges
+    /// only when it applies to edges.
+    pub expr: Option<SelectorExpr>,
 }
 
 /// Summary statistics for a diff result.
@@ -0,0 +1,500 @@
+/// Community detection for supply-network segmentation.
+///
+/// [`louvain`] partitions the nodes touched by a chosen set of edge types (by
+/// default the supply relationships returned by [`supply_edge_types`]) into
+/// segments of densely connected suppliers. The result can be emitted as a
+/// mapping or written back to the file as labels via [`apply_segment_labels`].
+///
+/// # Algorithm
+///
+/// Louvain modularity optimisation (Blondel et al., 2008), treating edges as
+/// undirected:
+///
+/// 1. **Local moving** — every node starts in its own segment. Nodes are
+///    visited in ascending ID order and moved to the neighbouring segment with
+///    the largest positive modularity gain. Ties keep the current segment,
+///    otherwise the lowest-numbered candidate wins. Passes repeat until no
+///    node moves.
+/// 2. **Aggregation** — each segment is collapsed into a single node whose
+///    self-loop carries the internal weight, and step 1 runs again on the
+///    smaller graph.
+///
+/// The process stops when a level produces no moves. Fixed visiting order and
+/// tie-breaking make the output deterministic for a given file, so successive
+/// runs produce diffable labels. Each level is O(E) per pass.
+///
+/// # Weighting
+///
+/// With [`EdgeWeighting::AnnualValue`] or [`EdgeWeighting::Volume`], edges
+/// lacking the property (or carrying a non-positive value) fall back to the
+/// mean weight of the edges that do have it, so relationships with unknown
+/// value are neither ignored nor dominant. Volumes are compared as raw numbers;
+/// mixing `volume_unit`s within one run is the caller's responsibility.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+
+use crate::enums::{EdgeType, EdgeTypeTag};
+use crate::file::OmtsFile;
+use crate::graph::OmtsGraph;
+use crate::types::Label;
+
+/// Upper bound on local-moving passes per level, guarding against
+/// floating-point oscillation on pathological inputs.
+const MAX_LOCAL_PASSES: usize = 100;
+
+/// Default label key written by [`apply_segment_labels`].
+pub const SEGMENT_LABEL_KEY: &str = "omts.segment";
+
+/// Which edge property to use as the relationship weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeWeighting {
+    /// Every edge has weight 1.
+    #[default]
+    Uniform,
+    /// Weight by `annual_value`.
+    AnnualValue,
+    /// Weight by `volume`.
+    Volume,
+}
+
+/// One segment produced by [`louvain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// Segment number, starting at 1 for the largest segment.
+    pub id: usize,
+    /// Member node IDs, sorted.
+    pub members: Vec<String>,
+}
+
+/// Result of [`louvain`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Segmentation {
+    /// Segments ordered by descending size, then by first member ID.
+    pub segments: Vec<Segment>,
+    /// Weighted Newman–Girvan modularity of the partition, in `[-0.5, 1]`.
+    pub modularity: f64,
+    /// Number of aggregation levels that produced at least one move.
+    pub levels: usize,
+}
+
+impl Segmentation {
+    /// Returns a map from node ID to segment number.
+    pub fn assignments(&self) -> HashMap<&str, usize> {
+        self.segments
+            .iter()
+            .flat_map(|seg| seg.members.iter().map(move |m| (m.as_str(), seg.id)))
+            .collect()
+    }
+}
+
+/// Returns the edge types that make up the supply subgraph: `supplies`,
+/// `subcontracts`, `tolls`, `distributes`, `brokers`, and `sells_to`.
+pub fn supply_edge_types() -> HashSet<EdgeTypeTag> {
+    [
+        EdgeType::Supplies,
+        EdgeType::Subcontracts,
+        EdgeType::Tolls,
+        EdgeType::Distributes,
+        EdgeType::Brokers,
+        EdgeType::SellsTo,
+    ]
+    .into_iter()
+    .map(EdgeTypeTag::Known)
+    .collect()
+}
+
+/// Symmetric weighted adjacency. Off-diagonal weights appear in both rows;
+/// a self-loop entry holds the full diagonal value `A_ii`.
+type Adjacency = Vec<BTreeMap<usize, f64>>;
+
+/// Segments the subgraph formed by edges whose type is in `edge_filter`.
+///
+/// `None` considers every edge. Only nodes incident to at least one
+/// considered edge are assigned a segment. `file` must be the [`OmtsFile`]
+/// that `graph` was built from; it supplies the edge weight properties.
+pub fn louvain(
+    graph: &OmtsGraph,
+    file: &OmtsFile,
+    edge_filter: Option<&HashSet<EdgeTypeTag>>,
+    weighting: EdgeWeighting,
+) -> Segmentation {
+    let (ids, adjacency) = weighted_adjacency(graph, file, edge_filter, weighting);
+
+    // membership[i] = segment of original node i at the current level.
+    let mut membership: Vec<usize> = (0..ids.len()).collect();
+    let mut level_graph = adjacency.clone();
+    let mut levels = 0;
+
+    loop {
+        let (communities, moved) = local_moving(&level_graph);
+        if !moved {
+            break;
+        }
+        levels += 1;
+        let (renumbered, count) = renumber(&communities);
+        for segment in &mut membership {
+            *segment = renumbered[*segment];
+        }
+        level_graph = aggregate(&level_graph, &renumbered, count);
+    }
+
+    let modularity = modularity(&adjacency, &membership);
+
+    let mut groups: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (node, &segment) in membership.iter().enumerate() {
+        groups
+            .entry(segment)
+            .or_default()
+            .push(ids[node].to_owned());
+    }
+    let mut members: Vec<Vec<String>> = groups.into_values().collect();
+    members.sort_by(|a, b| {
+        b.len()
+            .cmp(&a.len())
+            .then_with(|| a.first().cmp(&b.first()))
+    });
+
+    Segmentation {
+        segments: members
+            .into_iter()
+            .enumerate()
+            .map(|(i, members)| Segment { id: i + 1, members })
+            .collect(),
+        modularity,
+        levels,
+    }
+}
+
+/// Builds the ID-sorted node list and undirected weighted adjacency for the
+/// filtered subgraph. Self-loops and parallel edges are merged.
+fn weighted_adjacency<'a>(
+    graph: &'a OmtsGraph,
+    file: &OmtsFile,
+    edge_filter: Option<&HashSet<EdgeTypeTag>>,
+    weighting: EdgeWeighting,
+) -> (Vec<&'a str>, Adjacency) {
+    let mut raw_edges: Vec<(&str, &str, Option<f64>)> = Vec::new();
+    for edge_ref in graph.graph().edge_references() {
+        let weight = edge_ref.weight();
+        if edge_filter.is_some_and(|f| !f.contains(&weight.edge_type)) {
+            continue;
+        }
+        let (Some(src), Some(tgt)) = (
+            graph.node_weight(edge_ref.source()),
+            graph.node_weight(edge_ref.target()),
+        ) else {
+            continue;
+        };
+        if src.local_id == tgt.local_id {
+            continue;
+        }
+        let value = file
+            .edges
+            .get(weight.data_index)
+            .and_then(|edge| match weighting {
+                EdgeWeighting::Uniform => Some(1.0),
+                EdgeWeighting::AnnualValue => edge.properties.annual_value,
+                EdgeWeighting::Volume => edge.properties.volume,
+            })
+            .filter(|v| v.is_finite() && *v > 0.0);
+        raw_edges.push((&src.local_id, &tgt.local_id, value));
+    }
+
+    let known: Vec<f64> = raw_edges.iter().filter_map(|e| e.2).collect();
+    let fallback = if known.is_empty() {
+        1.0
+    } else {
+        known.iter().sum::<f64>() / known.len() as f64
+    };
+
+    let mut ids: Vec<&str> = raw_edges.iter().flat_map(|e| [e.0, e.1]).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let position: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let mut adjacency: Adjacency = vec![BTreeMap::new(); ids.len()];
+    for &(src, tgt, value) in &raw_edges {
+        let (Some(&a), Some(&b)) = (position.get(src), position.get(tgt)) else {
+            continue;
+        };
+        let w = value.unwrap_or(fallback);
+        *adjacency[a].entry(b).or_insert(0.0) += w;
+        *adjacency[b].entry(a).or_insert(0.0) += w;
+    }
+    (ids, adjacency)
+}
+
+/// Runs the local-moving phase. Returns each node's community and whether
+/// any node moved.
+fn local_moving(adjacency: &Adjacency) -> (Vec<usize>, bool) {
+    let n = adjacency.len();
+    let degree: Vec<f64> = adjacency.iter().map(|adj| adj.values().sum()).collect();
+    let two_m: f64 = degree.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    if two_m <= 0.0 {
+        return (community, false);
+    }
+
+    let mut total: Vec<f64> = degree.clone();
+    let mut moved_any = false;
+
+    for _ in 0..MAX_LOCAL_PASSES {
+        let mut moved = false;
+        for node in 0..n {
+            let current = community[node];
+            total[current] -= degree[node];
+
+            // Weight from `node` into each neighbouring community.
+            let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+            links.insert(current, 0.0);
+            for (&neighbour, &w) in &adjacency[node] {
+                if neighbour != node {
+                    *links.entry(community[neighbour]).or_insert(0.0) += w;
+                }
+            }
+
+            // Gain of joining `c`, up to a constant factor of 1/m.
+            let gain = |c: usize, k_in: f64| k_in - total[c] * degree[node] / two_m;
+            let mut best = current;
+            let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+            for (&candidate, &k_in) in &links {
+                let g = gain(candidate, k_in);
+                if g > best_gain + f64::EPSILON * two_m {
+                    best = candidate;
+                    best_gain = g;
+                }
+            }
+
+            total[best] += degree[node];
+            if best != current {
+                community[node] = best;
+                moved = true;
+                moved_any = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    (community, moved_any)
+}
+
+/// Maps arbitrary community numbers to `0..count`, in order of first
+/// appearance. Returns the mapping indexed by old number and the count.
+fn renumber(communities: &[usize]) -> (Vec<usize>, usize) {
+    let mut mapping: HashMap<usize, usize> = HashMap::new();
+    let renumbered: Vec<usize> = communities
+        .iter()
+        .map(|&c| {
+            let next = mapping.len();
+            *mapping.entry(c).or_insert(next)
+        })
+        .collect();
+    (renumbered, mapping.len())
+}
+
+/// Collapses each community into a single node.
+fn aggregate(adjacency: &Adjacency, community: &[usize], count: usize) -> Adjacency {
+    let mut next: Adjacency = vec![BTreeMap::new(); count];
+    for (node, adj) in adjacency.iter().enumerate() {
+        let from = community[node];
+        for (&neighbour, &w) in adj {
+            *next[from].entry(community[neighbour]).or_insert(0.0) += w;
+        }
+    }
+    next
+}
+
+/// Weighted modularity `Q = Σ_c (L_c / 2m − (d_c / 2m)²)`, where `L_c` is
+/// the sum of adjacency entries inside `c` (each internal edge counted from
+/// both ends) and `d_c` the total degree of `c`.
+fn modularity(adjacency: &Adjacency, labels: &[usize]) -> f64 {
+    let degree: Vec<f64> = adjacency.iter().map(|adj| adj.values().sum()).collect();
+    let two_m: f64 = degree.iter().sum();
+    if two_m <= 0.0 {
+        return 0.0;
+    }
+
+    let mut internal: HashMap<usize, f64> = HashMap::new();
+    let mut total_degree: HashMap<usize, f64> = HashMap::new();
+    for (node, adj) in adjacency.iter().enumerate() {
+        let label = labels[node];
+        *total_degree.entry(label).or_insert(0.0) += degree[node];
+        for (&neighbour, &w) in adj {
+            if labels[neighbour] == label {
+                *internal.entry(label).or_insert(0.0) += w;
+            }
+        }
+    }
+
+    total_degree
+        .iter()
+        .map(|(label, d)| {
+            let l = internal.get(label).copied().unwrap_or(0.0);
+            l / two_m - (d / two_m).powi(2)
+        })
+        .sum()
+}
+
+/// Returns a copy of `file` with each segmented node labelled `key=<segment>`.
+///
+/// Any existing label with the same key is replaced, and removed from nodes
+/// that are not part of `segmentation`, so re-running segmentation does not
+/// leave stale assignments behind.
+pub fn apply_segment_labels(file: &OmtsFile, segmentation: &Segmentation, key: &str) -> OmtsFile {
+    let assignments = segmentation.assignments();
+    let mut out = file.clone();
+    for node in &mut out.nodes {
+        let segment = assignments.get(&*node.id).copied();
+        let mut labels = node.labels.take().unwrap_or_default();
+        labels.retain(|label| label.key != key);
+        if let Some(segment) = segment {
+            labels.push(Label {
+                key: key.to_owned(),
+                value: Some(segment.to_string()),
+                extra: BTreeMap::new(),
+            });
+        }
+        node.labels = if labels.is_empty() {
+            None
+        } else {
+            Some(labels)
+        };
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+    use crate::graph::build_graph;
+    use crate::structures::Edge;
+    use crate::test_helpers::{minimal_file, org_node, ownership_edge, supplies_edge};
+
+    fn valued(mut edge: Edge, annual_value: f64) -> Edge {
+        edge.properties.annual_value = Some(annual_value);
+        edge
+    }
+
+    /// Two triangles joined by a single bridge edge.
+    fn two_clusters() -> OmtsFile {
+        let nodes = ["a1", "a2", "a3", "b1", "b2", "b3"]
+            .into_iter()
+            .map(org_node)
+            .collect();
+        let edges = vec![
+            supplies_edge("e1", "a1", "a2"),
+            supplies_edge("e2", "a2", "a3"),
+            supplies_edge("e3", "a3", "a1"),
+            supplies_edge("e4", "b1", "b2"),
+            supplies_edge("e5", "b2", "b3"),
+            supplies_edge("e6", "b3", "b1"),
+            supplies_edge("bridge", "a3", "b1"),
+        ];
+        minimal_file(nodes, edges)
+    }
+
+    fn segment(file: &OmtsFile, weighting: EdgeWeighting) -> Segmentation {
+        let graph = build_graph(file).expect("builds");
+        louvain(&graph, file, Some(&supply_edge_types()), weighting)
+    }
+
+    /// Two triangles joined by a bridge split into two segments.
+    #[test]
+    fn test_two_clusters_separated() {
+        let seg = segment(&two_clusters(), EdgeWeighting::Uniform);
+        assert_eq!(seg.segments.len(), 2);
+        let assignments = seg.assignments();
+        assert_eq!(assignments.get("a1"), assignments.get("a2"));
+        assert_eq!(assignments.get("b2"), assignments.get("b3"));
+        assert_ne!(assignments.get("a1"), assignments.get("b2"));
+        assert!(seg.modularity > 0.3, "modularity = {}", seg.modularity);
+    }
+
+    /// Output is identical across runs.
+    #[test]
+    fn test_deterministic() {
+        let file = two_clusters();
+        assert_eq!(
+            segment(&file, EdgeWeighting::Uniform),
+            segment(&file, EdgeWeighting::Uniform)
+        );
+    }
+
+    /// Edges outside the filter, and nodes touched only by them, are ignored.
+    #[test]
+    fn test_edge_filter_excludes_non_supply_edges() {
+        let mut file = two_clusters();
+        file.nodes.push(org_node("parent"));
+        file.edges.push(ownership_edge("own", "parent", "a1"));
+        let seg = segment(&file, EdgeWeighting::Uniform);
+        assert!(!seg.assignments().contains_key("parent"));
+
+        let graph = build_graph(&file).expect("builds");
+        let all = louvain(&graph, &file, None, EdgeWeighting::Uniform);
+        assert!(all.assignments().contains_key("parent"));
+    }
+
+    /// A heavy bridge pulls its endpoints into the same segment.
+    #[test]
+    fn test_annual_value_weighting() {
+        let nodes = ["a", "b", "c", "d"].into_iter().map(org_node).collect();
+        let edges = vec![
+            valued(supplies_edge("e1", "a", "b"), 10.0),
+            valued(supplies_edge("e2", "b", "c"), 1_000_000.0),
+            valued(supplies_edge("e3", "c", "d"), 10.0),
+        ];
+        let file = minimal_file(nodes, edges);
+        let seg = segment(&file, EdgeWeighting::AnnualValue);
+        let assignments = seg.assignments();
+        assert_eq!(assignments.get("b"), assignments.get("c"));
+    }
+
+    /// An empty supply subgraph yields no segments.
+    #[test]
+    fn test_empty() {
+        let seg = segment(
+            &minimal_file(vec![org_node("a")], vec![]),
+            EdgeWeighting::Uniform,
+        );
+        assert!(seg.segments.is_empty());
+        assert!(seg.modularity.abs() < f64::EPSILON);
+    }
+
+    /// Segment labels replace stale values and are removed from unsegmented nodes.
+    #[test]
+    fn test_apply_segment_labels() {
+        let mut file = two_clusters();
+        let mut loner = org_node("loner");
+        loner.labels = Some(vec![Label {
+            key: SEGMENT_LABEL_KEY.to_owned(),
+            value: Some("9".to_owned()),
+            extra: BTreeMap::new(),
+        }]);
+        file.nodes.push(loner);
+
+        let seg = segment(&file, EdgeWeighting::Uniform);
+        let labelled = apply_segment_labels(&file, &seg, SEGMENT_LABEL_KEY);
+
+        let a1 = labelled
+            .nodes
+            .iter()
+            .find(|n| &*n.id == "a1")
+            .expect("a1 present");
+        let labels = a1.labels.as_ref().expect("labelled");
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].key, SEGMENT_LABEL_KEY);
+        assert!(labels[0].value.is_some());
+
+        let loner = labelled
+            .nodes
+            .iter()
+            .find(|n| &*n.id == "loner")
+            .expect("loner present");
+        assert!(loner.labels.is_none(), "stale segment label removed");
+    }
+}
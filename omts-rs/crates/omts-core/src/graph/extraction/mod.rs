@@ -14,6 +14,13 @@
 /// collects all nodes within `radius` hops of the `center` node (inclusive of
 /// the center), then extracts the induced subgraph of that neighbourhood.
 ///
+/// # Traversal Extraction
+///
+/// [`reachable_subgraph`] extracts the region reached by a (depth-bounded,
+/// edge-type-filtered) BFS from a start node. [`path_subgraph`] extracts the
+/// union of a set of [`WeightedPath`]s: exactly the nodes and edges they
+/// traverse.
+///
 /// # Selector-Based Extraction
 ///
 /// [`selector_match`] scans all nodes and edges for property-predicate matches
@@ -29,12 +36,14 @@
 /// set to `None`.
 use std::collections::{HashSet, VecDeque};
 
-use petgraph::stable_graph::NodeIndex;
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
 use petgraph::visit::EdgeRef;
 
+use crate::enums::EdgeTypeTag;
 use crate::file::OmtsFile;
 use crate::graph::OmtsGraph;
-use crate::graph::queries::{Direction, QueryError};
+use crate::graph::paths::WeightedPath;
+use crate::graph::queries::{Direction, QueryError, reachable_within};
 use crate::graph::selectors::SelectorSet;
 
 #[cfg(test)]
@@ -173,6 +182,70 @@ pub fn ego_graph(
     assemble_subgraph(graph, file, &visited)
 }
 
+/// Extracts the region reachable from `start` as a subgraph.
+///
+/// The node set is `start` plus every node returned by [`reachable_within`]
+/// for the same arguments. Edges are included when both endpoints are in the
+/// region and, if `edge_filter` is `Some`, the edge type is in the filter — so
+/// "upstream via `supplies` only" yields a file containing only `supplies`
+/// edges even when other edge types connect the same nodes.
+///
+/// # Parameters
+///
+/// - `graph` — the graph to query.
+/// - `file` — the source [`OmtsFile`] used to build `graph`.
+/// - `start` — graph-local ID of the starting node.
+/// - `max_depth` — maximum number of hops from `start`; `None` for unbounded.
+/// - `direction` — which edges to follow.
+/// - `edge_filter` — optional set of allowed edge types; `None` traverses all.
+///
+/// # Errors
+///
+/// Returns [`QueryError::NodeNotFound`] if `start` does not exist in the graph.
+pub fn reachable_subgraph(
+    graph: &OmtsGraph,
+    file: &OmtsFile,
+    start: &str,
+    max_depth: Option<usize>,
+    direction: Direction,
+    edge_filter: Option<&HashSet<EdgeTypeTag>>,
+) -> Result<OmtsFile, QueryError> {
+    let mut region = reachable_within(graph, start, max_depth, direction, edge_filter)?;
+    if let Some(&start_idx) = graph.node_index(start) {
+        region.insert(start_idx);
+    }
+
+    Ok(assemble_filtered_subgraph(
+        graph,
+        file,
+        &region,
+        |edge| match edge_filter {
+            None => true,
+            Some(allowed) => graph
+                .edge_weight(edge)
+                .is_some_and(|w| allowed.contains(&w.edge_type)),
+        },
+    ))
+}
+
+/// Extracts the union of `paths` as a subgraph.
+///
+/// The output contains every node on any path and exactly the edges the paths
+/// traverse; other edges between the same nodes (parallel edges, shortcuts)
+/// are not included.
+///
+/// # Parameters
+///
+/// - `graph` — the graph the paths were computed on.
+/// - `file` — the source [`OmtsFile`] used to build `graph`.
+/// - `paths` — paths returned by [`crate::graph::k_shortest_paths`].
+pub fn path_subgraph(graph: &OmtsGraph, file: &OmtsFile, paths: &[WeightedPath]) -> OmtsFile {
+    let nodes: HashSet<NodeIndex> = paths.iter().flat_map(|p| p.nodes.iter().copied()).collect();
+    let edges: HashSet<EdgeIndex> = paths.iter().flat_map(|p| p.edges.iter().copied()).collect();
+
+    assemble_filtered_subgraph(graph, file, &nodes, |edge| edges.contains(&edge))
+}
+
 /// Result of a [`selector_match`] scan.
 ///
 /// Contains the indices into the originating `OmtsFile`'s `nodes` and `edges`
@@ -374,27 +447,47 @@ fn can_use_node_type_index(ss: &SelectorSet) -> bool {
         && ss.identifier_scheme_values.is_empty()
         && ss.jurisdictions.is_empty()
         && ss.names.is_empty()
+        && ss.properties.is_empty()
+        && ss.expr.is_none()
 }
 
 /// Returns `true` when `edge_types` is the only non-empty edge-applicable
 /// selector group, allowing the type index to replace a full linear scan.
 fn can_use_edge_type_index(ss: &SelectorSet) -> bool {
-    !ss.edge_types.is_empty() && ss.label_keys.is_empty() && ss.label_key_values.is_empty()
+    !ss.edge_types.is_empty()
+        && ss.label_keys.is_empty()
+        && ss.label_key_values.is_empty()
+        && ss.properties.is_empty()
+        && ss.expr.is_none()
 }
 
 /// Assembles an [`OmtsFile`] from a set of included [`NodeIndex`] values.
 ///
+/// Includes every edge whose source and target are both in `index_set`.
+fn assemble_subgraph(
+    graph: &OmtsGraph,
+    file: &OmtsFile,
+    index_set: &HashSet<NodeIndex>,
+) -> Result<OmtsFile, QueryError> {
+    Ok(assemble_filtered_subgraph(graph, file, index_set, |_| true))
+}
+
+/// Assembles an [`OmtsFile`] from a set of included [`NodeIndex`] values,
+/// keeping only the edges accepted by `keep_edge`.
+///
 /// Iterates all edges in the graph; includes an edge in the output only if
-/// both its source and target are in `index_set`.  Nodes are included in
+/// both its source and target are in `index_set` and `keep_edge` returns
+/// `true` for it.  Nodes are included in
 /// original file order (by `data_index`) to keep output deterministic.
 ///
 /// The `reporting_entity` header field is preserved only when the referenced
 /// node is present in `index_set`; otherwise it is set to `None`.
-fn assemble_subgraph(
+fn assemble_filtered_subgraph(
     graph: &OmtsGraph,
     file: &OmtsFile,
     index_set: &HashSet<NodeIndex>,
-) -> Result<OmtsFile, QueryError> {
+    keep_edge: impl Fn(EdgeIndex) -> bool,
+) -> OmtsFile {
     let g = graph.graph();
 
     let mut included_data_indices: HashSet<usize> = HashSet::with_capacity(index_set.len());
@@ -415,7 +508,7 @@ fn assemble_subgraph(
     let mut included_edge_data_indices: HashSet<usize> = HashSet::new();
     for &node_idx in index_set {
         for edge_ref in g.edges(node_idx) {
-            if index_set.contains(&edge_ref.target()) {
+            if index_set.contains(&edge_ref.target()) && keep_edge(edge_ref.id()) {
                 included_edge_data_indices.insert(edge_ref.weight().data_index);
             }
         }
@@ -438,7 +531,7 @@ fn assemble_subgraph(
         }
     });
 
-    Ok(OmtsFile {
+    OmtsFile {
         omts_version: file.omts_version.clone(),
         snapshot_date: file.snapshot_date.clone(),
         file_salt: file.file_salt.clone(),
@@ -449,5 +542,5 @@ fn assemble_subgraph(
         nodes,
         edges,
         extra: file.extra.clone(),
-    })
+    }
 }
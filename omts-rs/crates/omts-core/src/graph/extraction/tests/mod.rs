@@ -1,6 +1,6 @@
 #![allow(clippy::expect_used)]
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 
 use super::*;
 use crate::file::OmtsFile;
@@ -197,6 +197,83 @@ fn test_induced_subgraph_empty_node_ids_returns_empty_file() {
     assert_eq!(sub.edges.len(), 0);
 }
 
+/// Reachable subgraph upstream via `supplies` keeps only `supplies` edges.
+#[test]
+fn test_reachable_subgraph_filters_edges_by_type() {
+    // Graph: c -supplies-> b -supplies-> a, c -ownership-> a, d -ownership-> a
+    let nodes = vec![org_node("a"), org_node("b"), org_node("c"), org_node("d")];
+    let edges = vec![
+        supplies_edge("e-cb", "c", "b"),
+        supplies_edge("e-ba", "b", "a"),
+        ownership_edge("e-ca", "c", "a"),
+        ownership_edge("e-da", "d", "a"),
+    ];
+    let file = minimal_file(nodes, edges);
+    let graph = build_graph(&file).expect("builds");
+    let filter: HashSet<EdgeTypeTag> = [EdgeTypeTag::Known(crate::enums::EdgeType::Supplies)]
+        .into_iter()
+        .collect();
+
+    let sub = reachable_subgraph(&graph, &file, "a", None, Direction::Backward, Some(&filter))
+        .expect("should succeed");
+    let node_ids: Vec<String> = sub.nodes.iter().map(|n| n.id.to_string()).collect();
+    assert_eq!(node_ids, vec!["a", "b", "c"]);
+    let edge_ids: Vec<String> = sub.edges.iter().map(|e| e.id.to_string()).collect();
+    assert_eq!(
+        edge_ids,
+        vec!["e-cb", "e-ba"],
+        "ownership edge e-ca excluded"
+    );
+
+    let bounded = reachable_subgraph(&graph, &file, "a", Some(1), Direction::Backward, None)
+        .expect("should succeed");
+    let node_ids: Vec<String> = bounded.nodes.iter().map(|n| n.id.to_string()).collect();
+    assert_eq!(node_ids, vec!["a", "b", "c", "d"]);
+    assert_eq!(bounded.edges.len(), 4, "unfiltered region is induced");
+}
+
+/// Reachable subgraph reports an unknown start node.
+#[test]
+fn test_reachable_subgraph_unknown_node_returns_error() {
+    let file = minimal_file(vec![org_node("a")], vec![]);
+    let graph = build_graph(&file).expect("builds");
+    let err = reachable_subgraph(&graph, &file, "zzz", None, Direction::Forward, None)
+        .expect_err("unknown start");
+    assert_eq!(err, QueryError::NodeNotFound("zzz".to_owned()));
+}
+
+/// Path subgraph contains exactly the traversed edges, not parallel ones.
+#[test]
+fn test_path_subgraph_keeps_only_traversed_edges() {
+    // Graph: a -supplies-> b -supplies-> c, a -ownership-> b, a -supplies-> d
+    let nodes = vec![org_node("a"), org_node("b"), org_node("c"), org_node("d")];
+    let edges = vec![
+        supplies_edge("e-ab", "a", "b"),
+        supplies_edge("e-bc", "b", "c"),
+        ownership_edge("e-ab2", "a", "b"),
+        supplies_edge("e-ad", "a", "d"),
+    ];
+    let file = minimal_file(nodes, edges);
+    let graph = build_graph(&file).expect("builds");
+    let options = crate::graph::PathOptions {
+        edge_filter: Some(
+            [EdgeTypeTag::Known(crate::enums::EdgeType::Supplies)]
+                .into_iter()
+                .collect(),
+        ),
+        ..crate::graph::PathOptions::default()
+    };
+    let paths =
+        crate::graph::k_shortest_paths(&graph, Some(&file), "a", "c", 5, &options).expect("paths");
+    assert_eq!(paths.len(), 1);
+
+    let sub = path_subgraph(&graph, &file, &paths);
+    let node_ids: Vec<String> = sub.nodes.iter().map(|n| n.id.to_string()).collect();
+    assert_eq!(node_ids, vec!["a", "b", "c"]);
+    let edge_ids: Vec<String> = sub.edges.iter().map(|e| e.id.to_string()).collect();
+    assert_eq!(edge_ids, vec!["e-ab", "e-bc"]);
+}
+
 /// Ego-graph with radius 0 returns only the center node (no edges to others).
 #[test]
 fn test_ego_graph_radius_0_returns_center_only() {
@@ -0,0 +1,840 @@
+/// Persistent graph index: a compact binary sidecar for repeated queries.
+///
+/// A [`GraphIndex`] captures everything needed to answer structural queries
+/// on an `.omts` file without rebuilding [`OmtsGraph`] from scratch, and
+/// without parsing the file at all for queries that need only IDs, types,
+/// names, labels, and identifiers:
+///
+/// - **Node ID table** — one [`IndexedNode`] per node, in file order, with
+///   its type and display name.
+/// - **Edge table** — one [`IndexedEdge`] per edge, in file order.
+/// - **Adjacency lists per edge type** — one [`TypeAdjacency`] per edge type
+///   in compressed sparse row form: for node `n`, the outgoing edges of that
+///   type are `edges[offsets[n]..offsets[n + 1]]`.
+/// - **Canonical-identifier index** — [`crate::canonical::build_identifier_index`]
+///   keyed by the canonical string.
+/// - **Inverted indexes** — lowercased node names, node and edge labels,
+///   and node identifier schemes, each mapping to sorted element positions.
+/// - **Content hash** — the SHA-256 of the source file's bytes
+///   ([`content_hash`]). An index is fresh for a file exactly when the hashes
+///   are equal.
+///
+/// Positions are indices into `OmtsFile::nodes` / `OmtsFile::edges`, so an
+/// index built from a file can be used alongside that file.
+///
+/// # Encoding
+///
+/// [`GraphIndex::encode`] writes the 7-byte magic `OMTSIDX`, a format
+/// version byte ([`INDEX_FORMAT_VERSION`]), and the index as CBOR.
+/// [`GraphIndex::decode`] rejects other magics and versions and validates
+/// every stored position, so a truncated or foreign sidecar is reported as
+/// an [`IndexError`] rather than producing a wrong graph.
+///
+/// `omts-core` never touches the filesystem; reading and writing the sidecar
+/// is up to the caller.
+use std::collections::{BTreeMap, HashMap};
+
+use petgraph::stable_graph::{EdgeIndex, NodeIndex, StableDiGraph};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::boundary_hash::hex_encode;
+use crate::canonical::build_identifier_index;
+use crate::enums::{EdgeTypeTag, NodeTypeTag};
+use crate::file::OmtsFile;
+use crate::graph::extraction::SelectorMatchResult;
+use crate::graph::selectors::SelectorSet;
+use crate::graph::{EdgeWeight, GraphBuildError, NodeWeight, OmtsGraph};
+use crate::types::Label;
+
+/// Magic bytes at the start of every encoded index.
+const INDEX_MAGIC: &[u8; 7] = b"OMTSIDX";
+
+/// Version of the binary index layout written by [`GraphIndex::encode`].
+pub const INDEX_FORMAT_VERSION: u8 = 1;
+
+/// Returns the lowercase hex SHA-256 digest of `bytes`.
+///
+/// Used as [`GraphIndex::content_hash`]; hash the raw file bytes exactly as
+/// read from disk (before decompression or parsing).
+pub fn content_hash(bytes: &[u8]) -> String {
+    hex_encode(&Sha256::digest(bytes))
+}
+
+/// Errors from encoding, decoding, or expanding a [`GraphIndex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexError {
+    /// The bytes do not start with the index magic.
+    NotAnIndex,
+    /// The index was written with an unsupported format version.
+    UnsupportedVersion(u8),
+    /// CBOR encoding failed.
+    Encode(String),
+    /// CBOR decoding failed.
+    Decode(String),
+    /// The decoded index is internally inconsistent (e.g. a position out of
+    /// range).
+    Corrupt(String),
+}
+
+impl std::fmt::Display for IndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndexError::NotAnIndex => write!(f, "not an omts index (bad magic)"),
+            IndexError::UnsupportedVersion(v) => {
+                write!(
+                    f,
+                    "unsupported index format version {v} (expected {INDEX_FORMAT_VERSION})"
+                )
+            }
+            IndexError::Encode(msg) => write!(f, "index encode error: {msg}"),
+            IndexError::Decode(msg) => write!(f, "index decode error: {msg}"),
+            IndexError::Corrupt(msg) => write!(f, "corrupt index: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+/// One entry of the node ID table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedNode {
+    /// Graph-local node ID.
+    pub id: String,
+    /// Node type.
+    #[serde(rename = "type")]
+    pub node_type: NodeTypeTag,
+    /// Display name, if the node has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// One entry of the edge table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedEdge {
+    /// Graph-local edge ID.
+    pub id: String,
+    /// Edge type.
+    #[serde(rename = "type")]
+    pub edge_type: EdgeTypeTag,
+    /// Position of the source node in the node table.
+    pub source: u32,
+    /// Position of the target node in the node table.
+    pub target: u32,
+}
+
+/// Outgoing adjacency lists for one edge type, in compressed sparse row form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeAdjacency {
+    /// The edge type these lists cover.
+    pub edge_type: EdgeTypeTag,
+    /// `offsets[n]..offsets[n + 1]` is node `n`'s slice of `edges`; one entry
+    /// per node plus a final sentinel.
+    pub offsets: Vec<u32>,
+    /// Edge-table positions grouped by source node.
+    pub edges: Vec<u32>,
+}
+
+/// Inverted-index entry for a key that may carry values (labels, identifier
+/// schemes).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Postings {
+    /// Positions of every element carrying the key.
+    pub all: Vec<u32>,
+    /// Positions per value of the key; valueless entries appear only in `all`.
+    pub by_value: BTreeMap<String, Vec<u32>>,
+}
+
+/// A persistent index over one `.omts` file. See the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphIndex {
+    /// SHA-256 of the indexed file's bytes (see [`content_hash`]).
+    pub content_hash: String,
+    /// Node ID table, in file order.
+    pub nodes: Vec<IndexedNode>,
+    /// Edge table, in file order.
+    pub edges: Vec<IndexedEdge>,
+    /// Adjacency lists, one entry per edge type present in the file.
+    pub adjacency: Vec<TypeAdjacency>,
+    /// Canonical identifier string → node positions.
+    pub canonical_ids: BTreeMap<String, Vec<u32>>,
+    /// Lowercased node name → node positions.
+    pub names: BTreeMap<String, Vec<u32>>,
+    /// Node label key → node positions.
+    pub node_labels: BTreeMap<String, Postings>,
+    /// Edge label key → edge positions.
+    pub edge_labels: BTreeMap<String, Postings>,
+    /// Node identifier scheme → node positions.
+    pub identifier_schemes: BTreeMap<String, Postings>,
+}
+
+impl GraphIndex {
+    /// Builds the index for `file`, whose raw bytes hash to `content_hash`.
+    ///
+    /// Applies the same structural checks as [`crate::graph::build_graph`].
+    ///
+    /// # Errors
+    ///
+    /// - [`GraphBuildError::DuplicateNodeId`] — two nodes share the same ID.
+    /// - [`GraphBuildError::DanglingEdgeRef`] — an edge references a node that
+    ///   does not exist.
+    pub fn build(file: &OmtsFile, content_hash: String) -> Result<Self, GraphBuildError> {
+        let mut positions: HashMap<&str, u32> = HashMap::with_capacity(file.nodes.len());
+        let mut nodes = Vec::with_capacity(file.nodes.len());
+        let mut names: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        let mut node_labels: BTreeMap<String, Postings> = BTreeMap::new();
+        let mut identifier_schemes: BTreeMap<String, Postings> = BTreeMap::new();
+
+        for (i, node) in file.nodes.iter().enumerate() {
+            let pos = to_u32(i);
+            if positions.insert(&node.id, pos).is_some() {
+                return Err(GraphBuildError::DuplicateNodeId(node.id.to_string()));
+            }
+            nodes.push(IndexedNode {
+                id: node.id.to_string(),
+                node_type: node.node_type.clone(),
+                name: node.name.clone(),
+            });
+            if let Some(name) = &node.name {
+                names.entry(name.to_lowercase()).or_default().push(pos);
+            }
+            add_labels(&mut node_labels, node.labels.as_deref(), pos);
+            for id in node.identifiers.iter().flatten() {
+                add_posting(
+                    identifier_schemes.entry(id.scheme.clone()).or_default(),
+                    Some(&id.value),
+                    pos,
+                );
+            }
+        }
+
+        let mut edges = Vec::with_capacity(file.edges.len());
+        let mut edge_labels: BTreeMap<String, Postings> = BTreeMap::new();
+        for (i, edge) in file.edges.iter().enumerate() {
+            let resolve = |id: &str| {
+                positions
+                    .get(id)
+                    .copied()
+                    .ok_or_else(|| GraphBuildError::DanglingEdgeRef {
+                        edge_id: edge.id.to_string(),
+                        missing_node_id: id.to_owned(),
+                    })
+            };
+            edges.push(IndexedEdge {
+                id: edge.id.to_string(),
+                edge_type: edge.edge_type.clone(),
+                source: resolve(&edge.source)?,
+                target: resolve(&edge.target)?,
+            });
+            add_labels(
+                &mut edge_labels,
+                edge.properties.labels.as_deref(),
+                to_u32(i),
+            );
+        }
+
+        let canonical_ids = build_identifier_index(&file.nodes)
+            .into_iter()
+            .map(|(cid, idxs)| (cid.to_string(), idxs.into_iter().map(to_u32).collect()))
+            .collect();
+
+        Ok(GraphIndex {
+            content_hash,
+            adjacency: build_adjacency(nodes.len(), &edges),
+            nodes,
+            edges,
+            canonical_ids,
+            names,
+            node_labels,
+            edge_labels,
+            identifier_schemes,
+        })
+    }
+
+    /// Returns `true` if this index was built from a file whose bytes hash to
+    /// `hash`.
+    pub fn is_fresh(&self, hash: &str) -> bool {
+        self.content_hash == hash
+    }
+
+    /// Encodes the index as magic, format version, and CBOR payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IndexError::Encode`] if CBOR serialization fails.
+    pub fn encode(&self) -> Result<Vec<u8>, IndexError> {
+        let mut buf = Vec::with_capacity(64 * (self.nodes.len() + self.edges.len()) + 64);
+        buf.extend_from_slice(INDEX_MAGIC);
+        buf.push(INDEX_FORMAT_VERSION);
+        cbor4ii::serde::to_vec(buf, self).map_err(|e| IndexError::Encode(e.to_string()))
+    }
+
+    /// Decodes and validates an index written by [`encode`][Self::encode].
+    ///
+    /// # Errors
+    ///
+    /// - [`IndexError::NotAnIndex`] — missing magic.
+    /// - [`IndexError::UnsupportedVersion`] — a different format version.
+    /// - [`IndexError::Decode`] — malformed CBOR.
+    /// - [`IndexError::Corrupt`] — a position or adjacency list is out of range.
+    pub fn decode(bytes: &[u8]) -> Result<Self, IndexError> {
+        let rest = bytes
+            .strip_prefix(INDEX_MAGIC.as_slice())
+            .ok_or(IndexError::NotAnIndex)?;
+        let (&version, payload) = rest.split_first().ok_or(IndexError::NotAnIndex)?;
+        if version != INDEX_FORMAT_VERSION {
+            return Err(IndexError::UnsupportedVersion(version));
+        }
+        let index: GraphIndex =
+            cbor4ii::serde::from_slice(payload).map_err(|e| IndexError::Decode(e.to_string()))?;
+        index.validate()?;
+        Ok(index)
+    }
+
+    /// Expands the index into an [`OmtsGraph`].
+    ///
+    /// The result is identical to [`crate::graph::build_graph`] on the indexed
+    /// file: nodes and edges are inserted in file order, so node and edge
+    /// indices and every `data_index` match.
+    pub fn to_graph(&self) -> OmtsGraph {
+        let mut graph: StableDiGraph<NodeWeight, EdgeWeight> =
+            StableDiGraph::with_capacity(self.nodes.len(), self.edges.len());
+        let mut id_to_index: HashMap<String, NodeIndex> = HashMap::with_capacity(self.nodes.len());
+        let mut nodes_by_type: HashMap<NodeTypeTag, Vec<NodeIndex>> = HashMap::new();
+        let mut edges_by_type: HashMap<EdgeTypeTag, Vec<EdgeIndex>> = HashMap::new();
+        let mut node_indices: Vec<NodeIndex> = Vec::with_capacity(self.nodes.len());
+
+        for (data_index, node) in self.nodes.iter().enumerate() {
+            let idx = graph.add_node(NodeWeight {
+                local_id: node.id.clone(),
+                node_type: node.node_type.clone(),
+                data_index,
+            });
+            id_to_index.insert(node.id.clone(), idx);
+            nodes_by_type
+                .entry(node.node_type.clone())
+                .or_default()
+                .push(idx);
+            node_indices.push(idx);
+        }
+
+        for (data_index, edge) in self.edges.iter().enumerate() {
+            // Positions were validated on decode and by construction in `build`.
+            let (Some(&source), Some(&target)) = (
+                node_indices.get(edge.source as usize),
+                node_indices.get(edge.target as usize),
+            ) else {
+                continue;
+            };
+            let edge_idx = graph.add_edge(
+                source,
+                target,
+                EdgeWeight {
+                    local_id: edge.id.clone(),
+                    edge_type: edge.edge_type.clone(),
+                    data_index,
+                },
+            );
+            edges_by_type
+                .entry(edge.edge_type.clone())
+                .or_default()
+                .push(edge_idx);
+        }
+
+        OmtsGraph {
+            graph,
+            id_to_index,
+            nodes_by_type,
+            edges_by_type,
+        }
+    }
+
+    /// Returns the outgoing edge positions of type `edge_type` from the node at
+    /// position `node`.
+    pub fn out_edges(&self, node: u32, edge_type: &EdgeTypeTag) -> &[u32] {
+        let Some(adj) = self.adjacency.iter().find(|a| &a.edge_type == edge_type) else {
+            return &[];
+        };
+        let n = node as usize;
+        match (adj.offsets.get(n), adj.offsets.get(n + 1)) {
+            (Some(&lo), Some(&hi)) => adj.edges.get(lo as usize..hi as usize).unwrap_or(&[]),
+            _ => &[],
+        }
+    }
+
+    /// Returns the positions of nodes carrying the canonical identifier
+    /// `canonical` (see [`crate::canonical::CanonicalId`]).
+    pub fn nodes_with_canonical_id(&self, canonical: &str) -> &[u32] {
+        self.canonical_ids
+            .get(canonical)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// Evaluates `selectors` against the inverted indexes.
+    ///
+    /// Returns the same result as [`crate::graph::selector_match`] on the
+    /// indexed file, or `None` if the set uses a selector the index cannot
+    /// answer (jurisdiction, typed property predicates, or a `--where`
+    /// expression).
+    pub fn select(&self, selectors: &SelectorSet) -> Option<SelectorMatchResult> {
+        if !selectors.jurisdictions.is_empty()
+            || !selectors.properties.is_empty()
+            || selectors.expr.is_some()
+        {
+            return None;
+        }
+
+        let mut result = SelectorMatchResult::default();
+        if selectors.is_empty() {
+            result.node_indices = (0..self.nodes.len()).collect();
+            result.edge_indices = (0..self.edges.len()).collect();
+            return Some(result);
+        }
+
+        if selectors.has_node_selectors() {
+            let mut mask = vec![true; self.nodes.len()];
+            if !selectors.node_types.is_empty() {
+                let group: Vec<u32> = (0..self.nodes.len())
+                    .filter(|&i| {
+                        self.nodes
+                            .get(i)
+                            .is_some_and(|n| selectors.node_types.contains(&n.node_type))
+                    })
+                    .map(to_u32)
+                    .collect();
+                restrict(&mut mask, [group.as_slice()]);
+            }
+            restrict_labels(
+                &mut mask,
+                &self.node_labels,
+                &selectors.label_keys,
+                &selectors.label_key_values,
+            );
+            restrict_labels(
+                &mut mask,
+                &self.identifier_schemes,
+                &selectors.identifier_schemes,
+                &selectors.identifier_scheme_values,
+            );
+            if !selectors.names.is_empty() {
+                let patterns: Vec<String> =
+                    selectors.names.iter().map(|n| n.to_lowercase()).collect();
+                restrict(
+                    &mut mask,
+                    self.names
+                        .iter()
+                        .filter(|(name, _)| patterns.iter().any(|p| name.contains(p.as_str())))
+                        .map(|(_, postings)| postings.as_slice()),
+                );
+            }
+            result.node_indices = set_positions(&mask);
+        }
+
+        if selectors.has_edge_selectors() {
+            let mut mask = vec![true; self.edges.len()];
+            if !selectors.edge_types.is_empty() {
+                let group: Vec<u32> = (0..self.edges.len())
+                    .filter(|&i| {
+                        self.edges
+                            .get(i)
+                            .is_some_and(|e| selectors.edge_types.contains(&e.edge_type))
+                    })
+                    .map(to_u32)
+                    .collect();
+                restrict(&mut mask, [group.as_slice()]);
+            }
+            restrict_labels(
+                &mut mask,
+                &self.edge_labels,
+                &selectors.label_keys,
+                &selectors.label_key_values,
+            );
+            result.edge_indices = set_positions(&mask);
+        }
+
+        Some(result)
+    }
+
+    /// Checks that every stored position is in range.
+    fn validate(&self) -> Result<(), IndexError> {
+        let node_count = self.nodes.len();
+        let edge_count = self.edges.len();
+        let in_nodes = |p: &u32| (*p as usize) < node_count;
+        let in_edges = |p: &u32| (*p as usize) < edge_count;
+
+        if !self
+            .edges
+            .iter()
+            .all(|e| in_nodes(&e.source) && in_nodes(&e.target))
+        {
+            return Err(IndexError::Corrupt("edge endpoint out of range".to_owned()));
+        }
+        for adj in &self.adjacency {
+            let monotonic = adj.offsets.windows(2).all(|w| w[0] <= w[1]);
+            if adj.offsets.len() != node_count + 1
+                || !monotonic
+                || adj.offsets.last().map(|&o| o as usize) != Some(adj.edges.len())
+                || !adj.edges.iter().all(in_edges)
+            {
+                return Err(IndexError::Corrupt(format!(
+                    "adjacency for {:?} is malformed",
+                    adj.edge_type.as_ref()
+                )));
+            }
+        }
+        let postings_ok = |p: &Postings, ok: &dyn Fn(&u32) -> bool| {
+            p.all.iter().all(ok) && p.by_value.values().flatten().all(ok)
+        };
+        if !self.canonical_ids.values().flatten().all(in_nodes)
+            || !self.names.values().flatten().all(in_nodes)
+            || !self.node_labels.values().all(|p| postings_ok(p, &in_nodes))
+            || !self
+                .identifier_schemes
+                .values()
+                .all(|p| postings_ok(p, &in_nodes))
+            || !self.edge_labels.values().all(|p| postings_ok(p, &in_edges))
+        {
+            return Err(IndexError::Corrupt(
+                "inverted index position out of range".to_owned(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Converts a file position to the stored `u32` form.
+///
+/// Files are far below `u32::MAX` elements under any configured size limit;
+/// saturating keeps the conversion total.
+fn to_u32(i: usize) -> u32 {
+    u32::try_from(i).unwrap_or(u32::MAX)
+}
+
+/// Adds `pos` to `postings`, under `value` as well when present.
+fn add_posting(postings: &mut Postings, value: Option<&String>, pos: u32) {
+    if postings.all.last() != Some(&pos) {
+        postings.all.push(pos);
+    }
+    if let Some(value) = value {
+        let list = postings.by_value.entry(value.clone()).or_default();
+        if list.last() != Some(&pos) {
+            list.push(pos);
+        }
+    }
+}
+
+/// Adds every label of one element to a label inverted index.
+fn add_labels(index: &mut BTreeMap<String, Postings>, labels: Option<&[Label]>, pos: u32) {
+    for label in labels.into_iter().flatten() {
+        add_posting(
+            index.entry(label.key.clone()).or_default(),
+            label.value.as_ref(),
+            pos,
+        );
+    }
+}
+
+/// Builds the per-edge-type CSR adjacency lists.
+fn build_adjacency(node_count: usize, edges: &[IndexedEdge]) -> Vec<TypeAdjacency> {
+    let mut types: Vec<EdgeTypeTag> = Vec::new();
+    for edge in edges {
+        if !types.contains(&edge.edge_type) {
+            types.push(edge.edge_type.clone());
+        }
+    }
+    types
+        .into_iter()
+        .map(|edge_type| {
+            let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); node_count];
+            for (i, edge) in edges.iter().enumerate() {
+                if edge.edge_type == edge_type {
+                    if let Some(bucket) = buckets.get_mut(edge.source as usize) {
+                        bucket.push(to_u32(i));
+                    }
+                }
+            }
+            let mut offsets = Vec::with_capacity(node_count + 1);
+            let mut flat = Vec::new();
+            offsets.push(0);
+            for bucket in buckets {
+                flat.extend(bucket);
+                offsets.push(to_u32(flat.len()));
+            }
+            TypeAdjacency {
+                edge_type,
+                offsets,
+                edges: flat,
+            }
+        })
+        .collect()
+}
+
+/// ANDs `mask` with the union of `groups`.
+fn restrict<'a>(mask: &mut [bool], groups: impl IntoIterator<Item = &'a [u32]>) {
+    let mut hit = vec![false; mask.len()];
+    for group in groups {
+        for &p in group {
+            if let Some(h) = hit.get_mut(p as usize) {
+                *h = true;
+            }
+        }
+    }
+    for (m, h) in mask.iter_mut().zip(hit) {
+        *m &= h;
+    }
+}
+
+/// Applies a key group and a key=value group against a keyed inverted index.
+fn restrict_labels(
+    mask: &mut [bool],
+    index: &BTreeMap<String, Postings>,
+    keys: &[String],
+    key_values: &[(String, String)],
+) {
+    if !keys.is_empty() {
+        restrict(
+            mask,
+            keys.iter()
+                .filter_map(|k| index.get(k))
+                .map(|p| p.all.as_slice()),
+        );
+    }
+    if !key_values.is_empty() {
+        restrict(
+            mask,
+            key_values
+                .iter()
+                .filter_map(|(k, v)| index.get(k)?.by_value.get(v))
+                .map(Vec::as_slice),
+        );
+    }
+}
+
+/// Returns the positions set in `mask`, ascending.
+fn set_positions(mask: &[bool]) -> Vec<usize> {
+    mask.iter()
+        .enumerate()
+        .filter_map(|(i, &m)| m.then_some(i))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+    use crate::enums::{EdgeType, NodeType};
+    use crate::graph::{build_graph, selector_match};
+    use crate::test_helpers::{minimal_file, org_node, ownership_edge, supplies_edge};
+    use crate::types::Identifier;
+
+    fn label(key: &str, value: Option<&str>) -> Label {
+        Label {
+            key: key.to_owned(),
+            value: value.map(str::to_owned),
+            extra: Default::default(),
+        }
+    }
+
+    fn identifier(scheme: &str, value: &str) -> Identifier {
+        Identifier {
+            scheme: scheme.to_owned(),
+            value: value.to_owned(),
+            authority: None,
+            valid_from: None,
+            valid_to: None,
+            sensitivity: None,
+            verification_status: None,
+            verification_date: None,
+            extra: Default::default(),
+        }
+    }
+
+    /// a → b → c (supplies), a → c (ownership), with names, labels, identifiers.
+    fn sample() -> OmtsFile {
+        let mut a = org_node("a");
+        a.name = Some("Acme Holdings".to_owned());
+        a.labels = Some(vec![label("risk", Some("high")), label("tier1", None)]);
+        a.identifiers = Some(vec![identifier("lei", "529900T8BM49AURSDO55")]);
+        let mut b = org_node("b");
+        b.name = Some("Bolt Works".to_owned());
+        b.labels = Some(vec![label("risk", Some("low"))]);
+        let mut c = org_node("c");
+        c.name = Some("ACME Logistics".to_owned());
+        c.identifiers = Some(vec![identifier("duns", "081466849")]);
+        let mut e_ac = ownership_edge("e-ac", "a", "c");
+        e_ac.properties.labels = Some(vec![label("risk", Some("high"))]);
+        minimal_file(
+            vec![a, b, c],
+            vec![
+                supplies_edge("e-ab", "a", "b"),
+                supplies_edge("e-bc", "b", "c"),
+                e_ac,
+            ],
+        )
+    }
+
+    fn build(file: &OmtsFile) -> GraphIndex {
+        GraphIndex::build(file, content_hash(b"sample")).expect("builds")
+    }
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let index = build(&sample());
+        let bytes = index.encode().expect("encodes");
+        assert!(bytes.starts_with(b"OMTSIDX"));
+        let back = GraphIndex::decode(&bytes).expect("decodes");
+        assert_eq!(back, index);
+        assert!(back.is_fresh(&content_hash(b"sample")));
+        assert!(!back.is_fresh(&content_hash(b"sample2")));
+    }
+
+    #[test]
+    fn test_decode_rejects_foreign_and_truncated_bytes() {
+        assert_eq!(
+            GraphIndex::decode(b"{\"omts_version\":1}"),
+            Err(IndexError::NotAnIndex)
+        );
+        let mut bytes = build(&sample()).encode().expect("encodes");
+        bytes[7] = 99;
+        assert_eq!(
+            GraphIndex::decode(&bytes),
+            Err(IndexError::UnsupportedVersion(99))
+        );
+        let mut bytes = build(&sample()).encode().expect("encodes");
+        bytes.truncate(bytes.len() / 2);
+        assert!(matches!(
+            GraphIndex::decode(&bytes),
+            Err(IndexError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_out_of_range_positions() {
+        let mut index = build(&sample());
+        index.edges[0].target = 42;
+        let bytes = index.encode().expect("encodes");
+        assert!(matches!(
+            GraphIndex::decode(&bytes),
+            Err(IndexError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_graph_matches_build_graph() {
+        let file = sample();
+        let built = build_graph(&file).expect("builds");
+        let expanded = build(&file).to_graph();
+        assert_eq!(expanded.node_count(), built.node_count());
+        assert_eq!(expanded.edge_count(), built.edge_count());
+        for node in &file.nodes {
+            let a = *built.node_index(&node.id).expect("present");
+            let b = *expanded.node_index(&node.id).expect("present");
+            assert_eq!(a, b);
+            assert_eq!(
+                built.node_weight(a).map(|w| w.data_index),
+                expanded.node_weight(b).map(|w| w.data_index)
+            );
+        }
+        for e in built.graph().edge_indices() {
+            let (bw, ew) = (
+                built.edge_weight(e).expect("edge"),
+                expanded.edge_weight(e).expect("edge"),
+            );
+            assert_eq!(bw.local_id, ew.local_id);
+            assert_eq!(bw.data_index, ew.data_index);
+            assert_eq!(
+                built.graph().edge_endpoints(e),
+                expanded.graph().edge_endpoints(e)
+            );
+        }
+        let supplies = EdgeTypeTag::Known(EdgeType::Supplies);
+        assert_eq!(
+            built.edges_of_type(&supplies),
+            expanded.edges_of_type(&supplies)
+        );
+    }
+
+    #[test]
+    fn test_adjacency_lists_per_edge_type() {
+        let index = build(&sample());
+        let supplies = EdgeTypeTag::Known(EdgeType::Supplies);
+        let ownership = EdgeTypeTag::Known(EdgeType::Ownership);
+        assert_eq!(index.out_edges(0, &supplies), &[0]);
+        assert_eq!(index.out_edges(1, &supplies), &[1]);
+        assert_eq!(index.out_edges(0, &ownership), &[2]);
+        assert!(index.out_edges(2, &supplies).is_empty());
+        assert!(
+            index
+                .out_edges(0, &EdgeTypeTag::Known(EdgeType::Tolls))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_canonical_identifier_index() {
+        let index = build(&sample());
+        assert_eq!(
+            index.nodes_with_canonical_id("lei:529900T8BM49AURSDO55"),
+            &[0]
+        );
+        assert!(index.nodes_with_canonical_id("lei:unknown").is_empty());
+    }
+
+    #[test]
+    fn test_build_rejects_dangling_edge() {
+        let file = minimal_file(vec![org_node("a")], vec![supplies_edge("e", "a", "zz")]);
+        assert!(matches!(
+            GraphIndex::build(&file, String::new()),
+            Err(GraphBuildError::DanglingEdgeRef { .. })
+        ));
+    }
+
+    #[test]
+    fn test_select_agrees_with_selector_match() {
+        use crate::graph::selectors::Selector;
+
+        let file = sample();
+        let index = build(&file);
+        let cases: Vec<Vec<Selector>> = vec![
+            vec![Selector::Name("acme".to_owned())],
+            vec![
+                Selector::Name("acme".to_owned()),
+                Selector::LabelKey("risk".to_owned()),
+            ],
+            vec![Selector::LabelKeyValue(
+                "risk".to_owned(),
+                "high".to_owned(),
+            )],
+            vec![Selector::LabelKey("tier1".to_owned())],
+            vec![Selector::IdentifierScheme("duns".to_owned())],
+            vec![Selector::IdentifierSchemeValue(
+                "lei".to_owned(),
+                "529900T8BM49AURSDO55".to_owned(),
+            )],
+            vec![Selector::EdgeType(EdgeTypeTag::Known(EdgeType::Supplies))],
+            vec![
+                Selector::NodeType(NodeTypeTag::Known(NodeType::Organization)),
+                Selector::NodeType(NodeTypeTag::Known(NodeType::Facility)),
+            ],
+            vec![Selector::LabelKey("missing".to_owned())],
+        ];
+        for selectors in cases {
+            let set = SelectorSet::from_selectors(selectors.clone());
+            let expected = selector_match(&file, &set);
+            let got = index.select(&set).expect("indexable");
+            assert_eq!(got.node_indices, expected.node_indices, "{selectors:?}");
+            assert_eq!(got.edge_indices, expected.edge_indices, "{selectors:?}");
+        }
+    }
+
+    #[test]
+    fn test_select_declines_unindexed_selectors() {
+        let set =
+            SelectorSet::from_selectors(vec![crate::graph::selectors::Selector::Jurisdiction(
+                crate::newtypes::CountryCode::try_from("DE").expect("valid"),
+            )]);
+        assert!(build(&sample()).select(&set).is_none());
+    }
+}
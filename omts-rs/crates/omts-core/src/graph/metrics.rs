@@ -0,0 +1,486 @@
+/// Structural network metrics over an [`OmtsGraph`].
+///
+/// [`graph_metrics`] computes a fixed battery of whole-graph statistics used
+/// to track supplier-mapping coverage over time:
+///
+/// - degree distributions per edge type,
+/// - weakly and strongly connected components and their largest sizes,
+/// - the eccentricity ("diameter from") of the reporting entity,
+/// - the top-N organizations by `PageRank`,
+/// - isolated nodes (no incident edges),
+/// - average identifier count per organization.
+///
+/// All metrics are computed in O((N + E) · k) time, where k is the fixed
+/// `PageRank` iteration cap, so the function is safe to run on large files.
+///
+/// # Direction Conventions
+///
+/// Components, isolated nodes, and the reporting-entity eccentricity treat
+/// the graph as undirected: a supplier two hops upstream is "two hops away"
+/// regardless of whether the edges point towards or away from the reporting
+/// entity. Strongly connected components and `PageRank` follow edge direction.
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use petgraph::algo::tarjan_scc;
+use petgraph::stable_graph::NodeIndex;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, NodeIndexable};
+
+use crate::enums::{NodeType, NodeTypeTag};
+use crate::file::OmtsFile;
+use crate::graph::OmtsGraph;
+use crate::union_find::UnionFind;
+
+/// Damping factor used by the `PageRank` computation.
+const PAGERANK_DAMPING: f64 = 0.85;
+
+/// Maximum number of `PageRank` power iterations.
+const PAGERANK_MAX_ITERATIONS: usize = 100;
+
+/// L1 convergence threshold for `PageRank` power iteration.
+const PAGERANK_TOLERANCE: f64 = 1e-9;
+
+/// Default number of organizations reported by [`graph_metrics`] rankings.
+pub const DEFAULT_TOP_N: usize = 10;
+
+/// In- and out-degree histogram for one edge type.
+///
+/// Only nodes incident to at least one edge of the type are counted, so a
+/// sparse edge type is not swamped by a zero-degree bucket covering the rest
+/// of the graph.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DegreeDistribution {
+    /// Number of nodes incident to at least one edge of this type.
+    pub node_count: usize,
+    /// Map from out-degree to the number of nodes with that out-degree.
+    pub out_degree: BTreeMap<usize, usize>,
+    /// Map from in-degree to the number of nodes with that in-degree.
+    pub in_degree: BTreeMap<usize, usize>,
+    /// Largest out-degree observed.
+    pub max_out_degree: usize,
+    /// Largest in-degree observed.
+    pub max_in_degree: usize,
+    /// Mean total degree (in + out) over the counted nodes.
+    pub mean_degree: f64,
+}
+
+/// A node paired with a centrality score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CentralityScore {
+    /// Graph-local node ID.
+    pub node_id: String,
+    /// Display name of the node, if present.
+    pub name: Option<String>,
+    /// Centrality score; `PageRank` scores over all nodes sum to 1.
+    pub score: f64,
+}
+
+/// Result of [`graph_metrics`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GraphMetrics {
+    /// Degree distribution keyed by edge type string (e.g. `"supplies"`).
+    pub degree_distributions: BTreeMap<String, DegreeDistribution>,
+    /// Number of weakly connected components.
+    pub weak_component_count: usize,
+    /// Node count of the largest weakly connected component.
+    pub largest_weak_component: usize,
+    /// Number of strongly connected components.
+    pub strong_component_count: usize,
+    /// Node count of the largest strongly connected component.
+    pub largest_strong_component: usize,
+    /// Greatest undirected hop distance from the reporting entity to any node
+    /// in its component.
+    ///
+    /// `None` when the file declares no `reporting_entity` or the referenced
+    /// node is not in the graph.
+    pub diameter_from_reporting_entity: Option<usize>,
+    /// Top organizations by `PageRank`, highest first.
+    pub top_pagerank: Vec<CentralityScore>,
+    /// IDs of nodes with no incident edges, sorted.
+    pub isolated_nodes: Vec<String>,
+    /// Mean number of identifiers per organization node.
+    ///
+    /// `None` when the file contains no organization nodes.
+    pub avg_identifiers_per_organization: Option<f64>,
+}
+
+/// Computes structural metrics for `graph`.
+///
+/// `file` must be the [`OmtsFile`] that `graph` was built from; it supplies
+/// node names, identifiers, and the `reporting_entity` header. `top_n` caps
+/// the length of [`GraphMetrics::top_pagerank`].
+pub fn graph_metrics(graph: &OmtsGraph, file: &OmtsFile, top_n: usize) -> GraphMetrics {
+    let (weak_component_count, largest_weak_component) = weak_components(graph);
+    let (strong_component_count, largest_strong_component) = strong_components(graph);
+
+    let diameter_from_reporting_entity = file
+        .reporting_entity
+        .as_ref()
+        .and_then(|id| graph.node_index(id))
+        .map(|&start| undirected_eccentricity(graph, start));
+
+    GraphMetrics {
+        degree_distributions: degree_distributions(graph),
+        weak_component_count,
+        largest_weak_component,
+        strong_component_count,
+        largest_strong_component,
+        diameter_from_reporting_entity,
+        top_pagerank: top_organizations_by_pagerank(graph, file, top_n),
+        isolated_nodes: isolated_nodes(graph),
+        avg_identifiers_per_organization: avg_identifiers_per_organization(file),
+    }
+}
+
+/// Builds a [`DegreeDistribution`] for every edge type present in the graph.
+fn degree_distributions(graph: &OmtsGraph) -> BTreeMap<String, DegreeDistribution> {
+    // edge type → node → (out, in)
+    let mut per_type: BTreeMap<String, HashMap<NodeIndex, (usize, usize)>> = BTreeMap::new();
+    for edge_ref in graph.graph().edge_references() {
+        let degrees = per_type
+            .entry(edge_ref.weight().edge_type.as_str().to_owned())
+            .or_default();
+        degrees.entry(edge_ref.source()).or_default().0 += 1;
+        degrees.entry(edge_ref.target()).or_default().1 += 1;
+    }
+
+    per_type
+        .into_iter()
+        .map(|(edge_type, degrees)| {
+            let mut dist = DegreeDistribution {
+                node_count: degrees.len(),
+                ..DegreeDistribution::default()
+            };
+            let mut total = 0usize;
+            for &(out_deg, in_deg) in degrees.values() {
+                *dist.out_degree.entry(out_deg).or_insert(0) += 1;
+                *dist.in_degree.entry(in_deg).or_insert(0) += 1;
+                dist.max_out_degree = dist.max_out_degree.max(out_deg);
+                dist.max_in_degree = dist.max_in_degree.max(in_deg);
+                total += out_deg + in_deg;
+            }
+            if dist.node_count > 0 {
+                dist.mean_degree = total as f64 / dist.node_count as f64;
+            }
+            (edge_type, dist)
+        })
+        .collect()
+}
+
+/// Returns `(component count, largest component size)` for the undirected view.
+fn weak_components(graph: &OmtsGraph) -> (usize, usize) {
+    let g = graph.graph();
+    let mut uf = UnionFind::new(g.node_bound());
+    for edge_ref in g.edge_references() {
+        uf.union(edge_ref.source().index(), edge_ref.target().index());
+    }
+
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for idx in g.node_indices() {
+        *sizes.entry(uf.find(idx.index())).or_insert(0) += 1;
+    }
+    (sizes.len(), sizes.values().copied().max().unwrap_or(0))
+}
+
+/// Returns `(component count, largest component size)` for directed SCCs.
+fn strong_components(graph: &OmtsGraph) -> (usize, usize) {
+    let sccs = tarjan_scc(graph.graph());
+    let largest = sccs.iter().map(Vec::len).max().unwrap_or(0);
+    (sccs.len(), largest)
+}
+
+/// Greatest BFS hop distance from `start`, ignoring edge direction.
+fn undirected_eccentricity(graph: &OmtsGraph, start: NodeIndex) -> usize {
+    let g = graph.graph();
+    let mut dist: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+    let mut max_dist = 0usize;
+
+    dist.insert(start, 0);
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        let d = dist.get(&current).copied().unwrap_or(0);
+        max_dist = max_dist.max(d);
+        for neighbour in g.neighbors_undirected(current) {
+            if let Entry::Vacant(slot) = dist.entry(neighbour) {
+                slot.insert(d + 1);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    max_dist
+}
+
+/// Runs `PageRank` over the directed graph and returns the top `top_n`
+/// organization nodes.
+///
+/// Dangling nodes (no outgoing edges) redistribute their rank uniformly, so
+/// scores over all nodes sum to 1. Ties are broken by node ID for
+/// deterministic output.
+fn top_organizations_by_pagerank(
+    graph: &OmtsGraph,
+    file: &OmtsFile,
+    top_n: usize,
+) -> Vec<CentralityScore> {
+    let g = graph.graph();
+    let n = g.node_count();
+    if n == 0 || top_n == 0 {
+        return Vec::new();
+    }
+
+    let bound = g.node_bound();
+    let nodes: Vec<NodeIndex> = g.node_indices().collect();
+    let out_degree: Vec<usize> = (0..bound)
+        .map(|i| {
+            let idx = NodeIndex::new(i);
+            if g.contains_node(idx) {
+                g.edges(idx).count()
+            } else {
+                0
+            }
+        })
+        .collect();
+
+    let n_f = n as f64;
+    let mut rank = vec![0.0f64; bound];
+    for &idx in &nodes {
+        rank[idx.index()] = 1.0 / n_f;
+    }
+    let mut next = vec![0.0f64; bound];
+
+    for _ in 0..PAGERANK_MAX_ITERATIONS {
+        let dangling: f64 = nodes
+            .iter()
+            .filter(|idx| out_degree[idx.index()] == 0)
+            .map(|idx| rank[idx.index()])
+            .sum();
+        let base = (1.0 - PAGERANK_DAMPING) / n_f + PAGERANK_DAMPING * dangling / n_f;
+
+        for &idx in &nodes {
+            next[idx.index()] = base;
+        }
+        for edge_ref in g.edge_references() {
+            let src = edge_ref.source().index();
+            next[edge_ref.target().index()] +=
+                PAGERANK_DAMPING * rank[src] / out_degree[src] as f64;
+        }
+
+        let delta: f64 = nodes
+            .iter()
+            .map(|idx| (next[idx.index()] - rank[idx.index()]).abs())
+            .sum();
+        std::mem::swap(&mut rank, &mut next);
+        if delta < PAGERANK_TOLERANCE {
+            break;
+        }
+    }
+
+    let mut scores: Vec<CentralityScore> = nodes
+        .iter()
+        .filter_map(|&idx| {
+            let weight = graph.node_weight(idx)?;
+            if weight.node_type != NodeTypeTag::Known(NodeType::Organization) {
+                return None;
+            }
+            Some(CentralityScore {
+                node_id: weight.local_id.clone(),
+                name: file
+                    .nodes
+                    .get(weight.data_index)
+                    .and_then(|node| node.name.clone()),
+                score: rank[idx.index()],
+            })
+        })
+        .collect();
+
+    scores.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    scores.truncate(top_n);
+    scores
+}
+
+/// Returns the sorted IDs of nodes with neither incoming nor outgoing edges.
+fn isolated_nodes(graph: &OmtsGraph) -> Vec<String> {
+    let g = graph.graph();
+    let mut ids: Vec<String> = g
+        .node_indices()
+        .filter(|&idx| g.neighbors_undirected(idx).next().is_none())
+        .filter_map(|idx| graph.node_weight(idx).map(|w| w.local_id.clone()))
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Mean identifier count over organization nodes.
+fn avg_identifiers_per_organization(file: &OmtsFile) -> Option<f64> {
+    let org_type = NodeTypeTag::Known(NodeType::Organization);
+    let (orgs, ids) = file
+        .nodes
+        .iter()
+        .filter(|node| node.node_type == org_type)
+        .fold((0usize, 0usize), |(orgs, ids), node| {
+            (
+                orgs + 1,
+                ids + node.identifiers.as_ref().map_or(0, Vec::len),
+            )
+        });
+    if orgs == 0 {
+        None
+    } else {
+        Some(ids as f64 / orgs as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::graph::build_graph;
+    use crate::newtypes::NodeId;
+    use crate::test_helpers::{
+        facility_node, minimal_file, org_node, ownership_edge, supplies_edge,
+    };
+    use crate::types::Identifier;
+
+    fn identifier(value: &str) -> Identifier {
+        Identifier {
+            scheme: "duns".to_owned(),
+            value: value.to_owned(),
+            authority: None,
+            valid_from: None,
+            valid_to: None,
+            sensitivity: None,
+            verification_status: None,
+            verification_date: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// Two suppliers feed a buyer; the buyer owns a subsidiary; one isolated
+    /// facility; one supply cycle between `s1` and `s2`.
+    fn sample_file() -> OmtsFile {
+        let mut buyer = org_node("buyer");
+        buyer.identifiers = Some(vec![identifier("1"), identifier("2")]);
+        let nodes = vec![
+            buyer,
+            org_node("s1"),
+            org_node("s2"),
+            org_node("sub"),
+            facility_node("lonely"),
+        ];
+        let edges = vec![
+            supplies_edge("e1", "s1", "buyer"),
+            supplies_edge("e2", "s2", "buyer"),
+            supplies_edge("e3", "s1", "s2"),
+            supplies_edge("e4", "s2", "s1"),
+            ownership_edge("e5", "buyer", "sub"),
+        ];
+        let mut file = minimal_file(nodes, edges);
+        file.reporting_entity = Some(NodeId::try_from("sub").expect("valid id"));
+        file
+    }
+
+    fn metrics_for(file: &OmtsFile) -> GraphMetrics {
+        let graph = build_graph(file).expect("builds");
+        graph_metrics(&graph, file, DEFAULT_TOP_N)
+    }
+
+    /// An empty graph yields zeroed metrics without panicking.
+    #[test]
+    fn test_empty_graph() {
+        let m = metrics_for(&minimal_file(vec![], vec![]));
+        assert_eq!(m.weak_component_count, 0);
+        assert_eq!(m.largest_weak_component, 0);
+        assert!(m.top_pagerank.is_empty());
+        assert!(m.avg_identifiers_per_organization.is_none());
+        assert!(m.diameter_from_reporting_entity.is_none());
+    }
+
+    /// Degree histograms are built per edge type over incident nodes only.
+    #[test]
+    fn test_degree_distribution_per_edge_type() {
+        let m = metrics_for(&sample_file());
+        let supplies = m
+            .degree_distributions
+            .get("supplies")
+            .expect("supplies present");
+        assert_eq!(supplies.node_count, 3);
+        assert_eq!(supplies.max_in_degree, 2, "buyer receives two");
+        assert_eq!(supplies.max_out_degree, 2, "s1 and s2 each supply two");
+        assert_eq!(supplies.in_degree.get(&2), Some(&1));
+        assert!((supplies.mean_degree - 8.0 / 3.0).abs() < 1e-9);
+
+        let ownership = m
+            .degree_distributions
+            .get("ownership")
+            .expect("ownership present");
+        assert_eq!(ownership.node_count, 2);
+    }
+
+    /// Weak components count the isolated facility separately; the supply
+    /// cycle forms the only non-trivial SCC.
+    #[test]
+    fn test_components() {
+        let m = metrics_for(&sample_file());
+        assert_eq!(m.weak_component_count, 2);
+        assert_eq!(m.largest_weak_component, 4);
+        // {s1, s2}, {buyer}, {sub}, {lonely}
+        assert_eq!(m.strong_component_count, 4);
+        assert_eq!(m.largest_strong_component, 2);
+    }
+
+    /// Eccentricity from the reporting entity ignores edge direction.
+    #[test]
+    fn test_diameter_from_reporting_entity() {
+        let m = metrics_for(&sample_file());
+        // sub → buyer → s1/s2
+        assert_eq!(m.diameter_from_reporting_entity, Some(2));
+    }
+
+    /// Isolated nodes and identifier averages.
+    #[test]
+    fn test_isolated_and_identifier_average() {
+        let m = metrics_for(&sample_file());
+        assert_eq!(m.isolated_nodes, vec!["lonely".to_owned()]);
+        let avg = m.avg_identifiers_per_organization.expect("orgs present");
+        assert!((avg - 0.5).abs() < 1e-9, "2 identifiers over 4 orgs");
+    }
+
+    /// `PageRank` ranks the node receiving the most flow first and only
+    /// reports organizations.
+    #[test]
+    fn test_pagerank_top_n() {
+        let file = sample_file();
+        let graph = build_graph(&file).expect("builds");
+        let m = graph_metrics(&graph, &file, 2);
+        assert_eq!(m.top_pagerank.len(), 2);
+        assert!(m.top_pagerank[0].score >= m.top_pagerank[1].score);
+        assert!(
+            m.top_pagerank.iter().all(|s| s.node_id != "lonely"),
+            "facilities are excluded"
+        );
+
+        let all = graph_metrics(&graph, &file, 100);
+        assert_eq!(all.top_pagerank.len(), 4);
+    }
+
+    /// `PageRank` scores over all nodes sum to one.
+    #[test]
+    fn test_pagerank_is_normalised() {
+        let nodes = vec![org_node("a"), org_node("b"), org_node("c")];
+        let edges = vec![supplies_edge("e1", "a", "b"), supplies_edge("e2", "b", "c")];
+        let file = minimal_file(nodes, edges);
+        let m = metrics_for(&file);
+        let total: f64 = m.top_pagerank.iter().map(|s| s.score).sum();
+        assert!((total - 1.0).abs() < 1e-6, "total = {total}");
+        assert_eq!(m.top_pagerank[0].node_id, "c");
+    }
+}
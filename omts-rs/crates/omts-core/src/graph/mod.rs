@@ -27,25 +27,69 @@
 ///
 /// See the [`selectors`] submodule for [`selectors::Selector`] and
 /// [`selectors::SelectorSet`], which implement property-based filtering of
-/// nodes and edges.
+/// nodes and edges, [`selectors::SelectorExpr`] for boolean `--where`
+/// expressions, and [`selectors::PropertyPredicate`] for typed comparisons on
+/// any property.
+///
+/// # Weighted Paths
+///
+/// See the [`paths`] submodule for [`k_shortest_paths`], which finds the
+/// cheapest loopless paths under a configurable edge cost and an optional
+/// [`EdgePattern`] constraint on the edge-type sequence, reporting the edges
+/// of each path.
+///
+/// # Persistent Index
+///
+/// See the [`index`] submodule for [`GraphIndex`], a compact binary sidecar
+/// holding the node table, per-type adjacency lists, and identifier, name,
+/// and label indexes, so repeated queries can skip graph construction.
 ///
 /// # Cycle Detection
 ///
 /// See the [`cycles`] submodule for Kahn's algorithm cycle detection, used by
 /// the validation engine to enforce L3-MRG-02 (legal parentage must be a forest).
+///
+/// # Segmentation
+///
+/// See the [`communities`] submodule for [`louvain`], which clusters
+/// the supply subgraph into segments for category strategy work.
+///
+/// # Network Metrics
+///
+/// See the [`metrics`] submodule for [`graph_metrics`], which summarises
+/// degree distributions, connectivity, and centrality for `omts inspect`.
+pub mod communities;
 pub mod cycles;
 pub mod extraction;
+pub mod index;
+pub mod metrics;
+pub mod paths;
 pub mod queries;
 pub mod selectors;
 
+pub use communities::{
+    EdgeWeighting, Segment, Segmentation, apply_segment_labels, louvain, supply_edge_types,
+};
 pub use cycles::detect_cycles;
 pub use extraction::{
-    SelectorMatchResult, ego_graph, induced_subgraph, selector_match, selector_subgraph,
+    SelectorMatchResult, ego_graph, induced_subgraph, path_subgraph, reachable_subgraph,
+    selector_match, selector_subgraph,
+};
+pub use index::{
+    GraphIndex, INDEX_FORMAT_VERSION, IndexError, IndexedEdge, IndexedNode, content_hash,
+};
+pub use metrics::{CentralityScore, DegreeDistribution, GraphMetrics, graph_metrics};
+pub use paths::{
+    EdgePattern, EdgePatternError, PathCost, PathOptions, WeightedPath, k_shortest_paths,
 };
 pub use queries::{
-    DEFAULT_MAX_DEPTH, Direction, QueryError, all_paths, reachable_from, shortest_path,
+    DEFAULT_MAX_DEPTH, Direction, QueryError, all_paths, reachable_from, reachable_within,
+    shortest_path,
+};
+pub use selectors::{
+    CompareOp, ExprScope, FiniteNumber, PropertyPredicate, PropertyTest, PropertyValue, Selector,
+    SelectorExpr, SelectorExprError, SelectorSet,
 };
-pub use selectors::{Selector, SelectorSet};
 
 use std::collections::HashMap;
 
@@ -0,0 +1,918 @@
+/// Weighted and pattern-constrained path finding.
+///
+/// [`k_shortest_paths`] returns up to `k` loopless paths between two nodes in
+/// ascending order of total cost. Unlike [`all_paths`][super::queries::all_paths],
+/// each [`WeightedPath`] records the edges it traverses as well as the nodes,
+/// so parallel relationships between the same pair of nodes are distinct
+/// paths and every hop can be traced back to the edge that evidences it.
+///
+/// # Cost
+///
+/// [`PathCost`] selects the base cost of each edge:
+///
+/// - [`PathCost::Hops`] — every edge costs 1.
+/// - [`PathCost::InverseShareOfBuyerDemand`] and [`PathCost::InverseAnnualValue`]
+///   — an edge costs `mean / value`, so an edge carrying the mean value costs
+///   1 and critical, high-value relationships form the cheapest paths. Edges
+///   lacking the property (or carrying a non-positive value) cost 1.
+///
+/// [`PathOptions::edge_type_costs`] then multiplies the base cost per edge
+/// type; with [`PathCost::Hops`] this gives a per-type hop count. Multipliers
+/// that are negative or not finite are ignored.
+///
+/// # Edge-Type Patterns
+///
+/// An [`EdgePattern`] constrains the sequence of edge types along a path,
+/// much like a regular expression over edge types. Steps are separated by
+/// whitespace; each step is an edge type, an `|`-separated set of edge types,
+/// or `any`, optionally followed by `?` (zero or one), `*` (zero or more), or
+/// `+` (one or more):
+///
+/// ```text
+/// supplies+ operates
+/// ownership* legal_parentage?
+/// supplies|subcontracts any*
+/// ```
+///
+/// The whole edge sequence must match the pattern.
+///
+/// # Algorithm
+///
+/// Yen's k-shortest-paths algorithm over Dijkstra searches. Each search runs
+/// over the product of graph nodes, pattern states (the pattern is compiled
+/// to a small deterministic automaton), and path depth, so the depth limit and
+/// the pattern are enforced exactly. A search never extends a path through a
+/// node it has already visited.
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+
+use petgraph::stable_graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+
+use crate::enums::EdgeTypeTag;
+use crate::file::OmtsFile;
+use crate::graph::OmtsGraph;
+use crate::graph::queries::{DEFAULT_MAX_DEPTH, Direction, QueryError};
+
+/// Maximum number of steps in an [`EdgePattern`] after expanding `+`.
+///
+/// Pattern states are sets of step positions held in a `u64` bitmask.
+const MAX_PATTERN_STEPS: usize = 63;
+
+/// The base cost of traversing an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathCost {
+    /// Every edge costs 1.
+    #[default]
+    Hops,
+    /// Cost by the inverse of `share_of_buyer_demand`.
+    InverseShareOfBuyerDemand,
+    /// Cost by the inverse of `annual_value`.
+    InverseAnnualValue,
+}
+
+/// Options for [`k_shortest_paths`].
+#[derive(Debug, Clone)]
+pub struct PathOptions {
+    /// Base edge cost.
+    pub cost: PathCost,
+    /// Per-edge-type cost multipliers; unlisted types use 1.
+    pub edge_type_costs: HashMap<EdgeTypeTag, f64>,
+    /// Optional constraint on the sequence of edge types.
+    pub pattern: Option<EdgePattern>,
+    /// Which edges to follow (see [`Direction`]).
+    pub direction: Direction,
+    /// Optional set of allowed edge types; `None` traverses all.
+    pub edge_filter: Option<HashSet<EdgeTypeTag>>,
+    /// Maximum number of edges in any returned path.
+    pub max_depth: usize,
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        Self {
+            cost: PathCost::Hops,
+            edge_type_costs: HashMap::new(),
+            pattern: None,
+            direction: Direction::Forward,
+            edge_filter: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+/// A path returned by [`k_shortest_paths`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedPath {
+    /// Nodes from source to destination inclusive.
+    pub nodes: Vec<NodeIndex>,
+    /// Edges traversed, one fewer than `nodes`; `edges[i]` connects
+    /// `nodes[i]` and `nodes[i + 1]` (in either orientation when traversing
+    /// with [`Direction::Backward`] or [`Direction::Both`]).
+    pub edges: Vec<EdgeIndex>,
+    /// Sum of the edge costs.
+    pub cost: f64,
+}
+
+/// A constraint on the sequence of edge types along a path.
+///
+/// See the module documentation for the syntax. Construct with
+/// [`EdgePattern::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgePattern {
+    steps: Vec<PatternStep>,
+}
+
+/// One step of an [`EdgePattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PatternStep {
+    /// Accepted edge types; `None` accepts any type.
+    types: Option<Vec<EdgeTypeTag>>,
+    repeat: Repeat,
+}
+
+/// How many edges a [`PatternStep`] consumes. `+` is expanded to a `Once`
+/// step followed by a `ZeroOrMore` step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Repeat {
+    Once,
+    Optional,
+    ZeroOrMore,
+}
+
+/// Error returned by [`EdgePattern::parse`] for malformed input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgePatternError {
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for EdgePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for EdgePatternError {}
+
+impl EdgePattern {
+    /// Parses an edge-type pattern such as `supplies+ operates`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EdgePatternError`] if the pattern is empty, a step has no
+    /// edge type, or the pattern has more than 63 steps once `+` is expanded.
+    pub fn parse(input: &str) -> Result<Self, EdgePatternError> {
+        let mut steps = Vec::new();
+        for token in input.split_whitespace() {
+            let (body, repeats): (&str, &[Repeat]) = if let Some(b) = token.strip_suffix('+') {
+                (b, &[Repeat::Once, Repeat::ZeroOrMore])
+            } else if let Some(b) = token.strip_suffix('*') {
+                (b, &[Repeat::ZeroOrMore])
+            } else if let Some(b) = token.strip_suffix('?') {
+                (b, &[Repeat::Optional])
+            } else {
+                (token, &[Repeat::Once])
+            };
+
+            let types = if body.eq_ignore_ascii_case("any") {
+                None
+            } else {
+                let mut types = Vec::new();
+                for name in body.split('|') {
+                    if name.is_empty() {
+                        return Err(EdgePatternError {
+                            message: format!("missing edge type in step {token:?}"),
+                        });
+                    }
+                    let tag: EdgeTypeTag =
+                        serde_json::from_value(serde_json::Value::String(name.to_owned()))
+                            .map_err(|e| EdgePatternError {
+                                message: format!("edge type {name:?}: {e}"),
+                            })?;
+                    types.push(tag);
+                }
+                Some(types)
+            };
+
+            for &repeat in repeats {
+                steps.push(PatternStep {
+                    types: types.clone(),
+                    repeat,
+                });
+            }
+        }
+
+        if steps.is_empty() {
+            return Err(EdgePatternError {
+                message: "empty edge pattern".to_owned(),
+            });
+        }
+        if steps.len() > MAX_PATTERN_STEPS {
+            return Err(EdgePatternError {
+                message: format!("edge pattern has more than {MAX_PATTERN_STEPS} steps"),
+            });
+        }
+        Ok(Self { steps })
+    }
+
+    /// The pattern that accepts any sequence of edges.
+    fn any() -> Self {
+        Self {
+            steps: vec![PatternStep {
+                types: None,
+                repeat: Repeat::ZeroOrMore,
+            }],
+        }
+    }
+
+    /// Adds every position reachable from `set` without consuming an edge.
+    fn closure(&self, mut set: u64) -> u64 {
+        for (pos, step) in self.steps.iter().enumerate() {
+            if set & (1 << pos) != 0 && step.repeat != Repeat::Once {
+                set |= 1 << (pos + 1);
+            }
+        }
+        set
+    }
+
+    /// The state before any edge has been traversed.
+    fn start(&self) -> u64 {
+        self.closure(1)
+    }
+
+    /// The state after traversing an edge of type `edge_type` from `set`;
+    /// `0` means the pattern can no longer match.
+    fn advance(&self, set: u64, edge_type: &EdgeTypeTag) -> u64 {
+        let mut next = 0;
+        for (pos, step) in self.steps.iter().enumerate() {
+            if set & (1 << pos) == 0 {
+                continue;
+            }
+            let type_ok = step.types.as_ref().is_none_or(|t| t.contains(edge_type));
+            if type_ok {
+                next |= match step.repeat {
+                    Repeat::ZeroOrMore => 1 << pos,
+                    Repeat::Once | Repeat::Optional => 1 << (pos + 1),
+                };
+            }
+        }
+        self.closure(next)
+    }
+
+    /// Returns `true` if `set` contains the final (accepting) position.
+    fn accepts(&self, set: u64) -> bool {
+        set & (1 << self.steps.len()) != 0
+    }
+}
+
+impl std::str::FromStr for EdgePattern {
+    type Err = EdgePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Returns up to `k` loopless paths from `from` to `to`, cheapest first.
+///
+/// Ties in cost are broken by fewer edges, then by the edge IDs along the
+/// path, so the output is deterministic. With the default [`PathOptions`]
+/// (hop cost, no pattern) the first path is a shortest path by edge count.
+///
+/// When `from == to`, returns the single zero-length path if the pattern
+/// accepts an empty edge sequence, and no paths otherwise.
+///
+/// `file` supplies the edge properties that value-based costs read; it may
+/// be `None` under [`PathCost::Hops`], so a graph expanded from an index
+/// needs no parsed file. Without it, every edge costs as if it lacked the
+/// cost property.
+///
+/// # Errors
+///
+/// Returns [`QueryError::NodeNotFound`] if either `from` or `to` does not
+/// exist in the graph.
+pub fn k_shortest_paths(
+    graph: &OmtsGraph,
+    file: Option<&OmtsFile>,
+    from: &str,
+    to: &str,
+    k: usize,
+    options: &PathOptions,
+) -> Result<Vec<WeightedPath>, QueryError> {
+    let from_idx = *graph
+        .node_index(from)
+        .ok_or_else(|| QueryError::NodeNotFound(from.to_owned()))?;
+    let to_idx = *graph
+        .node_index(to)
+        .ok_or_else(|| QueryError::NodeNotFound(to.to_owned()))?;
+
+    let any = EdgePattern::any();
+    let pattern = options.pattern.as_ref().unwrap_or(&any);
+
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+    if from_idx == to_idx {
+        return Ok(if pattern.accepts(pattern.start()) {
+            vec![WeightedPath {
+                nodes: vec![from_idx],
+                edges: Vec::new(),
+                cost: 0.0,
+            }]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let costs = edge_costs(graph, file, options);
+    let search = Search {
+        graph,
+        costs: &costs,
+        pattern,
+        direction: options.direction,
+        edge_filter: options.edge_filter.as_ref(),
+        max_depth: options.max_depth,
+        target: to_idx,
+    };
+
+    let start = State {
+        node: from_idx,
+        pattern: pattern.start(),
+        depth: 0,
+    };
+    let Some(first) = search.run(start, &HashSet::new(), &HashSet::new()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut accepted: Vec<Route> = vec![first];
+    let mut candidates: Vec<Route> = Vec::new();
+
+    while accepted.len() < k {
+        let Some(last) = accepted.last().cloned() else {
+            break;
+        };
+        for i in 0..last.edges.len() {
+            let root = &last.edges[..i];
+            let banned_edges: HashSet<EdgeIndex> = accepted
+                .iter()
+                .filter(|r| r.edges.len() > i && r.edges[..i] == *root)
+                .map(|r| r.edges[i])
+                .collect();
+            let banned_nodes: HashSet<NodeIndex> = last.nodes[..i].iter().copied().collect();
+            let spur_start = State {
+                node: last.nodes[i],
+                pattern: last.states[i],
+                depth: i,
+            };
+            if let Some(spur) = search.run(spur_start, &banned_nodes, &banned_edges) {
+                let route = last.join(i, spur, &costs);
+                let seen = accepted
+                    .iter()
+                    .chain(&candidates)
+                    .any(|r| r.edges == route.edges);
+                if !seen {
+                    candidates.push(route);
+                }
+            }
+        }
+
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.rank(b, graph))
+            .map(|(i, _)| i);
+        match best {
+            Some(i) => accepted.push(candidates.swap_remove(i)),
+            None => break,
+        }
+    }
+
+    accepted.sort_by(|a, b| a.rank(b, graph));
+    Ok(accepted
+        .into_iter()
+        .map(|r| WeightedPath {
+            nodes: r.nodes,
+            edges: r.edges,
+            cost: r.cost,
+        })
+        .collect())
+}
+
+/// Computes the cost of every edge in the graph under `options`.
+fn edge_costs(
+    graph: &OmtsGraph,
+    file: Option<&OmtsFile>,
+    options: &PathOptions,
+) -> HashMap<EdgeIndex, f64> {
+    let value = |data_index: usize| -> Option<f64> {
+        let props = &file?.edges.get(data_index)?.properties;
+        match options.cost {
+            PathCost::Hops => None,
+            PathCost::InverseShareOfBuyerDemand => props.share_of_buyer_demand,
+            PathCost::InverseAnnualValue => props.annual_value,
+        }
+        .filter(|v| v.is_finite() && *v > 0.0)
+    };
+
+    let values: Vec<f64> = graph
+        .graph()
+        .edge_references()
+        .filter_map(|e| value(e.weight().data_index))
+        .collect();
+    let mean = if values.is_empty() {
+        1.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    };
+
+    graph
+        .graph()
+        .edge_references()
+        .map(|e| {
+            let weight = e.weight();
+            let base = value(weight.data_index).map_or(1.0, |v| mean / v);
+            let multiplier = options
+                .edge_type_costs
+                .get(&weight.edge_type)
+                .copied()
+                .filter(|m| m.is_finite() && *m >= 0.0)
+                .unwrap_or(1.0);
+            (e.id(), base * multiplier)
+        })
+        .collect()
+}
+
+/// A path under construction, with the pattern state at each node.
+#[derive(Debug, Clone)]
+struct Route {
+    nodes: Vec<NodeIndex>,
+    edges: Vec<EdgeIndex>,
+    /// `states[i]` is the pattern state on arriving at `nodes[i]`.
+    states: Vec<u64>,
+    cost: f64,
+}
+
+impl Route {
+    /// Joins the first `i` edges of `self` with `spur`, which starts at `self.nodes[i]`.
+    fn join(&self, i: usize, spur: Route, costs: &HashMap<EdgeIndex, f64>) -> Route {
+        let mut nodes = self.nodes[..i].to_vec();
+        nodes.extend(spur.nodes);
+        let mut edges = self.edges[..i].to_vec();
+        edges.extend(spur.edges);
+        let mut states = self.states[..i].to_vec();
+        states.extend(spur.states);
+        let cost = edges.iter().filter_map(|e| costs.get(e)).sum();
+        Route {
+            nodes,
+            edges,
+            states,
+            cost,
+        }
+    }
+
+    /// Orders routes by cost, then edge count, then edge IDs.
+    fn rank(&self, other: &Route, graph: &OmtsGraph) -> Ordering {
+        let ids = |r: &Route| -> Vec<String> {
+            r.edges
+                .iter()
+                .filter_map(|&e| graph.edge_weight(e).map(|w| w.local_id.clone()))
+                .collect()
+        };
+        self.cost
+            .total_cmp(&other.cost)
+            .then_with(|| self.edges.len().cmp(&other.edges.len()))
+            .then_with(|| ids(self).cmp(&ids(other)))
+    }
+}
+
+/// A node in the product of graph, pattern automaton, and depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    node: NodeIndex,
+    pattern: u64,
+    depth: usize,
+}
+
+/// Priority-queue entry; ordered so that [`BinaryHeap`] pops the cheapest.
+struct Entry {
+    cost: f64,
+    state: State,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.state.depth.cmp(&self.state.depth))
+            .then_with(|| other.state.node.index().cmp(&self.state.node.index()))
+            .then_with(|| other.state.pattern.cmp(&self.state.pattern))
+    }
+}
+
+/// Immutable context shared by every Dijkstra search in one query.
+struct Search<'a> {
+    graph: &'a OmtsGraph,
+    costs: &'a HashMap<EdgeIndex, f64>,
+    pattern: &'a EdgePattern,
+    direction: Direction,
+    edge_filter: Option<&'a HashSet<EdgeTypeTag>>,
+    max_depth: usize,
+    target: NodeIndex,
+}
+
+impl Search<'_> {
+    /// Finds the cheapest route from `start` to the target that avoids
+    /// `banned_nodes` and `banned_edges` and is accepted by the pattern.
+    fn run(
+        &self,
+        start: State,
+        banned_nodes: &HashSet<NodeIndex>,
+        banned_edges: &HashSet<EdgeIndex>,
+    ) -> Option<Route> {
+        let mut dist: HashMap<State, f64> = HashMap::new();
+        let mut prev: HashMap<State, (State, EdgeIndex)> = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        let mut steps: Vec<(EdgeIndex, NodeIndex)> = Vec::new();
+
+        dist.insert(start, 0.0);
+        heap.push(Entry {
+            cost: 0.0,
+            state: start,
+        });
+
+        while let Some(Entry { cost, state }) = heap.pop() {
+            if dist.get(&state).is_some_and(|&d| cost > d) {
+                continue;
+            }
+            if state.node == self.target && self.pattern.accepts(state.pattern) {
+                return Some(self.reconstruct(start, state, &prev));
+            }
+            if state.depth >= self.max_depth {
+                continue;
+            }
+
+            self.steps_into(state.node, &mut steps);
+            for &(edge, next_node) in &steps {
+                if banned_nodes.contains(&next_node)
+                    || banned_edges.contains(&edge)
+                    || on_route(&prev, start, state, next_node)
+                {
+                    continue;
+                }
+                let Some(weight) = self.graph.edge_weight(edge) else {
+                    continue;
+                };
+                let next_pattern = self.pattern.advance(state.pattern, &weight.edge_type);
+                if next_pattern == 0 {
+                    continue;
+                }
+                let next = State {
+                    node: next_node,
+                    pattern: next_pattern,
+                    depth: state.depth + 1,
+                };
+                let next_cost = cost + self.costs.get(&edge).copied().unwrap_or(1.0);
+                if dist.get(&next).is_none_or(|&d| next_cost < d) {
+                    dist.insert(next, next_cost);
+                    prev.insert(next, (state, edge));
+                    heap.push(Entry {
+                        cost: next_cost,
+                        state: next,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Fills `buf` with `(edge, neighbour)` pairs for one step from `node`,
+    /// respecting the direction and edge filter.
+    fn steps_into(&self, node: NodeIndex, buf: &mut Vec<(EdgeIndex, NodeIndex)>) {
+        buf.clear();
+        let g = self.graph.graph();
+        let passes = |t: &EdgeTypeTag| self.edge_filter.is_none_or(|f| f.contains(t));
+
+        if matches!(self.direction, Direction::Forward | Direction::Both) {
+            for e in g.edges(node) {
+                if passes(&e.weight().edge_type) {
+                    buf.push((e.id(), e.target()));
+                }
+            }
+        }
+        if matches!(self.direction, Direction::Backward | Direction::Both) {
+            for e in g.edges_directed(node, petgraph::Direction::Incoming) {
+                if passes(&e.weight().edge_type) {
+                    buf.push((e.id(), e.source()));
+                }
+            }
+        }
+    }
+
+    /// Walks the predecessor chain from `end` back to `start`.
+    fn reconstruct(
+        &self,
+        start: State,
+        end: State,
+        prev: &HashMap<State, (State, EdgeIndex)>,
+    ) -> Route {
+        let mut nodes = vec![end.node];
+        let mut states = vec![end.pattern];
+        let mut edges = Vec::new();
+        let mut current = end;
+        while current != start {
+            let Some(&(p, edge)) = prev.get(&current) else {
+                break;
+            };
+            edges.push(edge);
+            nodes.push(p.node);
+            states.push(p.pattern);
+            current = p;
+        }
+        nodes.reverse();
+        states.reverse();
+        edges.reverse();
+        let cost = edges.iter().filter_map(|e| self.costs.get(e)).sum();
+        Route {
+            nodes,
+            edges,
+            states,
+            cost,
+        }
+    }
+}
+
+/// Returns `true` if `node` is already on the route from `start` to `state`.
+fn on_route(
+    prev: &HashMap<State, (State, EdgeIndex)>,
+    start: State,
+    state: State,
+    node: NodeIndex,
+) -> bool {
+    let mut current = state;
+    loop {
+        if current.node == node {
+            return true;
+        }
+        if current == start {
+            return false;
+        }
+        match prev.get(&current) {
+            Some(&(p, _)) => current = p,
+            None => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+    use crate::enums::EdgeType;
+    use crate::graph::build_graph;
+    use crate::test_helpers::{minimal_file, org_node, supplies_edge, typed_edge};
+
+    /// Formats a path as its edge IDs.
+    fn edge_ids(graph: &OmtsGraph, path: &WeightedPath) -> Vec<String> {
+        path.edges
+            .iter()
+            .map(|&e| graph.edge_weight(e).expect("edge").local_id.clone())
+            .collect()
+    }
+
+    fn run(
+        file: &OmtsFile,
+        from: &str,
+        to: &str,
+        k: usize,
+        options: &PathOptions,
+    ) -> Vec<Vec<String>> {
+        let graph = build_graph(file).expect("builds");
+        k_shortest_paths(&graph, Some(file), from, to, k, options)
+            .expect("query")
+            .iter()
+            .map(|p| edge_ids(&graph, p))
+            .collect()
+    }
+
+    fn share(mut edge: crate::structures::Edge, value: f64) -> crate::structures::Edge {
+        edge.properties.share_of_buyer_demand = Some(value);
+        edge
+    }
+
+    /// a → b → d (2 hops), a → c → x → d (3 hops), plus a parallel a → b edge.
+    fn diamond() -> OmtsFile {
+        minimal_file(
+            ["a", "b", "c", "x", "d"]
+                .into_iter()
+                .map(org_node)
+                .collect(),
+            vec![
+                supplies_edge("e-ab", "a", "b"),
+                supplies_edge("e-ab2", "a", "b"),
+                supplies_edge("e-bd", "b", "d"),
+                supplies_edge("e-ac", "a", "c"),
+                supplies_edge("e-cx", "c", "x"),
+                supplies_edge("e-xd", "x", "d"),
+            ],
+        )
+    }
+
+    /// Paths are returned cheapest first with parallel edges as distinct paths.
+    #[test]
+    fn test_k_shortest_hops_order() {
+        let paths = run(&diamond(), "a", "d", 10, &PathOptions::default());
+        assert_eq!(
+            paths,
+            vec![
+                vec!["e-ab", "e-bd"],
+                vec!["e-ab2", "e-bd"],
+                vec!["e-ac", "e-cx", "e-xd"],
+            ]
+        );
+    }
+
+    /// `k` bounds the result count; `k = 0` returns nothing.
+    #[test]
+    fn test_k_limits_results() {
+        assert_eq!(
+            run(&diamond(), "a", "d", 1, &PathOptions::default()).len(),
+            1
+        );
+        assert!(run(&diamond(), "a", "d", 0, &PathOptions::default()).is_empty());
+    }
+
+    /// Inverse share-of-demand cost prefers the longer, more dependent route.
+    #[test]
+    fn test_inverse_share_prefers_critical_route() {
+        let file = minimal_file(
+            ["a", "b", "c", "d"].into_iter().map(org_node).collect(),
+            vec![
+                share(supplies_edge("e-ad", "a", "d"), 0.01),
+                share(supplies_edge("e-ab", "a", "b"), 0.9),
+                share(supplies_edge("e-bc", "b", "c"), 0.9),
+                share(supplies_edge("e-cd", "c", "d"), 0.9),
+            ],
+        );
+        let hops = run(&file, "a", "d", 1, &PathOptions::default());
+        assert_eq!(hops, vec![vec!["e-ad"]]);
+
+        let options = PathOptions {
+            cost: PathCost::InverseShareOfBuyerDemand,
+            ..PathOptions::default()
+        };
+        let graph = build_graph(&file).expect("builds");
+        let paths = k_shortest_paths(&graph, Some(&file), "a", "d", 2, &options).expect("query");
+        assert_eq!(edge_ids(&graph, &paths[0]), vec!["e-ab", "e-bc", "e-cd"]);
+        assert_eq!(edge_ids(&graph, &paths[1]), vec!["e-ad"]);
+        assert!(paths[0].cost < paths[1].cost);
+    }
+
+    /// Per-type multipliers change the cheapest route.
+    #[test]
+    fn test_edge_type_costs() {
+        let file = minimal_file(
+            ["a", "b", "d"].into_iter().map(org_node).collect(),
+            vec![
+                typed_edge("e-ad", EdgeType::Ownership, "a", "d"),
+                supplies_edge("e-ab", "a", "b"),
+                supplies_edge("e-bd", "b", "d"),
+            ],
+        );
+        let options = PathOptions {
+            edge_type_costs: HashMap::from([(EdgeTypeTag::Known(EdgeType::Ownership), 5.0)]),
+            ..PathOptions::default()
+        };
+        let paths = run(&file, "a", "d", 1, &options);
+        assert_eq!(paths, vec![vec!["e-ab", "e-bd"]]);
+    }
+
+    /// A pattern restricts the edge-type sequence of every returned path.
+    #[test]
+    fn test_pattern_constrains_sequence() {
+        let file = minimal_file(
+            ["a", "b", "c", "f"].into_iter().map(org_node).collect(),
+            vec![
+                typed_edge("e-af", EdgeType::Operates, "a", "f"),
+                supplies_edge("e-ab", "a", "b"),
+                supplies_edge("e-bc", "b", "c"),
+                typed_edge("e-cf", EdgeType::Operates, "c", "f"),
+                typed_edge("e-bf", EdgeType::Operates, "b", "f"),
+            ],
+        );
+        let options = PathOptions {
+            pattern: Some(EdgePattern::parse("supplies+ operates").expect("pattern")),
+            ..PathOptions::default()
+        };
+        let paths = run(&file, "a", "f", 10, &options);
+        assert_eq!(
+            paths,
+            vec![vec!["e-ab", "e-bf"], vec!["e-ab", "e-bc", "e-cf"]]
+        );
+
+        let exact = PathOptions {
+            pattern: Some(EdgePattern::parse("supplies supplies operates").expect("pattern")),
+            ..PathOptions::default()
+        };
+        assert_eq!(
+            run(&file, "a", "f", 10, &exact),
+            vec![vec!["e-ab", "e-bc", "e-cf"]]
+        );
+    }
+
+    /// The depth limit is exact, even when a cheaper route is longer.
+    #[test]
+    fn test_max_depth_enforced() {
+        let options = PathOptions {
+            max_depth: 2,
+            ..PathOptions::default()
+        };
+        let paths = run(&diamond(), "a", "d", 10, &options);
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().all(|p| p.len() <= 2));
+    }
+
+    /// Cycles never produce repeated nodes.
+    #[test]
+    fn test_paths_are_loopless() {
+        let file = minimal_file(
+            ["a", "b", "c"].into_iter().map(org_node).collect(),
+            vec![
+                supplies_edge("e-ab", "a", "b"),
+                supplies_edge("e-ba", "b", "a"),
+                supplies_edge("e-bc", "b", "c"),
+            ],
+        );
+        let graph = build_graph(&file).expect("builds");
+        let paths = k_shortest_paths(&graph, Some(&file), "a", "c", 10, &PathOptions::default())
+            .expect("query");
+        assert_eq!(paths.len(), 1);
+        let unique: HashSet<_> = paths[0].nodes.iter().collect();
+        assert_eq!(unique.len(), paths[0].nodes.len());
+    }
+
+    /// Backward traversal follows edges against their direction.
+    #[test]
+    fn test_backward_direction() {
+        let options = PathOptions {
+            direction: Direction::Backward,
+            ..PathOptions::default()
+        };
+        let paths = run(&diamond(), "d", "a", 10, &options);
+        assert_eq!(paths[0], vec!["e-bd", "e-ab"]);
+        assert_eq!(paths.len(), 3);
+    }
+
+    /// `from == to` yields the empty path only if the pattern accepts it.
+    #[test]
+    fn test_same_node() {
+        assert_eq!(
+            run(&diamond(), "a", "a", 5, &PathOptions::default()),
+            vec![Vec::<String>::new()]
+        );
+        let options = PathOptions {
+            pattern: Some(EdgePattern::parse("supplies").expect("pattern")),
+            ..PathOptions::default()
+        };
+        assert!(run(&diamond(), "a", "a", 5, &options).is_empty());
+    }
+
+    /// Unknown node IDs are reported.
+    #[test]
+    fn test_node_not_found() {
+        let file = diamond();
+        let graph = build_graph(&file).expect("builds");
+        let err = k_shortest_paths(&graph, Some(&file), "a", "zz", 1, &PathOptions::default())
+            .expect_err("missing node");
+        assert_eq!(err, QueryError::NodeNotFound("zz".to_owned()));
+    }
+
+    /// Malformed patterns are rejected.
+    #[test]
+    fn test_pattern_parse_errors() {
+        for input in ["", "   ", "supplies||operates", "+", "supplies |"] {
+            assert!(EdgePattern::parse(input).is_err(), "{input:?}");
+        }
+        let long = "supplies+ ".repeat(40);
+        assert!(EdgePattern::parse(&long).is_err());
+        assert!(EdgePattern::parse("any* supplies|subcontracts? operates").is_ok());
+    }
+}
@@ -14,7 +14,7 @@
 ///
 /// # Edge-Type Filtering
 ///
-/// All three query functions accept an optional `edge_filter: Option<&HashSet<EdgeTypeTag>>`.
+/// All query functions accept an optional `edge_filter: Option<&HashSet<EdgeTypeTag>>`.
 /// When `Some`, only edges whose `edge_type` is in the set are traversed.
 /// When `None`, all edge types are traversed.
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -30,7 +30,8 @@ mod tests;
 
 /// Controls which edges are followed during graph traversal.
 ///
-/// Used by [`reachable_from`], [`shortest_path`], and [`all_paths`].
+/// Used by [`reachable_from`], [`reachable_within`], [`shortest_path`], and
+/// [`all_paths`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     /// Follow outgoing edges only — traverse downstream from the start node.
@@ -126,7 +127,8 @@ fn neighbours_into(
 
 /// Returns the set of all nodes reachable from `start` via BFS.
 ///
-/// The start node itself is excluded from the result.
+/// The start node itself is excluded from the result. Equivalent to
+/// [`reachable_within`] with no depth bound.
 ///
 /// # Parameters
 ///
@@ -143,24 +145,54 @@ pub fn reachable_from(
     start: &str,
     direction: Direction,
     edge_filter: Option<&HashSet<EdgeTypeTag>>,
+) -> Result<HashSet<NodeIndex>, QueryError> {
+    reachable_within(graph, start, None, direction, edge_filter)
+}
+
+/// Returns the set of all nodes reachable from `start` within `max_depth` hops.
+///
+/// Runs a BFS that stops expanding once a node is `max_depth` hops from
+/// `start`; `None` leaves the traversal unbounded. The start node itself is
+/// excluded from the result, so `Some(0)` always returns an empty set.
+///
+/// # Parameters
+///
+/// - `graph` — the graph to query.
+/// - `start` — graph-local node ID of the starting node.
+/// - `max_depth` — maximum number of hops from `start`; `None` for unbounded.
+/// - `direction` — which edges to follow (see [`Direction`]).
+/// - `edge_filter` — optional set of allowed edge types; `None` traverses all.
+///
+/// # Errors
+///
+/// Returns [`QueryError::NodeNotFound`] if `start` does not exist in the graph.
+pub fn reachable_within(
+    graph: &OmtsGraph,
+    start: &str,
+    max_depth: Option<usize>,
+    direction: Direction,
+    edge_filter: Option<&HashSet<EdgeTypeTag>>,
 ) -> Result<HashSet<NodeIndex>, QueryError> {
     let start_idx = *graph
         .node_index(start)
         .ok_or_else(|| QueryError::NodeNotFound(start.to_owned()))?;
 
     let mut visited: HashSet<NodeIndex> = HashSet::new();
-    let mut queue: VecDeque<NodeIndex> = VecDeque::new();
+    let mut queue: VecDeque<(NodeIndex, usize)> = VecDeque::new();
     let mut nbuf: Vec<NodeIndex> = Vec::new();
 
     visited.insert(start_idx);
-    queue.push_back(start_idx);
+    queue.push_back((start_idx, 0));
 
-    while let Some(current) = queue.pop_front() {
+    while let Some((current, hops)) = queue.pop_front() {
+        if max_depth.is_some_and(|max| hops >= max) {
+            continue;
+        }
         neighbours_into(graph, current, direction, edge_filter, &mut nbuf);
         for &neighbour in &nbuf {
             if !visited.contains(&neighbour) {
                 visited.insert(neighbour);
-                queue.push_back(neighbour);
+                queue.push_back((neighbour, hops + 1));
             }
         }
     }
@@ -133,6 +133,62 @@ fn test_reachable_node_not_found() {
     assert_eq!(err, QueryError::NodeNotFound("nonexistent".to_owned()));
 }
 
+/// A depth bound stops the BFS after `max_depth` hops.
+#[test]
+fn test_reachable_within_depth_bound() {
+    let g = linear_chain(); // a → b → c → d
+    let reached =
+        reachable_within(&g, "a", Some(2), Direction::Forward, None).expect("should succeed");
+    assert_eq!(reached.len(), 2);
+    assert!(reached.contains(&idx(&g, "b")));
+    assert!(reached.contains(&idx(&g, "c")));
+    assert!(!reached.contains(&idx(&g, "d")));
+
+    let none =
+        reachable_within(&g, "a", Some(0), Direction::Forward, None).expect("should succeed");
+    assert!(none.is_empty());
+
+    let all = reachable_within(&g, "a", None, Direction::Forward, None).expect("should succeed");
+    assert_eq!(
+        all,
+        reachable_from(&g, "a", Direction::Forward, None).expect("should succeed")
+    );
+}
+
+/// Depth is measured along filtered edges: a node only reachable through a
+/// filtered-out shortcut is still found through the longer allowed route.
+#[test]
+fn test_reachable_within_depth_and_filter_combined() {
+    // Graph: d -supplies-> c -supplies-> b -supplies-> a, plus d -ownership-> a.
+    let nodes = vec![org_node("a"), org_node("b"), org_node("c"), org_node("d")];
+    let edges = vec![
+        supplies_edge("e-dc", "d", "c"),
+        supplies_edge("e-cb", "c", "b"),
+        supplies_edge("e-ba", "b", "a"),
+        ownership_edge("e-da", "d", "a"),
+    ];
+    let g = build_graph(&minimal_file(nodes, edges)).expect("builds");
+    let filter: HashSet<EdgeTypeTag> = [EdgeTypeTag::Known(EdgeType::Supplies)]
+        .into_iter()
+        .collect();
+
+    let upstream = reachable_within(&g, "a", Some(2), Direction::Backward, Some(&filter))
+        .expect("should succeed");
+    assert_eq!(upstream.len(), 2);
+    assert!(upstream.contains(&idx(&g, "b")));
+    assert!(upstream.contains(&idx(&g, "c")));
+    assert!(!upstream.contains(&idx(&g, "d")));
+}
+
+/// The bounded variant reports `NodeNotFound` like the unbounded one.
+#[test]
+fn test_reachable_within_node_not_found() {
+    let g = linear_chain();
+    let err = reachable_within(&g, "nonexistent", Some(1), Direction::Forward, None)
+        .expect_err("should fail for unknown node");
+    assert_eq!(err, QueryError::NodeNotFound("nonexistent".to_owned()));
+}
+
 /// Shortest path in a linear chain is the chain itself.
 #[test]
 fn test_shortest_path_linear_chain() {
@@ -0,0 +1,803 @@
+/// Boolean selector expressions: `AND`, `OR`, `NOT`, `IN`, and parentheses.
+///
+/// Implements Section 2.6 of the query technical specification (`query.md`).
+/// An expression such as
+///
+/// ```text
+/// type=organization AND jurisdiction IN (CN, VN) AND NOT label:risk.cleared
+/// ```
+///
+/// parses into a [`SelectorExpr`] tree whose leaves are ordinary [`Selector`]
+/// predicates. Expressions can state exclusions and arbitrary nesting, which
+/// the flag-based [`SelectorSet`][super::SelectorSet] groups cannot.
+///
+/// # Grammar
+///
+/// ```text
+/// expr      := or
+/// or        := and ( "OR" and )*
+/// and       := unary ( "AND" unary )*
+/// unary     := "NOT" unary | "(" expr ")" | predicate
+/// predicate := field [ ":" key ] [ op ]
+/// op        := "=" value | "!=" value | "IN" "(" value ( "," value )* ")"
+///              | ( "<" | "<=" | ">" | ">=" ) value
+///              | "BETWEEN" value "AND" value | "IS" [ "NOT" ] "NULL"
+/// field     := [ "node." | "edge." ] ( "type" | "label" | "identifier"
+///              | "jurisdiction" | "name" | path )
+/// path      := segment ( "." segment )*
+/// ```
+///
+/// Keywords are case-insensitive. Values are bare words or single- or
+/// double-quoted strings; quote any value containing whitespace, `(`, `)`,
+/// `,`, `=`, `!`, `<`, `>`, or `:`.
+///
+/// `label` and `identifier` take a key (`label:KEY`, `identifier:SCHEME`) and
+/// an optional value comparison; the other fields require a comparison and
+/// take no key. `name=PATTERN` is a case-insensitive substring match, as with
+/// the `--name` flag.
+///
+/// Any other field name is a property path and becomes a
+/// [`Selector::Property`] predicate (see [`super::property`]). Bare values
+/// compared against a path are typed by their form — `50` is a number,
+/// `2026-07-01` a date, anything else text — while quoted values are always
+/// text. The ordering operators, `BETWEEN` (inclusive), and `IS [NOT] NULL`
+/// are also accepted on `jurisdiction` and `name`, which then compare the raw
+/// field value.
+///
+/// # Scoping
+///
+/// The `node.` and `edge.` prefixes restrict a predicate to one element kind.
+/// An unprefixed `type` compares against the node type on nodes and the edge
+/// type on edges; an unprefixed `label` or property path applies to both. `identifier`,
+/// `jurisdiction`, and `name` are node-only and reject the `edge.` prefix.
+///
+/// A predicate evaluated against an element kind it does not apply to is
+/// `false`. An expression as a whole is evaluated against nodes only when
+/// [`SelectorExpr::applies_to_nodes`] is `true` (and likewise for edges),
+/// so `NOT jurisdiction=CN` selects nodes outside China rather than every edge.
+use std::fmt;
+
+use crate::enums::{EdgeTypeTag, NodeTypeTag};
+use crate::newtypes::CountryCode;
+use crate::structures::{Edge, Node};
+
+use super::Selector;
+use super::property::{CompareOp, PropertyPredicate, PropertyTest, PropertyValue};
+
+/// Maximum nesting depth of `NOT` and parentheses accepted by the parser.
+///
+/// Bounds recursion so that adversarial input cannot overflow the stack.
+const MAX_NESTING_DEPTH: usize = 64;
+
+/// The element kinds a predicate in a [`SelectorExpr`] is evaluated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprScope {
+    /// The predicate applies to nodes and edges alike.
+    Any,
+    /// The predicate applies to nodes only (`node.` prefix or a node-only field).
+    Node,
+    /// The predicate applies to edges only (`edge.` prefix or `edge.type`).
+    Edge,
+}
+
+/// A parsed boolean selector expression.
+///
+/// Construct with [`SelectorExpr::parse`] and evaluate with
+/// [`SelectorExpr::matches_node`] / [`SelectorExpr::matches_edge`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorExpr {
+    /// A single selector, restricted to the element kinds in `scope`.
+    Predicate {
+        /// The predicate to evaluate.
+        selector: Selector,
+        /// The element kinds the predicate applies to.
+        scope: ExprScope,
+    },
+    /// Logical negation of the inner expression.
+    Not(Box<SelectorExpr>),
+    /// Every operand must match.
+    And(Vec<SelectorExpr>),
+    /// At least one operand must match.
+    Or(Vec<SelectorExpr>),
+}
+
+/// Error returned by [`SelectorExpr::parse`] for malformed input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorExprError {
+    /// Byte offset into the input at which the error was detected.
+    pub offset: usize,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl fmt::Display for SelectorExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at offset {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for SelectorExprError {}
+
+impl SelectorExpr {
+    /// Parses a selector expression from its textual form.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SelectorExprError`] if the input is empty, contains an
+    /// unterminated string, has an empty property path segment, applies a
+    /// node-only field to edges, uses an ordering or `IS NULL` test on `type`,
+    /// `label`, or `identifier`, has an invalid jurisdiction code, is nested deeper than
+    /// 64 levels, or otherwise does not follow the grammar in the module docs.
+    pub fn parse(input: &str) -> Result<Self, SelectorExprError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: input.len(),
+            depth: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(tok) = parser.peek() {
+            return Err(SelectorExprError {
+                offset: tok.offset,
+                message: format!("unexpected {}", tok.kind),
+            });
+        }
+        Ok(expr)
+    }
+
+    /// Returns `true` if any predicate in the expression applies to nodes.
+    ///
+    /// Mirrors [`SelectorSet::has_node_selectors`][super::SelectorSet::has_node_selectors]:
+    /// callers skip node evaluation when this is `false`.
+    pub fn applies_to_nodes(&self) -> bool {
+        match self {
+            SelectorExpr::Predicate { selector, scope } => {
+                *scope != ExprScope::Edge && !matches!(selector, Selector::EdgeType(_))
+            }
+            SelectorExpr::Not(inner) => inner.applies_to_nodes(),
+            SelectorExpr::And(operands) | SelectorExpr::Or(operands) => {
+                operands.iter().any(SelectorExpr::applies_to_nodes)
+            }
+        }
+    }
+
+    /// Returns `true` if any predicate in the expression applies to edges.
+    ///
+    /// Mirrors [`SelectorSet::has_edge_selectors`][super::SelectorSet::has_edge_selectors]:
+    /// callers skip edge evaluation when this is `false`.
+    pub fn applies_to_edges(&self) -> bool {
+        match self {
+            SelectorExpr::Predicate { selector, scope } => {
+                *scope != ExprScope::Node && selector.applies_to_edges()
+            }
+            SelectorExpr::Not(inner) => inner.applies_to_edges(),
+            SelectorExpr::And(operands) | SelectorExpr::Or(operands) => {
+                operands.iter().any(SelectorExpr::applies_to_edges)
+            }
+        }
+    }
+
+    /// Evaluates the expression against `node`.
+    ///
+    /// Predicates scoped to edges evaluate to `false`.
+    pub fn matches_node(&self, node: &Node) -> bool {
+        match self {
+            SelectorExpr::Predicate { selector, scope } => {
+                *scope != ExprScope::Edge && selector.matches_node(node)
+            }
+            SelectorExpr::Not(inner) => !inner.matches_node(node),
+            SelectorExpr::And(operands) => operands.iter().all(|e| e.matches_node(node)),
+            SelectorExpr::Or(operands) => operands.iter().any(|e| e.matches_node(node)),
+        }
+    }
+
+    /// Evaluates the expression against `edge`.
+    ///
+    /// Predicates scoped to nodes, and node-only selectors, evaluate to `false`.
+    pub fn matches_edge(&self, edge: &Edge) -> bool {
+        match self {
+            SelectorExpr::Predicate { selector, scope } => {
+                *scope != ExprScope::Node && selector.matches_edge(edge)
+            }
+            SelectorExpr::Not(inner) => !inner.matches_edge(edge),
+            SelectorExpr::And(operands) => operands.iter().all(|e| e.matches_edge(edge)),
+            SelectorExpr::Or(operands) => operands.iter().any(|e| e.matches_edge(edge)),
+        }
+    }
+}
+
+impl std::str::FromStr for SelectorExpr {
+    type Err = SelectorExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Lexical token kinds produced by [`tokenize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Eq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// A bare word; keywords are recognised by the parser, not the lexer.
+    Word(String),
+    /// A quoted string; never interpreted as a keyword.
+    Quoted(String),
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::LParen => f.write_str("'('"),
+            TokenKind::RParen => f.write_str("')'"),
+            TokenKind::Comma => f.write_str("','"),
+            TokenKind::Colon => f.write_str("':'"),
+            TokenKind::Eq => f.write_str("'='"),
+            TokenKind::NotEq => f.write_str("'!='"),
+            TokenKind::Lt => f.write_str("'<'"),
+            TokenKind::Le => f.write_str("'<='"),
+            TokenKind::Gt => f.write_str("'>'"),
+            TokenKind::Ge => f.write_str("'>='"),
+            TokenKind::Word(w) => write!(f, "{w:?}"),
+            TokenKind::Quoted(q) => write!(f, "string {q:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    offset: usize,
+}
+
+/// Returns `true` for characters that terminate a bare word.
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace()
+        || matches!(
+            c,
+            '(' | ')' | ',' | ':' | '=' | '!' | '<' | '>' | '"' | '\''
+        )
+}
+
+/// Splits `input` into tokens, recording the byte offset of each.
+fn tokenize(input: &str) -> Result<Vec<Token>, SelectorExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(offset, c)) = chars.peek() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            ',' => TokenKind::Comma,
+            ':' => TokenKind::Colon,
+            '=' => TokenKind::Eq,
+            '<' | '>' => {
+                chars.next();
+                let or_equal = chars.peek().is_some_and(|&(_, next)| next == '=');
+                if or_equal {
+                    chars.next();
+                }
+                let kind = match (c, or_equal) {
+                    ('<', false) => TokenKind::Lt,
+                    ('<', true) => TokenKind::Le,
+                    (_, false) => TokenKind::Gt,
+                    (_, true) => TokenKind::Ge,
+                };
+                tokens.push(Token { kind, offset });
+                continue;
+            }
+            '!' => {
+                chars.next();
+                if chars.peek().is_some_and(|&(_, next)| next == '=') {
+                    chars.next();
+                    tokens.push(Token {
+                        kind: TokenKind::NotEq,
+                        offset,
+                    });
+                    continue;
+                }
+                return Err(SelectorExprError {
+                    offset,
+                    message: "expected '=' after '!'".to_owned(),
+                });
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                let mut closed = false;
+                for (_, ch) in chars.by_ref() {
+                    if ch == c {
+                        closed = true;
+                        break;
+                    }
+                    value.push(ch);
+                }
+                if !closed {
+                    return Err(SelectorExprError {
+                        offset,
+                        message: "unterminated string".to_owned(),
+                    });
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Quoted(value),
+                    offset,
+                });
+                continue;
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&(_, ch)) = chars.peek() {
+                    if is_delimiter(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Word(word),
+                    offset,
+                });
+                continue;
+            }
+        };
+        chars.next();
+        tokens.push(Token { kind, offset });
+    }
+
+    Ok(tokens)
+}
+
+/// The fields a predicate can test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Type,
+    Label,
+    Identifier,
+    Jurisdiction,
+    Name,
+    /// Any other name: a dotted property path.
+    Property(Vec<String>),
+}
+
+/// A value as written in the expression.
+#[derive(Debug, Clone)]
+struct Literal {
+    text: String,
+    /// Quoted literals are always text; bare ones are typed by their form.
+    quoted: bool,
+}
+
+impl Literal {
+    fn to_property_value(&self) -> PropertyValue {
+        if self.quoted {
+            PropertyValue::Text(self.text.clone())
+        } else {
+            PropertyValue::infer(&self.text)
+        }
+    }
+}
+
+/// A comparison following a field (and optional key).
+enum Comparison {
+    /// No operator: only valid for `label:KEY` and `identifier:SCHEME`.
+    Exists,
+    Eq(Literal),
+    NotEq(Literal),
+    In(Vec<Literal>),
+    Order(CompareOp, Literal),
+    Between(Literal, Literal),
+    /// `IS NULL`, or `IS NOT NULL` when `true`.
+    IsNull(bool),
+}
+
+/// Recursive-descent parser over the token stream.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Byte length of the input, used as the offset for end-of-input errors.
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        if tok.is_some() {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    /// Returns `true` and advances if the next token is the bare keyword `kw`.
+    fn eat_keyword(&mut self, kw: &str) -> bool {
+        let is_kw = matches!(
+            self.peek(),
+            Some(Token { kind: TokenKind::Word(w), .. }) if w.eq_ignore_ascii_case(kw)
+        );
+        if is_kw {
+            self.pos += 1;
+        }
+        is_kw
+    }
+
+    /// Returns `true` and advances if the next token has kind `kind`.
+    fn eat(&mut self, kind: &TokenKind) -> bool {
+        let matched = self.peek().is_some_and(|t| &t.kind == kind);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn error_here(&self, message: impl Into<String>) -> SelectorExprError {
+        SelectorExprError {
+            offset: self.peek().map_or(self.end, |t| t.offset),
+            message: message.into(),
+        }
+    }
+
+    fn expect(&mut self, kind: &TokenKind) -> Result<(), SelectorExprError> {
+        if self.eat(kind) {
+            return Ok(());
+        }
+        let found = self
+            .peek()
+            .map_or_else(|| "end of input".to_owned(), |t| t.kind.to_string());
+        Err(self.error_here(format!("expected {kind}, found {found}")))
+    }
+
+    fn parse_or(&mut self) -> Result<SelectorExpr, SelectorExprError> {
+        let mut operands = vec![self.parse_and()?];
+        while self.eat_keyword("OR") {
+            operands.push(self.parse_and()?);
+        }
+        Ok(flatten(operands, SelectorExpr::Or))
+    }
+
+    fn parse_and(&mut self) -> Result<SelectorExpr, SelectorExprError> {
+        let mut operands = vec![self.parse_unary()?];
+        while self.eat_keyword("AND") {
+            operands.push(self.parse_unary()?);
+        }
+        Ok(flatten(operands, SelectorExpr::And))
+    }
+
+    fn parse_unary(&mut self) -> Result<SelectorExpr, SelectorExprError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(self.error_here(format!(
+                "expression nested deeper than {MAX_NESTING_DEPTH} levels"
+            )));
+        }
+
+        if self.eat_keyword("NOT") {
+            self.depth += 1;
+            let inner = self.parse_unary()?;
+            self.depth -= 1;
+            return Ok(SelectorExpr::Not(Box::new(inner)));
+        }
+
+        if self.eat(&TokenKind::LParen) {
+            self.depth += 1;
+            let inner = self.parse_or()?;
+            self.depth -= 1;
+            self.expect(&TokenKind::RParen)?;
+            return Ok(inner);
+        }
+
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<SelectorExpr, SelectorExprError> {
+        let Some(tok) = self.next() else {
+            return Err(self.error_here("expected a predicate, found end of input"));
+        };
+        let TokenKind::Word(word) = &tok.kind else {
+            return Err(SelectorExprError {
+                offset: tok.offset,
+                message: format!("expected a field name, found {}", tok.kind),
+            });
+        };
+        let (scope, field) = parse_field(word).map_err(|message| SelectorExprError {
+            offset: tok.offset,
+            message,
+        })?;
+
+        let key = if self.eat(&TokenKind::Colon) {
+            Some(self.parse_value()?.text)
+        } else {
+            None
+        };
+        check_key(&field, key.is_some()).map_err(|message| SelectorExprError {
+            offset: tok.offset,
+            message,
+        })?;
+
+        let cmp_offset = self.peek().map_or(self.end, |t| t.offset);
+        let comparison = if self.eat(&TokenKind::Eq) {
+            Comparison::Eq(self.parse_value()?)
+        } else if self.eat(&TokenKind::NotEq) {
+            Comparison::NotEq(self.parse_value()?)
+        } else if self.eat_keyword("IN") {
+            Comparison::In(self.parse_value_list()?)
+        } else if let Some(op) = self.eat_order_op() {
+            Comparison::Order(op, self.parse_value()?)
+        } else if self.eat_keyword("BETWEEN") {
+            let low = self.parse_value()?;
+            if !self.eat_keyword("AND") {
+                return Err(self.error_here("expected AND in BETWEEN"));
+            }
+            Comparison::Between(low, self.parse_value()?)
+        } else if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            if !self.eat_keyword("NULL") {
+                return Err(self.error_here("expected NULL after IS"));
+            }
+            Comparison::IsNull(negated)
+        } else {
+            Comparison::Exists
+        };
+
+        build_predicate(scope, &field, key, comparison).map_err(|message| SelectorExprError {
+            offset: cmp_offset,
+            message,
+        })
+    }
+
+    /// Returns the operator and advances if the next token is `<`, `<=`, `>`, or `>=`.
+    fn eat_order_op(&mut self) -> Option<CompareOp> {
+        let op = match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Lt) => CompareOp::Lt,
+            Some(TokenKind::Le) => CompareOp::Le,
+            Some(TokenKind::Gt) => CompareOp::Gt,
+            Some(TokenKind::Ge) => CompareOp::Ge,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+
+    fn parse_value(&mut self) -> Result<Literal, SelectorExprError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Word(w)) => {
+                let value = Literal {
+                    text: w.clone(),
+                    quoted: false,
+                };
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(TokenKind::Quoted(q)) => {
+                let value = Literal {
+                    text: q.clone(),
+                    quoted: true,
+                };
+                self.pos += 1;
+                Ok(value)
+            }
+            Some(other) => {
+                let message = format!("expected a value, found {other}");
+                Err(self.error_here(message))
+            }
+            None => Err(self.error_here("expected a value, found end of input")),
+        }
+    }
+
+    fn parse_value_list(&mut self) -> Result<Vec<Literal>, SelectorExprError> {
+        self.expect(&TokenKind::LParen)?;
+        let mut values = vec![self.parse_value()?];
+        while self.eat(&TokenKind::Comma) {
+            values.push(self.parse_value()?);
+        }
+        self.expect(&TokenKind::RParen)?;
+        Ok(values)
+    }
+}
+
+/// Collapses a single-operand list to the operand itself.
+fn flatten(
+    mut operands: Vec<SelectorExpr>,
+    combine: fn(Vec<SelectorExpr>) -> SelectorExpr,
+) -> SelectorExpr {
+    if operands.len() == 1 {
+        if let Some(only) = operands.pop() {
+            return only;
+        }
+    }
+    combine(operands)
+}
+
+/// Splits an optional `node.`/`edge.` prefix from a field name.
+fn parse_field(word: &str) -> Result<(ExprScope, Field), String> {
+    let (scope, name) = if let Some(rest) = word.strip_prefix("node.") {
+        (ExprScope::Node, rest)
+    } else if let Some(rest) = word.strip_prefix("edge.") {
+        (ExprScope::Edge, rest)
+    } else {
+        (ExprScope::Any, word)
+    };
+
+    let field = match name.to_ascii_lowercase().as_str() {
+        "type" => Field::Type,
+        "label" => Field::Label,
+        "identifier" => Field::Identifier,
+        "jurisdiction" => Field::Jurisdiction,
+        "name" => Field::Name,
+        _ => {
+            let path: Vec<String> = name.split('.').map(str::to_owned).collect();
+            if path.iter().any(String::is_empty) {
+                return Err(format!("empty segment in property path {name:?}"));
+            }
+            Field::Property(path)
+        }
+    };
+
+    let node_only = matches!(field, Field::Identifier | Field::Jurisdiction | Field::Name);
+    if node_only && scope == ExprScope::Edge {
+        return Err(format!("field {name:?} applies to nodes only"));
+    }
+
+    Ok((scope, field))
+}
+
+/// Checks that `label` and `identifier` have a `:` key and no other field does.
+fn check_key(field: &Field, has_key: bool) -> Result<(), String> {
+    match (field, has_key) {
+        (Field::Label, false) => Err("label requires a key, e.g. label:KEY".to_owned()),
+        (Field::Identifier, false) => {
+            Err("identifier requires a scheme, e.g. identifier:SCHEME".to_owned())
+        }
+        (Field::Type | Field::Jurisdiction | Field::Name | Field::Property(_), true) => {
+            Err("only label and identifier take a ':' key".to_owned())
+        }
+        (Field::Label | Field::Identifier, true)
+        | (Field::Type | Field::Jurisdiction | Field::Name | Field::Property(_), false) => Ok(()),
+    }
+}
+
+/// Builds the expression for one predicate from its parsed parts.
+///
+/// `!=` becomes `NOT (field = value)`, `IN (a, b)` becomes
+/// `field = a OR field = b`, and `IS NOT NULL` becomes `NOT (field IS NULL)`.
+fn build_predicate(
+    scope: ExprScope,
+    field: &Field,
+    key: Option<String>,
+    comparison: Comparison,
+) -> Result<SelectorExpr, String> {
+    match comparison {
+        Comparison::Exists => match key {
+            Some(k) => Ok(leaf(scope, keyed_selector(field, k, None))),
+            None => Err("expected a comparison operator, IN, BETWEEN, or IS NULL".to_owned()),
+        },
+        Comparison::Eq(v) => single(scope, field, key, &v),
+        Comparison::NotEq(v) => Ok(SelectorExpr::Not(Box::new(single(scope, field, key, &v)?))),
+        Comparison::In(values) => {
+            let operands = values
+                .iter()
+                .map(|v| single(scope, field, key.clone(), v))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(flatten(operands, SelectorExpr::Or))
+        }
+        Comparison::Order(op, v) => property(
+            scope,
+            field,
+            PropertyTest::Compare(op, v.to_property_value()),
+        ),
+        Comparison::Between(low, high) => property(
+            scope,
+            field,
+            PropertyTest::Between(low.to_property_value(), high.to_property_value()),
+        ),
+        Comparison::IsNull(negated) => {
+            let expr = property(scope, field, PropertyTest::IsNull)?;
+            Ok(if negated {
+                SelectorExpr::Not(Box::new(expr))
+            } else {
+                expr
+            })
+        }
+    }
+}
+
+/// Builds a [`Selector::Property`] predicate for a typed test on `field`.
+///
+/// `jurisdiction` and `name` compare their raw field values; `type`, `label`,
+/// and `identifier` do not support typed tests.
+fn property(scope: ExprScope, field: &Field, test: PropertyTest) -> Result<SelectorExpr, String> {
+    let (scope, path) = match field {
+        Field::Property(path) => (scope, path.clone()),
+        Field::Jurisdiction => (ExprScope::Node, vec!["jurisdiction".to_owned()]),
+        Field::Name => (ExprScope::Node, vec!["name".to_owned()]),
+        Field::Type | Field::Label | Field::Identifier => {
+            return Err(
+                "ordering comparisons, BETWEEN, and IS NULL apply to property fields only"
+                    .to_owned(),
+            );
+        }
+    };
+    Ok(leaf(
+        scope,
+        Selector::Property(PropertyPredicate { path, test }),
+    ))
+}
+
+/// Builds the expression for `field[:key] = value`.
+fn single(
+    scope: ExprScope,
+    field: &Field,
+    key: Option<String>,
+    value: &Literal,
+) -> Result<SelectorExpr, String> {
+    match (field, key) {
+        (Field::Label | Field::Identifier, Some(k)) => Ok(leaf(
+            scope,
+            keyed_selector(field, k, Some(value.text.clone())),
+        )),
+        (Field::Jurisdiction, _) => {
+            let cc = CountryCode::try_from(value.text.as_str())
+                .map_err(|e| format!("jurisdiction: {e}"))?;
+            Ok(leaf(ExprScope::Node, Selector::Jurisdiction(cc)))
+        }
+        (Field::Name, _) => Ok(leaf(ExprScope::Node, Selector::Name(value.text.clone()))),
+        (Field::Type, _) => type_predicate(scope, &value.text),
+        (Field::Property(_), _) => property(
+            scope,
+            field,
+            PropertyTest::Compare(CompareOp::Eq, value.to_property_value()),
+        ),
+        (Field::Label | Field::Identifier, None) => Err("missing key".to_owned()),
+    }
+}
+
+/// Builds a `label:KEY[=VALUE]` or `identifier:SCHEME[=VALUE]` selector.
+fn keyed_selector(field: &Field, key: String, value: Option<String>) -> Selector {
+    match (field, value) {
+        (Field::Identifier, Some(v)) => Selector::IdentifierSchemeValue(key, v),
+        (Field::Identifier, None) => Selector::IdentifierScheme(key),
+        (_, Some(v)) => Selector::LabelKeyValue(key, v),
+        (_, None) => Selector::LabelKey(key),
+    }
+}
+
+/// Builds a `type=VALUE` predicate for the given scope.
+///
+/// Unscoped `type` expands to a node-type test on nodes OR an edge-type test
+/// on edges.
+fn type_predicate(scope: ExprScope, value: &str) -> Result<SelectorExpr, String> {
+    let node = || -> Result<SelectorExpr, String> {
+        let tag: NodeTypeTag = serde_json::from_value(serde_json::Value::String(value.to_owned()))
+            .map_err(|e| format!("type {value:?}: {e}"))?;
+        Ok(leaf(ExprScope::Node, Selector::NodeType(tag)))
+    };
+    let edge = || -> Result<SelectorExpr, String> {
+        let tag: EdgeTypeTag = serde_json::from_value(serde_json::Value::String(value.to_owned()))
+            .map_err(|e| format!("type {value:?}: {e}"))?;
+        Ok(leaf(ExprScope::Edge, Selector::EdgeType(tag)))
+    };
+    match scope {
+        ExprScope::Node => node(),
+        ExprScope::Edge => edge(),
+        ExprScope::Any => Ok(SelectorExpr::Or(vec![node()?, edge()?])),
+    }
+}
+
+fn leaf(scope: ExprScope, selector: Selector) -> SelectorExpr {
+    SelectorExpr::Predicate { selector, scope }
+}
@@ -17,14 +17,34 @@
 /// - Node-only selectors (`NodeType`, `IdentifierScheme`, `IdentifierSchemeValue`,
 ///   `Jurisdiction`, `Name`) are silently skipped when evaluating edges.
 /// - Edge-only selectors (`EdgeType`) are silently skipped when evaluating nodes.
-/// - `LabelKey` and `LabelKeyValue` apply to both nodes and edges.
+/// - `LabelKey`, `LabelKeyValue`, and `Property` apply to both nodes and edges.
+///
+/// # Boolean Expressions
+///
+/// The [`expr`] submodule parses `--where` expressions (`AND`, `OR`, `NOT`,
+/// `IN`, parentheses) into a [`SelectorExpr`] tree. A `SelectorSet` carries an
+/// optional expression in [`SelectorSet::expr`], which is combined with the flag
+/// groups.
+///
+/// # Typed Property Predicates
+///
+/// The [`property`] submodule implements [`Selector::Property`]: numeric,
+/// date, and text comparisons (`>`, `>=`, `BETWEEN`, `IS NULL`, …) on any
+/// known field or on `extra` extension fields by dotted path.
 use crate::enums::{EdgeTypeTag, NodeTypeTag};
 use crate::newtypes::CountryCode;
 use crate::structures::{Edge, Node};
+use crate::types::Label;
+
+pub mod expr;
+pub mod property;
 
 #[cfg(test)]
 mod tests;
 
+pub use expr::{ExprScope, SelectorExpr, SelectorExprError};
+pub use property::{CompareOp, FiniteNumber, PropertyPredicate, PropertyTest, PropertyValue};
+
 /// A single predicate that matches nodes, edges, or both.
 ///
 /// See `query.md` Section 2.1 for the full semantics of each variant.
@@ -46,6 +66,82 @@ pub enum Selector {
     Jurisdiction(CountryCode),
     /// Case-insensitive substring match on the node `name` field.
     Name(String),
+    /// Match elements whose property at a dotted path passes a typed test.
+    Property(PropertyPredicate),
+}
+
+impl Selector {
+    /// Returns `true` if this selector can match edges.
+    ///
+    /// Only `EdgeType`, `LabelKey`, `LabelKeyValue`, and `Property` apply to edges.
+    pub fn applies_to_edges(&self) -> bool {
+        matches!(
+            self,
+            Selector::EdgeType(_)
+                | Selector::LabelKey(_)
+                | Selector::LabelKeyValue(_, _)
+                | Selector::Property(_)
+        )
+    }
+
+    /// Returns `true` if `node` satisfies this single selector.
+    ///
+    /// Edge-only selectors never match a node.
+    pub fn matches_node(&self, node: &Node) -> bool {
+        match self {
+            Selector::NodeType(t) => &node.node_type == t,
+            Selector::EdgeType(_) => false,
+            Selector::LabelKey(k) => labels_have_key(node.labels.as_deref(), k),
+            Selector::LabelKeyValue(k, v) => labels_have_key_value(node.labels.as_deref(), k, v),
+            Selector::IdentifierScheme(s) => node
+                .identifiers
+                .as_ref()
+                .is_some_and(|ids| ids.iter().any(|id| &id.scheme == s)),
+            Selector::IdentifierSchemeValue(s, v) => node
+                .identifiers
+                .as_ref()
+                .is_some_and(|ids| ids.iter().any(|id| &id.scheme == s && &id.value == v)),
+            Selector::Jurisdiction(c) => node.jurisdiction.as_ref() == Some(c),
+            Selector::Name(pat) => node
+                .name
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(&pat.to_lowercase())),
+            Selector::Property(p) => p.matches_node(node),
+        }
+    }
+
+    /// Returns `true` if `edge` satisfies this single selector.
+    ///
+    /// Node-only selectors never match an edge.
+    pub fn matches_edge(&self, edge: &Edge) -> bool {
+        match self {
+            Selector::EdgeType(t) => &edge.edge_type == t,
+            Selector::LabelKey(k) => labels_have_key(edge.properties.labels.as_deref(), k),
+            Selector::LabelKeyValue(k, v) => {
+                labels_have_key_value(edge.properties.labels.as_deref(), k, v)
+            }
+            Selector::Property(p) => p.matches_edge(edge),
+            Selector::NodeType(_)
+            | Selector::IdentifierScheme(_)
+            | Selector::IdentifierSchemeValue(_, _)
+            | Selector::Jurisdiction(_)
+            | Selector::Name(_) => false,
+        }
+    }
+}
+
+/// Returns `true` if `labels` contains a label with key `key`.
+fn labels_have_key(labels: Option<&[Label]>, key: &str) -> bool {
+    labels.is_some_and(|labels| labels.iter().any(|lbl| lbl.key == key))
+}
+
+/// Returns `true` if `labels` contains a label with key `key` and value `value`.
+fn labels_have_key_value(labels: Option<&[Label]>, key: &str, value: &str) -> bool {
+    labels.is_some_and(|labels| {
+        labels
+            .iter()
+            .any(|lbl| lbl.key == key && lbl.value.as_deref() == Some(value))
+    })
 }
 
 /// Grouped selectors with AND-across-groups / OR-within-group composition.
@@ -87,6 +183,15 @@ pub struct SelectorSet {
     /// push the lowercased form here so that [`matches_node`][Self::matches_node]
     /// performs correct case-insensitive matching without redundant allocations.
     pub names_lowered: Vec<String>,
+    /// Typed property predicates (OR within group, applies to both nodes and edges).
+    pub properties: Vec<PropertyPredicate>,
+    /// Optional boolean expression (`--where`), combined with the groups above by AND.
+    ///
+    /// Evaluated against nodes only when [`SelectorExpr::applies_to_nodes`]
+    /// is `true` and against edges only when
+    /// [`SelectorExpr::applies_to_edges`] is `true`; otherwise it is skipped
+    /// for that element kind, just as node-only groups are skipped for edges.
+    pub expr: Option<SelectorExpr>,
 }
 
 impl SelectorSet {
@@ -104,12 +209,15 @@ impl SelectorSet {
             && self.identifier_scheme_values.is_empty()
             && self.jurisdictions.is_empty()
             && self.names.is_empty()
+            && self.properties.is_empty()
+            && self.expr.is_none()
     }
 
     /// Returns `true` if at least one node-applicable selector is set.
     ///
     /// Node-applicable selectors are: `node_types`, `label_keys`, `label_key_values`,
-    /// `identifier_schemes`, `identifier_scheme_values`, `jurisdictions`, `names`.
+    /// `identifier_schemes`, `identifier_scheme_values`, `jurisdictions`, `names`,
+    /// `properties`, and an `expr` for which [`SelectorExpr::applies_to_nodes`] holds.
     ///
     /// Used by [`crate::graph::selector_match`] to decide whether to evaluate
     /// nodes at all. When `false`, the caller should treat nodes as not matched by
@@ -122,11 +230,17 @@ impl SelectorSet {
             || !self.identifier_scheme_values.is_empty()
             || !self.jurisdictions.is_empty()
             || !self.names.is_empty()
+            || !self.properties.is_empty()
+            || self
+                .expr
+                .as_ref()
+                .is_some_and(SelectorExpr::applies_to_nodes)
     }
 
     /// Returns `true` if at least one edge-applicable selector is set.
     ///
-    /// Edge-applicable selectors are: `edge_types`, `label_keys`, `label_key_values`.
+    /// Edge-applicable selectors are: `edge_types`, `label_keys`, `label_key_values`,
+    /// `properties`, and an `expr` for which [`SelectorExpr::applies_to_edges`] holds.
     ///
     /// Used by [`crate::graph::selector_match`] to decide whether to evaluate
     /// edges at all. When `false`, the caller should treat edges as not matched by
@@ -135,6 +249,11 @@ impl SelectorSet {
         !self.edge_types.is_empty()
             || !self.label_keys.is_empty()
             || !self.label_key_values.is_empty()
+            || !self.properties.is_empty()
+            || self
+                .expr
+                .as_ref()
+                .is_some_and(SelectorExpr::applies_to_edges)
     }
 
     /// Builds a `SelectorSet` from a flat list of [`Selector`] values.
@@ -157,6 +276,7 @@ impl SelectorSet {
                     set.names_lowered.push(n.to_lowercase());
                     set.names.push(n);
                 }
+                Selector::Property(p) => set.properties.push(p),
             }
         }
         set
@@ -176,6 +296,8 @@ impl SelectorSet {
     /// 5. `identifier_scheme_values` — node must have an identifier matching any (scheme, value) pair.
     /// 6. `jurisdictions` — node's jurisdiction must match at least one entry.
     /// 7. `names` — node's name must contain at least one entry as a case-insensitive substring.
+    /// 8. `properties` — node must pass at least one property predicate.
+    /// 9. `expr` — node must satisfy the expression, if it applies to nodes.
     pub fn matches_node(&self, node: &Node) -> bool {
         if !self.node_types.is_empty() && !self.node_types.contains(&node.node_type) {
             return false;
@@ -250,6 +372,16 @@ impl SelectorSet {
             }
         }
 
+        if !self.properties.is_empty() && !self.properties.iter().any(|p| p.matches_node(node)) {
+            return false;
+        }
+
+        if let Some(expr) = &self.expr {
+            if expr.applies_to_nodes() && !expr.matches_node(node) {
+                return false;
+            }
+        }
+
         true
     }
 
@@ -263,6 +395,8 @@ impl SelectorSet {
     /// 1. `edge_types` — edge's type must match at least one entry.
     /// 2. `label_keys` — edge must have a label whose key matches any entry.
     /// 3. `label_key_values` — edge must have a label matching any (key, value) pair.
+    /// 4. `properties` — edge must pass at least one property predicate.
+    /// 5. `expr` — edge must satisfy the expression, if it applies to edges.
     pub fn matches_edge(&self, edge: &Edge) -> bool {
         if !self.edge_types.is_empty() && !self.edge_types.contains(&edge.edge_type) {
             return false;
@@ -292,6 +426,16 @@ impl SelectorSet {
             }
         }
 
+        if !self.properties.is_empty() && !self.properties.iter().any(|p| p.matches_edge(edge)) {
+            return false;
+        }
+
+        if let Some(expr) = &self.expr {
+            if expr.applies_to_edges() && !expr.matches_edge(edge) {
+                return false;
+            }
+        }
+
         true
     }
 }
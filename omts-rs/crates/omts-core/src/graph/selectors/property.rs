@@ -0,0 +1,334 @@
+/// Typed property predicates: numeric, date, and text comparisons on any field.
+///
+/// Implements Section 2.7 of the query technical specification (`query.md`).
+/// A [`PropertyPredicate`] names a property by a dotted path and tests its
+/// value with `=`, `<`, `<=`, `>`, `>=`, an inclusive range, or `IS NULL`.
+///
+/// # Path Resolution
+///
+/// The first path segment is resolved against the element's known fields:
+///
+/// - **Nodes** — every field of [`Node`] by its JSON name (`type`,
+///   `valid_to`, `quantity`, `outcome`, `attestation_status`, …).
+/// - **Edges** — the top-level `id`, `type`, `source`, `target`, and
+///   `identifiers`, then every field of [`EdgeProperties`] by its JSON name
+///   (`percentage`, `annual_value`, `share_of_buyer_demand`, …).
+///
+/// Names that are not known fields are looked up in the `extra` extension
+/// map (for edges: `properties.extra`, then the edge-level `extra`). Because
+/// extension keys are conventionally reverse-domain names containing dots,
+/// the longest prefix of the path that is an `extra` key wins, so
+/// `com.acme.risk.score` finds the key `com.acme.risk` and descends into its
+/// `score` member.
+///
+/// Remaining segments descend into JSON objects by key and into arrays by
+/// zero-based index (`identifiers.0.scheme`, `data_quality.confidence`).
+///
+/// # Comparison
+///
+/// The operand's type decides how the property is compared:
+///
+/// | Operand | Property must be | Comparison |
+/// |---|---|---|
+/// | [`PropertyValue::Number`] | a JSON number | numeric (equality within 1e-9) |
+/// | [`PropertyValue::Date`] | a `YYYY-MM-DD` string | chronological |
+/// | [`PropertyValue::Text`] | a string or boolean | lexicographic |
+///
+/// A property that is absent or `null`, or whose type does not fit the
+/// operand, fails every test except [`PropertyTest::IsNull`] (which passes
+/// only for absent or `null`).
+use serde_json::Value;
+
+use crate::dynvalue::DynMap;
+use crate::newtypes::CalendarDate;
+use crate::structures::{Edge, EdgeProperties, Node};
+
+/// Tolerance for numeric equality, matching the diff engine.
+const NUMERIC_EPSILON: f64 = 1e-9;
+
+/// A finite numeric operand.
+///
+/// Two operands are equal when their bit patterns are ([`f64::total_cmp`]),
+/// which gives [`PropertyValue`] a total equality so that selectors are
+/// `Eq`. Comparison against a property value uses a tolerance instead.
+#[derive(Debug, Clone, Copy)]
+pub struct FiniteNumber(f64);
+
+impl FiniteNumber {
+    /// Wraps `n`, or returns `None` if it is NaN or infinite.
+    pub fn new(n: f64) -> Option<Self> {
+        n.is_finite().then_some(Self(n))
+    }
+
+    /// Returns the wrapped value.
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for FiniteNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_cmp(&other.0).is_eq()
+    }
+}
+
+impl Eq for FiniteNumber {}
+
+/// A typed operand for a property comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyValue {
+    /// A finite number; compared against JSON numbers.
+    Number(FiniteNumber),
+    /// A calendar date; compared against `YYYY-MM-DD` strings.
+    Date(CalendarDate),
+    /// Free text; compared against strings and booleans.
+    Text(String),
+}
+
+impl PropertyValue {
+    /// Infers the operand type from an unquoted literal.
+    ///
+    /// Finite numbers become [`PropertyValue::Number`], `YYYY-MM-DD` strings
+    /// become [`PropertyValue::Date`], and everything else is
+    /// [`PropertyValue::Text`].
+    pub fn infer(literal: &str) -> Self {
+        if let Some(n) = literal.parse().ok().and_then(FiniteNumber::new) {
+            return PropertyValue::Number(n);
+        }
+        if let Ok(date) = CalendarDate::try_from(literal) {
+            return PropertyValue::Date(date);
+        }
+        PropertyValue::Text(literal.to_owned())
+    }
+}
+
+/// Ordering comparison operators for [`PropertyTest::Compare`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    /// `=`
+    Eq,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+/// The test a [`PropertyPredicate`] applies to the resolved value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyTest {
+    /// Compare the value against an operand.
+    Compare(CompareOp, PropertyValue),
+    /// Inclusive range: `low <= value <= high`.
+    Between(PropertyValue, PropertyValue),
+    /// The property is absent or `null`.
+    IsNull,
+}
+
+/// A typed test on the value at a property path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyPredicate {
+    /// Dotted path segments, e.g. `["data_quality", "confidence"]`.
+    pub path: Vec<String>,
+    /// The test applied to the resolved value.
+    pub test: PropertyTest,
+}
+
+impl PropertyPredicate {
+    /// Returns `true` if the property at [`path`][Self::path] on `node` passes the test.
+    pub fn matches_node(&self, node: &Node) -> bool {
+        self.test.matches(resolve_node(node, &self.path).as_ref())
+    }
+
+    /// Returns `true` if the property at [`path`][Self::path] on `edge` passes the test.
+    pub fn matches_edge(&self, edge: &Edge) -> bool {
+        self.test.matches(resolve_edge(edge, &self.path).as_ref())
+    }
+}
+
+impl PropertyTest {
+    /// Applies the test to a resolved value (`None` when the path is absent).
+    pub fn matches(&self, value: Option<&Value>) -> bool {
+        let value = value.filter(|v| !v.is_null());
+        match self {
+            PropertyTest::IsNull => value.is_none(),
+            PropertyTest::Compare(op, operand) => value.is_some_and(|v| {
+                compare(v, operand).is_some_and(|ord| match op {
+                    CompareOp::Eq => ord.is_eq(),
+                    CompareOp::Lt => ord.is_lt(),
+                    CompareOp::Le => ord.is_le(),
+                    CompareOp::Gt => ord.is_gt(),
+                    CompareOp::Ge => ord.is_ge(),
+                })
+            }),
+            PropertyTest::Between(low, high) => value.is_some_and(|v| {
+                compare(v, low).is_some_and(std::cmp::Ordering::is_ge)
+                    && compare(v, high).is_some_and(std::cmp::Ordering::is_le)
+            }),
+        }
+    }
+}
+
+/// Orders `value` relative to `operand`, or `None` if their types do not fit.
+fn compare(value: &Value, operand: &PropertyValue) -> Option<std::cmp::Ordering> {
+    match operand {
+        PropertyValue::Number(n) => {
+            let (v, n) = (value.as_f64()?, n.get());
+            if (v - n).abs() < NUMERIC_EPSILON {
+                Some(std::cmp::Ordering::Equal)
+            } else {
+                v.partial_cmp(&n)
+            }
+        }
+        PropertyValue::Date(d) => {
+            let v = CalendarDate::try_from(value.as_str()?).ok()?;
+            Some(v.cmp(d))
+        }
+        PropertyValue::Text(t) => match value {
+            Value::String(s) => Some(s.as_str().cmp(t.as_str())),
+            Value::Bool(b) => Some(if *b { "true" } else { "false" }.cmp(t.as_str())),
+            Value::Null | Value::Number(_) | Value::Array(_) | Value::Object(_) => None,
+        },
+    }
+}
+
+/// Resolves `path` against `node`.
+fn resolve_node(node: &Node, path: &[String]) -> Option<Value> {
+    let (first, rest) = path.split_first()?;
+    match node_field(node, first) {
+        Some(value) => descend(value, rest),
+        None => resolve_extra(&node.extra, path),
+    }
+}
+
+/// Resolves `path` against `edge`.
+fn resolve_edge(edge: &Edge, path: &[String]) -> Option<Value> {
+    let (first, rest) = path.split_first()?;
+    if let Some(value) = edge_field(edge, first) {
+        return descend(value, rest);
+    }
+    if let Some(value) = edge_property_field(&edge.properties, first) {
+        return descend(value, rest);
+    }
+    resolve_extra(&edge.properties.extra, path).or_else(|| resolve_extra(&edge.extra, path))
+}
+
+/// Looks up the longest dotted prefix of `path` in `extra` and descends into the rest.
+fn resolve_extra(extra: &DynMap, path: &[String]) -> Option<Value> {
+    (1..=path.len()).rev().find_map(|split| {
+        let key = path[..split].join(".");
+        extra
+            .get(&key)
+            .and_then(|v| descend(Value::from(v.clone()), &path[split..]))
+    })
+}
+
+/// Walks `rest` into `value` by object key or array index.
+fn descend(value: Value, rest: &[String]) -> Option<Value> {
+    let mut current = value;
+    for segment in rest {
+        current = match current {
+            Value::Object(mut map) => map.remove(segment)?,
+            Value::Array(mut items) => {
+                let index: usize = segment.parse().ok()?;
+                if index >= items.len() {
+                    return None;
+                }
+                items.swap_remove(index)
+            }
+            Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Serializes one field; absent optional fields become `Value::Null`.
+fn to_value<T: serde::Serialize>(field: &T) -> Option<Value> {
+    Some(serde_json::to_value(field).unwrap_or(Value::Null))
+}
+
+/// Returns the value of the known node field `name`, or `None` if `name` is
+/// not a field of [`Node`].
+fn node_field(node: &Node, name: &str) -> Option<Value> {
+    match name {
+        "id" => to_value(&node.id),
+        "type" => to_value(&node.node_type),
+        "identifiers" => to_value(&node.identifiers),
+        "data_quality" => to_value(&node.data_quality),
+        "labels" => to_value(&node.labels),
+        "name" => to_value(&node.name),
+        "jurisdiction" => to_value(&node.jurisdiction),
+        "status" => to_value(&node.status),
+        "governance_structure" => to_value(&node.governance_structure),
+        "operator" => to_value(&node.operator),
+        "address" => to_value(&node.address),
+        "geo" => to_value(&node.geo),
+        "commodity_code" => to_value(&node.commodity_code),
+        "unit" => to_value(&node.unit),
+        "role" => to_value(&node.role),
+        "attestation_type" => to_value(&node.attestation_type),
+        "standard" => to_value(&node.standard),
+        "issuer" => to_value(&node.issuer),
+        "valid_from" => to_value(&node.valid_from),
+        "valid_to" => to_value(&node.valid_to.clone().flatten()),
+        "outcome" => to_value(&node.outcome),
+        "attestation_status" => to_value(&node.attestation_status),
+        "reference" => to_value(&node.reference),
+        "risk_severity" => to_value(&node.risk_severity),
+        "risk_likelihood" => to_value(&node.risk_likelihood),
+        "lot_id" => to_value(&node.lot_id),
+        "quantity" => to_value(&node.quantity),
+        "production_date" => to_value(&node.production_date),
+        "origin_country" => to_value(&node.origin_country),
+        "emission_factor_source" => to_value(&node.emission_factor_source),
+        "installation_id" => to_value(&node.installation_id),
+        _ => None,
+    }
+}
+
+/// Returns the value of the top-level edge field `name`, or `None` if `name`
+/// is not one.
+fn edge_field(edge: &Edge, name: &str) -> Option<Value> {
+    match name {
+        "id" => to_value(&edge.id),
+        "type" => to_value(&edge.edge_type),
+        "source" => to_value(&edge.source),
+        "target" => to_value(&edge.target),
+        "identifiers" => to_value(&edge.identifiers),
+        _ => None,
+    }
+}
+
+/// Returns the value of the known edge property `name`, or `None` if `name`
+/// is not a field of [`EdgeProperties`].
+fn edge_property_field(props: &EdgeProperties, name: &str) -> Option<Value> {
+    match name {
+        "data_quality" => to_value(&props.data_quality),
+        "labels" => to_value(&props.labels),
+        "valid_from" => to_value(&props.valid_from),
+        "valid_to" => to_value(&props.valid_to.clone().flatten()),
+        "percentage" => to_value(&props.percentage),
+        "direct" => to_value(&props.direct),
+        "control_type" => to_value(&props.control_type),
+        "consolidation_basis" => to_value(&props.consolidation_basis),
+        "event_type" => to_value(&props.event_type),
+        "effective_date" => to_value(&props.effective_date),
+        "description" => to_value(&props.description),
+        "commodity" => to_value(&props.commodity),
+        "contract_ref" => to_value(&props.contract_ref),
+        "volume" => to_value(&props.volume),
+        "volume_unit" => to_value(&props.volume_unit),
+        "annual_value" => to_value(&props.annual_value),
+        "value_currency" => to_value(&props.value_currency),
+        "tier" => to_value(&props.tier),
+        "share_of_buyer_demand" => to_value(&props.share_of_buyer_demand),
+        "service_type" => to_value(&props.service_type),
+        "quantity" => to_value(&props.quantity),
+        "unit" => to_value(&props.unit),
+        "scope" => to_value(&props.scope),
+        _ => None,
+    }
+}
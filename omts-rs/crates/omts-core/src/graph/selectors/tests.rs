@@ -1,9 +1,12 @@
 #![allow(clippy::expect_used)]
 
 use super::*;
-use crate::enums::{EdgeType, NodeType};
+use crate::dynvalue::DynValue;
+use crate::enums::{AttestationOutcome, EdgeType, NodeType};
 use crate::structures::Node;
-use crate::test_helpers::{facility_node, node_id, org_node, ownership_edge, supplies_edge};
+use crate::test_helpers::{
+    date, facility_node, node_id, org_node, ownership_edge, supplies_edge, typed_node,
+};
 use crate::types::{Identifier, Label};
 use std::collections::BTreeMap;
 
@@ -458,3 +461,320 @@ fn test_matches_edge_empty_selector_set_matches_all() {
     assert!(ss.matches_edge(&supplies_edge("e1", "a", "b")));
     assert!(ss.matches_edge(&ownership_edge("e2", "a", "b")));
 }
+
+fn parse_expr(s: &str) -> SelectorExpr {
+    SelectorExpr::parse(s).expect("valid expression")
+}
+
+fn org_in(id: &str, cc: &str) -> Node {
+    let mut node = org_node(id);
+    node.jurisdiction = Some(country_code(cc));
+    node
+}
+
+/// The example from the query spec parses and evaluates as written.
+#[test]
+fn test_expr_spec_example() {
+    let expr =
+        parse_expr("type=organization AND jurisdiction IN (CN, VN) AND NOT label:risk.cleared");
+
+    let mut cleared = org_in("n1", "CN");
+    cleared.labels = Some(vec![label("risk.cleared", None)]);
+
+    assert!(expr.matches_node(&org_in("n2", "VN")));
+    assert!(!expr.matches_node(&org_in("n3", "DE")));
+    assert!(!expr.matches_node(&cleared));
+    assert!(!expr.matches_node(&facility_node("n4")));
+}
+
+/// `AND` binds tighter than `OR`; parentheses override precedence.
+#[test]
+fn test_expr_precedence_and_parentheses() {
+    let node = org_in("n1", "DE");
+    assert!(parse_expr("jurisdiction=DE OR type=facility AND jurisdiction=CN").matches_node(&node));
+    assert!(
+        !parse_expr("(jurisdiction=DE OR type=facility) AND jurisdiction=CN").matches_node(&node)
+    );
+}
+
+/// Keywords are case-insensitive and `!=` negates the comparison.
+#[test]
+fn test_expr_keywords_case_insensitive_and_not_equal() {
+    let node = org_in("n1", "DE");
+    assert!(parse_expr("not jurisdiction=CN and type != facility").matches_node(&node));
+    assert!(!parse_expr("jurisdiction != DE").matches_node(&node));
+}
+
+/// Quoted values may contain delimiters; `name` is a case-insensitive substring match.
+#[test]
+fn test_expr_quoted_values_and_name_substring() {
+    let mut node = org_node("n1");
+    node.name = Some("Acme (Shenzhen) Ltd".to_owned());
+    node.labels = Some(vec![label("url", Some("https://acme.example"))]);
+    assert!(parse_expr(r#"name="acme (shenzhen)""#).matches_node(&node));
+    assert!(parse_expr("label:url='https://acme.example'").matches_node(&node));
+}
+
+/// `identifier:SCHEME` and `identifier:SCHEME=VALUE` test node identifiers.
+#[test]
+fn test_expr_identifier_predicates() {
+    let mut node = org_node("n1");
+    node.identifiers = Some(vec![identifier("duns", "123456789")]);
+    assert!(parse_expr("identifier:duns").matches_node(&node));
+    assert!(parse_expr("identifier:duns=123456789").matches_node(&node));
+    assert!(!parse_expr("identifier:lei").matches_node(&node));
+}
+
+/// Unscoped `type` tests node type on nodes and edge type on edges.
+#[test]
+fn test_expr_unscoped_type_applies_to_both() {
+    let expr = parse_expr("type IN (organization, supplies)");
+    assert!(expr.applies_to_nodes());
+    assert!(expr.applies_to_edges());
+    assert!(expr.matches_node(&org_node("n1")));
+    assert!(expr.matches_edge(&supplies_edge("e1", "a", "b")));
+    assert!(!expr.matches_edge(&ownership_edge("e2", "a", "b")));
+}
+
+/// `node.` and `edge.` prefixes restrict a label predicate to one element kind.
+#[test]
+fn test_expr_scoped_label() {
+    let mut node = org_node("n1");
+    node.labels = Some(vec![label("flagged", None)]);
+    let mut edge = supplies_edge("e1", "a", "b");
+    edge.properties.labels = Some(vec![label("flagged", None)]);
+
+    let node_scoped = parse_expr("node.label:flagged");
+    assert!(node_scoped.applies_to_nodes());
+    assert!(!node_scoped.applies_to_edges());
+    assert!(node_scoped.matches_node(&node));
+    assert!(!node_scoped.matches_edge(&edge));
+
+    let edge_scoped = parse_expr("edge.label:flagged");
+    assert!(!edge_scoped.applies_to_nodes());
+    assert!(edge_scoped.matches_edge(&edge));
+    assert!(!edge_scoped.matches_node(&node));
+}
+
+/// A node-only expression does not apply to edges, so `NOT` does not select every edge.
+#[test]
+fn test_expr_node_only_negation_does_not_apply_to_edges() {
+    let expr = parse_expr("NOT jurisdiction=CN");
+    assert!(expr.applies_to_nodes());
+    assert!(!expr.applies_to_edges());
+}
+
+/// Malformed expressions are rejected with an offset into the input.
+#[test]
+fn test_expr_parse_errors() {
+    for (input, offset) in [
+        ("", 0),
+        ("type=organization AND", 21),
+        ("(type=organization", 18),
+        ("a..b=red", 0),
+        ("type > good", 5),
+        ("label:k IS NULL", 8),
+        ("percentage BETWEEN 1 2", 21),
+        ("valid_to IS EMPTY", 12),
+        ("edge.jurisdiction=DE", 0),
+        ("jurisdiction=XYZ", 12),
+        ("label", 0),
+        ("name:x=y", 0),
+        ("type=good)", 9),
+        ("name='open", 5),
+        ("type ! good", 5),
+    ] {
+        let err = SelectorExpr::parse(input).expect_err(input);
+        assert_eq!(err.offset, offset, "{input:?}: {err}");
+    }
+}
+
+/// Nesting beyond the depth limit is rejected rather than overflowing the stack.
+#[test]
+fn test_expr_nesting_limit() {
+    let deep = format!("{}type=good{}", "(".repeat(100), ")".repeat(100));
+    assert!(SelectorExpr::parse(&deep).is_err());
+    let shallow = format!("{}type=good{}", "(".repeat(10), ")".repeat(10));
+    assert!(SelectorExpr::parse(&shallow).is_ok());
+}
+
+/// An expression on a `SelectorSet` is combined by AND with the flag groups.
+#[test]
+fn test_selector_set_expr_anded_with_groups() {
+    let mut ss = SelectorSet::from_selectors(vec![Selector::NodeType(NodeTypeTag::Known(
+        NodeType::Organization,
+    ))]);
+    ss.expr = Some(parse_expr("NOT jurisdiction=CN"));
+    assert!(ss.matches_node(&org_in("n1", "DE")));
+    assert!(!ss.matches_node(&org_in("n2", "CN")));
+    assert!(!ss.matches_node(&facility_node("n3")));
+    assert!(!ss.has_edge_selectors());
+}
+
+/// An expression that does not apply to edges is skipped when matching edges.
+#[test]
+fn test_selector_set_expr_skipped_for_other_kind() {
+    let mut ss = SelectorSet::from_selectors(vec![Selector::EdgeType(EdgeTypeTag::Known(
+        EdgeType::Supplies,
+    ))]);
+    ss.expr = Some(parse_expr("jurisdiction=DE"));
+    assert!(ss.has_node_selectors());
+    assert!(ss.matches_edge(&supplies_edge("e1", "a", "b")));
+    assert!(ss.matches_node(&org_in("n1", "DE")));
+}
+
+fn owned_pct(id: &str, pct: Option<f64>) -> Edge {
+    let mut edge = ownership_edge(id, "a", "b");
+    edge.properties.percentage = pct;
+    edge
+}
+
+fn attestation(id: &str, valid_to: Option<&str>) -> Node {
+    let mut node = typed_node(id, NodeType::Attestation);
+    node.valid_to = Some(valid_to.map(date));
+    node
+}
+
+/// Numeric comparisons on a known edge property, including `!=` and ranges.
+#[test]
+fn test_property_numeric_comparisons() {
+    let high = owned_pct("e1", Some(75.0));
+    let half = owned_pct("e2", Some(50.0));
+    let expr = parse_expr("edge.type=ownership AND percentage > 50");
+    assert!(expr.matches_edge(&high));
+    assert!(!expr.matches_edge(&half));
+    assert!(parse_expr("percentage >= 50").matches_edge(&half));
+    assert!(parse_expr("percentage <= 50.0").matches_edge(&half));
+    assert!(parse_expr("percentage = 50").matches_edge(&half));
+    assert!(parse_expr("percentage != 50").matches_edge(&high));
+    assert!(parse_expr("percentage BETWEEN 50 AND 75").matches_edge(&high));
+    assert!(!parse_expr("percentage BETWEEN 10 AND 49.5").matches_edge(&half));
+}
+
+/// Date comparisons on attestation validity, and `IS NULL` for open-ended ones.
+#[test]
+fn test_property_date_comparisons_and_null() {
+    let expiring = attestation("a1", Some("2026-05-31"));
+    let later = attestation("a2", Some("2027-01-01"));
+    let open = attestation("a3", None);
+    let expr = parse_expr("type=attestation AND valid_to < 2026-07-01");
+    assert!(expr.matches_node(&expiring));
+    assert!(!expr.matches_node(&later));
+    assert!(!expr.matches_node(&open));
+    assert!(parse_expr("valid_to IS NULL").matches_node(&open));
+    assert!(parse_expr("valid_to is not null").matches_node(&later));
+    assert!(!parse_expr("valid_to IS NOT NULL").matches_node(&open));
+}
+
+/// A missing property or a type mismatch fails every comparison.
+#[test]
+fn test_property_missing_or_mismatched_fails() {
+    let edge = owned_pct("e1", None);
+    assert!(!parse_expr("percentage > 0").matches_edge(&edge));
+    assert!(!parse_expr("percentage <= 0").matches_edge(&edge));
+    assert!(parse_expr("percentage IS NULL").matches_edge(&edge));
+
+    let pct = owned_pct("e2", Some(60.0));
+    assert!(!parse_expr("percentage > '50'").matches_edge(&pct));
+    assert!(!parse_expr("percentage > 2026-01-01").matches_edge(&pct));
+}
+
+/// Enum-valued and text properties compare as strings.
+#[test]
+fn test_property_text_comparison() {
+    let mut node = typed_node("a1", NodeType::Attestation);
+    node.outcome = Some(AttestationOutcome::ConditionalPass);
+    assert!(parse_expr("outcome = conditional_pass").matches_node(&node));
+    assert!(parse_expr("outcome IN (pass, conditional_pass)").matches_node(&node));
+    assert!(!parse_expr("outcome = pass").matches_node(&node));
+    assert!(parse_expr("attestation_status IS NULL").matches_node(&node));
+}
+
+/// Extension fields resolve by the longest dotted key, then descend into JSON.
+#[test]
+fn test_property_extra_path_access() {
+    let mut node = org_node("n1");
+    node.extra.insert(
+        "com.acme.risk".to_owned(),
+        DynValue::from(serde_json::json!({"score": 7, "tags": ["x", "y"]})),
+    );
+    node.extra
+        .insert("tier".to_owned(), DynValue::from(serde_json::json!(2)));
+    assert!(parse_expr("com.acme.risk.score >= 7").matches_node(&node));
+    assert!(parse_expr("com.acme.risk.tags.1 = y").matches_node(&node));
+    assert!(parse_expr("com.acme.risk.tags.2 IS NULL").matches_node(&node));
+    assert!(parse_expr("node.tier = 2").matches_node(&node));
+    assert!(parse_expr("com.acme.other IS NULL").matches_node(&node));
+}
+
+/// Header field names are ordinary paths: an element that carries one as an
+/// extension can be filtered on it.
+#[test]
+fn test_property_header_named_extension() {
+    let mut node = org_node("n1");
+    node.extra.insert(
+        "snapshot_date".to_owned(),
+        DynValue::from(serde_json::json!("2025-12-01")),
+    );
+    assert!(parse_expr("snapshot_date < 2026-01-01").matches_node(&node));
+    assert!(!parse_expr("snapshot_date >= 2026-01-01").matches_node(&node));
+    assert!(parse_expr("reporting_entity IS NULL").matches_node(&node));
+    assert!(parse_expr("snapshot_date IS NULL").matches_node(&org_node("n2")));
+}
+
+/// Known nested fields are reachable by path, and scoping still applies.
+#[test]
+fn test_property_nested_known_field_and_scope() {
+    let mut node = org_node("n1");
+    node.identifiers = Some(vec![identifier("lei", "ABC")]);
+    assert!(parse_expr("identifiers.0.scheme = lei").matches_node(&node));
+
+    let expr = parse_expr("edge.percentage > 10");
+    assert!(!expr.applies_to_nodes());
+    assert!(expr.applies_to_edges());
+    assert!(!parse_expr("node.percentage > 10").matches_edge(&owned_pct("e1", Some(50.0))));
+}
+
+/// Typed tests on `jurisdiction` and `name` compare the raw field value.
+#[test]
+fn test_property_tests_on_builtin_fields() {
+    let mut node = org_in("n1", "DE");
+    node.name = None;
+    assert!(parse_expr("jurisdiction IS NOT NULL").matches_node(&node));
+    assert!(parse_expr("name IS NULL").matches_node(&node));
+    assert!(parse_expr("jurisdiction BETWEEN AA AND FZ").matches_node(&node));
+    assert!(!parse_expr("jurisdiction > DE").applies_to_edges());
+}
+
+/// `Selector::Property` fills the `properties` group, OR within the group.
+#[test]
+fn test_selector_set_property_group() {
+    let gt = |n: f64| {
+        Selector::Property(PropertyPredicate {
+            path: vec!["percentage".to_owned()],
+            test: PropertyTest::Compare(
+                CompareOp::Gt,
+                PropertyValue::Number(FiniteNumber::new(n).expect("finite")),
+            ),
+        })
+    };
+    let ss = SelectorSet::from_selectors(vec![gt(90.0), gt(40.0)]);
+    assert_eq!(ss.properties.len(), 2);
+    assert!(ss.has_node_selectors());
+    assert!(ss.has_edge_selectors());
+    assert!(ss.matches_edge(&owned_pct("e1", Some(50.0))));
+    assert!(!ss.matches_edge(&owned_pct("e2", Some(30.0))));
+    assert!(!ss.matches_node(&org_node("n1")));
+}
+
+/// Numeric operands are compared by bit pattern, so selectors are `Eq`.
+#[test]
+fn test_selectors_are_eq() {
+    fn assert_eq_impl<T: Eq>(_: &T) {}
+    let expr = parse_expr("percentage BETWEEN 10 AND 25");
+    assert_eq_impl(&expr);
+    assert_eq!(expr, parse_expr("percentage BETWEEN 10 AND 25"));
+    assert_ne!(expr, parse_expr("percentage BETWEEN 10 AND 26"));
+    assert!(FiniteNumber::new(f64::NAN).is_none());
+    assert!(FiniteNumber::new(f64::INFINITY).is_none());
+}
@@ -6,7 +6,9 @@ pub mod cbor;
 pub mod check_digits;
 #[cfg(feature = "compression")]
 pub mod compression;
+pub mod conflict_review;
 pub mod convert;
+pub mod dedup;
 pub mod diff;
 pub mod dynvalue;
 pub mod encoding;
@@ -23,8 +25,10 @@ pub mod rules_l1_eid;
 pub mod sensitivity;
 pub mod serde_helpers;
 pub mod structures;
+pub mod temporal;
 pub mod types;
 pub mod union_find;
+pub mod unmerge;
 pub mod validation;
 
 pub use boundary_hash::{BoundaryHashError, boundary_ref_value, decode_salt, generate_file_salt};
@@ -32,7 +36,12 @@ pub use canonical::{CanonicalId, build_identifier_index};
 pub use cbor::{CborError, decode_cbor, encode_cbor};
 #[cfg(feature = "compression")]
 pub use compression::{CompressionError, compress_zstd, decompress_zstd};
+pub use conflict_review::{
+    ConflictDecision, ConflictFilter, ConflictRecord, ConflictResolution, ConflictReviewError,
+    ElementKind, ResolveOutput, list_conflicts, resolve_conflicts,
+};
 pub use convert::{ConvertError, convert};
+pub use dedup::{DedupConfig, DedupOutput, FuzzyMatch, IdentifierGroup, dedup};
 pub use diff::{
     DiffFilter, DiffResult, DiffSummary, EdgeDiff, EdgeRef, EdgesDiff, IdentifierFieldDiff,
     IdentifierSetDiff, LabelSetDiff, NodeDiff, NodeRef, NodesDiff, PropertyChange, diff,
@@ -49,9 +58,14 @@ pub use enums::{
 pub use file::OmtsFile;
 pub use file_parse::{OmtsDecodeError, parse_omts};
 pub use graph::{
-    DEFAULT_MAX_DEPTH, Direction, EdgeWeight, GraphBuildError, NodeWeight, OmtsGraph, QueryError,
-    Selector, SelectorMatchResult, SelectorSet, all_paths, build_graph, detect_cycles, ego_graph,
-    induced_subgraph, reachable_from, selector_match, selector_subgraph, shortest_path,
+    CentralityScore, CompareOp, DEFAULT_MAX_DEPTH, DegreeDistribution, Direction, EdgePattern,
+    EdgePatternError, EdgeWeight, EdgeWeighting, FiniteNumber, GraphBuildError, GraphIndex,
+    GraphMetrics, IndexError, NodeWeight, OmtsGraph, PathCost, PathOptions, PropertyPredicate,
+    PropertyTest, PropertyValue, QueryError, Segment, Segmentation, Selector, SelectorExpr,
+    SelectorExprError, SelectorMatchResult, SelectorSet, WeightedPath, all_paths,
+    apply_segment_labels, build_graph, content_hash, detect_cycles, ego_graph, graph_metrics,
+    induced_subgraph, k_shortest_paths, louvain, path_subgraph, reachable_from, reachable_subgraph,
+    reachable_within, selector_match, selector_subgraph, shortest_path, supply_edge_types,
 };
 pub use identity::{
     EdgeCompositeKey, build_edge_candidate_index, edge_composite_key,
@@ -59,21 +73,32 @@ pub use identity::{
     temporal_compatible,
 };
 pub use merge::{
-    Conflict, ConflictEntry, MergeMetadata, SameAsThreshold, ScalarMergeResult,
-    apply_same_as_edges, build_conflicts_value, merge_identifiers, merge_labels, merge_scalars,
+    Conflict, ConflictEntry, EdgeProvenance, EnrichmentStage, MergeMetadata, NodeProvenance,
+    SameAsThreshold, ScalarMergeResult, apply_same_as_edges, build_conflicts_value,
+    merge_identifiers, merge_labels, merge_scalars,
 };
 pub use merge_pipeline::{
-    MergeConfig, MergeError, MergeOutput, MergeWarning, merge, merge_with_config,
+    ConflictPolicy, EnrichmentBase, GroupExplanation, GroupMember, IdChanges, IdCoalescence,
+    IdSplit, LinkBasis, MergeConfig, MergeError, MergeExplanation, MergeLink, MergeOutput,
+    MergePreview, MergeWarning, PreviewGroup, RejectedCandidate, RejectionReason, StableIds, merge,
+    merge_preview, merge_with_config,
 };
 pub use newtypes::{CalendarDate, CountryCode, EdgeId, FileSalt, NewtypeError, NodeId, SemVer};
 pub use redaction::{
-    EdgeAction, NodeAction, RedactError, classify_edge, classify_node, filter_edge_properties,
-    filter_identifiers, redact,
+    BoundaryMatch, BoundaryResolution, DropReason, DroppedIdentifier, EdgeAction, EdgeOmitReason,
+    EdgePropertyRule, GENERALIZED_KEY, GeneralizeMethod, GeneralizeRule, GeneralizedGeo,
+    GeneralizedProperty, IdentifierRule, MAX_SUBSET_IDENTIFIERS, MIN_MATCHED_VALUE_LEN, NodeAction,
+    NodeDisposition, NodeReason, NodeRule, OmittedEdge, PropertyAction, RedactError, RedactOutput,
+    RedactionPolicy, RedactionReport, StrippedProperty, audit_disclosure, classify_edge,
+    classify_node, filter_edge_properties, filter_identifiers, redact, redact_with_policy,
+    redact_with_report, resolve_boundary_refs,
 };
 pub use sensitivity::{effective_property_sensitivity, effective_sensitivity};
 pub use structures::{Edge, EdgeProperties, Node};
+pub use temporal::{as_of, valid_on};
 pub use types::{DataQuality, Geo, GeoParseError, Identifier, Label, parse_geo};
 pub use union_find::UnionFind;
+pub use unmerge::{UnmergeError, UnmergeOutput, extract_source, split_nodes};
 pub use validation::external::{ExternalDataSource, LeiRecord, NatRegRecord};
 pub use validation::{
     Diagnostic, Level, Location, ParseError, RuleId, Severity, ValidateOutput, ValidationConfig,
@@ -13,6 +13,8 @@
 /// - [`merge_labels`] — set-union of [`Label`] arrays, sorted by `(key, value)`.
 /// - [`Conflict`] / [`ConflictEntry`] — deterministic conflict representation.
 /// - [`MergeMetadata`] — provenance record written into the merged file header.
+/// - [`NodeProvenance`] / [`EdgeProvenance`] — per-element provenance that lets
+///   a merge be split again.
 /// - [`SameAsThreshold`] — configurable confidence gate for `same_as` edges.
 /// - [`apply_same_as_edges`] — feeds qualifying `same_as` edges into a
 ///   [`UnionFind`] structure after the identifier-based pass.
@@ -26,7 +28,11 @@ mod types;
 pub use ops::{
     apply_same_as_edges, build_conflicts_value, merge_identifiers, merge_labels, merge_scalars,
 };
-pub use types::{Conflict, ConflictEntry, MergeMetadata, SameAsThreshold, ScalarMergeResult};
+pub(crate) use types::file_name;
+pub use types::{
+    Conflict, ConflictEntry, EdgeProvenance, EnrichmentStage, MergeMetadata, NodeProvenance,
+    SameAsThreshold, ScalarMergeResult,
+};
 
 #[cfg(test)]
 mod tests;
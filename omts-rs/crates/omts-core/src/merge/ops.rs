@@ -54,6 +54,7 @@ where
         .map(|(json_val, source, _)| ConflictEntry {
             value: json_val,
             source_file: source.to_owned(),
+            reporting_entity: None,
         })
         .collect();
 
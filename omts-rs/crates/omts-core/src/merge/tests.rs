@@ -513,14 +513,18 @@ fn build_conflicts_sorted_by_field() {
             values: vec![ConflictEntry {
                 value: json!("z"),
                 source_file: "a.json".to_owned(),
+                reporting_entity: None,
             }],
+            resolution: None,
         },
         Conflict {
             field: "a_field".to_owned(),
             values: vec![ConflictEntry {
                 value: json!("a"),
                 source_file: "a.json".to_owned(),
+                reporting_entity: None,
             }],
+            resolution: None,
         },
     ];
     let val = build_conflicts_value(conflicts).expect("non-empty conflicts");
@@ -538,12 +542,15 @@ fn build_conflicts_single_conflict_serialises() {
             ConflictEntry {
                 value: json!("Acme"),
                 source_file: "a.json".to_owned(),
+                reporting_entity: None,
             },
             ConflictEntry {
                 value: json!("ACME Corp"),
                 source_file: "b.json".to_owned(),
+                reporting_entity: None,
             },
         ],
+        resolution: None,
     }];
     let val = build_conflicts_value(conflicts).expect("non-empty");
     let arr = val.as_array().expect("array");
@@ -561,6 +568,8 @@ fn merge_metadata_round_trip() {
         merged_node_count: 10,
         merged_edge_count: 5,
         conflict_count: 2,
+        enrichment: None,
+        source_names: vec!["suppliers.omts".to_owned()],
     };
     let json = serde_json::to_string(&meta).expect("serialize");
     let back: MergeMetadata = serde_json::from_str(&json).expect("deserialize");
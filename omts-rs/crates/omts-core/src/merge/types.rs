@@ -79,13 +79,19 @@ pub struct ConflictEntry {
     pub value: serde_json::Value,
     /// The source file that contributed this value.
     pub source_file: String,
+    /// The contributing file's reporting entity, recorded for
+    /// perspective-dependent properties such as `tier` (merge.md Section 4.2)
+    /// so consumers can tell which perspective a value is relative to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reporting_entity: Option<String>,
 }
 
 /// A recorded conflict on a single property within a merge group.
 ///
 /// When two or more source nodes/edges disagree on a scalar property, the
-/// property is omitted from the merged output and a `Conflict` is appended to
-/// the `_conflicts` array (merge.md Section 4.1).
+/// property is omitted from the merged output (unless a conflict policy
+/// resolves it) and a `Conflict` is appended to the `_conflicts` array
+/// (merge.md Section 4.1).
 ///
 /// Entries within a `Conflict` are sorted by `(source_file, json_value)`;
 /// multiple `Conflict` records in a `_conflicts` array are sorted by `field`.
@@ -95,6 +101,41 @@ pub struct Conflict {
     pub field: String,
     /// All distinct values seen for this property, with provenance.
     pub values: Vec<ConflictEntry>,
+    /// Name of the conflict policy that chose the merged value, when one was
+    /// applied (merge.md Section 4.5).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+}
+
+/// One source node folded into a merged node, recorded in the merged node's
+/// `_provenance` array when provenance recording is enabled (merge.md Section
+/// 4.6).
+///
+/// Enough to split the merged node back into its constituents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeProvenance {
+    /// The source file that contributed the node.
+    pub source_file: String,
+    /// The node's ID in its source file.
+    pub node_id: String,
+    /// Canonical forms of the identifiers the node carried, including
+    /// `internal` ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identifiers: Vec<String>,
+}
+
+/// One source edge folded into a merged edge, recorded in the merged edge's
+/// `properties._provenance` array when provenance recording is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeProvenance {
+    /// The source file that contributed the edge.
+    pub source_file: String,
+    /// The edge's ID in its source file.
+    pub edge_id: String,
+    /// The edge's `source` node ID in its source file.
+    pub source: String,
+    /// The edge's `target` node ID in its source file.
+    pub target: String,
 }
 
 /// Provenance record written into the merged file header.
@@ -117,6 +158,55 @@ pub struct MergeMetadata {
     pub merged_edge_count: usize,
     /// Total number of conflicts recorded across all nodes and edges.
     pub conflict_count: usize,
+    /// Whether the merge ran before or after identifier enrichment, when
+    /// known (merge-semantics Section 9).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrichment: Option<EnrichmentStage>,
+    /// File names of the inputs, in input order: entry `i` names the input
+    /// labelled `file_{i}`. Recorded with provenance (merge.md Section 4.6) so
+    /// a source can be extracted by name; empty when the names are unknown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_names: Vec<String>,
+}
+
+impl MergeMetadata {
+    /// Returns the `file_{i}` label of the input named `source`, matched
+    /// against [`MergeMetadata::source_names`] by its file name.
+    ///
+    /// `Ok(None)` when no input has that name; `Err` with the matching labels
+    /// when several do.
+    pub fn label_for_name(&self, source: &str) -> Result<Option<String>, Vec<String>> {
+        let name = file_name(source);
+        let labels: Vec<String> = self
+            .source_names
+            .iter()
+            .enumerate()
+            .filter(|(_, recorded)| recorded.as_str() == name)
+            .map(|(i, _)| format!("file_{i}"))
+            .collect();
+        match labels.len() {
+            0 => Ok(None),
+            1 => Ok(labels.into_iter().next()),
+            _ => Err(labels),
+        }
+    }
+}
+
+/// The last component of `path`, split on `/` and `\`.
+pub(crate) fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// The stage of an enrichment workflow a merge was performed at, recorded in
+/// [`MergeMetadata::enrichment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichmentStage {
+    /// The inputs had not yet been enriched.
+    PreEnrichment,
+    /// The inputs were enriched after an earlier merge, and links new since
+    /// that merge were proposed rather than merged.
+    PostEnrichment,
 }
 
 /// Result of merging N optional scalar values from a merge group.
@@ -0,0 +1,152 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::file::OmtsFile;
+use crate::merge::NodeProvenance;
+use crate::structures::Node;
+use crate::union_find::UnionFind;
+
+use super::types::MergeError;
+
+/// The merge groups of an earlier, pre-enrichment merge, used to hold back
+/// links that enrichment formed since (merge.md Section 4.8).
+///
+/// Set [`MergeConfig::enrichment_base`](super::MergeConfig::enrichment_base)
+/// to use it: input nodes are placed in the base's merge groups through the
+/// base's `_provenance` records, and a link that would extend a base group
+/// becomes a proposed `same_as` edge instead of a merge.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichmentBase {
+    /// `(source file label, node ID)` -> position of its merged node in the
+    /// base file.
+    groups: HashMap<(String, String), usize>,
+    /// Number of input files the base was merged from.
+    input_count: usize,
+}
+
+impl EnrichmentBase {
+    /// Reads the merge groups of `base` from its nodes' `_provenance`.
+    ///
+    /// # Errors
+    ///
+    /// [`MergeError::InvalidEnrichmentBase`] — `merge_metadata.source_files`
+    /// is missing, a `_provenance` record is malformed, or no node carries
+    /// one (the base was merged without
+    /// [`MergeConfig::record_provenance`](super::MergeConfig::record_provenance)).
+    pub fn from_file(base: &OmtsFile) -> Result<Self, MergeError> {
+        let input_count = base
+            .extra
+            .get("merge_metadata")
+            .map(|m| serde_json::Value::from(m.clone()))
+            .and_then(|m| m.get("source_files")?.as_array().map(Vec::len))
+            .ok_or_else(|| {
+                MergeError::InvalidEnrichmentBase(
+                    "no `merge_metadata.source_files`; the base is not a merge result".to_owned(),
+                )
+            })?;
+        let mut groups = HashMap::new();
+        for (pos, node) in base.nodes.iter().enumerate() {
+            let Some(value) = node.extra.get("_provenance") else {
+                continue;
+            };
+            let entries: Vec<NodeProvenance> =
+                serde_json::from_value(serde_json::Value::from(value.clone())).map_err(|e| {
+                    MergeError::InvalidEnrichmentBase(format!("node `{}`: {e}", node.id))
+                })?;
+            for entry in entries {
+                groups.insert((entry.source_file, entry.node_id), pos);
+            }
+        }
+        if groups.is_empty() {
+            return Err(MergeError::InvalidEnrichmentBase(
+                "no node carries `_provenance`".to_owned(),
+            ));
+        }
+        Ok(Self {
+            groups,
+            input_count,
+        })
+    }
+
+    /// Checks that `files` are the inputs of the base merge, in its order.
+    ///
+    /// Input nodes are placed in base groups by position (`file_N`) and ID,
+    /// so reordered, added, or removed inputs would map to the wrong groups.
+    ///
+    /// # Errors
+    ///
+    /// [`MergeError::InvalidEnrichmentBase`] — the number of inputs differs
+    /// from the base's `merge_metadata.source_files`, or a `_provenance`
+    /// record names a node that its input does not contain.
+    pub(super) fn check_inputs(&self, files: &[OmtsFile]) -> Result<(), MergeError> {
+        if files.len() != self.input_count {
+            return Err(MergeError::InvalidEnrichmentBase(format!(
+                "the base was merged from {} input files, but {} were given",
+                self.input_count,
+                files.len()
+            )));
+        }
+        let ids: Vec<HashSet<&str>> = files
+            .iter()
+            .map(|f| f.nodes.iter().map(|n| &*n.id).collect())
+            .collect();
+        let mut members: Vec<&(String, String)> = self.groups.keys().collect();
+        members.sort();
+        for (label, node_id) in members {
+            let input = label
+                .strip_prefix("file_")
+                .and_then(|i| i.parse::<usize>().ok())
+                .and_then(|i| ids.get(i));
+            if !input.is_some_and(|ids| ids.contains(node_id.as_str())) {
+                return Err(MergeError::InvalidEnrichmentBase(format!(
+                    "`_provenance` names node `{node_id}` of {label}, which is not in that \
+                     input; pass the inputs in the order of the base merge"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Applies only the links an [`EnrichmentBase`] already established.
+///
+/// Each set tracks the base group of its members. Two sets combine if they
+/// belong to the same base group or if neither contains a base node; a link
+/// from a base group to another base group, or to nodes the base does not
+/// contain, is held back.
+pub(super) struct BaseGuard {
+    /// Base group of the set rooted at each ordinal.
+    set_group: Vec<Option<usize>>,
+}
+
+impl BaseGuard {
+    /// Looks up each input node (`all_nodes`, from file `node_origins`) in
+    /// the base.
+    pub(super) fn new(base: &EnrichmentBase, all_nodes: &[Node], node_origins: &[usize]) -> Self {
+        let set_group = all_nodes
+            .iter()
+            .zip(node_origins)
+            .map(|(node, &file_idx)| {
+                let key = (format!("file_{file_idx}"), node.id.to_string());
+                base.groups.get(&key).copied()
+            })
+            .collect();
+        Self { set_group }
+    }
+
+    /// Joins `a` and `b` in `uf`, or returns `false` if their sets differ in
+    /// base group.
+    pub(super) fn union(&mut self, uf: &mut UnionFind, a: usize, b: usize) -> bool {
+        let (root_a, root_b) = (uf.find(a), uf.find(b));
+        if root_a == root_b {
+            return true;
+        }
+        let group = self.set_group[root_a];
+        if group != self.set_group[root_b] {
+            return false;
+        }
+        uf.union(a, b);
+        let root = uf.find(a);
+        self.set_group[root] = group;
+        true
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::newtypes::NodeId;
+use crate::union_find::UnionFind;
+
+/// Why each merge group was formed and which candidates were turned down,
+/// produced when [`MergeConfig::explain`](super::MergeConfig::explain) is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeExplanation {
+    /// One entry per merged node built from two or more source nodes, in
+    /// merged-node order.
+    pub groups: Vec<GroupExplanation>,
+    /// Candidate pairs that were not merged, with the reason. Pairs that ended
+    /// up in the same group through another link are omitted.
+    pub rejected: Vec<RejectedCandidate>,
+}
+
+/// How one merged node came to combine its members.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupExplanation {
+    /// ID of the merged node in the output file.
+    pub merged_id: NodeId,
+    /// The source nodes combined into the merged node, in input order.
+    pub members: Vec<GroupMember>,
+    /// The links that connected the members: one fewer than the member count.
+    ///
+    /// Members that share no identifier are joined through a chain of links
+    /// (A–B by LEI, B–C by DUNS), which is how transitive merges show up.
+    pub links: Vec<MergeLink>,
+}
+
+/// A source node, identified by its input file and file-local ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupMember {
+    /// Source-file label (`file_{i}`, as in conflict records).
+    pub source_file: String,
+    /// Display name of the input file, from
+    /// [`MergeConfig::source_paths`](super::MergeConfig::source_paths).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    /// The node's ID in its source file.
+    pub node_id: NodeId,
+}
+
+/// A link between two source nodes that put them in the same group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeLink {
+    /// One endpoint.
+    pub a: GroupMember,
+    /// The other endpoint.
+    pub b: GroupMember,
+    /// What linked them.
+    #[serde(flatten)]
+    pub basis: LinkBasis,
+}
+
+/// The evidence behind a [`MergeLink`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "basis", rename_all = "snake_case")]
+pub enum LinkBasis {
+    /// Both nodes carry this identifier (canonical form, e.g. `lei:5493...`).
+    Identifier {
+        /// Canonical form of the shared identifier.
+        identifier: String,
+    },
+    /// A `same_as` edge asserted the nodes are the same entity.
+    SameAs {
+        /// ID of the `same_as` edge in its source file.
+        edge_id: String,
+        /// The edge's `confidence`, if any.
+        confidence: Option<String>,
+    },
+}
+
+/// A candidate pair that was not merged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RejectedCandidate {
+    /// One endpoint.
+    pub a: GroupMember,
+    /// The other endpoint.
+    pub b: GroupMember,
+    /// Why the pair was not merged.
+    #[serde(flatten)]
+    pub reason: RejectionReason,
+}
+
+/// Why a [`RejectedCandidate`] was not merged.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum RejectionReason {
+    /// The nodes share an identifier whose validity periods do not overlap.
+    TemporalIncompatibility {
+        /// Canonical form of the shared identifier.
+        identifier: String,
+    },
+    /// The nodes share an identifier value but with different authorities.
+    AuthorityMismatch {
+        /// Canonical form of the shared identifier.
+        identifier: String,
+    },
+    /// The shared LEI is annulled and therefore excluded from matching.
+    AnnulledLei {
+        /// Canonical form of the annulled LEI.
+        identifier: String,
+    },
+    /// A `same_as` edge joins the nodes, but its confidence is below the
+    /// configured threshold.
+    BelowSameAsThreshold {
+        /// ID of the `same_as` edge in its source file.
+        edge_id: String,
+        /// The edge's `confidence`, if any.
+        confidence: Option<String>,
+    },
+    /// The nodes are linked, but the link would join two merge groups of the
+    /// enrichment base; shared identifiers are proposed as a `same_as` edge.
+    EnrichmentMatch {
+        /// The link that was held back.
+        link: LinkBasis,
+    },
+}
+
+/// Collects links and rejections by node ordinal while the pipeline runs.
+#[derive(Default)]
+pub(super) struct Explainer {
+    links: Vec<(usize, usize, LinkBasis)>,
+    rejections: Vec<(usize, usize, RejectionReason)>,
+}
+
+impl Explainer {
+    /// Records evidence that nodes `a` and `b` are the same entity.
+    pub(super) fn link(&mut self, a: usize, b: usize, basis: LinkBasis) {
+        self.links.push((a.min(b), a.max(b), basis));
+    }
+
+    /// Records a candidate pair the identity rules turned down.
+    pub(super) fn reject(&mut self, a: usize, b: usize, reason: RejectionReason) {
+        if a != b {
+            self.rejections.push((a.min(b), a.max(b), reason));
+        }
+    }
+
+    /// Builds the explanation once the merge groups are final.
+    ///
+    /// `merged_nodes` lists `(merged ID, representative)` in output order,
+    /// `groups` maps a representative to its member ordinals, and `member`
+    /// describes a node ordinal.
+    pub(super) fn finish(
+        mut self,
+        merged_nodes: &[(NodeId, usize)],
+        groups: &HashMap<usize, Vec<usize>>,
+        uf: &mut UnionFind,
+        member: impl Fn(usize) -> GroupMember,
+    ) -> MergeExplanation {
+        self.links.sort();
+        self.links.dedup();
+        let mut links_by_group: HashMap<usize, Vec<(usize, usize, LinkBasis)>> = HashMap::new();
+        for link in self.links {
+            links_by_group
+                .entry(uf.find(link.0))
+                .or_default()
+                .push(link);
+        }
+
+        let mut explained = Vec::new();
+        for (merged_id, rep) in merged_nodes {
+            let Some(members) = groups.get(rep).filter(|m| m.len() > 1) else {
+                continue;
+            };
+            // Keep the first link (in sorted order) joining each pair of
+            // components: a spanning tree of the group.
+            let position: HashMap<usize, usize> = members
+                .iter()
+                .enumerate()
+                .map(|(i, &ord)| (ord, i))
+                .collect();
+            let mut tree = UnionFind::new(members.len());
+            let mut links = Vec::new();
+            for (a, b, basis) in links_by_group.remove(rep).unwrap_or_default() {
+                let (Some(&pa), Some(&pb)) = (position.get(&a), position.get(&b)) else {
+                    continue;
+                };
+                if tree.find(pa) != tree.find(pb) {
+                    tree.union(pa, pb);
+                    links.push(MergeLink {
+                        a: member(a),
+                        b: member(b),
+                        basis,
+                    });
+                }
+            }
+            let mut ordered = members.clone();
+            ordered.sort_unstable();
+            explained.push(GroupExplanation {
+                merged_id: merged_id.clone(),
+                members: ordered.into_iter().map(&member).collect(),
+                links,
+            });
+        }
+
+        self.rejections.sort();
+        self.rejections.dedup();
+        let rejected = self
+            .rejections
+            .into_iter()
+            .filter(|(a, b, _)| uf.find(*a) != uf.find(*b))
+            .map(|(a, b, reason)| RejectedCandidate {
+                a: member(a),
+                b: member(b),
+                reason,
+            })
+            .collect();
+
+        MergeExplanation {
+            groups: explained,
+            rejected,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use crate::canonical::CanonicalId;
+use crate::enums::{EdgeType, EdgeTypeTag};
+use crate::file::OmtsFile;
+use crate::identity::{identifiers_match, is_lei_annulled, temporal_compatible};
+use crate::structures::Node;
+use crate::types::Identifier;
+use crate::union_find::UnionFind;
+
+use super::enrichment::BaseGuard;
+use super::explain::{Explainer, LinkBasis, RejectionReason};
+use super::types::MergeConfig;
+
+/// The input nodes and the union-find grouping them into merge groups.
+pub(super) struct NodeGroups<'a> {
+    /// Every input node, in input-file order; indices are node ordinals.
+    pub(super) all_nodes: Vec<Node>,
+    /// Input-file index of each node ordinal.
+    pub(super) node_origins: Vec<usize>,
+    /// Per input file: local node ID -> node ordinal.
+    pub(super) per_file_id_maps: Vec<HashMap<&'a str, usize>>,
+    /// Canonical identifier -> ordinals of the nodes carrying it (annulled
+    /// LEIs and `internal` identifiers excluded).
+    pub(super) id_index: HashMap<CanonicalId, Vec<usize>>,
+    /// Union-find over node ordinals; each root is one merge group.
+    pub(super) uf: UnionFind,
+    /// Node-ordinal pairs sharing an identifier that
+    /// [`MergeConfig::enrichment_base`] kept apart, in discovery order.
+    pub(super) proposals: Vec<(usize, usize)>,
+}
+
+/// Resolves node identity across `files` (merge.md Steps 1-2): nodes sharing
+/// a matching identifier, or joined by a `same_as` edge at or above
+/// [`MergeConfig::same_as_threshold`], end up in the same union-find set.
+///
+/// With [`MergeConfig::enrichment_base`], links that would extend a base
+/// group are not applied; identifier links among them are returned as
+/// proposals.
+///
+/// With an `explainer`, each link and each rejected candidate pair is
+/// recorded.
+pub(super) fn resolve_groups<'a>(
+    files: &'a [OmtsFile],
+    config: &MergeConfig,
+    mut explainer: Option<&mut Explainer>,
+) -> NodeGroups<'a> {
+    let mut all_nodes: Vec<Node> = Vec::new();
+    let mut node_origins: Vec<usize> = Vec::new();
+
+    for (file_idx, file) in files.iter().enumerate() {
+        for node in &file.nodes {
+            all_nodes.push(node.clone());
+            node_origins.push(file_idx);
+        }
+    }
+
+    let mut id_index: HashMap<CanonicalId, Vec<usize>> = HashMap::new();
+    let mut annulled_index: HashMap<CanonicalId, Vec<usize>> = HashMap::new();
+    for (node_idx, node) in all_nodes.iter().enumerate() {
+        let Some(identifiers) = node.identifiers.as_ref() else {
+            continue;
+        };
+        for id in identifiers {
+            if id.scheme == "internal" {
+                continue;
+            }
+            if is_lei_annulled(id) {
+                if explainer.is_some() {
+                    let canonical = CanonicalId::from_identifier(id);
+                    annulled_index.entry(canonical).or_default().push(node_idx);
+                }
+                continue;
+            }
+            let canonical = CanonicalId::from_identifier(id);
+            id_index.entry(canonical).or_default().push(node_idx);
+        }
+    }
+
+    let mut uf = UnionFind::new(all_nodes.len());
+    let mut guard = config
+        .enrichment_base
+        .as_ref()
+        .map(|base| BaseGuard::new(base, &all_nodes, &node_origins));
+    let mut proposals: Vec<(usize, usize)> = Vec::new();
+
+    // Links are applied in canonical-ID order, each list in ordinal order:
+    // with an enrichment base, which link is held back depends on the order.
+    let mut links: Vec<(&CanonicalId, &Vec<usize>)> = id_index.iter().collect();
+    links.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    for (canonical, node_indices) in links {
+        if node_indices.len() < 2 {
+            continue;
+        }
+        for i in 0..node_indices.len() {
+            for j in (i + 1)..node_indices.len() {
+                let idx_a = node_indices[i];
+                let idx_b = node_indices[j];
+                let node_a = &all_nodes[idx_a];
+                let node_b = &all_nodes[idx_b];
+                let ids_a = node_a.identifiers.as_deref().unwrap_or(&[]);
+                let ids_b = node_b.identifiers.as_deref().unwrap_or(&[]);
+                let mut matched = None;
+                'outer: for id_a in ids_a {
+                    for id_b in ids_b {
+                        if identifiers_match(id_a, id_b) {
+                            matched = Some(id_a);
+                            break 'outer;
+                        }
+                    }
+                }
+                let joined = matched.is_some() && join(&mut uf, guard.as_mut(), idx_a, idx_b);
+                if matched.is_some() && !joined {
+                    proposals.push((idx_a, idx_b));
+                }
+                if let Some(explainer) = explainer.as_mut() {
+                    match matched {
+                        Some(id) => {
+                            let basis = LinkBasis::Identifier {
+                                identifier: CanonicalId::from_identifier(id).into_string(),
+                            };
+                            if joined {
+                                explainer.link(idx_a, idx_b, basis);
+                            } else {
+                                let reason = RejectionReason::EnrichmentMatch { link: basis };
+                                explainer.reject(idx_a, idx_b, reason);
+                            }
+                        }
+                        None => {
+                            if let Some(reason) = identifier_rejection(canonical, ids_a, ids_b) {
+                                explainer.reject(idx_a, idx_b, reason);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut file_node_offsets: Vec<usize> = Vec::with_capacity(files.len());
+    {
+        let mut offset = 0usize;
+        for file in files.iter() {
+            file_node_offsets.push(offset);
+            offset += file.nodes.len();
+        }
+    }
+
+    let mut per_file_id_maps: Vec<HashMap<&str, usize>> = Vec::with_capacity(files.len());
+    for (file_idx, file) in files.iter().enumerate() {
+        let offset = file_node_offsets[file_idx];
+        let mut map: HashMap<&str, usize> = HashMap::new();
+        for (local_idx, node) in file.nodes.iter().enumerate() {
+            map.insert(node.id.as_ref(), offset + local_idx);
+        }
+        per_file_id_maps.push(map);
+    }
+
+    let edges = files
+        .iter()
+        .enumerate()
+        .flat_map(|(file_idx, file)| file.edges.iter().map(move |edge| (file_idx, edge)));
+    for (file_idx, edge) in edges {
+        let is_same_as = matches!(&edge.edge_type, EdgeTypeTag::Known(EdgeType::SameAs));
+        if !is_same_as {
+            continue;
+        }
+
+        let id_map = &per_file_id_maps[file_idx];
+
+        let confidence_str: Option<&str> = edge
+            .properties
+            .extra
+            .get("confidence")
+            .and_then(|v| v.as_str())
+            .or_else(|| edge.extra.get("confidence").and_then(|v| v.as_str()));
+
+        let Some(&src_ord) = id_map.get(&*edge.source as &str) else {
+            continue;
+        };
+        let Some(&tgt_ord) = id_map.get(&*edge.target as &str) else {
+            continue;
+        };
+
+        let honoured = config.same_as_threshold.honours(confidence_str);
+        let joined = honoured && join(&mut uf, guard.as_mut(), src_ord, tgt_ord);
+        if let Some(explainer) = explainer.as_mut() {
+            let edge_id = edge.id.to_string();
+            let confidence = confidence_str.map(str::to_owned);
+            if joined {
+                explainer.link(
+                    src_ord,
+                    tgt_ord,
+                    LinkBasis::SameAs {
+                        edge_id,
+                        confidence,
+                    },
+                );
+            } else if honoured {
+                let link = LinkBasis::SameAs {
+                    edge_id,
+                    confidence,
+                };
+                explainer.reject(src_ord, tgt_ord, RejectionReason::EnrichmentMatch { link });
+            } else {
+                let reason = RejectionReason::BelowSameAsThreshold {
+                    edge_id,
+                    confidence,
+                };
+                explainer.reject(src_ord, tgt_ord, reason);
+            }
+        }
+    }
+
+    if let Some(explainer) = explainer {
+        let mut annulled_links: Vec<(&CanonicalId, &Vec<usize>)> = annulled_index.iter().collect();
+        annulled_links.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        for (canonical, annulled) in annulled_links {
+            let live = id_index.get(canonical).map_or(&[][..], Vec::as_slice);
+            for (i, &a) in annulled.iter().enumerate() {
+                for &b in annulled[i + 1..].iter().chain(live) {
+                    let reason = RejectionReason::AnnulledLei {
+                        identifier: canonical.as_str().to_owned(),
+                    };
+                    explainer.reject(a, b, reason);
+                }
+            }
+        }
+    }
+
+    NodeGroups {
+        all_nodes,
+        node_origins,
+        per_file_id_maps,
+        id_index,
+        uf,
+        proposals,
+    }
+}
+
+/// Joins `a` and `b`, through `guard` when an enrichment base is set; returns
+/// whether they are now in one group.
+fn join(uf: &mut UnionFind, guard: Option<&mut BaseGuard>, a: usize, b: usize) -> bool {
+    match guard {
+        Some(guard) => guard.union(uf, a, b),
+        None => {
+            uf.union(a, b);
+            true
+        }
+    }
+}
+
+/// Explains why two nodes indexed under `canonical` did not match, for the
+/// merge explanation.
+fn identifier_rejection(
+    canonical: &CanonicalId,
+    ids_a: &[Identifier],
+    ids_b: &[Identifier],
+) -> Option<RejectionReason> {
+    let shared = |ids: &[Identifier]| -> Vec<Identifier> {
+        ids.iter()
+            .filter(|id| CanonicalId::from_identifier(id) == *canonical)
+            .cloned()
+            .collect()
+    };
+    let (shared_a, shared_b) = (shared(ids_a), shared(ids_b));
+    let identifier = canonical.as_str().to_owned();
+    let temporal_clash = shared_a
+        .iter()
+        .any(|a| shared_b.iter().any(|b| !temporal_compatible(a, b)));
+    if temporal_clash {
+        Some(RejectionReason::TemporalIncompatibility { identifier })
+    } else if shared_a.is_empty() || shared_b.is_empty() {
+        None
+    } else {
+        Some(RejectionReason::AuthorityMismatch { identifier })
+    }
+}
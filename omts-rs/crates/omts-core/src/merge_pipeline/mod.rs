@@ -6,17 +6,48 @@
 /// 1. Identifier-index construction and union-find for node identity resolution.
 /// 2. `same_as` edge processing to extend merge groups.
 /// 3. Merge-group safety-limit warnings.
-/// 4. Per-group property merge (scalars, identifiers, labels, conflicts).
-/// 5. Edge candidate grouping and property merge.
+/// 4. Per-group property merge (scalars, identifiers, labels, conflicts),
+///    filling conflicting properties per the configured conflict policies.
+/// 5. Edge candidate grouping and property merge, reconciling the
+///    perspective-dependent `tier` property.
 /// 6. Deterministic output ordering.
 /// 7. Post-merge L1 validation.
 ///
+/// With [`MergeConfig::explain`], the links (shared identifiers, `same_as`
+/// edges) behind each merge group and the rejected candidate pairs are
+/// reported in [`MergeOutput::explanation`].
+///
+/// With [`MergeConfig::stable_ids`], merged nodes and edges keep the IDs
+/// they had in a previous merge, and [`MergeOutput::id_changes`] reports the
+/// IDs that were minted, split, coalesced, or retired.
+///
+/// With [`MergeConfig::enrichment_base`], only links already established by
+/// a pre-enrichment merge combine its groups; new ones are proposed as
+/// `same_as` edges for review.
+///
+/// [`merge_preview`] runs only the identity resolution of steps 1-3 and
+/// reports the resulting groups and expected conflicts.
+///
 /// The primary entry point is [`merge`].
+mod enrichment;
+mod explain;
+mod groups;
 mod pipeline;
+mod policy;
+mod preview;
+mod stable;
+mod tier;
 mod types;
 
+pub use enrichment::EnrichmentBase;
+pub use explain::{
+    GroupExplanation, GroupMember, LinkBasis, MergeExplanation, MergeLink, RejectedCandidate,
+    RejectionReason,
+};
 pub use pipeline::{merge, merge_with_config};
-pub use types::{MergeConfig, MergeError, MergeOutput, MergeWarning};
+pub use preview::{MergePreview, PreviewGroup, merge_preview};
+pub use stable::{IdChanges, IdCoalescence, IdSplit, StableIds};
+pub use types::{ConflictPolicy, MergeConfig, MergeError, MergeOutput, MergeWarning};
 
 #[cfg(test)]
 mod tests;
@@ -1,14 +1,14 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use crate::boundary_hash::generate_file_salt;
 use crate::canonical::CanonicalId;
 use crate::dynvalue::DynValue;
 use crate::enums::{EdgeType, EdgeTypeTag};
 use crate::file::OmtsFile;
-use crate::identity::{edges_match, identifiers_match, is_lei_annulled};
+use crate::identity::{edges_match, is_lei_annulled};
 use crate::merge::{
-    Conflict, MergeMetadata, ScalarMergeResult, build_conflicts_value, merge_identifiers,
-    merge_labels, merge_scalars,
+    Conflict, EdgeProvenance, EnrichmentStage, MergeMetadata, NodeProvenance, ScalarMergeResult,
+    build_conflicts_value, file_name, merge_identifiers, merge_labels, merge_scalars,
 };
 use crate::newtypes::{CalendarDate, NodeId};
 use crate::structures::{Edge, EdgeProperties, Node};
@@ -16,6 +16,11 @@ use crate::types::Identifier;
 use crate::union_find::UnionFind;
 use crate::validation::{ValidationConfig, validate};
 
+use super::explain::{Explainer, GroupMember};
+use super::groups::{NodeGroups, resolve_groups};
+use super::policy::{NodeScalars, PolicyResolver, ScalarSource, node_sources, validate_policies};
+use super::stable::{EdgeIds, constituent_key, match_keys};
+use super::tier::Perspectives;
 use super::types::{MergeConfig, MergeError, MergeOutput, MergeWarning};
 
 /// Merges two or more OMTS files into a single deduplicated file.
@@ -45,6 +50,17 @@ pub fn merge_with_config(
     if files.is_empty() {
         return Err(MergeError::NoInputFiles);
     }
+    validate_policies(&config.conflict_policies, files.len())?;
+    if let Some(base) = &config.enrichment_base {
+        if config.pre_enrichment {
+            return Err(MergeError::InvalidEnrichmentBase(
+                "a merge against an enrichment base cannot also be pre-enrichment".to_owned(),
+            ));
+        }
+        base.check_inputs(files)?;
+    }
+    let resolver = PolicyResolver::new(&config.conflict_policies);
+    let mut explainer = config.explain.then(Explainer::default);
 
     let source_labels: Vec<String> = files
         .iter()
@@ -52,87 +68,18 @@ pub fn merge_with_config(
         .map(|(i, _)| format!("file_{i}"))
         .collect();
 
-    let mut all_nodes: Vec<Node> = Vec::new();
-    let mut node_origins: Vec<usize> = Vec::new();
-
-    for (file_idx, file) in files.iter().enumerate() {
-        for node in &file.nodes {
-            all_nodes.push(node.clone());
-            node_origins.push(file_idx);
-        }
-    }
-
+    let NodeGroups {
+        all_nodes,
+        node_origins,
+        per_file_id_maps,
+        id_index,
+        mut uf,
+        proposals,
+    } = resolve_groups(files, config, explainer.as_mut());
     let total_nodes = all_nodes.len();
 
-    let mut id_index: HashMap<CanonicalId, Vec<usize>> = HashMap::new();
-    for (node_idx, node) in all_nodes.iter().enumerate() {
-        let Some(identifiers) = node.identifiers.as_ref() else {
-            continue;
-        };
-        for id in identifiers {
-            if id.scheme == "internal" {
-                continue;
-            }
-            if is_lei_annulled(id) {
-                continue;
-            }
-            let canonical = CanonicalId::from_identifier(id);
-            id_index.entry(canonical).or_default().push(node_idx);
-        }
-    }
-
-    let mut uf = UnionFind::new(total_nodes);
-
-    for node_indices in id_index.values() {
-        if node_indices.len() < 2 {
-            continue;
-        }
-        for i in 0..node_indices.len() {
-            for j in (i + 1)..node_indices.len() {
-                let idx_a = node_indices[i];
-                let idx_b = node_indices[j];
-                let node_a = &all_nodes[idx_a];
-                let node_b = &all_nodes[idx_b];
-                let ids_a = node_a.identifiers.as_deref().unwrap_or(&[]);
-                let ids_b = node_b.identifiers.as_deref().unwrap_or(&[]);
-                let mut matched = false;
-                'outer: for id_a in ids_a {
-                    for id_b in ids_b {
-                        if identifiers_match(id_a, id_b) {
-                            matched = true;
-                            break 'outer;
-                        }
-                    }
-                }
-                if matched {
-                    uf.union(idx_a, idx_b);
-                }
-            }
-        }
-    }
-
     let mut all_edges: Vec<Edge> = Vec::new();
     let mut edge_origins: Vec<usize> = Vec::new();
-
-    let mut file_node_offsets: Vec<usize> = Vec::with_capacity(files.len());
-    {
-        let mut offset = 0usize;
-        for file in files.iter() {
-            file_node_offsets.push(offset);
-            offset += file.nodes.len();
-        }
-    }
-
-    let mut per_file_id_maps: Vec<HashMap<&str, usize>> = Vec::with_capacity(files.len());
-    for (file_idx, file) in files.iter().enumerate() {
-        let offset = file_node_offsets[file_idx];
-        let mut map: HashMap<&str, usize> = HashMap::new();
-        for (local_idx, node) in file.nodes.iter().enumerate() {
-            map.insert(node.id.as_ref(), offset + local_idx);
-        }
-        per_file_id_maps.push(map);
-    }
-
     for (file_idx, file) in files.iter().enumerate() {
         for edge in &file.edges {
             all_edges.push(edge.clone());
@@ -140,35 +87,30 @@ pub fn merge_with_config(
         }
     }
 
-    for (edge_idx, edge) in all_edges.iter().enumerate() {
-        let is_same_as = matches!(&edge.edge_type, EdgeTypeTag::Known(EdgeType::SameAs));
-        if !is_same_as {
-            continue;
-        }
-
-        let file_idx = edge_origins[edge_idx];
-        let id_map = &per_file_id_maps[file_idx];
-
-        let confidence_str: Option<&str> = edge
-            .properties
-            .extra
-            .get("confidence")
-            .and_then(|v| v.as_str())
-            .or_else(|| edge.extra.get("confidence").and_then(|v| v.as_str()));
-
-        if !config.same_as_threshold.honours(confidence_str) {
-            continue;
+    let perspective_groups: Vec<Option<usize>> = files
+        .iter()
+        .enumerate()
+        .map(|(file_idx, file)| {
+            let entity = file.reporting_entity.as_ref()?;
+            let ord = per_file_id_maps[file_idx].get(&**entity as &str).copied()?;
+            Some(uf.find(ord))
+        })
+        .collect();
+    let primary_perspective = match config.primary_perspective {
+        None => None,
+        Some(idx) if idx >= files.len() => {
+            return Err(MergeError::InvalidPrimaryPerspective(format!(
+                "file index {idx} is out of range for {} input file(s)",
+                files.len()
+            )));
         }
-
-        let Some(&src_ord) = id_map.get(&*edge.source as &str) else {
-            continue;
-        };
-        let Some(&tgt_ord) = id_map.get(&*edge.target as &str) else {
-            continue;
-        };
-
-        uf.union(src_ord, tgt_ord);
-    }
+        Some(idx) => Some(perspective_groups[idx].ok_or_else(|| {
+            MergeError::InvalidPrimaryPerspective(format!(
+                "{} has no reporting_entity that resolves to a node",
+                source_labels[idx]
+            ))
+        })?),
+    };
 
     let mut warnings: Vec<MergeWarning> = Vec::new();
 
@@ -227,23 +169,74 @@ pub fn merge_with_config(
 
     let mut conflict_count = 0usize;
 
-    for (idx, (_key, rep)) in group_sort_keys.iter().enumerate() {
-        let new_id_str = format!("n-{idx}");
-        let new_id = NodeId::try_from(new_id_str.as_str())
-            .map_err(|e| MergeError::InternalDataError(e.to_string()))?;
+    let (new_ids, id_changes) = match &config.stable_ids {
+        Some(stable) => {
+            let group_keys: Vec<BTreeSet<String>> = group_sort_keys
+                .iter()
+                .map(|(_key, rep)| {
+                    groups[rep]
+                        .iter()
+                        .flat_map(|&ord| {
+                            let node = &all_nodes[ord];
+                            let source = &source_labels[node_origins[ord]];
+                            match_keys(&node.node_type, node.identifiers.as_deref())
+                                .into_iter()
+                                .chain([constituent_key(&node.node_type, source, &node.id)])
+                        })
+                        .collect()
+                })
+                .collect();
+            let (ids, changes) = stable.assign_node_ids(&group_keys)?;
+            (ids, Some(changes))
+        }
+        None => {
+            let ids = (0..group_sort_keys.len())
+                .map(|idx| {
+                    NodeId::try_from(format!("n-{idx}").as_str())
+                        .map_err(|e| MergeError::InternalDataError(e.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            (ids, None)
+        }
+    };
+    for (new_id, (_key, rep)) in new_ids.into_iter().zip(&group_sort_keys) {
         rep_to_new_id.insert(*rep, new_id.clone());
         merged_nodes.push((new_id, *rep));
     }
 
+    let explanation = explainer.map(|explainer| {
+        explainer.finish(&merged_nodes, &groups, &mut uf, |ord| GroupMember {
+            source_file: source_labels[node_origins[ord]].clone(),
+            source_path: config.source_paths.get(node_origins[ord]).cloned(),
+            node_id: all_nodes[ord].id.clone(),
+        })
+    });
+
+    let perspective_labels: Vec<Option<String>> = files
+        .iter()
+        .zip(&perspective_groups)
+        .map(|(file, group)| {
+            group
+                .and_then(|g| rep_to_new_id.get(&g))
+                .or(file.reporting_entity.as_ref())
+                .map(ToString::to_string)
+        })
+        .collect();
+    let perspectives =
+        Perspectives::new(perspective_groups, perspective_labels, primary_perspective);
+
     let mut output_nodes: Vec<Node> = Vec::new();
 
     for (new_id, rep) in &merged_nodes {
         let member_ordinals = &groups[rep];
 
-        let src_labels_for_group: Vec<&str> = member_ordinals
-            .iter()
-            .map(|&ord| source_labels[node_origins[ord]].as_str())
-            .collect();
+        let sources = node_sources(
+            member_ordinals,
+            &all_nodes,
+            &node_origins,
+            files,
+            &source_labels,
+        );
 
         let id_slices: Vec<Option<&[Identifier]>> = member_ordinals
             .iter()
@@ -257,47 +250,41 @@ pub fn merge_with_config(
             .collect();
         let merged_labels = merge_labels(&label_slices);
 
-        let name_inputs: Vec<(Option<String>, &str)> = member_ordinals
-            .iter()
-            .zip(src_labels_for_group.iter())
-            .map(|(&ord, &src)| (all_nodes[ord].name.clone(), src))
-            .collect();
-        let (merged_name, name_conflict) = resolve_scalar_merge(&name_inputs, "name");
-
         let node_type = all_nodes[member_ordinals[0]].node_type.clone();
-
-        let jurisdiction_inputs: Vec<(Option<crate::newtypes::CountryCode>, &str)> =
-            member_ordinals
-                .iter()
-                .zip(src_labels_for_group.iter())
-                .map(|(&ord, &src)| (all_nodes[ord].jurisdiction.clone(), src))
-                .collect();
-        let (merged_jurisdiction, jurisdiction_conflict) =
-            resolve_scalar_merge(&jurisdiction_inputs, "jurisdiction");
-
-        let status_inputs: Vec<(Option<crate::enums::OrganizationStatus>, &str)> = member_ordinals
-            .iter()
-            .zip(src_labels_for_group.iter())
-            .map(|(&ord, &src)| (all_nodes[ord].status.clone(), src))
-            .collect();
-        let (merged_status, status_conflict) = resolve_scalar_merge(&status_inputs, "status");
-
-        let mut node_conflicts: Vec<Conflict> = Vec::new();
-        if let Some(c) = name_conflict {
-            node_conflicts.push(c);
-        }
-        if let Some(c) = jurisdiction_conflict {
-            node_conflicts.push(c);
-        }
-        if let Some(c) = status_conflict {
-            node_conflicts.push(c);
-        }
+        let members: Vec<&Node> = member_ordinals.iter().map(|&o| &all_nodes[o]).collect();
+        let NodeScalars {
+            name: merged_name,
+            jurisdiction: merged_jurisdiction,
+            status: merged_status,
+            quantity: merged_quantity,
+            direct_emissions_co2e: merged_direct_emissions,
+            indirect_emissions_co2e: merged_indirect_emissions,
+            conflicts: node_conflicts,
+        } = resolver.node_scalars(&sources, &members);
         conflict_count += node_conflicts.len();
 
         let mut extra = BTreeMap::new();
         if let Some(conflicts_val) = build_conflicts_value(node_conflicts) {
             extra.insert("_conflicts".to_owned(), DynValue::from(conflicts_val));
         }
+        if config.record_provenance {
+            let mut ordered = member_ordinals.clone();
+            ordered.sort_unstable();
+            let provenance: Vec<NodeProvenance> = ordered
+                .into_iter()
+                .map(|ord| NodeProvenance {
+                    source_file: source_labels[node_origins[ord]].clone(),
+                    node_id: all_nodes[ord].id.to_string(),
+                    identifiers: all_nodes[ord]
+                        .identifiers
+                        .iter()
+                        .flatten()
+                        .map(|id| CanonicalId::from_identifier(id).into_string())
+                        .collect(),
+                })
+                .collect();
+            extra.insert("_provenance".to_owned(), provenance_value(&provenance));
+        }
 
         let mut merged_node = Node {
             id: new_id.clone(),
@@ -338,11 +325,11 @@ pub fn merge_with_config(
         merged_node.risk_severity = rep_node.risk_severity.clone();
         merged_node.risk_likelihood = rep_node.risk_likelihood.clone();
         merged_node.lot_id = rep_node.lot_id.clone();
-        merged_node.quantity = rep_node.quantity;
+        merged_node.quantity = merged_quantity;
         merged_node.production_date = rep_node.production_date.clone();
         merged_node.origin_country = rep_node.origin_country.clone();
-        merged_node.direct_emissions_co2e = rep_node.direct_emissions_co2e;
-        merged_node.indirect_emissions_co2e = rep_node.indirect_emissions_co2e;
+        merged_node.direct_emissions_co2e = merged_direct_emissions;
+        merged_node.indirect_emissions_co2e = merged_indirect_emissions;
         merged_node.emission_factor_source = rep_node.emission_factor_source.clone();
         merged_node.installation_id = rep_node.installation_id.clone();
 
@@ -482,7 +469,7 @@ pub fn merge_with_config(
     });
 
     let mut output_edges: Vec<Edge> = Vec::new();
-    let mut edge_new_id_counter = 0usize;
+    let mut edge_ids = EdgeIds::new(config.stable_ids.as_ref());
 
     for (_src_cid, _tgt_cid, _type_str, _edge_cid, rep) in &edge_group_sort_keys {
         let member_ordinals = &edge_groups[rep];
@@ -515,11 +502,15 @@ pub fn merge_with_config(
                     continue;
                 };
 
-                let new_edge_id_str = format!("e-{edge_new_id_counter}");
-                edge_new_id_counter += 1;
-                let Ok(new_edge_id) = NodeId::try_from(new_edge_id_str.as_str()) else {
-                    continue;
-                };
+                let new_edge_id = edge_ids.next(&edge.edge_type, &new_src_id, &new_tgt_id)?;
+
+                let mut properties = edge.properties.clone();
+                if config.record_provenance {
+                    let provenance = [edge_provenance(edge, &source_labels[file_idx])];
+                    properties
+                        .extra
+                        .insert("_provenance".to_owned(), provenance_value(&provenance));
+                }
 
                 output_edges.push(Edge {
                     id: new_edge_id,
@@ -527,7 +518,7 @@ pub fn merge_with_config(
                     source: new_src_id,
                     target: new_tgt_id,
                     identifiers: edge.identifiers.clone(),
-                    properties: edge.properties.clone(),
+                    properties,
                     extra: edge.extra.clone(),
                 });
             }
@@ -568,6 +559,47 @@ pub fn merge_with_config(
             continue;
         };
 
+        let tier_inputs: Vec<(Option<u32>, usize, &str)> = member_ordinals
+            .iter()
+            .map(|&ord| {
+                let file_idx = edge_origins[ord];
+                (
+                    all_edges[ord].properties.tier,
+                    file_idx,
+                    source_labels[file_idx].as_str(),
+                )
+            })
+            .collect();
+        let (merged_tier, tier_conflict) = perspectives.reconcile_tier(&tier_inputs);
+
+        let sources: Vec<ScalarSource<'_>> = member_ordinals
+            .iter()
+            .map(|&ord| {
+                let file_idx = edge_origins[ord];
+                ScalarSource {
+                    label: source_labels[file_idx].as_str(),
+                    file_idx,
+                    data_quality: all_edges[ord].properties.data_quality.as_ref(),
+                    snapshot_date: &files[file_idx].snapshot_date,
+                }
+            })
+            .collect();
+        let props = |ord: &usize| &all_edges[*ord].properties;
+        let mut edge_conflicts: Vec<Conflict> = tier_conflict.into_iter().collect();
+        let mut numeric = |field: &str, value: fn(&EdgeProperties) -> Option<f64>| {
+            resolver.representative(
+                field,
+                &sources,
+                member_ordinals.iter().map(|o| value(props(o))),
+                &mut edge_conflicts,
+            )
+        };
+        let merged_percentage = numeric("percentage", |p| p.percentage);
+        let merged_volume = numeric("volume", |p| p.volume);
+        let merged_annual_value = numeric("annual_value", |p| p.annual_value);
+        let merged_share = numeric("share_of_buyer_demand", |p| p.share_of_buyer_demand);
+        let merged_quantity = numeric("quantity", |p| p.quantity);
+
         let rep_props = &all_edges[member_ordinals[0]].properties;
         let mut merged_props = EdgeProperties {
             data_quality: rep_props.data_quality.clone(),
@@ -578,7 +610,7 @@ pub fn merge_with_config(
             },
             valid_from: rep_props.valid_from.clone(),
             valid_to: rep_props.valid_to.clone(),
-            percentage: rep_props.percentage,
+            percentage: merged_percentage,
             direct: rep_props.direct,
             control_type: rep_props.control_type.clone(),
             consolidation_basis: rep_props.consolidation_basis.clone(),
@@ -587,32 +619,38 @@ pub fn merge_with_config(
             description: rep_props.description.clone(),
             commodity: rep_props.commodity.clone(),
             contract_ref: rep_props.contract_ref.clone(),
-            volume: rep_props.volume,
+            volume: merged_volume,
             volume_unit: rep_props.volume_unit.clone(),
-            annual_value: rep_props.annual_value,
+            annual_value: merged_annual_value,
             value_currency: rep_props.value_currency.clone(),
-            tier: rep_props.tier,
-            share_of_buyer_demand: rep_props.share_of_buyer_demand,
+            tier: merged_tier,
+            share_of_buyer_demand: merged_share,
             service_type: rep_props.service_type.clone(),
-            quantity: rep_props.quantity,
+            quantity: merged_quantity,
             unit: rep_props.unit.clone(),
             scope: rep_props.scope.clone(),
             extra: BTreeMap::new(),
         };
 
-        let edge_conflicts: Vec<Conflict> = Vec::new();
         conflict_count += edge_conflicts.len();
         if let Some(conflicts_val) = build_conflicts_value(edge_conflicts) {
             merged_props
                 .extra
                 .insert("_conflicts".to_owned(), DynValue::from(conflicts_val));
         }
+        if config.record_provenance {
+            let mut ordered = member_ordinals.clone();
+            ordered.sort_unstable();
+            let provenance: Vec<EdgeProvenance> = ordered
+                .into_iter()
+                .map(|ord| edge_provenance(&all_edges[ord], &source_labels[edge_origins[ord]]))
+                .collect();
+            merged_props
+                .extra
+                .insert("_provenance".to_owned(), provenance_value(&provenance));
+        }
 
-        let new_edge_id_str = format!("e-{edge_new_id_counter}");
-        edge_new_id_counter += 1;
-        let Ok(new_edge_id) = NodeId::try_from(new_edge_id_str.as_str()) else {
-            continue;
-        };
+        let new_edge_id = edge_ids.next(&first_edge.edge_type, &new_src, &new_tgt)?;
 
         output_edges.push(Edge {
             id: new_edge_id,
@@ -629,6 +667,54 @@ pub fn merge_with_config(
         });
     }
 
+    // Links held back by the enrichment base become one `same_as` edge per
+    // pair of merged nodes (merge-semantics Section 9).
+    let position: HashMap<usize, usize> = merged_nodes
+        .iter()
+        .enumerate()
+        .map(|(pos, (_, rep))| (*rep, pos))
+        .collect();
+    let mut proposed_pairs: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for &(a, b) in &proposals {
+        let (Some(&pos_a), Some(&pos_b)) = (position.get(&uf.find(a)), position.get(&uf.find(b)))
+        else {
+            continue;
+        };
+        if pos_a != pos_b {
+            proposed_pairs.insert((pos_a.min(pos_b), pos_a.max(pos_b)));
+        }
+    }
+    let proposed_same_as = proposed_pairs.len();
+    let same_as = EdgeTypeTag::Known(EdgeType::SameAs);
+    for (pos_a, pos_b) in proposed_pairs {
+        let source = merged_nodes[pos_a].0.clone();
+        let target = merged_nodes[pos_b].0.clone();
+        let mut properties = EdgeProperties::default();
+        properties.extra.insert(
+            "confidence".to_owned(),
+            DynValue::String("probable".to_owned()),
+        );
+        properties.extra.insert(
+            "basis".to_owned(),
+            DynValue::String("enrichment_match".to_owned()),
+        );
+        if config.record_provenance {
+            properties.extra.insert(
+                "_provenance".to_owned(),
+                provenance_value::<EdgeProvenance>(&[]),
+            );
+        }
+        output_edges.push(Edge {
+            id: edge_ids.next(&same_as, &source, &target)?,
+            edge_type: same_as.clone(),
+            source,
+            target,
+            identifiers: None,
+            properties,
+            extra: BTreeMap::new(),
+        });
+    }
+
     let mut reporting_entities: Vec<String> = files
         .iter()
         .filter_map(|f| f.reporting_entity.as_ref().map(ToString::to_string))
@@ -636,11 +722,9 @@ pub fn merge_with_config(
     reporting_entities.sort();
     reporting_entities.dedup();
 
-    let output_reporting_entity: Option<NodeId> = if reporting_entities.len() == 1 {
-        NodeId::try_from(reporting_entities[0].as_str()).ok()
-    } else {
-        None
-    };
+    let output_reporting_entity: Option<NodeId> = perspectives
+        .output()
+        .and_then(|rep| rep_to_new_id.get(&rep).cloned());
 
     let latest_date: Option<CalendarDate> = files.iter().map(|f| f.snapshot_date.clone()).max();
 
@@ -666,6 +750,22 @@ pub fn merge_with_config(
         merged_node_count: output_nodes.len(),
         merged_edge_count: output_edges.len(),
         conflict_count,
+        enrichment: if config.enrichment_base.is_some() {
+            Some(EnrichmentStage::PostEnrichment)
+        } else {
+            config
+                .pre_enrichment
+                .then_some(EnrichmentStage::PreEnrichment)
+        },
+        source_names: if config.record_provenance {
+            config
+                .source_paths
+                .iter()
+                .map(|path| file_name(path).to_owned())
+                .collect()
+        } else {
+            Vec::new()
+        },
     };
 
     let mut file_extra = BTreeMap::new();
@@ -706,6 +806,9 @@ pub fn merge_with_config(
         metadata,
         warnings,
         conflict_count,
+        explanation,
+        id_changes,
+        proposed_same_as,
     })
 }
 
@@ -752,6 +855,21 @@ fn epoch_secs_to_ymdhms(secs: u64) -> (u32, u32, u32, u32, u32, u32) {
     (y, m, d, hour, minute, second)
 }
 
+/// Provenance entry for a source edge.
+fn edge_provenance(edge: &Edge, source_file: &str) -> EdgeProvenance {
+    EdgeProvenance {
+        source_file: source_file.to_owned(),
+        edge_id: edge.id.to_string(),
+        source: edge.source.to_string(),
+        target: edge.target.to_string(),
+    }
+}
+
+/// Serializes a `_provenance` array.
+fn provenance_value<T: serde::Serialize>(entries: &[T]) -> DynValue {
+    DynValue::from(serde_json::to_value(entries).unwrap_or(serde_json::Value::Null))
+}
+
 /// Merges N optional scalar values using [`merge_scalars`], returning the
 /// agreed value and an optional [`crate::merge::Conflict`] record.
 pub(super) fn resolve_scalar_merge<T>(
@@ -767,6 +885,7 @@ where
             let conflict = Conflict {
                 field: field_name.to_owned(),
                 values: entries,
+                resolution: None,
             };
             (None, Some(conflict))
         }
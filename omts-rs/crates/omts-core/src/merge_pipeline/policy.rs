@@ -0,0 +1,338 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::enums::Confidence;
+use crate::enums::OrganizationStatus;
+use crate::file::OmtsFile;
+use crate::merge::{Conflict, ScalarMergeResult, merge_scalars};
+use crate::newtypes::{CalendarDate, CountryCode};
+use crate::structures::Node;
+use crate::types::DataQuality;
+
+use super::types::{ConflictPolicy, MergeError};
+
+/// Properties always compared across a merge group; conflicting values are
+/// omitted unless a policy resolves them.
+const COMPARED_FIELDS: &[&str] = &["name", "jurisdiction", "status"];
+
+/// Numeric properties otherwise copied from a group's first member; with a
+/// policy they are compared and resolved.
+const NUMERIC_FIELDS: &[&str] = &[
+    "quantity",
+    "direct_emissions_co2e",
+    "indirect_emissions_co2e",
+    "percentage",
+    "volume",
+    "annual_value",
+    "share_of_buyer_demand",
+];
+
+/// Checks that every configured policy names a resolvable property and fits
+/// its type.
+///
+/// # Errors
+///
+/// Returns [`MergeError::InvalidConflictPolicy`] for an unknown property, for
+/// `tier` (resolved by perspective instead), for a numeric policy on a
+/// non-numeric property, or for a source priority naming a missing file.
+pub(super) fn validate_policies(
+    policies: &BTreeMap<String, ConflictPolicy>,
+    file_count: usize,
+) -> Result<(), MergeError> {
+    for (field, policy) in policies {
+        if field == "tier" {
+            return Err(MergeError::InvalidConflictPolicy(
+                "`tier` is reconciled by reporting-entity perspective; use the primary \
+                 perspective instead"
+                    .to_owned(),
+            ));
+        }
+        let numeric = NUMERIC_FIELDS.contains(&field.as_str());
+        if !numeric && !COMPARED_FIELDS.contains(&field.as_str()) {
+            return Err(MergeError::InvalidConflictPolicy(format!(
+                "`{field}` is not a resolvable property"
+            )));
+        }
+        if policy.is_numeric() && !numeric {
+            return Err(MergeError::InvalidConflictPolicy(format!(
+                "`{}` applies only to numeric properties, not `{field}`",
+                policy.name()
+            )));
+        }
+        if let ConflictPolicy::SourcePriority(order) = policy {
+            if let Some(idx) = order.iter().find(|&&idx| idx >= file_count) {
+                return Err(MergeError::InvalidConflictPolicy(format!(
+                    "source priority for `{field}` names file index {idx}, but there are \
+                     {file_count} input file(s)"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The provenance of one merge-group member, used to rank its values.
+pub(super) struct ScalarSource<'a> {
+    /// Conflict-entry label of the member's file.
+    pub(super) label: &'a str,
+    /// Index of the member's file among the merge inputs.
+    pub(super) file_idx: usize,
+    /// The member's own `data_quality`.
+    pub(super) data_quality: Option<&'a DataQuality>,
+    /// `snapshot_date` of the member's file.
+    pub(super) snapshot_date: &'a CalendarDate,
+}
+
+/// The [`ScalarSource`] of each node ordinal in `members`.
+pub(super) fn node_sources<'a>(
+    members: &[usize],
+    all_nodes: &'a [Node],
+    node_origins: &[usize],
+    files: &'a [OmtsFile],
+    labels: &'a [String],
+) -> Vec<ScalarSource<'a>> {
+    members
+        .iter()
+        .map(|&ord| {
+            let file_idx = node_origins[ord];
+            ScalarSource {
+                label: labels[file_idx].as_str(),
+                file_idx,
+                data_quality: all_nodes[ord].data_quality.as_ref(),
+                snapshot_date: &files[file_idx].snapshot_date,
+            }
+        })
+        .collect()
+}
+
+/// The merged scalar properties of one node group and the conflicts found
+/// while merging them.
+pub(super) struct NodeScalars {
+    pub(super) name: Option<String>,
+    pub(super) jurisdiction: Option<CountryCode>,
+    pub(super) status: Option<OrganizationStatus>,
+    pub(super) quantity: Option<f64>,
+    pub(super) direct_emissions_co2e: Option<f64>,
+    pub(super) indirect_emissions_co2e: Option<f64>,
+    pub(super) conflicts: Vec<Conflict>,
+}
+
+/// Resolves merge-group scalars according to
+/// [`MergeConfig::conflict_policies`](super::MergeConfig::conflict_policies).
+pub(super) struct PolicyResolver<'a> {
+    policies: &'a BTreeMap<String, ConflictPolicy>,
+}
+
+impl<'a> PolicyResolver<'a> {
+    pub(super) fn new(policies: &'a BTreeMap<String, ConflictPolicy>) -> Self {
+        Self { policies }
+    }
+
+    /// Merges the compared and numeric scalars of a node group; `sources` is
+    /// parallel to `members`.
+    pub(super) fn node_scalars(
+        &self,
+        sources: &[ScalarSource<'_>],
+        members: &[&Node],
+    ) -> NodeScalars {
+        let mut conflicts = Vec::new();
+        let name = self.compared(
+            "name",
+            sources,
+            members.iter().map(|n| n.name.clone()),
+            &mut conflicts,
+        );
+        let jurisdiction = self.compared(
+            "jurisdiction",
+            sources,
+            members.iter().map(|n| n.jurisdiction.clone()),
+            &mut conflicts,
+        );
+        let status = self.compared(
+            "status",
+            sources,
+            members.iter().map(|n| n.status.clone()),
+            &mut conflicts,
+        );
+        let quantity = self.representative(
+            "quantity",
+            sources,
+            members.iter().map(|n| n.quantity),
+            &mut conflicts,
+        );
+        let direct_emissions_co2e = self.representative(
+            "direct_emissions_co2e",
+            sources,
+            members.iter().map(|n| n.direct_emissions_co2e),
+            &mut conflicts,
+        );
+        let indirect_emissions_co2e = self.representative(
+            "indirect_emissions_co2e",
+            sources,
+            members.iter().map(|n| n.indirect_emissions_co2e),
+            &mut conflicts,
+        );
+        NodeScalars {
+            name,
+            jurisdiction,
+            status,
+            quantity,
+            direct_emissions_co2e,
+            indirect_emissions_co2e,
+            conflicts,
+        }
+    }
+
+    /// Merges a property that is always compared across the group.
+    ///
+    /// Agreeing values merge to that value. On disagreement a conflict is
+    /// pushed to `conflicts`, and the merged value is the policy's choice, or
+    /// `None` without a policy.
+    pub(super) fn compared<T>(
+        &self,
+        field: &str,
+        sources: &[ScalarSource<'_>],
+        values: impl IntoIterator<Item = Option<T>>,
+        conflicts: &mut Vec<Conflict>,
+    ) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned + Clone,
+    {
+        let values: Vec<Option<T>> = values.into_iter().collect();
+        let inputs: Vec<(Option<T>, &str)> = values
+            .iter()
+            .zip(sources)
+            .map(|(value, source)| (value.clone(), source.label))
+            .collect();
+        match merge_scalars(&inputs) {
+            ScalarMergeResult::Agreed(value) => value,
+            ScalarMergeResult::Conflict(entries) => {
+                let policy = self.policies.get(field);
+                conflicts.push(Conflict {
+                    field: field.to_owned(),
+                    values: entries,
+                    resolution: policy.map(|p| p.name().to_owned()),
+                });
+                policy.and_then(|policy| resolve(policy, sources, &values))
+            }
+        }
+    }
+
+    /// Merges a property that is copied from the group's first member unless
+    /// a policy is configured for it, in which case it is compared like
+    /// [`Self::compared`].
+    pub(super) fn representative<T>(
+        &self,
+        field: &str,
+        sources: &[ScalarSource<'_>],
+        values: impl IntoIterator<Item = Option<T>>,
+        conflicts: &mut Vec<Conflict>,
+    ) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned + Clone,
+    {
+        if self.policies.contains_key(field) {
+            self.compared(field, sources, values, conflicts)
+        } else {
+            values.into_iter().next().flatten()
+        }
+    }
+}
+
+/// Picks the merged value from conflicting `values` (parallel to `sources`).
+fn resolve<T>(
+    policy: &ConflictPolicy,
+    sources: &[ScalarSource<'_>],
+    values: &[Option<T>],
+) -> Option<T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let present: Vec<(&ScalarSource<'_>, &T)> = sources
+        .iter()
+        .zip(values)
+        .filter_map(|(source, value)| value.as_ref().map(|v| (source, v)))
+        .collect();
+
+    let numbers = || -> Vec<(f64, &T, usize)> {
+        present
+            .iter()
+            .filter_map(|&(source, value)| {
+                let number = serde_json::to_value(value).ok()?.as_f64()?;
+                Some((number, value, source.file_idx))
+            })
+            .collect()
+    };
+
+    match policy {
+        ConflictPolicy::SourcePriority(order) => best_by(&present, |source| {
+            order
+                .iter()
+                .position(|&idx| idx == source.file_idx)
+                .unwrap_or(order.len())
+        }),
+        ConflictPolicy::MostRecentlyVerified => best_by(&present, |source| {
+            Reverse(source.data_quality.and_then(|dq| dq.last_verified.clone()))
+        }),
+        ConflictPolicy::HighestConfidence => best_by(&present, |source| {
+            Reverse(
+                source
+                    .data_quality
+                    .and_then(|dq| dq.confidence.as_ref())
+                    .map_or(0, confidence_rank),
+            )
+        }),
+        ConflictPolicy::NewestSnapshot => {
+            best_by(&present, |source| Reverse(source.snapshot_date.clone()))
+        }
+        ConflictPolicy::Max => extreme(numbers(), |a, b| b.total_cmp(a)),
+        ConflictPolicy::Min => extreme(numbers(), f64::total_cmp),
+        ConflictPolicy::Mean => {
+            let numbers = numbers();
+            if numbers.is_empty() {
+                return None;
+            }
+            let mean = numbers.iter().map(|(n, _, _)| n).sum::<f64>() / numbers.len() as f64;
+            serde_json::from_value(serde_json::Value::from(mean)).ok()
+        }
+    }
+}
+
+/// The value whose source has the smallest `key`, ties going to the earliest
+/// input file.
+fn best_by<T, K, F>(present: &[(&ScalarSource<'_>, &T)], key: F) -> Option<T>
+where
+    T: Clone,
+    K: Ord,
+    F: Fn(&ScalarSource<'_>) -> K,
+{
+    present
+        .iter()
+        .min_by(|(a, _), (b, _)| key(a).cmp(&key(b)).then(a.file_idx.cmp(&b.file_idx)))
+        .map(|&(_, value)| value.clone())
+}
+
+/// The value ordered first by `cmp`, ties going to the earliest input file.
+fn extreme<T, F>(numbers: Vec<(f64, &T, usize)>, cmp: F) -> Option<T>
+where
+    T: Clone,
+    F: Fn(&f64, &f64) -> Ordering,
+{
+    numbers
+        .into_iter()
+        .min_by(|a, b| cmp(&a.0, &b.0).then(a.2.cmp(&b.2)))
+        .map(|(_, value, _)| value.clone())
+}
+
+/// Ranks a `data_quality.confidence` level; higher is more trustworthy.
+fn confidence_rank(confidence: &Confidence) -> u8 {
+    match confidence {
+        Confidence::Verified => 4,
+        Confidence::Reported => 3,
+        Confidence::Inferred => 2,
+        Confidence::Estimated => 1,
+    }
+}
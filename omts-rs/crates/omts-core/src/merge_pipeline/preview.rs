@@ -0,0 +1,140 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::file::OmtsFile;
+use crate::structures::Node;
+
+use super::explain::GroupMember;
+use super::groups::{NodeGroups, resolve_groups};
+use super::policy::{PolicyResolver, node_sources, validate_policies};
+use super::types::{MergeConfig, MergeError};
+
+/// What merging a set of files would do, computed by [`merge_preview`]
+/// without building the merged file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergePreview {
+    /// Number of input files.
+    pub file_count: usize,
+    /// Number of input nodes across all files.
+    pub node_count: usize,
+    /// Number of nodes the merged file would have: one per merge group.
+    pub merged_node_count: usize,
+    /// Number of merge groups of each size, keyed by size.
+    pub group_sizes: BTreeMap<usize, usize>,
+    /// The configured [`MergeConfig::group_size_limit`].
+    pub group_size_limit: usize,
+    /// Merge groups with two or more members, largest first.
+    pub groups: Vec<PreviewGroup>,
+    /// Number of merge groups with a conflicting value, per node property.
+    pub conflicts: BTreeMap<String, usize>,
+    /// Input nodes that would not be merged with any other node.
+    pub singletons: Vec<GroupMember>,
+}
+
+/// One merge group of a [`MergePreview`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreviewGroup {
+    /// Number of members.
+    pub size: usize,
+    /// Whether `size` exceeds the group size limit.
+    pub oversized: bool,
+    /// The source nodes that would be merged, in input order.
+    pub members: Vec<GroupMember>,
+    /// Properties whose values would conflict, in merge order.
+    pub conflicts: Vec<String>,
+}
+
+/// Runs identity resolution over `files` and reports the resulting merge
+/// groups without building the merged file (merge.md Section 8.2).
+///
+/// Groups form exactly as in [`merge_with_config`](super::merge_with_config):
+/// from matching identifiers and from `same_as` edges at or above
+/// [`MergeConfig::same_as_threshold`]. Conflicts are counted for the node
+/// properties the merge compares (`name`, `jurisdiction`, `status`, and any
+/// numeric property with a conflict policy); edge and `tier` conflicts are
+/// not previewed.
+///
+/// # Errors
+///
+/// - [`MergeError::NoInputFiles`] — `files` is empty.
+/// - [`MergeError::InvalidConflictPolicy`] — a configured policy does not
+///   apply to its property.
+/// - [`MergeError::InvalidEnrichmentBase`] — `files` are not the inputs of
+///   [`MergeConfig::enrichment_base`], in its order.
+pub fn merge_preview(files: &[OmtsFile], config: &MergeConfig) -> Result<MergePreview, MergeError> {
+    if files.is_empty() {
+        return Err(MergeError::NoInputFiles);
+    }
+    validate_policies(&config.conflict_policies, files.len())?;
+    if let Some(base) = &config.enrichment_base {
+        base.check_inputs(files)?;
+    }
+    let resolver = PolicyResolver::new(&config.conflict_policies);
+
+    let source_labels: Vec<String> = (0..files.len()).map(|i| format!("file_{i}")).collect();
+    let NodeGroups {
+        all_nodes,
+        node_origins,
+        mut uf,
+        ..
+    } = resolve_groups(files, config, None);
+
+    let mut groups: HashMap<usize, Vec<usize>> = HashMap::new();
+    for ord in 0..all_nodes.len() {
+        groups.entry(uf.find(ord)).or_default().push(ord);
+    }
+    let member = |ord: usize| GroupMember {
+        source_file: source_labels[node_origins[ord]].clone(),
+        source_path: config.source_paths.get(node_origins[ord]).cloned(),
+        node_id: all_nodes[ord].id.clone(),
+    };
+
+    let mut group_sizes: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut multi: Vec<(usize, PreviewGroup)> = Vec::new();
+    let mut conflicts: BTreeMap<String, usize> = BTreeMap::new();
+    let mut singletons: Vec<usize> = Vec::new();
+    for members in groups.values_mut() {
+        members.sort_unstable();
+        *group_sizes.entry(members.len()).or_default() += 1;
+        if let [only] = members.as_slice() {
+            singletons.push(*only);
+            continue;
+        }
+
+        let sources = node_sources(members, &all_nodes, &node_origins, files, &source_labels);
+        let nodes: Vec<&Node> = members.iter().map(|&ord| &all_nodes[ord]).collect();
+        let fields: Vec<String> = resolver
+            .node_scalars(&sources, &nodes)
+            .conflicts
+            .into_iter()
+            .map(|conflict| conflict.field)
+            .collect();
+        for field in &fields {
+            *conflicts.entry(field.clone()).or_default() += 1;
+        }
+
+        multi.push((
+            members[0],
+            PreviewGroup {
+                size: members.len(),
+                oversized: members.len() > config.group_size_limit,
+                members: members.iter().map(|&ord| member(ord)).collect(),
+                conflicts: fields,
+            },
+        ));
+    }
+    multi.sort_by(|(first_a, a), (first_b, b)| b.size.cmp(&a.size).then(first_a.cmp(first_b)));
+    singletons.sort_unstable();
+
+    Ok(MergePreview {
+        file_count: files.len(),
+        node_count: all_nodes.len(),
+        merged_node_count: groups.len(),
+        group_sizes,
+        group_size_limit: config.group_size_limit,
+        groups: multi.into_iter().map(|(_, group)| group).collect(),
+        conflicts,
+        singletons: singletons.into_iter().map(member).collect(),
+    })
+}
@@ -0,0 +1,289 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::canonical::CanonicalId;
+use crate::enums::{EdgeTypeTag, NodeTypeTag};
+use crate::file::OmtsFile;
+use crate::merge::NodeProvenance;
+use crate::newtypes::NodeId;
+use crate::types::Identifier;
+
+use super::types::MergeError;
+
+/// The node and edge IDs of a previously merged file, used to keep merged IDs
+/// stable across runs (merge.md Section 4.7).
+///
+/// Set [`MergeConfig::stable_ids`](super::MergeConfig::stable_ids) to reuse
+/// them: each merged node that matches a previous node by identifier, or by a
+/// source node recorded in the previous node's `_provenance`, keeps that
+/// node's ID, and each merged edge with the same type and endpoints as a
+/// previous edge keeps that edge's ID.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StableIds {
+    /// Previous node IDs, in file order.
+    nodes: Vec<NodeId>,
+    /// Match key -> positions in `nodes` carrying it.
+    node_index: HashMap<String, Vec<usize>>,
+    /// `(edge type, source, target)` -> previous edge IDs, in file order.
+    edge_index: HashMap<(String, String, String), Vec<NodeId>>,
+    /// Every node and edge ID of the previous file, never minted anew.
+    used: HashSet<String>,
+}
+
+impl StableIds {
+    /// Indexes the node identifiers, node `_provenance` records, and edges
+    /// of a previously merged file.
+    pub fn from_file(previous: &OmtsFile) -> Self {
+        let mut stable = Self::default();
+        for (pos, node) in previous.nodes.iter().enumerate() {
+            let constituents: Vec<NodeProvenance> = node
+                .extra
+                .get("_provenance")
+                .and_then(|value| serde_json::from_value(value.clone().into()).ok())
+                .unwrap_or_default();
+            let keys = match_keys(&node.node_type, node.identifiers.as_deref())
+                .into_iter()
+                .chain(constituents.iter().map(|entry| {
+                    constituent_key(&node.node_type, &entry.source_file, &entry.node_id)
+                }));
+            for key in keys {
+                stable.node_index.entry(key).or_default().push(pos);
+            }
+            stable.nodes.push(node.id.clone());
+            stable.used.insert(node.id.to_string());
+        }
+        for edge in &previous.edges {
+            stable
+                .edge_index
+                .entry(edge_key(&edge.edge_type, &edge.source, &edge.target))
+                .or_default()
+                .push(edge.id.clone());
+            stable.used.insert(edge.id.to_string());
+        }
+        stable
+    }
+
+    /// Names the merged nodes, one per entry of `groups` (the match keys of
+    /// each group's members, in output order).
+    ///
+    /// Candidate pairs are ranked by the number of shared keys, and each
+    /// previous ID goes to at most one group; ties go to the earlier previous
+    /// node, then the earlier group. Groups left unmatched get fresh `n-{k}`
+    /// IDs that were not used in the previous file.
+    pub(super) fn assign_node_ids(
+        &self,
+        groups: &[BTreeSet<String>],
+    ) -> Result<(Vec<NodeId>, IdChanges), MergeError> {
+        let mut overlap: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        for (group, keys) in groups.iter().enumerate() {
+            for key in keys {
+                for &pos in self.node_index.get(key).into_iter().flatten() {
+                    *overlap.entry((group, pos)).or_default() += 1;
+                }
+            }
+        }
+
+        let mut pairs: Vec<(usize, usize, usize)> = overlap
+            .iter()
+            .map(|(&(group, pos), &count)| (count, pos, group))
+            .collect();
+        pairs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+        let mut ids: Vec<Option<NodeId>> = vec![None; groups.len()];
+        let mut taken = vec![false; self.nodes.len()];
+        for (_, pos, group) in pairs {
+            if ids[group].is_none() && !taken[pos] {
+                ids[group] = Some(self.nodes[pos].clone());
+                taken[pos] = true;
+            }
+        }
+
+        let mut minter = Minter::new("n", Some(&self.used));
+        let mut changes = IdChanges::default();
+        let mut assigned = Vec::with_capacity(groups.len());
+        for id in ids {
+            match id {
+                Some(id) => {
+                    changes.reused += 1;
+                    assigned.push(id);
+                }
+                None => {
+                    let id = minter.next()?;
+                    changes.minted.push(id.clone());
+                    assigned.push(id);
+                }
+            }
+        }
+
+        let mut matched_groups: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        let mut matched_previous: Vec<Vec<usize>> = vec![Vec::new(); groups.len()];
+        for &(group, pos) in overlap.keys() {
+            matched_groups[pos].push(group);
+            matched_previous[group].push(pos);
+        }
+        for (pos, matched) in matched_groups.iter().enumerate() {
+            match matched.len() {
+                0 => changes.retired.push(self.nodes[pos].clone()),
+                1 => {}
+                _ => changes.split.push(IdSplit {
+                    previous: self.nodes[pos].clone(),
+                    into: matched.iter().map(|&g| assigned[g].clone()).collect(),
+                }),
+            }
+        }
+        for (group, matched) in matched_previous.iter().enumerate() {
+            if matched.len() > 1 {
+                changes.coalesced.push(IdCoalescence {
+                    merged: assigned[group].clone(),
+                    from: matched.iter().map(|&pos| self.nodes[pos].clone()).collect(),
+                });
+            }
+        }
+
+        Ok((assigned, changes))
+    }
+}
+
+/// Hands out merged edge IDs: a previous edge's ID when one with the same
+/// type and endpoints is still unclaimed, otherwise a fresh `e-{k}`.
+///
+/// Without [`StableIds`] this is the plain `e-0`, `e-1`, ... sequence.
+pub(super) struct EdgeIds<'a> {
+    stable: Option<&'a StableIds>,
+    claimed: HashSet<NodeId>,
+    minter: Minter<'a>,
+}
+
+impl<'a> EdgeIds<'a> {
+    pub(super) fn new(stable: Option<&'a StableIds>) -> Self {
+        Self {
+            stable,
+            claimed: HashSet::new(),
+            minter: Minter::new("e", stable.map(|s| &s.used)),
+        }
+    }
+
+    /// Returns the ID for a merged edge.
+    pub(super) fn next(
+        &mut self,
+        edge_type: &EdgeTypeTag,
+        source: &NodeId,
+        target: &NodeId,
+    ) -> Result<NodeId, MergeError> {
+        if let Some(stable) = self.stable {
+            let previous = stable.edge_index.get(&edge_key(edge_type, source, target));
+            if let Some(id) = previous
+                .into_iter()
+                .flatten()
+                .find(|id| !self.claimed.contains(*id))
+            {
+                self.claimed.insert(id.clone());
+                return Ok(id.clone());
+            }
+        }
+        self.minter.next()
+    }
+}
+
+/// Mints `{prefix}-{k}` IDs in sequence, skipping any in `used`.
+struct Minter<'a> {
+    prefix: &'static str,
+    used: Option<&'a HashSet<String>>,
+    counter: usize,
+}
+
+impl<'a> Minter<'a> {
+    fn new(prefix: &'static str, used: Option<&'a HashSet<String>>) -> Self {
+        Self {
+            prefix,
+            used,
+            counter: 0,
+        }
+    }
+
+    fn next(&mut self) -> Result<NodeId, MergeError> {
+        loop {
+            let candidate = format!("{}-{}", self.prefix, self.counter);
+            self.counter += 1;
+            if !self.used.is_some_and(|used| used.contains(&candidate)) {
+                return NodeId::try_from(candidate.as_str())
+                    .map_err(|e| MergeError::InternalDataError(e.to_string()));
+            }
+        }
+    }
+}
+
+/// The keys a node is matched on across runs: its node type with each
+/// external identifier in canonical form, and each `internal` identifier that
+/// names its issuing system in `authority`.
+pub(super) fn match_keys(
+    node_type: &NodeTypeTag,
+    identifiers: Option<&[Identifier]>,
+) -> Vec<String> {
+    identifiers
+        .unwrap_or(&[])
+        .iter()
+        .filter_map(|id| {
+            let canonical = CanonicalId::from_identifier(id);
+            if id.scheme != "internal" {
+                return Some(format!("{}|{canonical}", node_type.as_str()));
+            }
+            id.authority
+                .as_deref()
+                .map(|authority| format!("{}|{authority}|{canonical}", node_type.as_str()))
+        })
+        .collect()
+}
+
+/// The key a node is matched on through `_provenance`: its node type with
+/// the source file label and the node's ID in that file.
+///
+/// It lets nodes without identifier keys keep their IDs when the inputs are
+/// passed in the same order with the same node IDs.
+pub(super) fn constituent_key(node_type: &NodeTypeTag, source_file: &str, node_id: &str) -> String {
+    format!("{}|_provenance|{source_file}|{node_id}", node_type.as_str())
+}
+
+fn edge_key(edge_type: &EdgeTypeTag, source: &NodeId, target: &NodeId) -> (String, String, String) {
+    (
+        edge_type.as_str().to_owned(),
+        source.to_string(),
+        target.to_string(),
+    )
+}
+
+/// How the merged node IDs differ from the previous file's, produced when
+/// [`MergeConfig::stable_ids`](super::MergeConfig::stable_ids) is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IdChanges {
+    /// Number of merged nodes that kept a previous ID.
+    pub reused: usize,
+    /// IDs minted for merged nodes that matched no unclaimed previous node.
+    pub minted: Vec<NodeId>,
+    /// Previous nodes whose identifiers now belong to several merged nodes.
+    pub split: Vec<IdSplit>,
+    /// Merged nodes that combine several previous nodes.
+    pub coalesced: Vec<IdCoalescence>,
+    /// Previous node IDs that no merged node matched.
+    pub retired: Vec<NodeId>,
+}
+
+/// A previous node whose identifiers are now spread over several merged nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdSplit {
+    /// The previous node ID. The merged node sharing most identifiers with it
+    /// keeps it unless another merged node claimed it first.
+    pub previous: NodeId,
+    /// The merged nodes it split into, in output order.
+    pub into: Vec<NodeId>,
+}
+
+/// A merged node that combines several previous nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdCoalescence {
+    /// The merged node ID.
+    pub merged: NodeId,
+    /// The previous node IDs it combines, in previous-file order.
+    pub from: Vec<NodeId>,
+}
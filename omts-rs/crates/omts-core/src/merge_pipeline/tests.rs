@@ -7,7 +7,7 @@ use crate::structures::{Edge, Node};
 use crate::test_helpers::{file_salt, node_id, semver, supplies_edge as make_supplies_edge};
 use crate::types::Identifier;
 use crate::validation::{ValidationConfig, validate};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 const SALT_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
 const SALT_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
@@ -379,3 +379,1126 @@ fn merge_colliding_node_ids_across_files_are_distinct() {
         "the two edges must connect different (source, target) pairs"
     );
 }
+
+/// Two files describing the same `supplies` edge (supplier → manufacturer)
+/// from different perspectives: a buyer two tiers up (tier 2) and the
+/// manufacturer itself (tier 1).
+fn tier_perspective_files() -> (OmtsFile, OmtsFile) {
+    let supplier = || {
+        make_org_node(
+            "sup",
+            Some("Supplier"),
+            Some(vec![make_identifier("duns", "111111111")]),
+        )
+    };
+    let maker = |id: &str| {
+        make_org_node(
+            id,
+            Some("Maker"),
+            Some(vec![make_identifier("duns", "222222222")]),
+        )
+    };
+    let tiered = |tier: u32| {
+        let mut edge = make_supplies_edge("e-sup", "sup", "maker");
+        edge.properties.tier = Some(tier);
+        edge
+    };
+
+    let mut buyer_view = minimal_file(
+        SALT_A,
+        vec![
+            make_org_node(
+                "buyer",
+                Some("Buyer"),
+                Some(vec![make_identifier("duns", "333333333")]),
+            ),
+            supplier(),
+            maker("maker"),
+        ],
+        vec![tiered(2)],
+    );
+    buyer_view.reporting_entity = Some(node_id("buyer"));
+
+    let mut maker_view = minimal_file(SALT_B, vec![supplier(), maker("maker")], vec![tiered(1)]);
+    maker_view.reporting_entity = Some(node_id("maker"));
+
+    (buyer_view, maker_view)
+}
+
+fn tier_conflicts(output: &MergeOutput) -> serde_json::Value {
+    let edge = output
+        .file
+        .edges
+        .iter()
+        .find(|e| e.properties.tier.is_some() || e.properties.extra.contains_key("_conflicts"))
+        .expect("supplies edge");
+    edge.properties
+        .extra
+        .get("_conflicts")
+        .cloned()
+        .map(serde_json::Value::from)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+#[test]
+fn merge_mixed_perspectives_without_primary_moves_tiers_to_conflicts() {
+    let (a, b) = tier_perspective_files();
+    let output = merge(&[a, b]).expect("merge succeeds");
+
+    assert!(output.file.reporting_entity.is_none());
+    assert!(
+        output
+            .file
+            .edges
+            .iter()
+            .all(|e| e.properties.tier.is_none())
+    );
+
+    let conflicts = tier_conflicts(&output);
+    assert_eq!(conflicts[0]["field"], "tier");
+    let values = conflicts[0]["values"].as_array().expect("tier values");
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0]["source_file"], "file_0");
+    assert_eq!(values[0]["value"], 2);
+    assert_eq!(values[1]["source_file"], "file_1");
+    assert_eq!(values[1]["value"], 1);
+
+    // Reporting entities are given as merged node IDs.
+    let merged_ids: std::collections::HashSet<&str> =
+        output.file.nodes.iter().map(|n| &*n.id).collect();
+    for value in values {
+        let entity = value["reporting_entity"]
+            .as_str()
+            .expect("reporting_entity");
+        assert!(merged_ids.contains(entity), "{entity}");
+    }
+    assert_ne!(values[0]["reporting_entity"], values[1]["reporting_entity"]);
+}
+
+#[test]
+fn merge_mixed_perspectives_even_single_source_tiers_go_to_conflicts() {
+    let (a, mut b) = tier_perspective_files();
+    b.edges.clear();
+    let output = merge(&[a, b]).expect("merge succeeds");
+
+    let conflicts = tier_conflicts(&output);
+    assert_eq!(conflicts[0]["values"].as_array().map(Vec::len), Some(1));
+    assert_eq!(conflicts[0]["values"][0]["value"], 2);
+}
+
+#[test]
+fn merge_primary_perspective_retains_its_tiers() {
+    let (a, b) = tier_perspective_files();
+    let config = MergeConfig {
+        primary_perspective: Some(1),
+        ..MergeConfig::default()
+    };
+    let output = merge_with_config(&[a, b], &config).expect("merge succeeds");
+
+    let edge = output
+        .file
+        .edges
+        .iter()
+        .find(|e| e.properties.tier.is_some())
+        .expect("edge keeps a tier");
+    assert_eq!(edge.properties.tier, Some(1));
+
+    let entity = output
+        .file
+        .reporting_entity
+        .as_ref()
+        .expect("primary reporting entity retained");
+    let maker = output
+        .file
+        .nodes
+        .iter()
+        .find(|n| n.name.as_deref() == Some("Maker"))
+        .expect("maker node");
+    assert_eq!(entity, &maker.id);
+
+    let conflicts = tier_conflicts(&output);
+    let values = conflicts[0]["values"].as_array().expect("tier values");
+    assert_eq!(values.len(), 1);
+    assert_eq!(values[0]["source_file"], "file_0");
+    assert_eq!(values[0]["value"], 2);
+    assert!(values[0]["reporting_entity"].is_string());
+    assert_eq!(output.conflict_count, 1);
+}
+
+#[test]
+fn merge_shared_perspective_merges_tier_as_scalar() {
+    let (a, _) = tier_perspective_files();
+    let mut b = a.clone();
+    b.file_salt = file_salt(SALT_B);
+    let output = merge(&[a.clone(), b.clone()]).expect("merge succeeds");
+
+    assert_eq!(output.conflict_count, 0);
+    assert!(
+        output
+            .file
+            .edges
+            .iter()
+            .any(|e| e.properties.tier == Some(2))
+    );
+    // The shared reporting entity is rewritten to its merged node ID.
+    let entity = output.file.reporting_entity.as_ref().expect("entity");
+    assert!(output.file.nodes.iter().any(|n| &n.id == entity));
+
+    if let Some(edge) = b.edges.first_mut() {
+        edge.properties.tier = Some(3);
+    }
+    let output = merge(&[a, b]).expect("merge succeeds");
+    let conflicts = tier_conflicts(&output);
+    assert_eq!(conflicts[0]["field"], "tier");
+    assert!(conflicts[0]["values"][0].get("reporting_entity").is_none());
+}
+
+#[test]
+fn merge_rejects_unusable_primary_perspective() {
+    let (a, mut b) = tier_perspective_files();
+    let out_of_range = MergeConfig {
+        primary_perspective: Some(2),
+        ..MergeConfig::default()
+    };
+    assert!(matches!(
+        merge_with_config(&[a.clone(), b.clone()], &out_of_range),
+        Err(MergeError::InvalidPrimaryPerspective(_))
+    ));
+
+    b.reporting_entity = None;
+    let no_entity = MergeConfig {
+        primary_perspective: Some(1),
+        ..MergeConfig::default()
+    };
+    assert!(matches!(
+        merge_with_config(&[a, b], &no_entity),
+        Err(MergeError::InvalidPrimaryPerspective(_))
+    ));
+}
+
+/// Two files naming one organization differently, each with its own
+/// `data_quality`, and a `supplies` edge whose `annual_value` differs.
+fn policy_files(
+    quality_a: Option<crate::types::DataQuality>,
+    quality_b: Option<crate::types::DataQuality>,
+) -> (OmtsFile, OmtsFile) {
+    let build = |salt: &str, name: &str, quality, annual_value| {
+        let mut org = make_org_node(
+            "org",
+            Some(name),
+            Some(vec![make_identifier("duns", "444444444")]),
+        );
+        org.data_quality = quality;
+        let buyer = make_org_node(
+            "buyer",
+            Some("Buyer"),
+            Some(vec![make_identifier("duns", "555555555")]),
+        );
+        let mut edge = make_supplies_edge("e-1", "org", "buyer");
+        edge.properties.annual_value = Some(annual_value);
+        minimal_file(salt, vec![org, buyer], vec![edge])
+    };
+    (
+        build(SALT_A, "Acme GmbH", quality_a, 100.0),
+        build(SALT_B, "ACME Gesellschaft", quality_b, 300.0),
+    )
+}
+
+fn quality(
+    confidence: Option<crate::enums::Confidence>,
+    last_verified: Option<&str>,
+) -> Option<crate::types::DataQuality> {
+    Some(crate::types::DataQuality {
+        confidence,
+        source: None,
+        last_verified: last_verified.map(crate::test_helpers::date),
+        extra: BTreeMap::new(),
+    })
+}
+
+fn merge_with_policy(files: &[OmtsFile], field: &str, policy: ConflictPolicy) -> MergeOutput {
+    let config = MergeConfig {
+        conflict_policies: BTreeMap::from([(field.to_owned(), policy)]),
+        ..MergeConfig::default()
+    };
+    merge_with_config(files, &config).expect("merge succeeds")
+}
+
+fn merged_org(output: &MergeOutput) -> &Node {
+    output
+        .file
+        .nodes
+        .iter()
+        .find(|n| n.name.as_deref() != Some("Buyer"))
+        .expect("merged org")
+}
+
+fn conflict_record(extra: &crate::dynvalue::DynMap, field: &str) -> serde_json::Value {
+    let conflicts = serde_json::Value::from(extra.get("_conflicts").cloned().expect("_conflicts"));
+    conflicts
+        .as_array()
+        .and_then(|all| all.iter().find(|c| c["field"] == field))
+        .cloned()
+        .expect("conflict for field")
+}
+
+#[test]
+fn merge_without_policy_leaves_conflicting_name_empty() {
+    let (a, b) = policy_files(None, None);
+    let output = merge(&[a, b]).expect("merge succeeds");
+    let org = merged_org(&output);
+    assert!(org.name.is_none());
+    assert!(
+        conflict_record(&org.extra, "name")
+            .get("resolution")
+            .is_none()
+    );
+    // Unconfigured numeric properties keep the first member's value silently.
+    assert_eq!(output.file.edges[0].properties.annual_value, Some(100.0));
+    assert!(
+        !output.file.edges[0]
+            .properties
+            .extra
+            .contains_key("_conflicts")
+    );
+}
+
+#[test]
+fn merge_source_priority_fills_name_and_keeps_conflict() {
+    let (a, b) = policy_files(None, None);
+    let output = merge_with_policy(&[a, b], "name", ConflictPolicy::SourcePriority(vec![1, 0]));
+    let org = merged_org(&output);
+    assert_eq!(org.name.as_deref(), Some("ACME Gesellschaft"));
+
+    let record = conflict_record(&org.extra, "name");
+    assert_eq!(record["resolution"], "source_priority");
+    assert_eq!(record["values"].as_array().map(Vec::len), Some(2));
+    assert_eq!(output.conflict_count, 1);
+}
+
+#[test]
+fn merge_highest_confidence_prefers_verified_source() {
+    use crate::enums::Confidence;
+    let (a, b) = policy_files(
+        quality(Some(Confidence::Estimated), None),
+        quality(Some(Confidence::Verified), None),
+    );
+    let output = merge_with_policy(&[a, b], "name", ConflictPolicy::HighestConfidence);
+    assert_eq!(
+        merged_org(&output).name.as_deref(),
+        Some("ACME Gesellschaft")
+    );
+}
+
+#[test]
+fn merge_most_recently_verified_prefers_latest_verification() {
+    let (a, b) = policy_files(
+        quality(None, Some("2026-01-15")),
+        quality(None, Some("2025-06-30")),
+    );
+    let output = merge_with_policy(&[a, b], "name", ConflictPolicy::MostRecentlyVerified);
+    assert_eq!(merged_org(&output).name.as_deref(), Some("Acme GmbH"));
+}
+
+#[test]
+fn merge_newest_snapshot_prefers_latest_file() {
+    let (a, mut b) = policy_files(None, None);
+    b.snapshot_date = crate::test_helpers::date("2026-03-01");
+    let output = merge_with_policy(&[a, b], "name", ConflictPolicy::NewestSnapshot);
+    assert_eq!(
+        merged_org(&output).name.as_deref(),
+        Some("ACME Gesellschaft")
+    );
+}
+
+#[test]
+fn merge_selection_ties_go_to_earliest_file() {
+    let (a, b) = policy_files(None, None);
+    let output = merge_with_policy(&[a, b], "name", ConflictPolicy::HighestConfidence);
+    assert_eq!(merged_org(&output).name.as_deref(), Some("Acme GmbH"));
+}
+
+#[test]
+fn merge_numeric_policies_resolve_edge_values() {
+    let (a, b) = policy_files(None, None);
+    let files = [a, b];
+    for (policy, expected) in [
+        (ConflictPolicy::Max, 300.0),
+        (ConflictPolicy::Min, 100.0),
+        (ConflictPolicy::Mean, 200.0),
+    ] {
+        let name = policy.name();
+        let output = merge_with_policy(&files, "annual_value", policy);
+        let props = &output.file.edges[0].properties;
+        assert_eq!(props.annual_value, Some(expected), "{name}");
+        let record = conflict_record(&props.extra, "annual_value");
+        assert_eq!(record["resolution"], name);
+    }
+}
+
+#[test]
+fn merge_rejects_inapplicable_policies() {
+    let (a, b) = policy_files(None, None);
+    let files = [a, b];
+    for (field, policy) in [
+        ("website", ConflictPolicy::NewestSnapshot),
+        ("name", ConflictPolicy::Mean),
+        ("tier", ConflictPolicy::Min),
+        ("name", ConflictPolicy::SourcePriority(vec![0, 2])),
+    ] {
+        let config = MergeConfig {
+            conflict_policies: BTreeMap::from([(field.to_owned(), policy)]),
+            ..MergeConfig::default()
+        };
+        assert!(
+            matches!(
+                merge_with_config(&files, &config),
+                Err(MergeError::InvalidConflictPolicy(_))
+            ),
+            "{field}"
+        );
+    }
+}
+
+fn explain(files: &[OmtsFile]) -> MergeExplanation {
+    let config = MergeConfig {
+        explain: true,
+        ..MergeConfig::default()
+    };
+    merge_with_config(files, &config)
+        .expect("merge succeeds")
+        .explanation
+        .expect("explanation requested")
+}
+
+#[test]
+fn merge_without_explain_has_no_explanation() {
+    let file = minimal_file(SALT_A, vec![make_org_node("a", Some("A"), None)], vec![]);
+    assert!(
+        merge(&[file])
+            .expect("merge succeeds")
+            .explanation
+            .is_none()
+    );
+}
+
+#[test]
+fn explain_reports_transitive_chain() {
+    let a = minimal_file(
+        SALT_A,
+        vec![make_org_node(
+            "a",
+            Some("Acme"),
+            Some(vec![make_identifier("duns", "111111111")]),
+        )],
+        vec![],
+    );
+    let b = minimal_file(
+        SALT_B,
+        vec![make_org_node(
+            "b",
+            Some("Acme"),
+            Some(vec![
+                make_identifier("duns", "111111111"),
+                make_identifier("lei", "5493006MHB84DD0ZWV18"),
+            ]),
+        )],
+        vec![],
+    );
+    let c = minimal_file(
+        SALT_C,
+        vec![make_org_node(
+            "c",
+            Some("Acme"),
+            Some(vec![make_identifier("lei", "5493006MHB84DD0ZWV18")]),
+        )],
+        vec![],
+    );
+
+    let explanation = explain(&[a, b, c]);
+    assert_eq!(explanation.groups.len(), 1);
+    let group = &explanation.groups[0];
+    let members: Vec<(&str, &str)> = group
+        .members
+        .iter()
+        .map(|m| (m.source_file.as_str(), &*m.node_id))
+        .collect();
+    assert_eq!(
+        members,
+        vec![("file_0", "a"), ("file_1", "b"), ("file_2", "c")]
+    );
+
+    assert_eq!(group.links.len(), 2);
+    let described: Vec<(&str, &str, &LinkBasis)> = group
+        .links
+        .iter()
+        .map(|l| (&*l.a.node_id, &*l.b.node_id, &l.basis))
+        .collect();
+    assert!(described.iter().any(|(a, b, basis)| {
+        (*a, *b) == ("a", "b")
+            && matches!(basis, LinkBasis::Identifier { identifier } if identifier.starts_with("duns:"))
+    }));
+    assert!(described.iter().any(|(a, b, basis)| {
+        (*a, *b) == ("b", "c")
+            && matches!(basis, LinkBasis::Identifier { identifier } if identifier.starts_with("lei:"))
+    }));
+    assert!(explanation.rejected.is_empty());
+}
+
+#[test]
+fn explain_reports_temporal_rejection() {
+    let dated = |from: &str, to: &str| {
+        let mut id = make_identifier("duns", "111111111");
+        id.valid_from = Some(crate::test_helpers::date(from));
+        id.valid_to = Some(Some(crate::test_helpers::date(to)));
+        id
+    };
+    let a = minimal_file(
+        SALT_A,
+        vec![make_org_node(
+            "old",
+            Some("Old Co"),
+            Some(vec![dated("2010-01-01", "2015-12-31")]),
+        )],
+        vec![],
+    );
+    let b = minimal_file(
+        SALT_B,
+        vec![make_org_node(
+            "new",
+            Some("New Co"),
+            Some(vec![dated("2020-01-01", "2025-12-31")]),
+        )],
+        vec![],
+    );
+
+    let explanation = explain(&[a, b]);
+    assert!(explanation.groups.is_empty());
+    assert_eq!(explanation.rejected.len(), 1);
+    let rejected = &explanation.rejected[0];
+    assert_eq!(&*rejected.a.node_id, "old");
+    assert_eq!(&*rejected.b.node_id, "new");
+    assert!(matches!(
+        rejected.reason,
+        RejectionReason::TemporalIncompatibility { .. }
+    ));
+}
+
+#[test]
+fn explain_reports_same_as_links_and_threshold_rejections() {
+    use crate::enums::EdgeType;
+    use crate::test_helpers::typed_edge;
+
+    let same_as = |id: &str, source: &str, target: &str, confidence: &str| {
+        let mut edge = typed_edge(id, EdgeType::SameAs, source, target);
+        edge.properties.extra.insert(
+            "confidence".to_owned(),
+            serde_json::Value::from(confidence).into(),
+        );
+        edge
+    };
+    let file = minimal_file(
+        SALT_A,
+        vec![
+            make_org_node("x1", Some("X"), None),
+            make_org_node("x2", Some("X"), None),
+            make_org_node("y1", Some("Y"), None),
+            make_org_node("y2", Some("Y"), None),
+        ],
+        vec![
+            same_as("sa-x", "x1", "x2", "definite"),
+            same_as("sa-y", "y1", "y2", "probable"),
+        ],
+    );
+
+    let explanation = explain(&[file]);
+    assert_eq!(explanation.groups.len(), 1);
+    assert_eq!(
+        explanation.groups[0].links[0].basis,
+        LinkBasis::SameAs {
+            edge_id: "sa-x".to_owned(),
+            confidence: Some("definite".to_owned()),
+        }
+    );
+    assert_eq!(explanation.rejected.len(), 1);
+    assert_eq!(
+        explanation.rejected[0].reason,
+        RejectionReason::BelowSameAsThreshold {
+            edge_id: "sa-y".to_owned(),
+            confidence: Some("probable".to_owned()),
+        }
+    );
+}
+
+#[test]
+fn explain_reports_annulled_lei_rejection() {
+    let annulled = || {
+        let mut id = make_identifier("lei", "7LTWFZYICNSX8D621K86");
+        id.extra.insert(
+            "entity_status".to_owned(),
+            serde_json::Value::from("ANNULLED").into(),
+        );
+        id
+    };
+    let a = minimal_file(
+        SALT_A,
+        vec![make_org_node("a", Some("Gone"), Some(vec![annulled()]))],
+        vec![],
+    );
+    let b = minimal_file(
+        SALT_B,
+        vec![make_org_node("b", Some("Gone"), Some(vec![annulled()]))],
+        vec![],
+    );
+
+    let explanation = explain(&[a, b]);
+    assert!(explanation.groups.is_empty());
+    assert_eq!(explanation.rejected.len(), 1);
+    assert_eq!(
+        explanation.rejected[0].reason,
+        RejectionReason::AnnulledLei {
+            identifier: "lei:7LTWFZYICNSX8D621K86".to_owned(),
+        }
+    );
+}
+
+fn with_stable_ids(files: &[OmtsFile], previous: &OmtsFile) -> MergeOutput {
+    let config = MergeConfig {
+        stable_ids: Some(StableIds::from_file(previous)),
+        ..MergeConfig::default()
+    };
+    merge_with_config(files, &config).expect("merge succeeds")
+}
+
+fn id_of(file: &OmtsFile, duns: &str) -> String {
+    file.nodes
+        .iter()
+        .find(|n| n.identifiers.iter().flatten().any(|id| id.value == duns))
+        .map(|n| n.id.to_string())
+        .expect("node with duns")
+}
+
+#[test]
+fn stable_ids_survive_a_new_group_sorting_first() {
+    let duns = |v: &str| Some(vec![make_identifier("duns", v)]);
+    let acme = make_org_node("acme", Some("Acme"), duns("200000000"));
+    let beta = make_org_node("beta", Some("Beta"), duns("300000000"));
+    let month_1 = minimal_file(
+        SALT_A,
+        vec![acme.clone(), beta.clone()],
+        vec![make_supplies_edge("e1", "beta", "acme")],
+    );
+    let previous = merge(&[month_1]).expect("merge succeeds").file;
+
+    let month_2 = minimal_file(
+        SALT_B,
+        vec![
+            acme,
+            beta,
+            make_org_node("aaa", Some("Aardvark"), duns("100000000")),
+        ],
+        vec![
+            make_supplies_edge("e1", "beta", "acme"),
+            make_supplies_edge("e2", "aaa", "acme"),
+        ],
+    );
+    let fresh = merge(std::slice::from_ref(&month_2)).expect("merge succeeds");
+    assert_ne!(
+        id_of(&fresh.file, "200000000"),
+        id_of(&previous, "200000000")
+    );
+
+    let output = with_stable_ids(&[month_2], &previous);
+    for duns in ["200000000", "300000000"] {
+        assert_eq!(id_of(&output.file, duns), id_of(&previous, duns));
+    }
+    let new_id = id_of(&output.file, "100000000");
+    assert!(previous.nodes.iter().all(|n| *n.id != *new_id));
+
+    let old_edge = &previous.edges[0];
+    let kept = output
+        .file
+        .edges
+        .iter()
+        .find(|e| e.source == old_edge.source)
+        .expect("beta edge");
+    assert_eq!(kept.id, old_edge.id);
+    assert!(
+        output
+            .file
+            .edges
+            .iter()
+            .all(|e| e.id == old_edge.id || e.source == node_id(&new_id))
+    );
+
+    let changes = output.id_changes.expect("id changes requested");
+    assert_eq!(changes.reused, 2);
+    assert_eq!(changes.minted, vec![node_id(&new_id)]);
+    assert!(changes.split.is_empty() && changes.coalesced.is_empty());
+    assert!(changes.retired.is_empty());
+}
+
+#[test]
+fn stable_ids_keep_nodes_without_identifiers_through_provenance() {
+    let duns = |v: &str| Some(vec![make_identifier("duns", v)]);
+    let facility = |file: &OmtsFile| {
+        file.nodes
+            .iter()
+            .find(|n| n.node_type == NodeTypeTag::Known(NodeType::Facility))
+            .map(|n| n.id.to_string())
+            .expect("facility")
+    };
+    let plant = crate::test_helpers::facility_node("plant");
+    let month_1 = minimal_file(
+        SALT_A,
+        vec![
+            make_org_node("acme", Some("Acme"), duns("200000000")),
+            plant.clone(),
+        ],
+        vec![],
+    );
+    let month_2 = minimal_file(
+        SALT_A,
+        vec![
+            make_org_node("aaa", Some("Aardvark"), duns("100000000")),
+            make_org_node("acme", Some("Acme"), duns("200000000")),
+            plant,
+        ],
+        vec![],
+    );
+    let provenance = MergeConfig {
+        record_provenance: true,
+        ..MergeConfig::default()
+    };
+    let previous = merge_with_config(&[month_1], &provenance)
+        .expect("merge succeeds")
+        .file;
+
+    let output = with_stable_ids(std::slice::from_ref(&month_2), &previous);
+    assert_eq!(facility(&output.file), facility(&previous));
+    let changes = output.id_changes.expect("id changes requested");
+    assert_eq!(changes.reused, 2);
+    assert!(changes.retired.is_empty());
+
+    // Without `_provenance` the facility cannot be matched.
+    let mut bare = previous.clone();
+    for node in &mut bare.nodes {
+        node.extra.remove("_provenance");
+    }
+    let output = with_stable_ids(&[month_2], &bare);
+    assert_ne!(facility(&output.file), facility(&previous));
+}
+
+#[test]
+fn stable_ids_report_splits_coalescences_and_retirements() {
+    let duns = |values: &[&str]| {
+        Some(
+            values
+                .iter()
+                .map(|v| make_identifier("duns", v))
+                .collect::<Vec<_>>(),
+        )
+    };
+    let previous = minimal_file(
+        SALT_A,
+        vec![
+            make_org_node("p-x", None, duns(&["111111111"])),
+            make_org_node("p-y", None, duns(&["222222222"])),
+            make_org_node("p-z", None, duns(&["333333333", "444444444"])),
+            make_org_node("p-gone", None, duns(&["555555555"])),
+        ],
+        vec![],
+    );
+    let current = minimal_file(
+        SALT_B,
+        vec![
+            make_org_node("q1", None, duns(&["111111111", "222222222"])),
+            make_org_node("q2", None, duns(&["333333333"])),
+            make_org_node("q3", None, duns(&["444444444"])),
+        ],
+        vec![],
+    );
+
+    let output = with_stable_ids(&[current], &previous);
+    let ids: Vec<&str> = output.file.nodes.iter().map(|n| &*n.id).collect();
+    assert_eq!(ids, ["p-x", "p-z", "n-0"]);
+
+    let changes = output.id_changes.expect("id changes requested");
+    assert_eq!(changes.reused, 2);
+    assert_eq!(changes.minted, vec![node_id("n-0")]);
+    assert_eq!(
+        changes.split,
+        vec![IdSplit {
+            previous: node_id("p-z"),
+            into: vec![node_id("p-z"), node_id("n-0")],
+        }]
+    );
+    assert_eq!(
+        changes.coalesced,
+        vec![IdCoalescence {
+            merged: node_id("p-x"),
+            from: vec![node_id("p-x"), node_id("p-y")],
+        }]
+    );
+    assert_eq!(changes.retired, vec![node_id("p-gone")]);
+}
+
+#[test]
+fn preview_reports_groups_conflicts_and_singletons() {
+    let duns = |v: &str| Some(vec![make_identifier("duns", v)]);
+    let a = minimal_file(
+        SALT_A,
+        vec![
+            make_org_node("a1", Some("Acme"), duns("111111111")),
+            make_org_node("a2", Some("Lonely"), duns("999999999")),
+        ],
+        vec![],
+    );
+    let b = minimal_file(
+        SALT_B,
+        vec![make_org_node("b1", Some("ACME Corp"), duns("111111111"))],
+        vec![],
+    );
+    let c = minimal_file(
+        SALT_C,
+        vec![make_org_node("c1", Some("Acme"), duns("111111111"))],
+        vec![],
+    );
+    let files = [a, b, c];
+    let config = MergeConfig {
+        group_size_limit: 2,
+        ..MergeConfig::default()
+    };
+
+    let preview = merge_preview(&files, &config).expect("preview succeeds");
+    assert_eq!(preview.node_count, 4);
+    assert_eq!(preview.merged_node_count, 2);
+    assert_eq!(preview.group_sizes, BTreeMap::from([(1, 1), (3, 1)]));
+    assert_eq!(preview.groups.len(), 1);
+    let group = &preview.groups[0];
+    assert!(group.oversized);
+    let members: Vec<(&str, &str)> = group
+        .members
+        .iter()
+        .map(|m| (m.source_file.as_str(), &*m.node_id))
+        .collect();
+    assert_eq!(
+        members,
+        [("file_0", "a1"), ("file_1", "b1"), ("file_2", "c1")]
+    );
+    assert_eq!(group.conflicts, ["name"]);
+    assert_eq!(preview.conflicts, BTreeMap::from([("name".to_owned(), 1)]));
+    assert_eq!(preview.singletons.len(), 1);
+    assert_eq!(&*preview.singletons[0].node_id, "a2");
+
+    let merged = merge_with_config(&files, &config).expect("merge succeeds");
+    assert_eq!(merged.file.nodes.len(), preview.merged_node_count);
+    assert_eq!(merged.warnings.len(), 1);
+}
+
+#[test]
+fn preview_counts_resolved_conflicts_and_rejects_empty_input() {
+    let (a, b) = policy_files(None, None);
+    let config = MergeConfig {
+        conflict_policies: BTreeMap::from([("name".to_owned(), ConflictPolicy::NewestSnapshot)]),
+        ..MergeConfig::default()
+    };
+    let preview = merge_preview(&[a, b], &config).expect("preview succeeds");
+    assert_eq!(preview.conflicts.get("name"), Some(&1));
+
+    assert!(matches!(
+        merge_preview(&[], &MergeConfig::default()),
+        Err(MergeError::NoInputFiles)
+    ));
+}
+
+/// Pre-enrichment inputs: `acme` in both files (shared DUNS), `supplier` in
+/// file 0 and `vendor` in file 1 with unrelated DUNS.
+fn enrichment_inputs(vendor_duns: &[&str]) -> Vec<OmtsFile> {
+    let duns = |values: &[&str]| Some(values.iter().map(|v| make_identifier("duns", v)).collect());
+    vec![
+        minimal_file(
+            SALT_A,
+            vec![
+                make_org_node("acme", Some("Acme"), duns(&["100000000"])),
+                make_org_node("supplier", Some("Supplier"), duns(&["200000000"])),
+            ],
+            vec![],
+        ),
+        minimal_file(
+            SALT_B,
+            vec![
+                make_org_node("acme-2", Some("Acme"), duns(&["100000000"])),
+                make_org_node("vendor", Some("Vendor"), duns(vendor_duns)),
+                make_org_node("new", Some("New"), duns(&["200000000"])),
+            ],
+            vec![],
+        ),
+    ]
+}
+
+fn enrichment_base() -> EnrichmentBase {
+    let mut pre = enrichment_inputs(&["300000000"]);
+    pre[1].nodes.pop();
+    let config = MergeConfig {
+        record_provenance: true,
+        ..MergeConfig::default()
+    };
+    let base = merge_with_config(&pre, &config)
+        .expect("merge succeeds")
+        .file;
+    EnrichmentBase::from_file(&base).expect("base has provenance")
+}
+
+#[test]
+fn enrichment_base_proposes_new_links_instead_of_merging() {
+    // Enrichment gave `vendor` the supplier's DUNS.
+    let files = enrichment_inputs(&["300000000", "200000000"]);
+    let config = MergeConfig {
+        enrichment_base: Some(enrichment_base()),
+        explain: true,
+        ..MergeConfig::default()
+    };
+    let output = merge_with_config(&files, &config).expect("merge succeeds");
+
+    // acme + acme-2 (established), supplier, vendor, and new: the supplier's
+    // DUNS would extend its base group to both `vendor` and `new`.
+    assert_eq!(output.file.nodes.len(), 4);
+    assert_eq!(output.proposed_same_as, 3);
+    let proposed: Vec<&Edge> = output
+        .file
+        .edges
+        .iter()
+        .filter(|e| {
+            e.properties.extra.get("basis").and_then(|v| v.as_str()) == Some("enrichment_match")
+        })
+        .collect();
+    assert_eq!(proposed.len(), 3);
+    assert!(proposed.iter().all(|e| {
+        e.properties
+            .extra
+            .get("confidence")
+            .and_then(|v| v.as_str())
+            == Some("probable")
+    }));
+    let name_of = |id: &str| {
+        output
+            .file
+            .nodes
+            .iter()
+            .find(|n| n.id.to_string() == id)
+            .and_then(|n| n.name.clone())
+            .expect("proposed end exists")
+    };
+    let mut pairs: Vec<Vec<String>> = proposed
+        .iter()
+        .map(|e| {
+            let mut ends = vec![name_of(&e.source), name_of(&e.target)];
+            ends.sort();
+            ends
+        })
+        .collect();
+    pairs.sort();
+    assert_eq!(
+        pairs,
+        [
+            ["New", "Supplier"],
+            ["New", "Vendor"],
+            ["Supplier", "Vendor"]
+        ]
+    );
+    assert_eq!(
+        output.metadata.enrichment,
+        Some(crate::merge::EnrichmentStage::PostEnrichment)
+    );
+
+    let explanation = output.explanation.expect("explanation");
+    assert!(explanation.rejected.iter().any(|r| matches!(
+        &r.reason,
+        RejectionReason::EnrichmentMatch {
+            link: LinkBasis::Identifier { identifier }
+        } if identifier == "duns:200000000"
+    )));
+
+    // Without a base the same inputs collapse vendor into the supplier.
+    let plain = merge(&files).expect("merge succeeds");
+    assert_eq!(plain.file.nodes.len(), 2);
+    assert_eq!(plain.proposed_same_as, 0);
+    assert_eq!(plain.metadata.enrichment, None);
+}
+
+#[test]
+fn pre_enrichment_merge_is_recorded_in_metadata() {
+    let config = MergeConfig {
+        pre_enrichment: true,
+        ..MergeConfig::default()
+    };
+    let output =
+        merge_with_config(&enrichment_inputs(&["300000000"]), &config).expect("merge succeeds");
+    assert_eq!(
+        output.metadata.enrichment,
+        Some(crate::merge::EnrichmentStage::PreEnrichment)
+    );
+    let recorded = output
+        .file
+        .extra
+        .get("merge_metadata")
+        .map(|m| serde_json::Value::from(m.clone()));
+    assert_eq!(
+        recorded.as_ref().and_then(|m| m.get("enrichment")),
+        Some(&serde_json::json!("pre_enrichment"))
+    );
+
+    let both = MergeConfig {
+        pre_enrichment: true,
+        enrichment_base: Some(enrichment_base()),
+        ..MergeConfig::default()
+    };
+    assert!(matches!(
+        merge_with_config(&enrichment_inputs(&["300000000"]), &both),
+        Err(MergeError::InvalidEnrichmentBase(_))
+    ));
+}
+
+#[test]
+fn enrichment_base_rejects_inputs_that_differ_from_the_base() {
+    let config = MergeConfig {
+        enrichment_base: Some(enrichment_base()),
+        ..MergeConfig::default()
+    };
+    let mut files = enrichment_inputs(&["300000000"]);
+
+    files.reverse();
+    let err = merge_with_config(&files, &config).expect_err("reordered inputs");
+    assert!(
+        matches!(&err, MergeError::InvalidEnrichmentBase(msg) if msg.contains("`acme` of file_0")),
+        "{err}"
+    );
+    assert!(merge_preview(&files, &config).is_err());
+
+    files.pop();
+    let err = merge_with_config(&files, &config).expect_err("missing input");
+    assert!(
+        matches!(&err, MergeError::InvalidEnrichmentBase(msg) if msg.contains("2 input files, but 1")),
+        "{err}"
+    );
+}
+
+#[test]
+fn enrichment_base_requires_provenance() {
+    let base = merge(&enrichment_inputs(&["300000000"]))
+        .expect("merge succeeds")
+        .file;
+    assert!(matches!(
+        EnrichmentBase::from_file(&base),
+        Err(MergeError::InvalidEnrichmentBase(_))
+    ));
+}
+
+/// Identifier sets of the merged nodes and of the ends of each proposed
+/// `same_as` edge, independent of generated IDs and salts.
+fn enrichment_groups(output: &MergeOutput) -> (Vec<Vec<String>>, Vec<Vec<Vec<String>>>) {
+    let identifiers = |node: &Node| -> Vec<String> {
+        node.identifiers
+            .iter()
+            .flatten()
+            .map(|id| format!("{}:{}", id.scheme, id.value))
+            .collect()
+    };
+    let by_id: HashMap<String, Vec<String>> = output
+        .file
+        .nodes
+        .iter()
+        .map(|n| (n.id.to_string(), identifiers(n)))
+        .collect();
+    let nodes = output.file.nodes.iter().map(identifiers).collect();
+    let proposals = output
+        .file
+        .edges
+        .iter()
+        .filter(|e| {
+            e.properties.extra.get("basis").and_then(|v| v.as_str()) == Some("enrichment_match")
+        })
+        .map(|e| {
+            [&e.source, &e.target]
+                .iter()
+                .map(|end| by_id.get(&end.to_string()).cloned().unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    (nodes, proposals)
+}
+
+#[test]
+fn enrichment_merge_is_deterministic() {
+    // `b` carries the identifiers of two different base groups.
+    let lei = make_identifier("lei", "5493006MHB84DD0ZWV18");
+    let duns = make_identifier("duns", "081466849");
+    let f0 = minimal_file(
+        SALT_A,
+        vec![make_org_node("a", Some("A"), Some(vec![lei.clone()]))],
+        vec![],
+    );
+    let f1 = minimal_file(
+        SALT_B,
+        vec![make_org_node("c", Some("C"), Some(vec![duns.clone()]))],
+        vec![],
+    );
+    let f2_pre = minimal_file(SALT_C, vec![make_org_node("b", Some("B"), None)], vec![]);
+    let f2 = minimal_file(
+        SALT_C,
+        vec![make_org_node("b", Some("B"), Some(vec![lei, duns]))],
+        vec![],
+    );
+    let provenance = MergeConfig {
+        record_provenance: true,
+        ..MergeConfig::default()
+    };
+    let base = merge_with_config(&[f0.clone(), f1.clone(), f2_pre], &provenance)
+        .expect("merge succeeds")
+        .file;
+    let config = MergeConfig {
+        enrichment_base: Some(EnrichmentBase::from_file(&base).expect("base has provenance")),
+        ..MergeConfig::default()
+    };
+
+    let files = [f0, f1, f2];
+    let first = enrichment_groups(&merge_with_config(&files, &config).expect("merge succeeds"));
+    for _ in 0..8 {
+        let again = merge_with_config(&files, &config).expect("merge succeeds");
+        assert_eq!(enrichment_groups(&again), first);
+    }
+}
+
+#[test]
+fn enrichment_base_keeps_new_nodes_out_of_base_groups() {
+    let lei = || Some(vec![make_identifier("lei", "5493006MHB84DD0ZWV18")]);
+    let duns = || Some(vec![make_identifier("duns", "081466849")]);
+    let f0 = minimal_file(SALT_A, vec![make_org_node("a", Some("A"), lei())], vec![]);
+    let f1 = minimal_file(
+        SALT_B,
+        vec![
+            make_org_node("b", Some("B"), lei()),
+            make_org_node("c", Some("C"), duns()),
+            make_org_node("d", Some("D"), duns()),
+        ],
+        vec![],
+    );
+    let provenance = MergeConfig {
+        record_provenance: true,
+        ..MergeConfig::default()
+    };
+    let empty = minimal_file(SALT_B, vec![], vec![]);
+    let base = merge_with_config(&[f0.clone(), empty], &provenance)
+        .expect("merge succeeds")
+        .file;
+    let config = MergeConfig {
+        enrichment_base: Some(EnrichmentBase::from_file(&base).expect("base has provenance")),
+        ..MergeConfig::default()
+    };
+    let output = merge_with_config(&[f0, f1], &config).expect("merge succeeds");
+
+    // `b` is only proposed for `a`; `c` and `d`, both new, merge as usual.
+    assert_eq!(output.file.nodes.len(), 3);
+    assert_eq!(output.proposed_same_as, 1);
+}
@@ -0,0 +1,105 @@
+use crate::merge::{Conflict, ConflictEntry, ScalarMergeResult, merge_scalars};
+
+use super::pipeline::resolve_scalar_merge;
+
+/// The reporting-entity perspectives of the merge inputs, used to reconcile
+/// the perspective-dependent `tier` property (merge.md Section 4.2).
+///
+/// A file's perspective is the merge group of its `reporting_entity` node, so
+/// two files whose reporting entities merge share a perspective even when
+/// their file-local IDs differ.
+pub(super) struct Perspectives {
+    /// Per input file: the union-find representative of its reporting entity.
+    groups: Vec<Option<usize>>,
+    /// Per input file: the reporting entity written into tier conflicts.
+    labels: Vec<Option<String>>,
+    /// The perspective whose tiers are retained.
+    primary: Option<usize>,
+    /// Whether the inputs span more than one perspective.
+    mixed: bool,
+}
+
+impl Perspectives {
+    /// Builds the perspective table.
+    ///
+    /// `groups[i]` is the representative of file `i`'s reporting entity (or
+    /// `None` when it has none); `labels[i]` identifies it in conflict records.
+    pub(super) fn new(
+        groups: Vec<Option<usize>>,
+        labels: Vec<Option<String>>,
+        primary: Option<usize>,
+    ) -> Self {
+        let mixed = groups.iter().any(|g| *g != groups[0]);
+        Self {
+            groups,
+            labels,
+            primary,
+            mixed,
+        }
+    }
+
+    /// The perspective of the merged file: the primary one, or the one all
+    /// inputs share.
+    pub(super) fn output(&self) -> Option<usize> {
+        match self.primary {
+            Some(primary) => Some(primary),
+            None if !self.mixed => self.groups.first().copied().flatten(),
+            None => None,
+        }
+    }
+
+    /// Reconciles the `tier` values of one edge group.
+    ///
+    /// `tiers` holds `(tier, file index, source label)` per member edge. When
+    /// all inputs share a perspective, tiers merge like any scalar. Otherwise
+    /// the agreed tier of the primary-perspective members is kept, and every
+    /// other tier value (all of them, without a primary perspective) is
+    /// recorded in the returned conflict with its source's reporting entity.
+    pub(super) fn reconcile_tier(
+        &self,
+        tiers: &[(Option<u32>, usize, &str)],
+    ) -> (Option<u32>, Option<Conflict>) {
+        if !self.mixed {
+            let inputs: Vec<(Option<u32>, &str)> =
+                tiers.iter().map(|&(tier, _, src)| (tier, src)).collect();
+            return resolve_scalar_merge(&inputs, "tier");
+        }
+
+        let retained = self.primary.and_then(|primary| {
+            let inputs: Vec<(Option<u32>, &str)> = tiers
+                .iter()
+                .filter(|&&(_, file, _)| self.groups[file] == Some(primary))
+                .map(|&(tier, _, src)| (tier, src))
+                .collect();
+            match merge_scalars(&inputs) {
+                ScalarMergeResult::Agreed(tier) => tier,
+                ScalarMergeResult::Conflict(_) => None,
+            }
+        });
+
+        let mut values: Vec<ConflictEntry> = tiers
+            .iter()
+            .filter_map(|&(tier, file, src)| {
+                let tier = tier?;
+                (retained != Some(tier)).then(|| ConflictEntry {
+                    value: serde_json::Value::from(tier),
+                    source_file: src.to_owned(),
+                    reporting_entity: self.labels[file].clone(),
+                })
+            })
+            .collect();
+        values.sort_by(|a, b| {
+            a.source_file
+                .cmp(&b.source_file)
+                .then_with(|| a.value.to_string().cmp(&b.value.to_string()))
+        });
+        values.dedup();
+
+        let conflict = (!values.is_empty()).then(|| Conflict {
+            field: "tier".to_owned(),
+            values,
+            resolution: None,
+        });
+        (retained, conflict)
+    }
+}
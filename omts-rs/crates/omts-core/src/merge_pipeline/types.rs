@@ -1,6 +1,12 @@
+use std::collections::BTreeMap;
+
 use crate::file::OmtsFile;
 use crate::merge::{MergeMetadata, SameAsThreshold};
 
+use super::enrichment::EnrichmentBase;
+use super::explain::MergeExplanation;
+use super::stable::{IdChanges, StableIds};
+
 /// Errors that can occur during the merge pipeline.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MergeError {
@@ -15,6 +21,15 @@ pub enum MergeError {
     SaltGenerationFailed(String),
     /// A required OMTS version or date string could not be constructed.
     InternalDataError(String),
+    /// [`MergeConfig::primary_perspective`] does not name an input file whose
+    /// `reporting_entity` resolves to a node.
+    InvalidPrimaryPerspective(String),
+    /// An entry in [`MergeConfig::conflict_policies`] names a property that
+    /// cannot be resolved, or a policy that does not apply to it.
+    InvalidConflictPolicy(String),
+    /// The file given as [`MergeConfig::enrichment_base`] has no usable
+    /// `_provenance` records, or [`MergeConfig::pre_enrichment`] is also set.
+    InvalidEnrichmentBase(String),
 }
 
 impl std::fmt::Display for MergeError {
@@ -30,6 +45,11 @@ impl std::fmt::Display for MergeError {
             Self::InternalDataError(msg) => {
                 write!(f, "internal data error during merge: {msg}")
             }
+            Self::InvalidPrimaryPerspective(msg) => {
+                write!(f, "invalid primary perspective: {msg}")
+            }
+            Self::InvalidConflictPolicy(msg) => write!(f, "invalid conflict policy: {msg}"),
+            Self::InvalidEnrichmentBase(msg) => write!(f, "invalid enrichment base: {msg}"),
         }
     }
 }
@@ -69,6 +89,54 @@ impl std::fmt::Display for MergeWarning {
     }
 }
 
+/// How conflicting values of one property are resolved to a merged value
+/// (merge.md Section 4.5).
+///
+/// A resolved property carries the chosen value, and its full conflict record
+/// is still written to `_conflicts`. Ties left by a selection policy go to the
+/// value from the earliest input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Prefer sources in this order of input-file indices; files not listed
+    /// rank after all listed ones.
+    SourcePriority(Vec<usize>),
+    /// Prefer the value whose `data_quality.last_verified` is most recent.
+    /// Sources without one rank last.
+    MostRecentlyVerified,
+    /// Prefer the value whose `data_quality.confidence` is highest
+    /// (`verified` > `reported` > `inferred` > `estimated` > absent).
+    HighestConfidence,
+    /// Prefer the value from the file with the newest `snapshot_date`.
+    NewestSnapshot,
+    /// Take the largest value (numeric properties only).
+    Max,
+    /// Take the smallest value (numeric properties only).
+    Min,
+    /// Take the arithmetic mean of the members' values (numeric properties
+    /// only).
+    Mean,
+}
+
+impl ConflictPolicy {
+    /// The policy's name as recorded in a resolved conflict's `resolution`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SourcePriority(_) => "source_priority",
+            Self::MostRecentlyVerified => "most_recently_verified",
+            Self::HighestConfidence => "highest_confidence",
+            Self::NewestSnapshot => "newest_snapshot",
+            Self::Max => "max",
+            Self::Min => "min",
+            Self::Mean => "mean",
+        }
+    }
+
+    /// Whether the policy computes over numbers rather than selecting a source.
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Self::Max | Self::Min | Self::Mean)
+    }
+}
+
 /// Configuration for the merge pipeline.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MergeConfig {
@@ -87,6 +155,91 @@ pub struct MergeConfig {
     ///
     /// Default: `"<unknown>"`.
     pub default_source_label: String,
+
+    /// Index into the input files of the source whose perspective is primary
+    /// when the inputs have different reporting entities (merge.md Section
+    /// 4.2).
+    ///
+    /// `tier` is relative to a file's `reporting_entity`. With a primary
+    /// perspective, the merged file keeps that source's `reporting_entity` and
+    /// `tier` values; tiers from other perspectives go to `_conflicts`. With
+    /// `None`, every `tier` from a multi-perspective merge goes to
+    /// `_conflicts` and the merged file has no `reporting_entity`.
+    ///
+    /// Default: `None`.
+    pub primary_perspective: Option<usize>,
+
+    /// Resolution policy per property name (merge.md Section 4.5).
+    ///
+    /// Without a policy, conflicting `name`, `jurisdiction`, and `status`
+    /// values are omitted from the merged node and recorded in `_conflicts`,
+    /// and other scalars are copied from the group's first member. With a
+    /// policy, the property is compared across all members, the chosen value
+    /// is kept, and any disagreement is still recorded in `_conflicts`.
+    ///
+    /// Default: empty.
+    pub conflict_policies: BTreeMap<String, ConflictPolicy>,
+
+    /// Whether to record why each merge group formed and which candidate
+    /// pairs were rejected, in [`MergeOutput::explanation`].
+    ///
+    /// Default: `false`.
+    pub explain: bool,
+
+    /// Whether to write `_provenance` on every merged node and edge, naming
+    /// the source file and original ID of each member (merge.md Section 4.6).
+    ///
+    /// Provenance is what `unmerge` needs to split a merged node or extract
+    /// one source's contribution.
+    ///
+    /// Default: `false`.
+    pub record_provenance: bool,
+
+    /// IDs of a previous merge to keep stable (merge.md Section 4.7).
+    ///
+    /// Merged nodes that match a previous node by identifier keep its ID, and
+    /// merged edges with the same type and endpoints as a previous edge keep
+    /// that edge's ID; everything else gets a fresh ID not used in the
+    /// previous file. The differences are reported in
+    /// [`MergeOutput::id_changes`].
+    ///
+    /// Default: `None` (IDs `n-0`, `n-1`, ... and `e-0`, `e-1`, ...).
+    pub stable_ids: Option<StableIds>,
+
+    /// Merge groups of a pre-enrichment merge to hold to (merge.md Section
+    /// 4.8).
+    ///
+    /// Links between nodes of the same base group, and links among nodes the
+    /// base does not contain, merge as usual. A shared identifier that would
+    /// extend a base group is emitted as a `same_as` edge with
+    /// `confidence: "probable"` and `basis: "enrichment_match"` for review
+    /// instead; a `same_as` edge that would extend one is not honoured. The
+    /// merged file's `merge_metadata` records `enrichment: "post_enrichment"`.
+    ///
+    /// Default: `None`.
+    pub enrichment_base: Option<EnrichmentBase>,
+
+    /// Whether this merge runs before identifier enrichment, to serve later
+    /// as an [`EnrichmentBase`] (merge.md Section 4.8). The merged file's
+    /// `merge_metadata` records `enrichment: "pre_enrichment"`.
+    ///
+    /// Cannot be combined with [`MergeConfig::enrichment_base`], which makes
+    /// the merge post-enrichment.
+    ///
+    /// Default: `false`.
+    pub pre_enrichment: bool,
+
+    /// Display names of the input files (typically their paths), in input
+    /// order.
+    ///
+    /// Source labels stay positional (`file_{i}`); these names are reported
+    /// next to them so a reader can tell which input a label stands for.
+    /// Inputs past the end of the list have no display name. With
+    /// [`MergeConfig::record_provenance`], the file name of each is recorded
+    /// in `merge_metadata.source_names`.
+    ///
+    /// Default: empty.
+    pub source_paths: Vec<String>,
 }
 
 impl Default for MergeConfig {
@@ -95,6 +248,14 @@ impl Default for MergeConfig {
             group_size_limit: 50,
             same_as_threshold: SameAsThreshold::default(),
             default_source_label: "<unknown>".to_owned(),
+            primary_perspective: None,
+            conflict_policies: BTreeMap::new(),
+            explain: false,
+            record_provenance: false,
+            stable_ids: None,
+            enrichment_base: None,
+            pre_enrichment: false,
+            source_paths: Vec::new(),
         }
     }
 }
@@ -110,4 +271,13 @@ pub struct MergeOutput {
     pub warnings: Vec<MergeWarning>,
     /// Total number of conflict records across all merged nodes and edges.
     pub conflict_count: usize,
+    /// Why each merge group formed; `Some` only when [`MergeConfig::explain`]
+    /// is set.
+    pub explanation: Option<MergeExplanation>,
+    /// Which merged node IDs were reused, minted, split, coalesced, or
+    /// retired; `Some` only when [`MergeConfig::stable_ids`] is set.
+    pub id_changes: Option<IdChanges>,
+    /// Number of `same_as` edges proposed for links that
+    /// [`MergeConfig::enrichment_base`] held back.
+    pub proposed_same_as: usize,
 }
@@ -0,0 +1,563 @@
+use std::collections::HashMap;
+
+use crate::dynvalue::{DynMap, DynValue};
+use crate::enums::{DisclosureScope, EdgeType, EdgeTypeTag, NodeType, NodeTypeTag, Sensitivity};
+use crate::file::OmtsFile;
+use crate::sensitivity::{effective_property_sensitivity, effective_sensitivity};
+use crate::structures::{Edge, Node};
+use crate::types::{Identifier, Label};
+use crate::validation::{Diagnostic, Location, RuleId, Severity, ValidationResult};
+
+/// Values shorter than this are never matched against free text, so short
+/// codes such as `"42"` do not flag every string that happens to equal them.
+pub const MIN_MATCHED_VALUE_LEN: usize = 4;
+
+/// `extra` keys holding merge conflict records, audited under SDA-03.
+const CONFLICT_KEYS: [&str; 2] = ["_conflicts", "_resolutions"];
+
+/// Schemes recognised at the head of a canonical identifier string.
+const CORE_SCHEMES: [&str; 6] = ["lei", "duns", "gln", "nat-reg", "vat", "internal"];
+
+/// Scans `file` for content that is unsafe to disclose at `scope` but that
+/// the sensitivity model of L1-SDI-02 does not see (redaction.md Section 7.5).
+///
+/// The checks cover what survives redaction untouched: `extra` fields,
+/// labels, `_conflicts` and `_resolutions` records, `_provenance`, merge
+/// metadata, and the neighbourhood of `boundary_ref` nodes. Definite leaks
+/// are reported as [`Severity::Error`]; the neighbourhood heuristics of
+/// SDA-04 as [`Severity::Warning`]. `internal` scope has nothing to leak and
+/// yields an empty result.
+pub fn audit_disclosure(file: &OmtsFile, scope: &DisclosureScope) -> ValidationResult {
+    if matches!(scope, DisclosureScope::Internal) {
+        return ValidationResult::new();
+    }
+    let mut audit = Audit {
+        scope,
+        sensitive: HashMap::new(),
+        leaves: Vec::new(),
+        diags: Vec::new(),
+    };
+    audit.check_persons(file);
+    audit.collect_identifiers(file);
+    audit.check_extras(file);
+    audit.match_leaves();
+    audit.check_boundary_refs(file);
+    ValidationResult::from_diagnostics(audit.diags)
+}
+
+/// Where a scanned value lives: a node, an edge, or the file header.
+#[derive(Clone, Copy)]
+enum Owner<'a> {
+    Node(&'a Node),
+    Edge(&'a Edge),
+    Header,
+}
+
+impl Owner<'_> {
+    fn location(&self, path: &str) -> Location {
+        match self {
+            Self::Node(node) => Location::Node {
+                node_id: node.id.to_string(),
+                field: Some(path.to_owned()),
+            },
+            Self::Edge(edge) => Location::Edge {
+                edge_id: edge.id.to_string(),
+                field: Some(path.to_owned()),
+            },
+            Self::Header => Location::Global,
+        }
+    }
+
+    /// The node type used to rate identifiers found here; edges and the
+    /// header follow the scheme defaults.
+    fn node_type(&self) -> NodeTypeTag {
+        match self {
+            Self::Node(node) => node.node_type.clone(),
+            Self::Edge(_) | Self::Header => NodeTypeTag::Known(NodeType::Organization),
+        }
+    }
+}
+
+/// A value that must not appear in the audited file, with where it came from.
+struct Sensitive {
+    origin: String,
+    /// `true` for person names, which are reported under SDA-01.
+    person_name: bool,
+}
+
+/// A string found while scanning, kept until every sensitive value is known.
+struct Leaf {
+    rule: RuleId,
+    location: Location,
+    path: String,
+    value: String,
+}
+
+struct Audit<'s> {
+    scope: &'s DisclosureScope,
+    sensitive: HashMap<String, Sensitive>,
+    leaves: Vec<Leaf>,
+    diags: Vec<Diagnostic>,
+}
+
+impl Audit<'_> {
+    fn exceeds(&self, sensitivity: &Sensitivity) -> bool {
+        match self.scope {
+            DisclosureScope::Internal => false,
+            DisclosureScope::Partner => matches!(sensitivity, Sensitivity::Confidential),
+            DisclosureScope::Public => {
+                matches!(
+                    sensitivity,
+                    Sensitivity::Restricted | Sensitivity::Confidential
+                )
+            }
+        }
+    }
+
+    fn report(&mut self, rule: RuleId, severity: Severity, location: Location, message: String) {
+        self.diags
+            .push(Diagnostic::new(rule, severity, location, message));
+    }
+
+    fn remember(&mut self, value: &str, origin: String, person_name: bool) {
+        if value.chars().count() < MIN_MATCHED_VALUE_LEN {
+            return;
+        }
+        self.sensitive.entry(value.to_owned()).or_insert(Sensitive {
+            origin,
+            person_name,
+        });
+    }
+
+    /// SDA-01: `person` nodes in a public file. Their names are remembered so
+    /// copies elsewhere in the file are reported too.
+    fn check_persons(&mut self, file: &OmtsFile) {
+        if !matches!(self.scope, DisclosureScope::Public) {
+            return;
+        }
+        for node in &file.nodes {
+            if node.node_type != NodeTypeTag::Known(NodeType::Person) {
+                continue;
+            }
+            let node_id: &str = &node.id;
+            let (field, message) = match &node.name {
+                Some(name) => (
+                    Some("name".to_owned()),
+                    format!(
+                        "person node \"{node_id}\" names \"{name}\"; person nodes must not \
+                         appear at public scope"
+                    ),
+                ),
+                None => (
+                    None,
+                    format!("person node \"{node_id}\" must not appear at public scope"),
+                ),
+            };
+            self.report(
+                RuleId::Sda01,
+                Severity::Error,
+                Location::Node {
+                    node_id: node_id.to_owned(),
+                    field,
+                },
+                message,
+            );
+            if let Some(name) = &node.name {
+                self.remember(name, format!("the name of person node \"{node_id}\""), true);
+            }
+        }
+    }
+
+    /// Remembers the values of identifiers above the scope threshold; L1-SDI-02
+    /// reports the identifiers themselves, this audit their copies.
+    fn collect_identifiers(&mut self, file: &OmtsFile) {
+        for node in &file.nodes {
+            for id in node.identifiers.as_deref().unwrap_or(&[]) {
+                if self.exceeds(&effective_sensitivity(id, &node.node_type)) {
+                    let origin = format!("the {} identifier of node \"{}\"", id.scheme, node.id);
+                    self.remember(&id.value, origin, false);
+                }
+            }
+        }
+    }
+
+    /// SDA-02, SDA-03, and SDA-05 over every `extra` map and label.
+    fn check_extras(&mut self, file: &OmtsFile) {
+        self.scan_map(&file.extra, "", Owner::Header);
+        for node in &file.nodes {
+            let owner = Owner::Node(node);
+            self.scan_map(&node.extra, "", owner);
+            for (i, id) in node
+                .identifiers
+                .as_deref()
+                .unwrap_or(&[])
+                .iter()
+                .enumerate()
+            {
+                self.scan_map(&id.extra, &format!("identifiers[{i}]."), owner);
+            }
+            self.scan_labels(node.labels.as_deref(), "", owner);
+        }
+        for edge in &file.edges {
+            let owner = Owner::Edge(edge);
+            self.scan_map(&edge.extra, "", owner);
+            self.scan_map(&edge.properties.extra, "properties.", owner);
+            self.scan_labels(edge.properties.labels.as_deref(), "properties.", owner);
+            self.check_conflict_fields(edge);
+        }
+    }
+
+    fn scan_map(&mut self, map: &DynMap, prefix: &str, owner: Owner<'_>) {
+        for (key, value) in map {
+            let rule = if CONFLICT_KEYS.contains(&key.as_str()) {
+                RuleId::Sda03
+            } else {
+                RuleId::Sda02
+            };
+            self.scan_value(value, &format!("{prefix}{key}"), key, &rule, owner);
+        }
+    }
+
+    fn scan_labels(&mut self, labels: Option<&[Label]>, prefix: &str, owner: Owner<'_>) {
+        for (i, label) in labels.unwrap_or(&[]).iter().enumerate() {
+            if let Some(value) = &label.value {
+                self.scan_string(
+                    value,
+                    &format!("{prefix}labels[{i}].value"),
+                    "value",
+                    &RuleId::Sda02,
+                    owner,
+                );
+            }
+            self.scan_map(&label.extra, &format!("{prefix}labels[{i}]."), owner);
+        }
+    }
+
+    /// Walks `value`; `key` is the nearest enclosing object key.
+    fn scan_value(
+        &mut self,
+        value: &DynValue,
+        path: &str,
+        key: &str,
+        rule: &RuleId,
+        owner: Owner<'_>,
+    ) {
+        match value {
+            DynValue::Object(obj) => {
+                if let Some(id) = as_identifier(value) {
+                    self.check_identifier(&id, path, rule, owner);
+                    return;
+                }
+                for (k, v) in obj {
+                    self.scan_value(v, &format!("{path}.{k}"), k, rule, owner);
+                }
+            }
+            DynValue::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.scan_value(item, &format!("{path}[{i}]"), key, rule, owner);
+                }
+            }
+            DynValue::String(s) => self.scan_string(s, path, key, rule, owner),
+            DynValue::Null
+            | DynValue::Bool(_)
+            | DynValue::Integer(_)
+            | DynValue::UnsignedInteger(_)
+            | DynValue::Float(_) => {}
+        }
+    }
+
+    fn scan_string(&mut self, s: &str, path: &str, key: &str, rule: &RuleId, owner: Owner<'_>) {
+        if matches!(key, "source_file" | "source_files") && looks_like_path(s) {
+            self.report(
+                RuleId::Sda05,
+                Severity::Error,
+                owner.location(path),
+                format!("`{path}` names the source file path \"{s}\""),
+            );
+            return;
+        }
+        if key == "source_names" {
+            self.report(
+                RuleId::Sda05,
+                Severity::Error,
+                owner.location(path),
+                format!("`{path}` names the source file \"{s}\""),
+            );
+            return;
+        }
+        if let Some(id) = parse_canonical(s) {
+            if self.check_identifier(&id, path, rule, owner) {
+                return;
+            }
+        }
+        self.leaves.push(Leaf {
+            rule: rule.clone(),
+            location: owner.location(path),
+            path: path.to_owned(),
+            value: s.to_owned(),
+        });
+    }
+
+    /// Reports `id` if it is above the scope threshold; returns whether it was.
+    fn check_identifier(
+        &mut self,
+        id: &Identifier,
+        path: &str,
+        rule: &RuleId,
+        owner: Owner<'_>,
+    ) -> bool {
+        let sensitivity = effective_sensitivity(id, &owner.node_type());
+        if !self.exceeds(&sensitivity) {
+            return false;
+        }
+        self.report(
+            rule.clone(),
+            Severity::Error,
+            owner.location(path),
+            format!(
+                "`{path}` holds a {} {} identifier, which is not disclosed at {} scope",
+                sensitivity_name(&sensitivity),
+                id.scheme,
+                scope_name(self.scope)
+            ),
+        );
+        self.remember(
+            &id.value,
+            format!("the {} identifier in `{path}`", id.scheme),
+            false,
+        );
+        true
+    }
+
+    /// SDA-03: conflict records on properties the scope strips from the edge.
+    fn check_conflict_fields(&mut self, edge: &Edge) {
+        for key in CONFLICT_KEYS {
+            let Some(records) = edge.properties.extra.get(key).and_then(DynValue::as_array) else {
+                continue;
+            };
+            for (i, record) in records.iter().enumerate() {
+                let Some(field) = record.get("field").and_then(DynValue::as_str) else {
+                    continue;
+                };
+                let sensitivity = effective_property_sensitivity(edge, field);
+                if !self.exceeds(&sensitivity) {
+                    continue;
+                }
+                let path = format!("properties.{key}[{i}]");
+                self.report(
+                    RuleId::Sda03,
+                    Severity::Error,
+                    Owner::Edge(edge).location(&path),
+                    format!(
+                        "`{path}` records values of `{field}`, which is {} and not \
+                         disclosed at {} scope",
+                        sensitivity_name(&sensitivity),
+                        scope_name(self.scope)
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Reports every scanned string equal to a sensitive value.
+    fn match_leaves(&mut self) {
+        let leaves = std::mem::take(&mut self.leaves);
+        for leaf in leaves {
+            let Some(sensitive) = self.sensitive.get(&leaf.value) else {
+                continue;
+            };
+            let rule = if sensitive.person_name {
+                RuleId::Sda01
+            } else {
+                leaf.rule
+            };
+            let message = format!("`{}` repeats {}", leaf.path, sensitive.origin);
+            self.report(rule, Severity::Error, leaf.location, message);
+        }
+    }
+
+    /// SDA-04: `boundary_ref` nodes that are not bare stubs, or whose
+    /// neighbours identify them.
+    fn check_boundary_refs(&mut self, file: &OmtsFile) {
+        let nodes: HashMap<&str, &Node> = file.nodes.iter().map(|n| (&*n.id, n)).collect();
+        for node in &file.nodes {
+            if is_boundary_ref(node) {
+                self.check_stub(node);
+            }
+        }
+        for edge in &file.edges {
+            let (Some(source), Some(target)) = (nodes.get(&*edge.source), nodes.get(&*edge.target))
+            else {
+                continue;
+            };
+            for (bref, other) in [(*source, *target), (*target, *source)] {
+                if is_boundary_ref(bref) && !is_boundary_ref(other) {
+                    self.check_neighbour(bref, other, edge);
+                }
+            }
+        }
+        for node in &file.nodes {
+            let Some(operator) = node.operator.as_deref() else {
+                continue;
+            };
+            if let Some(bref) = nodes.get(operator).filter(|n| is_boundary_ref(n)) {
+                if is_located(node) {
+                    self.report(
+                        RuleId::Sda04,
+                        Severity::Warning,
+                        bref_location(bref),
+                        format!(
+                            "boundary_ref \"{operator}\" is the `operator` of facility \"{}\", \
+                             whose location identifies its operator",
+                            node.id
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_stub(&mut self, node: &Node) {
+        let mut fields: Vec<String> = [
+            ("name", node.name.is_some()),
+            ("jurisdiction", node.jurisdiction.is_some()),
+            ("address", node.address.is_some()),
+            ("geo", node.geo.is_some()),
+            (
+                "labels",
+                node.labels.as_ref().is_some_and(|l| !l.is_empty()),
+            ),
+        ]
+        .iter()
+        .filter(|(_, present)| *present)
+        .map(|(field, _)| (*field).to_owned())
+        .collect();
+        fields.extend(node.extra.keys().cloned());
+        for field in fields {
+            self.report(
+                RuleId::Sda04,
+                Severity::Error,
+                Location::Node {
+                    node_id: node.id.to_string(),
+                    field: Some(field.clone()),
+                },
+                format!(
+                    "boundary_ref \"{}\" carries `{field}`; boundary references must be \
+                     bare stubs",
+                    node.id
+                ),
+            );
+        }
+    }
+
+    fn check_neighbour(&mut self, bref: &Node, other: &Node, edge: &Edge) {
+        let reason = match &edge.edge_type {
+            EdgeTypeTag::Known(EdgeType::SameAs) => {
+                self.report(
+                    RuleId::Sda04,
+                    Severity::Error,
+                    Location::Edge {
+                        edge_id: edge.id.to_string(),
+                        field: None,
+                    },
+                    format!(
+                        "same_as edge \"{}\" equates boundary_ref \"{}\" with node \"{}\"",
+                        edge.id, bref.id, other.id
+                    ),
+                );
+                return;
+            }
+            EdgeTypeTag::Known(EdgeType::LegalParentage) if has_public_lei(other) => {
+                "whose LEI makes its legal parentage a matter of public record"
+            }
+            EdgeTypeTag::Known(EdgeType::Operates) if is_located(other) => {
+                "whose location identifies its operator"
+            }
+            EdgeTypeTag::Known(_) | EdgeTypeTag::Extension(_) => return,
+        };
+        self.report(
+            RuleId::Sda04,
+            Severity::Warning,
+            bref_location(bref),
+            format!(
+                "boundary_ref \"{}\" is linked by {} edge \"{}\" to node \"{}\", {reason}",
+                bref.id,
+                edge.edge_type.as_str(),
+                edge.id,
+                other.id
+            ),
+        );
+    }
+}
+
+fn is_boundary_ref(node: &Node) -> bool {
+    node.node_type == NodeTypeTag::Known(NodeType::BoundaryRef)
+}
+
+fn is_located(node: &Node) -> bool {
+    node.address.is_some() || node.geo.is_some()
+}
+
+fn has_public_lei(node: &Node) -> bool {
+    node.identifiers.as_deref().unwrap_or(&[]).iter().any(|id| {
+        id.scheme == "lei" && effective_sensitivity(id, &node.node_type) == Sensitivity::Public
+    })
+}
+
+fn bref_location(node: &Node) -> Location {
+    Location::Node {
+        node_id: node.id.to_string(),
+        field: None,
+    }
+}
+
+/// An identifier record `{scheme, value, ...}` embedded in free-form data.
+fn as_identifier(value: &DynValue) -> Option<Identifier> {
+    value.get("scheme")?.as_str()?;
+    value.get("value")?.as_str()?;
+    serde_json::from_value(serde_json::Value::from(value.clone())).ok()
+}
+
+/// An identifier from its canonical string (`scheme:value` or
+/// `scheme:authority:value`), if `s` starts with a core scheme.
+fn parse_canonical(s: &str) -> Option<Identifier> {
+    let (scheme, rest) = s.split_once(':')?;
+    if !CORE_SCHEMES.contains(&scheme) {
+        return None;
+    }
+    let value = rest.rsplit(':').next().filter(|v| !v.is_empty())?;
+    Some(Identifier {
+        scheme: scheme.to_owned(),
+        value: value.to_owned(),
+        authority: None,
+        valid_from: None,
+        valid_to: None,
+        sensitivity: None,
+        verification_status: None,
+        verification_date: None,
+        extra: DynMap::new(),
+    })
+}
+
+/// `true` if `s` carries a directory or an `.omts` file name rather than an
+/// opaque label such as `file_0`.
+fn looks_like_path(s: &str) -> bool {
+    let lower = s.to_ascii_lowercase();
+    s.contains('/') || s.contains('\\') || lower.contains(".omts")
+}
+
+fn scope_name(scope: &DisclosureScope) -> &'static str {
+    match scope {
+        DisclosureScope::Internal => "internal",
+        DisclosureScope::Partner => "partner",
+        DisclosureScope::Public => "public",
+    }
+}
+
+fn sensitivity_name(sensitivity: &Sensitivity) -> &'static str {
+    match sensitivity {
+        Sensitivity::Public => "public",
+        Sensitivity::Restricted => "restricted",
+        Sensitivity::Confidential => "confidential",
+    }
+}
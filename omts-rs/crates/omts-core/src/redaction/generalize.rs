@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::dynvalue::DynValue;
+
+/// Extension key holding generalised values on edge properties and nodes.
+pub const GENERALIZED_KEY: &str = "_generalized";
+
+/// How a value is coarsened before disclosure (redaction.md Section 7.4).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum GeneralizeMethod {
+    /// Replace a number with the band it falls in. `bands` are strictly
+    /// increasing cut points; `[25, 50]` gives `< 25`, `25–50`, and `>= 50`.
+    Bands {
+        /// The cut points.
+        bands: Vec<f64>,
+    },
+    /// Replace a number with its order of magnitude, `[10^k, 10^(k+1))`.
+    Magnitude,
+    /// Round `geo` coordinates to `decimals` decimal places.
+    Round {
+        /// Decimal places kept.
+        decimals: u32,
+    },
+    /// Replace a `geo` shape with its centroid as a `{lat, lon}` point.
+    Centroid,
+    /// Replace a `geo` shape with its bounding box as a `GeoJSON` polygon.
+    Bbox,
+}
+
+impl GeneralizeMethod {
+    /// Returns `true` for the methods that apply to `geo` rather than to
+    /// numeric edge properties.
+    pub fn is_geo(&self) -> bool {
+        match self {
+            Self::Round { .. } | Self::Centroid | Self::Bbox => true,
+            Self::Bands { .. } | Self::Magnitude => false,
+        }
+    }
+}
+
+/// The range `{min, max}` standing in for `value`, with `min` inclusive and
+/// `max` exclusive; an open end is left out. `unit` is the companion unit or
+/// currency, if any. Returns `None` for geo methods and non-finite values.
+pub(super) fn generalize_number(
+    value: f64,
+    method: &GeneralizeMethod,
+    unit: Option<&str>,
+) -> Option<DynValue> {
+    if !value.is_finite() {
+        return None;
+    }
+    let (min, max) = match method {
+        GeneralizeMethod::Bands { bands } => {
+            let upper = bands.iter().position(|cut| value < *cut);
+            let min = match upper {
+                Some(0) => None,
+                Some(i) => bands.get(i - 1).copied(),
+                None => bands.last().copied(),
+            };
+            (min, upper.and_then(|i| bands.get(i).copied()))
+        }
+        GeneralizeMethod::Magnitude => {
+            let (low, high) = magnitude(value.abs());
+            if value < 0.0 {
+                (Some(-high), Some(-low))
+            } else {
+                (Some(low), Some(high))
+            }
+        }
+        GeneralizeMethod::Round { .. } | GeneralizeMethod::Centroid | GeneralizeMethod::Bbox => {
+            return None;
+        }
+    };
+
+    let mut range = BTreeMap::new();
+    if let Some(min) = min {
+        range.insert("min".to_owned(), number(min));
+    }
+    if let Some(max) = max {
+        range.insert("max".to_owned(), number(max));
+    }
+    if let Some(unit) = unit {
+        range.insert("unit".to_owned(), DynValue::String(unit.to_owned()));
+    }
+    Some(DynValue::Object(range))
+}
+
+/// `[10^k, 10^(k+1))` containing the non-negative `value`; `[0, 0)` for zero.
+fn magnitude(value: f64) -> (f64, f64) {
+    if value == 0.0 {
+        return (0.0, 0.0);
+    }
+    let mut low = 10f64.powf(value.log10().floor());
+    // `log10` can land just below an exact power of ten.
+    if low * 10.0 <= value {
+        low *= 10.0;
+    } else if low > value {
+        low /= 10.0;
+    }
+    (low, low * 10.0)
+}
+
+/// An integral number as an integer, so bands read `1000000`, not `1e6`.
+fn number(value: f64) -> DynValue {
+    if value.fract() == 0.0 && value.abs() < 9_007_199_254_740_992.0 {
+        DynValue::Integer(value as i64)
+    } else {
+        DynValue::Float(value)
+    }
+}
+
+/// The coarsened `geo` value, or `None` if `geo` holds no coordinates.
+///
+/// Accepts the `{lat, lon}` point form and `GeoJSON` geometries, including
+/// `GeometryCollection`. Returns `None` for numeric methods.
+pub(super) fn generalize_geo(geo: &DynValue, method: &GeneralizeMethod) -> Option<DynValue> {
+    let mut positions = Vec::new();
+    collect_positions(geo, &mut positions);
+    if positions.is_empty() {
+        return None;
+    }
+    match method {
+        GeneralizeMethod::Round { decimals } => Some(round_geo(geo, *decimals)),
+        GeneralizeMethod::Centroid => {
+            let (lon, lat) = centroid(geo).unwrap_or_else(|| mean(&positions));
+            Some(point(lat, lon))
+        }
+        GeneralizeMethod::Bbox => {
+            let (mut min_lon, mut min_lat) = (f64::INFINITY, f64::INFINITY);
+            let (mut max_lon, mut max_lat) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+            for (lon, lat) in &positions {
+                min_lon = min_lon.min(*lon);
+                min_lat = min_lat.min(*lat);
+                max_lon = max_lon.max(*lon);
+                max_lat = max_lat.max(*lat);
+            }
+            if min_lon == max_lon && min_lat == max_lat {
+                return Some(point(min_lat, min_lon));
+            }
+            let ring = [
+                (min_lon, min_lat),
+                (max_lon, min_lat),
+                (max_lon, max_lat),
+                (min_lon, max_lat),
+                (min_lon, min_lat),
+            ]
+            .iter()
+            .map(|(lon, lat)| DynValue::Array(vec![DynValue::Float(*lon), DynValue::Float(*lat)]))
+            .collect();
+            let mut polygon = BTreeMap::new();
+            polygon.insert("type".to_owned(), DynValue::String("Polygon".to_owned()));
+            polygon.insert(
+                "coordinates".to_owned(),
+                DynValue::Array(vec![DynValue::Array(ring)]),
+            );
+            Some(DynValue::Object(polygon))
+        }
+        GeneralizeMethod::Bands { .. } | GeneralizeMethod::Magnitude => None,
+    }
+}
+
+fn point(lat: f64, lon: f64) -> DynValue {
+    let mut obj = BTreeMap::new();
+    obj.insert("lat".to_owned(), DynValue::Float(lat));
+    obj.insert("lon".to_owned(), DynValue::Float(lon));
+    DynValue::Object(obj)
+}
+
+/// Appends every `(lon, lat)` position in `geo` to `out`.
+fn collect_positions(geo: &DynValue, out: &mut Vec<(f64, f64)>) {
+    if let (Some(lat), Some(lon)) = (
+        geo.get("lat").and_then(DynValue::as_f64),
+        geo.get("lon").and_then(DynValue::as_f64),
+    ) {
+        out.push((lon, lat));
+        return;
+    }
+    if let Some(coordinates) = geo.get("coordinates") {
+        collect_coordinate_positions(coordinates, out);
+    }
+    if let Some(geometries) = geo.get("geometries").and_then(DynValue::as_array) {
+        for geometry in geometries {
+            collect_positions(geometry, out);
+        }
+    }
+}
+
+fn collect_coordinate_positions(value: &DynValue, out: &mut Vec<(f64, f64)>) {
+    let Some(items) = value.as_array() else {
+        return;
+    };
+    if let Some(position) = as_position(value) {
+        out.push(position);
+        return;
+    }
+    for item in items {
+        collect_coordinate_positions(item, out);
+    }
+}
+
+/// A `GeoJSON` position `[lon, lat, ...]`.
+fn as_position(value: &DynValue) -> Option<(f64, f64)> {
+    let items = value.as_array()?;
+    let lon = items.first()?.as_f64()?;
+    let lat = items.get(1)?.as_f64()?;
+    Some((lon, lat))
+}
+
+fn mean(positions: &[(f64, f64)]) -> (f64, f64) {
+    let n = positions.len() as f64;
+    let (lon, lat) = positions
+        .iter()
+        .fold((0.0, 0.0), |(x, y), (lon, lat)| (x + lon, y + lat));
+    (lon / n, lat / n)
+}
+
+/// The area-weighted centroid of a `Polygon` or `MultiPolygon`, from the
+/// exterior rings. `None` for other geometries or zero total area.
+fn centroid(geo: &DynValue) -> Option<(f64, f64)> {
+    let coordinates = geo.get("coordinates")?;
+    let polygons: Vec<&DynValue> = match geo.get("type")?.as_str()? {
+        "Polygon" => vec![coordinates],
+        "MultiPolygon" => coordinates.as_array()?.iter().collect(),
+        _ => return None,
+    };
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for polygon in polygons {
+        let Some(exterior) = polygon.as_array().and_then(|rings| rings.first()) else {
+            continue;
+        };
+        let ring: Vec<(f64, f64)> = exterior
+            .as_array()
+            .map(|points| points.iter().filter_map(as_position).collect())
+            .unwrap_or_default();
+        for pair in ring.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            let cross = x0 * y1 - x1 * y0;
+            area += cross;
+            cx += (x0 + x1) * cross;
+            cy += (y0 + y1) * cross;
+        }
+    }
+    if area == 0.0 {
+        return None;
+    }
+    // `area` holds twice the signed area; the centroid divides by 6A = 3 * area.
+    Some((cx / (3.0 * area), cy / (3.0 * area)))
+}
+
+/// `geo` with every coordinate rounded to `decimals` places.
+fn round_geo(geo: &DynValue, decimals: u32) -> DynValue {
+    let Some(obj) = geo.as_object() else {
+        return geo.clone();
+    };
+    let factor = 10f64.powi(i32::try_from(decimals).unwrap_or(i32::MAX));
+    let rounded = obj
+        .iter()
+        .map(|(key, value)| {
+            let value = match key.as_str() {
+                "lat" | "lon" | "coordinates" | "bbox" => round_numbers(value, factor),
+                "geometries" => match value.as_array() {
+                    Some(items) => {
+                        DynValue::Array(items.iter().map(|g| round_geo(g, decimals)).collect())
+                    }
+                    None => value.clone(),
+                },
+                _ => value.clone(),
+            };
+            (key.clone(), value)
+        })
+        .collect();
+    DynValue::Object(rounded)
+}
+
+fn round_numbers(value: &DynValue, factor: f64) -> DynValue {
+    match value {
+        DynValue::Float(f) => DynValue::Float((f * factor).round() / factor),
+        DynValue::Array(items) => {
+            DynValue::Array(items.iter().map(|v| round_numbers(v, factor)).collect())
+        }
+        DynValue::Null
+        | DynValue::Bool(_)
+        | DynValue::Integer(_)
+        | DynValue::UnsignedInteger(_)
+        | DynValue::String(_)
+        | DynValue::Object(_) => value.clone(),
+    }
+}
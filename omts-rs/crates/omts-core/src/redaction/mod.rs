@@ -9,6 +9,16 @@
 /// - Edge action classification (Sections 6.1–6.4)
 /// - [`redact`]: the top-level pipeline that produces a valid redacted
 ///   [`OmtsFile`] from a higher-trust source file.
+/// - [`RedactionPolicy`]: per-partner rules layered on top of the scope,
+///   applied by [`redact_with_policy`] (Section 7.1).
+/// - [`RedactionReport`]: the audit record returned by [`redact_with_report`]
+///   (Section 7.2).
+/// - [`resolve_boundary_refs`]: maps `boundary_ref` nodes back to known
+///   candidates by recomputing their hashes (Section 7.3).
+/// - [`GeneralizeMethod`]: bucketing, rounding, and geo coarsening applied by
+///   the `generalize` rules of a policy (Section 7.4).
+/// - [`audit_disclosure`]: scans a file for content its disclosure scope does
+///   not permit but the sensitivity model misses (Section 7.5).
 ///
 /// The lower-level functions (`classify_node`, `filter_identifiers`, etc.) are
 /// deliberately pure-functional: they take inputs and return outputs without
@@ -18,6 +28,7 @@ use std::fmt;
 
 use crate::boundary_hash::{BoundaryHashError, boundary_ref_value, decode_salt};
 use crate::canonical::CanonicalId;
+use crate::dynvalue::DynValue;
 use crate::enums::{DisclosureScope, EdgeType, EdgeTypeTag, NodeType, NodeTypeTag, Sensitivity};
 use crate::file::OmtsFile;
 use crate::newtypes::NodeId;
@@ -26,11 +37,32 @@ use crate::structures::{Edge, EdgeProperties, Node};
 use crate::types::Identifier;
 use crate::validation::{ValidationConfig, validate};
 
+mod audit;
+mod generalize;
+mod policy;
+mod report;
+mod resolve;
+
+pub use audit::{MIN_MATCHED_VALUE_LEN, audit_disclosure};
+pub use generalize::{GENERALIZED_KEY, GeneralizeMethod};
+use generalize::{generalize_geo, generalize_number};
+pub use policy::{
+    EdgePropertyRule, GeneralizeRule, IdentifierRule, NodeRule, PropertyAction, RedactionPolicy,
+};
+pub use report::{
+    DropReason, DroppedIdentifier, EdgeOmitReason, GeneralizedGeo, GeneralizedProperty,
+    NodeDisposition, NodeReason, OmittedEdge, RedactOutput, RedactionReport, StrippedProperty,
+};
+pub use resolve::{
+    BoundaryMatch, BoundaryResolution, MAX_SUBSET_IDENTIFIERS, resolve_boundary_refs,
+};
+
 /// The disposition assigned to a node during redaction.
 ///
 /// Classification follows the table in redaction.md Section 5 crossed with the
 /// target disclosure scope.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum NodeAction {
     /// Node appears in output, possibly with filtered identifiers.
     Retain,
@@ -174,15 +206,26 @@ fn sensitivity_allowed(sensitivity: &Sensitivity, scope: &DisclosureScope) -> bo
 ///   sensitivity lookups via the `_property_sensitivity` override map).
 /// * `target_scope` — the disclosure scope being targeted.
 pub fn filter_edge_properties(edge: &Edge, target_scope: &DisclosureScope) -> EdgeProperties {
+    strip_edge_properties(edge, target_scope, &RedactionPolicy::default())
+}
+
+/// [`filter_edge_properties`] with the edge property rules of `policy`
+/// taking precedence over the sensitivity threshold.
+fn strip_edge_properties(
+    edge: &Edge,
+    target_scope: &DisclosureScope,
+    policy: &RedactionPolicy,
+) -> EdgeProperties {
     if matches!(target_scope, DisclosureScope::Internal) {
         return edge.properties.clone();
     }
 
     let props = &edge.properties;
+    let generalized = edge_generalizations(edge, target_scope, policy);
 
     let keep = |name: &str| -> bool {
-        let s = effective_property_sensitivity(edge, name);
-        sensitivity_allowed(&s, target_scope)
+        !generalized.iter().any(|(g, _)| g.property == name)
+            && property_verdict(edge, name, target_scope, policy).is_none()
     };
 
     let percentage = if keep("percentage") {
@@ -303,6 +346,16 @@ pub fn filter_edge_properties(edge: &Edge, target_scope: &DisclosureScope) -> Ed
             extra.insert(key.clone(), value.clone());
         }
     }
+    if !generalized.is_empty() {
+        let mut ranges = match extra.remove(GENERALIZED_KEY) {
+            Some(DynValue::Object(existing)) => existing,
+            Some(_) | None => BTreeMap::new(),
+        };
+        for (g, range) in generalized {
+            ranges.insert(g.property, range);
+        }
+        extra.insert(GENERALIZED_KEY.to_owned(), DynValue::Object(ranges));
+    }
 
     EdgeProperties {
         data_quality,
@@ -332,6 +385,107 @@ pub fn filter_edge_properties(edge: &Edge, target_scope: &DisclosureScope) -> Ed
     }
 }
 
+/// Why property `name` is stripped from `edge`, or `None` if it is kept: a
+/// policy rule decides first, then the scope's sensitivity threshold.
+fn property_verdict(
+    edge: &Edge,
+    name: &str,
+    target_scope: &DisclosureScope,
+    policy: &RedactionPolicy,
+) -> Option<DropReason> {
+    match policy.property_action(edge, name) {
+        Some(PropertyAction::Keep) => None,
+        Some(PropertyAction::Drop) => Some(DropReason::Policy),
+        None => {
+            let sensitivity = effective_property_sensitivity(edge, name);
+            if sensitivity_allowed(&sensitivity, target_scope) {
+                None
+            } else {
+                Some(DropReason::Sensitivity { sensitivity })
+            }
+        }
+    }
+}
+
+/// The numeric properties of `edge` that a `generalize` rule of `policy`
+/// replaces at `target_scope`, each with its range. Edge property rules take
+/// precedence: a property they keep or drop is not generalised.
+fn edge_generalizations(
+    edge: &Edge,
+    target_scope: &DisclosureScope,
+    policy: &RedactionPolicy,
+) -> Vec<(GeneralizedProperty, DynValue)> {
+    if policy.generalize.is_empty() || matches!(target_scope, DisclosureScope::Internal) {
+        return Vec::new();
+    }
+    let Ok(serde_json::Value::Object(props)) = serde_json::to_value(&edge.properties) else {
+        return Vec::new();
+    };
+    props
+        .iter()
+        .filter_map(|(name, value)| {
+            if policy.property_action(edge, name).is_some() {
+                return None;
+            }
+            let method = policy.edge_generalization(edge, name, target_scope)?;
+            let unit = match name.as_str() {
+                "annual_value" => props.get("value_currency"),
+                "volume" => props.get("volume_unit"),
+                "quantity" => props.get("unit"),
+                _ => None,
+            }
+            .and_then(serde_json::Value::as_str);
+            let range = generalize_number(value.as_f64()?, method, unit)?;
+            Some((
+                GeneralizedProperty {
+                    edge_id: edge.id.clone(),
+                    property: name.clone(),
+                    method: method.clone(),
+                },
+                range,
+            ))
+        })
+        .collect()
+}
+
+/// The properties [`strip_edge_properties`] removes from `edge`, with the
+/// reason for each.
+fn stripped_properties(
+    edge: &Edge,
+    target_scope: &DisclosureScope,
+    policy: &RedactionPolicy,
+) -> Vec<StrippedProperty> {
+    if matches!(target_scope, DisclosureScope::Internal) {
+        return Vec::new();
+    }
+    let present: Vec<String> = serde_json::to_value(&edge.properties)
+        .ok()
+        .and_then(|value| {
+            value
+                .as_object()
+                .map(|props| props.keys().cloned().collect())
+        })
+        .unwrap_or_default();
+    let generalized = edge_generalizations(edge, target_scope, policy);
+
+    present
+        .into_iter()
+        .filter(|property| !generalized.iter().any(|(g, _)| g.property == *property))
+        .filter_map(|property| {
+            let reason = if property == "_property_sensitivity" {
+                matches!(target_scope, DisclosureScope::Public).then_some(DropReason::PublicScope)
+            } else {
+                property_verdict(edge, &property, target_scope, policy)
+            }?;
+            Some(StrippedProperty {
+                edge_id: edge.id.clone(),
+                property,
+                reason,
+            })
+        })
+        .collect()
+}
+
 /// Classifies an edge into an [`EdgeAction`] based on the actions of its
 /// source and target nodes and the target scope.
 ///
@@ -357,27 +511,40 @@ pub fn classify_edge(
     target_action: &NodeAction,
     target_scope: &DisclosureScope,
 ) -> EdgeAction {
+    match edge_omit_reason(edge, source_action, target_action, target_scope) {
+        Some(_) => EdgeAction::Omit,
+        None => EdgeAction::Retain,
+    }
+}
+
+/// Why [`classify_edge`] omits `edge`, or `None` if it is retained.
+fn edge_omit_reason(
+    edge: &Edge,
+    source_action: &NodeAction,
+    target_action: &NodeAction,
+    target_scope: &DisclosureScope,
+) -> Option<EdgeOmitReason> {
     // Section 6.4: beneficial_ownership edges unconditionally omitted in public scope.
     if matches!(target_scope, DisclosureScope::Public) {
         if let EdgeTypeTag::Known(EdgeType::BeneficialOwnership) = &edge.edge_type {
-            return EdgeAction::Omit;
+            return Some(EdgeOmitReason::BeneficialOwnership);
         }
     }
 
     // Section 6.3: either endpoint omitted → edge omitted.
     if matches!(source_action, NodeAction::Omit) || matches!(target_action, NodeAction::Omit) {
-        return EdgeAction::Omit;
+        return Some(EdgeOmitReason::EndpointOmitted);
     }
 
     // Section 6.2: both endpoints replaced → edge omitted.
     if matches!(source_action, NodeAction::Replace) && matches!(target_action, NodeAction::Replace)
     {
-        return EdgeAction::Omit;
+        return Some(EdgeOmitReason::BothEndpointsReplaced);
     }
 
     // Section 6.1: boundary crossing (one Retain, one Replace) → retained.
     // Also covers both-Retain case.
-    EdgeAction::Retain
+    None
 }
 
 /// Errors that can occur during redaction.
@@ -458,31 +625,84 @@ pub fn redact(
     scope: DisclosureScope,
     retain_ids: &HashSet<NodeId>,
 ) -> Result<OmtsFile, RedactError> {
+    redact_with_policy(file, scope, retain_ids, &RedactionPolicy::default())
+}
+
+/// [`redact`] with the overrides of a [`RedactionPolicy`] (redaction.md
+/// Section 7.1).
+///
+/// - **Nodes** — the first node rule matching a node decides between
+///   `Retain`, `Replace`, and `Omit`, taking precedence over `retain_ids`. A
+///   node the scope omits stays omitted, and a `boundary_ref` node is retained
+///   as-is unless a rule omits it.
+/// - **Identifiers** — identifiers dropped by the policy are removed from
+///   retained nodes and do not contribute to boundary reference hashes.
+/// - **Edge properties** — a matching rule keeps or drops a property
+///   regardless of its effective sensitivity.
+///
+/// The policy is not applied at `internal` scope, which short-circuits as in
+/// [`redact`]. Post-redaction L1 validation runs as usual.
+///
+/// # Errors
+///
+/// Same as [`redact`].
+pub fn redact_with_policy(
+    file: &OmtsFile,
+    scope: DisclosureScope,
+    retain_ids: &HashSet<NodeId>,
+    policy: &RedactionPolicy,
+) -> Result<OmtsFile, RedactError> {
+    redact_with_report(file, scope, retain_ids, policy).map(|output| output.file)
+}
+
+/// [`redact_with_policy`] that also returns a [`RedactionReport`]: every
+/// node's disposition (with the opaque value of each replacement), and every
+/// identifier, edge, and edge property removed, each with its reason
+/// (redaction.md Section 7.2).
+///
+/// # Errors
+///
+/// Same as [`redact`].
+pub fn redact_with_report(
+    file: &OmtsFile,
+    scope: DisclosureScope,
+    retain_ids: &HashSet<NodeId>,
+    policy: &RedactionPolicy,
+) -> Result<RedactOutput, RedactError> {
     if matches!(scope, DisclosureScope::Internal) {
         let mut out = file.clone();
         out.disclosure_scope = Some(DisclosureScope::Internal);
-        return Ok(out);
+        let nodes = file
+            .nodes
+            .iter()
+            .map(|node| NodeDisposition {
+                id: node.id.clone(),
+                node_type: node.node_type.clone(),
+                action: NodeAction::Retain,
+                reason: NodeReason::Scope,
+                opaque_value: None,
+            })
+            .collect();
+        let report = RedactionReport {
+            scope,
+            nodes,
+            identifiers_dropped: Vec::new(),
+            edges_omitted: Vec::new(),
+            properties_stripped: Vec::new(),
+            properties_generalized: Vec::new(),
+            geo_generalized: Vec::new(),
+        };
+        return Ok(RedactOutput { file: out, report });
     }
 
     let salt = decode_salt(&file.file_salt)?;
 
     let mut node_actions: HashMap<NodeId, NodeAction> = HashMap::new();
+    let mut node_reasons: HashMap<NodeId, NodeReason> = HashMap::new();
     for node in &file.nodes {
-        let base = classify_node(node, &scope);
-        let action = match base {
-            NodeAction::Omit => NodeAction::Omit,
-            NodeAction::Retain | NodeAction::Replace => {
-                // Pass-through boundary_ref nodes regardless of retain_ids;
-                // also retain nodes the producer explicitly placed in retain_ids.
-                let is_bref = matches!(&node.node_type, NodeTypeTag::Known(NodeType::BoundaryRef));
-                if is_bref || retain_ids.contains(&node.id) {
-                    NodeAction::Retain
-                } else {
-                    NodeAction::Replace
-                }
-            }
-        };
+        let (action, reason) = node_disposition(node, &scope, retain_ids, policy);
         node_actions.insert(node.id.clone(), action);
+        node_reasons.insert(node.id.clone(), reason);
     }
 
     let mut boundary_ref_values: HashMap<NodeId, String> = HashMap::new();
@@ -491,10 +711,7 @@ pub fn redact(
         if !matches!(action, Some(NodeAction::Replace)) {
             continue;
         }
-        let public_ids: Vec<CanonicalId> = node
-            .identifiers
-            .as_deref()
-            .unwrap_or(&[])
+        let public_ids: Vec<CanonicalId> = policy_identifiers(node, policy)
             .iter()
             .filter(|id| {
                 matches!(
@@ -510,6 +727,8 @@ pub fn redact(
     }
 
     let mut output_nodes: Vec<Node> = Vec::with_capacity(file.nodes.len());
+    let mut identifiers_dropped: Vec<DroppedIdentifier> = Vec::new();
+    let mut geo_generalized: Vec<GeneralizedGeo> = Vec::new();
     for node in &file.nodes {
         let Some(action) = node_actions.get(&node.id) else {
             continue;
@@ -525,11 +744,18 @@ pub fn redact(
                 output_nodes.push(stub);
             }
             NodeAction::Retain => {
-                let filtered_ids = filter_identifiers(
-                    node.identifiers.as_deref().unwrap_or(&[]),
-                    &node.node_type,
-                    &scope,
-                );
+                let mut filtered_ids: Vec<Identifier> = Vec::new();
+                for id in node.identifiers.as_deref().unwrap_or(&[]) {
+                    match identifier_verdict(node, id, &scope, policy) {
+                        None => filtered_ids.push(id.clone()),
+                        Some(reason) => identifiers_dropped.push(DroppedIdentifier {
+                            node_id: node.id.clone(),
+                            scheme: id.scheme.clone(),
+                            value: id.value.clone(),
+                            reason,
+                        }),
+                    }
+                }
                 let mut retained = node.clone();
                 retained.identifiers = if filtered_ids.is_empty() {
                     // Keep an explicit empty array (not None) to distinguish
@@ -540,23 +766,49 @@ pub fn redact(
                 } else {
                     Some(filtered_ids)
                 };
+                if let (Some(method), Some(geo)) =
+                    (policy.geo_generalization(node, &scope), &node.geo)
+                {
+                    retained.geo = generalize_geo(geo, method);
+                    mark_geo_generalized(&mut retained, method);
+                    geo_generalized.push(GeneralizedGeo {
+                        node_id: node.id.clone(),
+                        method: method.clone(),
+                        removed: retained.geo.is_none(),
+                    });
+                }
                 output_nodes.push(retained);
             }
         }
     }
 
     let mut output_edges: Vec<Edge> = Vec::with_capacity(file.edges.len());
+    let mut edges_omitted: Vec<OmittedEdge> = Vec::new();
+    let mut properties_stripped: Vec<StrippedProperty> = Vec::new();
+    let mut properties_generalized: Vec<GeneralizedProperty> = Vec::new();
     for edge in &file.edges {
         let source_action = node_actions.get(&edge.source).unwrap_or(&NodeAction::Omit);
         let target_action = node_actions.get(&edge.target).unwrap_or(&NodeAction::Omit);
 
-        let edge_action = classify_edge(edge, source_action, target_action, &scope);
-        if matches!(edge_action, EdgeAction::Omit) {
+        if let Some(reason) = edge_omit_reason(edge, source_action, target_action, &scope) {
+            edges_omitted.push(OmittedEdge {
+                id: edge.id.clone(),
+                edge_type: edge.edge_type.clone(),
+                source: edge.source.clone(),
+                target: edge.target.clone(),
+                reason,
+            });
             continue;
         }
 
         let mut retained_edge = edge.clone();
-        retained_edge.properties = filter_edge_properties(edge, &scope);
+        retained_edge.properties = strip_edge_properties(edge, &scope, policy);
+        properties_stripped.extend(stripped_properties(edge, &scope, policy));
+        properties_generalized.extend(
+            edge_generalizations(edge, &scope, policy)
+                .into_iter()
+                .map(|(g, _)| g),
+        );
         output_edges.push(retained_edge);
     }
 
@@ -564,7 +816,7 @@ pub fn redact(
         omts_version: file.omts_version.clone(),
         snapshot_date: file.snapshot_date.clone(),
         file_salt: file.file_salt.clone(),
-        disclosure_scope: Some(scope),
+        disclosure_scope: Some(scope.clone()),
         previous_snapshot_ref: file.previous_snapshot_ref.clone(),
         snapshot_sequence: file.snapshot_sequence,
         reporting_entity: file.reporting_entity.clone(),
@@ -587,7 +839,121 @@ pub fn redact(
         return Err(RedactError::InvalidOutput(messages.join("; ")));
     }
 
-    Ok(output)
+    let nodes = file
+        .nodes
+        .iter()
+        .map(|node| {
+            let action = node_actions
+                .get(&node.id)
+                .cloned()
+                .unwrap_or(NodeAction::Omit);
+            let opaque_value = match action {
+                NodeAction::Replace => boundary_ref_values.get(&node.id).cloned(),
+                NodeAction::Retain | NodeAction::Omit => None,
+            };
+            NodeDisposition {
+                id: node.id.clone(),
+                node_type: node.node_type.clone(),
+                action,
+                reason: node_reasons
+                    .get(&node.id)
+                    .copied()
+                    .unwrap_or(NodeReason::Scope),
+                opaque_value,
+            }
+        })
+        .collect();
+    let report = RedactionReport {
+        scope,
+        nodes,
+        identifiers_dropped,
+        edges_omitted,
+        properties_stripped,
+        properties_generalized,
+        geo_generalized,
+    };
+
+    Ok(RedactOutput {
+        file: output,
+        report,
+    })
+}
+
+/// The [`NodeAction`] for `node` and why it was chosen (step 3 of
+/// redaction.md Section 7).
+fn node_disposition(
+    node: &Node,
+    scope: &DisclosureScope,
+    retain_ids: &HashSet<NodeId>,
+    policy: &RedactionPolicy,
+) -> (NodeAction, NodeReason) {
+    if matches!(classify_node(node, scope), NodeAction::Omit) {
+        return (NodeAction::Omit, NodeReason::Scope);
+    }
+    // Pass-through boundary_ref nodes regardless of retain_ids; also retain
+    // nodes the producer explicitly placed in retain_ids. A matching policy
+    // rule takes precedence over both, but never replaces a boundary_ref.
+    let is_bref = matches!(&node.node_type, NodeTypeTag::Known(NodeType::BoundaryRef));
+    match policy.node_action(node) {
+        Some(NodeAction::Omit) => (NodeAction::Omit, NodeReason::Policy),
+        Some(NodeAction::Retain) => (NodeAction::Retain, NodeReason::Policy),
+        Some(NodeAction::Replace) if !is_bref => (NodeAction::Replace, NodeReason::Policy),
+        Some(NodeAction::Replace) | None => {
+            if is_bref {
+                (NodeAction::Retain, NodeReason::BoundaryRef)
+            } else if retain_ids.contains(&node.id) {
+                (NodeAction::Retain, NodeReason::RetainSet)
+            } else {
+                (NodeAction::Replace, NodeReason::NotRetained)
+            }
+        }
+    }
+}
+
+/// Why identifier `id` is removed from retained `node`, or `None` if it is
+/// kept: the policy first, then the scope's sensitivity threshold (the rule
+/// of [`filter_identifiers`]).
+fn identifier_verdict(
+    node: &Node,
+    id: &Identifier,
+    scope: &DisclosureScope,
+    policy: &RedactionPolicy,
+) -> Option<DropReason> {
+    if policy.drops_identifier(node, id) {
+        return Some(DropReason::Policy);
+    }
+    let sensitivity = effective_sensitivity(id, &node.node_type);
+    if sensitivity_allowed(&sensitivity, scope) {
+        None
+    } else {
+        Some(DropReason::Sensitivity { sensitivity })
+    }
+}
+
+/// Records `method` under `_generalized.geo` on `node`, so recipients know
+/// the coordinates are coarse.
+fn mark_geo_generalized(node: &mut Node, method: &GeneralizeMethod) {
+    let Ok(marker) = serde_json::to_value(method) else {
+        return;
+    };
+    let mut markers = match node.extra.remove(GENERALIZED_KEY) {
+        Some(DynValue::Object(existing)) => existing,
+        Some(_) | None => BTreeMap::new(),
+    };
+    markers.insert("geo".to_owned(), DynValue::from(marker));
+    node.extra
+        .insert(GENERALIZED_KEY.to_owned(), DynValue::Object(markers));
+}
+
+/// The identifiers of `node` that `policy` does not drop.
+fn policy_identifiers(node: &Node, policy: &RedactionPolicy) -> Vec<Identifier> {
+    node.identifiers
+        .as_deref()
+        .unwrap_or(&[])
+        .iter()
+        .filter(|id| !policy.drops_identifier(node, id))
+        .cloned()
+        .collect()
 }
 
 /// Constructs a minimal `boundary_ref` node with a single `opaque` identifier.
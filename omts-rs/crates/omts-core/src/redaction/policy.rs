@@ -0,0 +1,412 @@
+use serde::Deserialize;
+
+use crate::enums::DisclosureScope;
+use crate::graph::SelectorExpr;
+use crate::structures::{Edge, Node};
+use crate::types::Identifier;
+
+use super::NodeAction;
+use super::generalize::GeneralizeMethod;
+
+/// Per-partner overrides layered on top of a disclosure scope (redaction.md
+/// Section 7.1).
+///
+/// A policy is read from a document of up to four rule lists, each rule
+/// matching elements with a `--where` selector expression:
+///
+/// ```json
+/// {
+///   "nodes": [
+///     { "where": "type=facility AND jurisdiction=CN", "action": "replace" }
+///   ],
+///   "drop_identifiers": [{ "scheme": "duns" }],
+///   "edge_properties": [
+///     { "property": "annual_value", "action": "drop" },
+///     { "where": "type=supplies", "property": "contract_ref", "action": "keep" }
+///   ],
+///   "generalize": [
+///     { "property": "percentage", "method": "bands", "bands": [25, 50] },
+///     { "property": "geo", "method": "round", "decimals": 1, "scopes": ["public"] }
+///   ]
+/// }
+/// ```
+///
+/// Within each list the first matching rule wins. Policies never loosen the
+/// scope for nodes or identifiers: a node the scope omits stays omitted, and
+/// identifiers can only be dropped. Edge properties can be kept above the
+/// scope's sensitivity threshold, since that is a per-agreement decision.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(try_from = "RawPolicy")]
+pub struct RedactionPolicy {
+    /// Node rules, overriding the `retain_ids` retain-or-replace choice.
+    pub nodes: Vec<NodeRule>,
+    /// Identifiers to remove even where the scope would keep them.
+    pub drop_identifiers: Vec<IdentifierRule>,
+    /// Edge property rules, overriding the scope's sensitivity threshold.
+    pub edge_properties: Vec<EdgePropertyRule>,
+    /// Generalisation rules, disclosing coarsened values in place of exact
+    /// ones (Section 7.4).
+    pub generalize: Vec<GeneralizeRule>,
+}
+
+/// Assigns a [`NodeAction`] to the nodes matching `selector`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeRule {
+    /// Which nodes the rule applies to.
+    pub selector: SelectorExpr,
+    /// The action for matching nodes. `Omit` and `Replace` apply to any node
+    /// the scope does not already omit; `boundary_ref` nodes are never
+    /// replaced again.
+    pub action: NodeAction,
+}
+
+/// Drops identifiers with `scheme` from the nodes matching `selector`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdentifierRule {
+    /// The identifier scheme, e.g. `duns`.
+    pub scheme: String,
+    /// Which nodes the rule applies to; `None` means all nodes.
+    pub selector: Option<SelectorExpr>,
+}
+
+/// Keeps or drops one edge property on the edges matching `selector`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgePropertyRule {
+    /// The property name, e.g. `annual_value` or an extension field.
+    pub property: String,
+    /// Which edges the rule applies to; `None` means all edges.
+    pub selector: Option<SelectorExpr>,
+    /// Whether the property is kept or dropped.
+    pub action: PropertyAction,
+}
+
+/// Coarsens `property` with `method` on the elements matching `selector`.
+///
+/// `property` is `geo` for node coordinates, or the name of a numeric edge
+/// property such as `annual_value`, `percentage`, or `volume`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneralizeRule {
+    /// The property name.
+    pub property: String,
+    /// Which nodes (`geo`) or edges the rule applies to; `None` means all.
+    pub selector: Option<SelectorExpr>,
+    /// The target scopes the rule applies at; `None` means every scope
+    /// except `internal`.
+    pub scopes: Option<Vec<DisclosureScope>>,
+    /// How the value is coarsened.
+    pub method: GeneralizeMethod,
+}
+
+/// What an [`EdgePropertyRule`] does with its property.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PropertyAction {
+    /// Keep the property whatever its sensitivity.
+    Keep,
+    /// Remove the property.
+    Drop,
+}
+
+impl RedactionPolicy {
+    /// Returns `true` if the policy has no rules.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+            && self.drop_identifiers.is_empty()
+            && self.edge_properties.is_empty()
+            && self.generalize.is_empty()
+    }
+
+    /// The action of the first node rule matching `node`, if any.
+    pub(super) fn node_action(&self, node: &Node) -> Option<&NodeAction> {
+        self.nodes
+            .iter()
+            .find(|rule| rule.selector.matches_node(node))
+            .map(|rule| &rule.action)
+    }
+
+    /// Returns `true` if an identifier rule drops `id` from `node`.
+    pub(super) fn drops_identifier(&self, node: &Node, id: &Identifier) -> bool {
+        self.drop_identifiers.iter().any(|rule| {
+            rule.scheme == id.scheme
+                && rule
+                    .selector
+                    .as_ref()
+                    .is_none_or(|selector| selector.matches_node(node))
+        })
+    }
+
+    /// The action of the first rule for property `name` matching `edge`, if
+    /// any.
+    pub(super) fn property_action(&self, edge: &Edge, name: &str) -> Option<PropertyAction> {
+        self.edge_properties
+            .iter()
+            .find(|rule| {
+                rule.property == name
+                    && rule
+                        .selector
+                        .as_ref()
+                        .is_none_or(|selector| selector.matches_edge(edge))
+            })
+            .map(|rule| rule.action)
+    }
+
+    /// The method of the first generalisation rule for edge property `name`
+    /// matching `edge` at `scope`, if any.
+    pub(super) fn edge_generalization(
+        &self,
+        edge: &Edge,
+        name: &str,
+        scope: &DisclosureScope,
+    ) -> Option<&GeneralizeMethod> {
+        self.generalize
+            .iter()
+            .filter(|rule| !rule.method.is_geo())
+            .find(|rule| {
+                rule.property == name
+                    && rule.applies_at(scope)
+                    && rule
+                        .selector
+                        .as_ref()
+                        .is_none_or(|selector| selector.matches_edge(edge))
+            })
+            .map(|rule| &rule.method)
+    }
+
+    /// The method of the first `geo` generalisation rule matching `node` at
+    /// `scope`, if any.
+    pub(super) fn geo_generalization(
+        &self,
+        node: &Node,
+        scope: &DisclosureScope,
+    ) -> Option<&GeneralizeMethod> {
+        self.generalize
+            .iter()
+            .filter(|rule| rule.method.is_geo())
+            .find(|rule| {
+                rule.applies_at(scope)
+                    && rule
+                        .selector
+                        .as_ref()
+                        .is_none_or(|selector| selector.matches_node(node))
+            })
+            .map(|rule| &rule.method)
+    }
+}
+
+impl GeneralizeRule {
+    fn applies_at(&self, scope: &DisclosureScope) -> bool {
+        match &self.scopes {
+            Some(scopes) => scopes.contains(scope),
+            None => !matches!(scope, DisclosureScope::Internal),
+        }
+    }
+}
+
+/// The policy document as written, before selector expressions are parsed.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPolicy {
+    #[serde(default)]
+    nodes: Vec<RawNodeRule>,
+    #[serde(default)]
+    drop_identifiers: Vec<RawIdentifierRule>,
+    #[serde(default)]
+    edge_properties: Vec<RawEdgePropertyRule>,
+    #[serde(default)]
+    generalize: Vec<RawGeneralizeRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNodeRule {
+    #[serde(rename = "where")]
+    selector: String,
+    action: NodeAction,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawIdentifierRule {
+    scheme: String,
+    #[serde(default, rename = "where")]
+    selector: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawEdgePropertyRule {
+    property: String,
+    #[serde(default, rename = "where")]
+    selector: Option<String>,
+    action: PropertyAction,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawGeneralizeRule {
+    property: String,
+    #[serde(default, rename = "where")]
+    selector: Option<String>,
+    #[serde(default)]
+    scopes: Option<Vec<DisclosureScope>>,
+    method: RawMethod,
+    #[serde(default)]
+    bands: Option<Vec<f64>>,
+    #[serde(default)]
+    decimals: Option<u32>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RawMethod {
+    Bands,
+    Magnitude,
+    Round,
+    Centroid,
+    Bbox,
+}
+
+/// Decimal places beyond this are below `f64` resolution for coordinates.
+const MAX_GEO_DECIMALS: u32 = 15;
+
+impl TryFrom<RawPolicy> for RedactionPolicy {
+    type Error = String;
+
+    fn try_from(raw: RawPolicy) -> Result<Self, Self::Error> {
+        let mut policy = Self::default();
+        for (i, rule) in raw.nodes.into_iter().enumerate() {
+            let selector = parse_selector(&rule.selector, &format!("nodes[{i}]"))?;
+            if !selector.applies_to_nodes() {
+                return Err(format!("nodes[{i}]: `where` never matches a node"));
+            }
+            policy.nodes.push(NodeRule {
+                selector,
+                action: rule.action,
+            });
+        }
+        for (i, rule) in raw.drop_identifiers.into_iter().enumerate() {
+            let context = format!("drop_identifiers[{i}]");
+            let selector = rule
+                .selector
+                .map(|s| parse_selector(&s, &context))
+                .transpose()?;
+            if selector.as_ref().is_some_and(|s| !s.applies_to_nodes()) {
+                return Err(format!("{context}: `where` never matches a node"));
+            }
+            policy.drop_identifiers.push(IdentifierRule {
+                scheme: rule.scheme,
+                selector,
+            });
+        }
+        for (i, rule) in raw.edge_properties.into_iter().enumerate() {
+            let context = format!("edge_properties[{i}]");
+            let selector = rule
+                .selector
+                .map(|s| parse_selector(&s, &context))
+                .transpose()?;
+            if selector.as_ref().is_some_and(|s| !s.applies_to_edges()) {
+                return Err(format!("{context}: `where` never matches an edge"));
+            }
+            policy.edge_properties.push(EdgePropertyRule {
+                property: rule.property,
+                selector,
+                action: rule.action,
+            });
+        }
+        for (i, rule) in raw.generalize.into_iter().enumerate() {
+            let context = format!("generalize[{i}]");
+            policy.generalize.push(generalize_rule(rule, &context)?);
+        }
+        Ok(policy)
+    }
+}
+
+fn generalize_rule(rule: RawGeneralizeRule, context: &str) -> Result<GeneralizeRule, String> {
+    let geo = rule.property == "geo";
+    let method = match (rule.method, rule.bands, rule.decimals) {
+        (RawMethod::Bands, Some(bands), None) => {
+            if bands.is_empty() || bands.iter().any(|b| !b.is_finite()) {
+                return Err(format!("{context}: `bands` must be finite numbers"));
+            }
+            if bands.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err(format!("{context}: `bands` must be strictly increasing"));
+            }
+            GeneralizeMethod::Bands { bands }
+        }
+        (RawMethod::Bands, None, _) => {
+            return Err(format!("{context}: method `bands` requires `bands`"));
+        }
+        (RawMethod::Round, None, Some(decimals)) => {
+            if decimals > MAX_GEO_DECIMALS {
+                return Err(format!(
+                    "{context}: `decimals` must be at most {MAX_GEO_DECIMALS}"
+                ));
+            }
+            GeneralizeMethod::Round { decimals }
+        }
+        (RawMethod::Round, _, None) => {
+            return Err(format!("{context}: method `round` requires `decimals`"));
+        }
+        (RawMethod::Magnitude, None, None) => GeneralizeMethod::Magnitude,
+        (RawMethod::Centroid, None, None) => GeneralizeMethod::Centroid,
+        (RawMethod::Bbox, None, None) => GeneralizeMethod::Bbox,
+        (
+            RawMethod::Bands
+            | RawMethod::Round
+            | RawMethod::Magnitude
+            | RawMethod::Centroid
+            | RawMethod::Bbox,
+            _,
+            _,
+        ) => {
+            return Err(format!(
+                "{context}: `bands` is only allowed with method `bands`, \
+                 `decimals` only with method `round`"
+            ));
+        }
+    };
+    if geo != method.is_geo() {
+        return Err(if geo {
+            format!("{context}: `geo` takes method `round`, `centroid`, or `bbox`")
+        } else {
+            format!(
+                "{context}: `{}` is an edge property; use method `bands` or `magnitude`",
+                rule.property
+            )
+        });
+    }
+
+    let selector = rule
+        .selector
+        .map(|s| parse_selector(&s, context))
+        .transpose()?;
+    match &selector {
+        Some(s) if geo && !s.applies_to_nodes() => {
+            return Err(format!("{context}: `where` never matches a node"));
+        }
+        Some(s) if !geo && !s.applies_to_edges() => {
+            return Err(format!("{context}: `where` never matches an edge"));
+        }
+        Some(_) | None => {}
+    }
+    if rule
+        .scopes
+        .as_ref()
+        .is_some_and(|scopes| scopes.contains(&DisclosureScope::Internal))
+    {
+        return Err(format!(
+            "{context}: `internal` scope output is never generalised"
+        ));
+    }
+
+    Ok(GeneralizeRule {
+        property: rule.property,
+        selector,
+        scopes: rule.scopes,
+        method,
+    })
+}
+
+fn parse_selector(input: &str, context: &str) -> Result<SelectorExpr, String> {
+    SelectorExpr::parse(input).map_err(|e| format!("{context}: invalid `where` expression {e}"))
+}
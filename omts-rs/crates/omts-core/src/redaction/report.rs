@@ -0,0 +1,175 @@
+use serde::Serialize;
+
+use crate::enums::{DisclosureScope, EdgeTypeTag, NodeTypeTag, Sensitivity};
+use crate::file::OmtsFile;
+use crate::newtypes::{EdgeId, NodeId};
+
+use super::NodeAction;
+use super::generalize::GeneralizeMethod;
+
+/// The result of [`super::redact_with_report`].
+#[derive(Debug, Clone)]
+pub struct RedactOutput {
+    /// The redacted file.
+    pub file: OmtsFile,
+    /// What was removed or replaced, and why.
+    pub report: RedactionReport,
+}
+
+/// An audit record of one redaction (redaction.md Section 7.2).
+///
+/// Lists every node's disposition and everything removed from the retained
+/// part of the graph, each with the rule that removed it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RedactionReport {
+    /// The target disclosure scope.
+    pub scope: DisclosureScope,
+    /// Every input node, in input order.
+    pub nodes: Vec<NodeDisposition>,
+    /// Identifiers removed from retained nodes.
+    pub identifiers_dropped: Vec<DroppedIdentifier>,
+    /// Edges left out of the output.
+    pub edges_omitted: Vec<OmittedEdge>,
+    /// Properties removed from retained edges.
+    pub properties_stripped: Vec<StrippedProperty>,
+    /// Edge properties disclosed as a range instead of their exact value.
+    pub properties_generalized: Vec<GeneralizedProperty>,
+    /// Retained nodes whose `geo` was coarsened.
+    pub geo_generalized: Vec<GeneralizedGeo>,
+}
+
+impl RedactionReport {
+    /// Number of nodes with `action`.
+    pub fn count(&self, action: &NodeAction) -> usize {
+        self.nodes.iter().filter(|n| n.action == *action).count()
+    }
+}
+
+/// What happened to one input node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeDisposition {
+    /// The node ID (kept by `boundary_ref` replacements).
+    pub id: NodeId,
+    /// The input node's type.
+    #[serde(rename = "type")]
+    pub node_type: NodeTypeTag,
+    /// Retained, replaced, or omitted.
+    pub action: NodeAction,
+    /// Why the action was chosen.
+    pub reason: NodeReason,
+    /// The `opaque` identifier of the `boundary_ref` replacing the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opaque_value: Option<String>,
+}
+
+/// Why a node was retained, replaced, or omitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeReason {
+    /// The scope decided: the node type is omitted at this scope, or the
+    /// scope is `internal` and everything is retained.
+    Scope,
+    /// A node rule of the redaction policy matched.
+    Policy,
+    /// The node is in the retain set.
+    RetainSet,
+    /// The node is already a `boundary_ref` and passes through.
+    BoundaryRef,
+    /// The node is outside the retain set and was replaced.
+    NotRetained,
+}
+
+/// Why an identifier or edge property was removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DropReason {
+    /// Its effective sensitivity exceeds the scope's threshold.
+    Sensitivity {
+        /// The effective sensitivity.
+        sensitivity: Sensitivity,
+    },
+    /// A rule of the redaction policy dropped it.
+    Policy,
+    /// Removed at `public` scope whatever its sensitivity; applies to the
+    /// `_property_sensitivity` object.
+    PublicScope,
+}
+
+/// An identifier removed from a retained node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DroppedIdentifier {
+    /// The owning node.
+    pub node_id: NodeId,
+    /// The identifier scheme.
+    pub scheme: String,
+    /// The identifier value.
+    pub value: String,
+    /// Why it was removed.
+    #[serde(flatten)]
+    pub reason: DropReason,
+}
+
+/// An edge left out of the output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OmittedEdge {
+    /// The edge ID.
+    pub id: EdgeId,
+    /// The edge type.
+    #[serde(rename = "type")]
+    pub edge_type: EdgeTypeTag,
+    /// The source node ID.
+    pub source: NodeId,
+    /// The target node ID.
+    pub target: NodeId,
+    /// Why it was omitted.
+    pub reason: EdgeOmitReason,
+}
+
+/// Why an edge was omitted (redaction.md Sections 6.2–6.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeOmitReason {
+    /// `beneficial_ownership` edges are omitted at `public` scope.
+    BeneficialOwnership,
+    /// An endpoint was omitted or is not in the file.
+    EndpointOmitted,
+    /// Both endpoints were replaced with `boundary_ref` nodes.
+    BothEndpointsReplaced,
+}
+
+/// A property removed from a retained edge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StrippedProperty {
+    /// The edge ID.
+    pub edge_id: EdgeId,
+    /// The property name.
+    pub property: String,
+    /// Why it was removed.
+    #[serde(flatten)]
+    pub reason: DropReason,
+}
+
+/// An edge property replaced by a range under `_generalized`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeneralizedProperty {
+    /// The edge ID.
+    pub edge_id: EdgeId,
+    /// The property name.
+    pub property: String,
+    /// The generalisation applied.
+    #[serde(flatten)]
+    pub method: GeneralizeMethod,
+}
+
+/// A retained node whose `geo` was coarsened, or removed when it held no
+/// readable coordinates.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeneralizedGeo {
+    /// The node ID.
+    pub node_id: NodeId,
+    /// The generalisation applied.
+    #[serde(flatten)]
+    pub method: GeneralizeMethod,
+    /// `true` if the `geo` value was removed instead.
+    pub removed: bool,
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::boundary_hash::{BoundaryHashError, decode_salt, salted_hash};
+use crate::canonical::CanonicalId;
+use crate::enums::{NodeType, NodeTypeTag, Sensitivity};
+use crate::file::OmtsFile;
+use crate::newtypes::NodeId;
+use crate::sensitivity::effective_sensitivity;
+use crate::structures::Node;
+
+/// Candidates with more public identifiers than this are only hashed over
+/// their full identifier set; smaller ones are also hashed over every subset.
+pub const MAX_SUBSET_IDENTIFIERS: usize = 8;
+
+/// The candidates matching one `boundary_ref` node (redaction.md Section 7.3).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoundaryResolution {
+    /// The `boundary_ref` node ID in the redacted file.
+    pub node_id: NodeId,
+    /// The node's `opaque` identifier value.
+    pub opaque_value: String,
+    /// Matching candidates, complete matches first. Empty if unresolved.
+    pub matches: Vec<BoundaryMatch>,
+}
+
+/// A candidate node whose identifiers reproduce a boundary reference hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoundaryMatch {
+    /// The candidate node ID.
+    pub candidate_id: NodeId,
+    /// The canonical identifiers that produce the hash, sorted.
+    pub identifiers: Vec<String>,
+    /// `true` if the hash uses all of the candidate's public identifiers;
+    /// `false` if the sender held only some of them.
+    pub complete: bool,
+}
+
+/// Maps each `boundary_ref` node of `redacted` to the `candidates` whose
+/// public identifiers reproduce its opaque value under the file salt.
+///
+/// A candidate is hashed over its full set of public identifiers and, if it
+/// has at most [`MAX_SUBSET_IDENTIFIERS`] of them, over every non-empty
+/// subset, so a candidate from master data richer than the sender's still
+/// matches. Boundary references produced by the random path (nodes without
+/// public identifiers) never resolve. Results follow the input order of the
+/// `boundary_ref` nodes.
+///
+/// # Errors
+///
+/// Returns [`BoundaryHashError::InvalidSalt`] if `redacted.file_salt` cannot
+/// be decoded.
+pub fn resolve_boundary_refs(
+    redacted: &OmtsFile,
+    candidates: &[Node],
+) -> Result<Vec<BoundaryResolution>, BoundaryHashError> {
+    let salt = decode_salt(&redacted.file_salt)?;
+
+    let mut by_hash: HashMap<String, Vec<BoundaryMatch>> = HashMap::new();
+    for candidate in candidates {
+        if is_boundary_ref(candidate) {
+            continue;
+        }
+        let mut canonicals: Vec<String> = public_canonicals(candidate);
+        if canonicals.is_empty() {
+            continue;
+        }
+        canonicals.sort_unstable();
+        canonicals.dedup();
+        for subset in identifier_subsets(&canonicals) {
+            let hash = salted_hash(&subset, &salt);
+            let matches = by_hash.entry(hash).or_default();
+            if matches.iter().any(|m| m.candidate_id == candidate.id) {
+                continue;
+            }
+            matches.push(BoundaryMatch {
+                candidate_id: candidate.id.clone(),
+                identifiers: subset.iter().map(|s| (*s).to_owned()).collect(),
+                complete: subset.len() == canonicals.len(),
+            });
+        }
+    }
+
+    let mut resolutions = Vec::new();
+    for node in &redacted.nodes {
+        if !is_boundary_ref(node) {
+            continue;
+        }
+        let Some(opaque) = node
+            .identifiers
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .find(|id| id.scheme == "opaque")
+        else {
+            continue;
+        };
+        let mut matches = by_hash.get(&opaque.value).cloned().unwrap_or_default();
+        matches.sort_by(|a, b| {
+            b.complete
+                .cmp(&a.complete)
+                .then_with(|| b.identifiers.len().cmp(&a.identifiers.len()))
+        });
+        resolutions.push(BoundaryResolution {
+            node_id: node.id.clone(),
+            opaque_value: opaque.value.clone(),
+            matches,
+        });
+    }
+    Ok(resolutions)
+}
+
+fn is_boundary_ref(node: &Node) -> bool {
+    node.node_type == NodeTypeTag::Known(NodeType::BoundaryRef)
+}
+
+/// Canonical strings of the identifiers `node` would contribute to a
+/// boundary reference hash (Section 4.1, step 1).
+fn public_canonicals(node: &Node) -> Vec<String> {
+    node.identifiers
+        .as_deref()
+        .unwrap_or(&[])
+        .iter()
+        .filter(|id| {
+            matches!(
+                effective_sensitivity(id, &node.node_type),
+                Sensitivity::Public
+            )
+        })
+        .map(|id| CanonicalId::from_identifier(id).as_str().to_owned())
+        .collect()
+}
+
+/// The full sorted set followed, for small sets, by every proper non-empty
+/// subset. Subsets keep the sorted order, as the hash requires.
+fn identifier_subsets(sorted: &[String]) -> Vec<Vec<&str>> {
+    let full: Vec<&str> = sorted.iter().map(String::as_str).collect();
+    if sorted.len() > MAX_SUBSET_IDENTIFIERS {
+        return vec![full];
+    }
+    let all_mask = (1usize << sorted.len()) - 1;
+    let mut subsets = vec![full];
+    for mask in 1..all_mask {
+        subsets.push(
+            sorted
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, s)| s.as_str())
+                .collect(),
+        );
+    }
+    subsets
+}
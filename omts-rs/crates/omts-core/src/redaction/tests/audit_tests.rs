@@ -0,0 +1,266 @@
+#![allow(clippy::expect_used)]
+
+use crate::enums::DisclosureScope;
+use crate::file::OmtsFile;
+use crate::redaction::audit_disclosure;
+use crate::validation::{Diagnostic, Location, RuleId, Severity};
+
+const SALT: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+fn file(scope: &str, nodes: serde_json::Value, edges: serde_json::Value) -> OmtsFile {
+    file_with_header(scope, nodes, edges, serde_json::json!({}))
+}
+
+fn file_with_header(
+    scope: &str,
+    nodes: serde_json::Value,
+    edges: serde_json::Value,
+    extra: serde_json::Value,
+) -> OmtsFile {
+    let mut value = serde_json::json!({
+        "omts_version": "1.0.0",
+        "snapshot_date": "2026-02-19",
+        "file_salt": SALT,
+        "disclosure_scope": scope,
+    });
+    if let (Some(obj), serde_json::Value::Object(extra)) = (value.as_object_mut(), extra) {
+        obj.extend(extra);
+        obj.insert("nodes".to_owned(), nodes);
+        obj.insert("edges".to_owned(), edges);
+    }
+    serde_json::from_value(value).expect("valid OMTS file")
+}
+
+fn audit(file: &OmtsFile, scope: &DisclosureScope) -> Vec<Diagnostic> {
+    audit_disclosure(file, scope).diagnostics
+}
+
+fn codes(diags: &[Diagnostic]) -> Vec<&str> {
+    diags.iter().map(|d| d.rule_id.code()).collect()
+}
+
+#[test]
+fn clean_file_has_no_findings() {
+    let f = file(
+        "public",
+        serde_json::json!([
+            { "id": "org-1", "type": "organization", "name": "Acme",
+              "identifiers": [{ "scheme": "lei", "value": "5493006MHB84DD0ZWV18" }] },
+            { "id": "org-2", "type": "boundary_ref",
+              "identifiers": [{ "scheme": "opaque", "value": "abc123" }] }
+        ]),
+        serde_json::json!([
+            { "id": "e-1", "type": "supplies", "source": "org-2", "target": "org-1" }
+        ]),
+    );
+    assert!(audit(&f, &DisclosureScope::Public).is_empty());
+}
+
+#[test]
+fn internal_scope_audits_nothing() {
+    let f = file(
+        "internal",
+        serde_json::json!([{ "id": "p-1", "type": "person", "name": "Jane Doe" }]),
+        serde_json::json!([]),
+    );
+    assert!(audit(&f, &DisclosureScope::Internal).is_empty());
+}
+
+#[test]
+fn person_nodes_and_copied_names_are_flagged_at_public_scope() {
+    let f = file(
+        "public",
+        serde_json::json!([
+            { "id": "p-1", "type": "person", "name": "Jane Doe" },
+            { "id": "org-1", "type": "organization", "name": "Acme",
+              "labels": [{ "key": "contact", "value": "Jane Doe" }] }
+        ]),
+        serde_json::json!([]),
+    );
+    let diags = audit(&f, &DisclosureScope::Public);
+    assert_eq!(codes(&diags), ["SDA-01", "SDA-01"]);
+    assert_eq!(
+        diags[1].location,
+        Location::Node {
+            node_id: "org-1".to_owned(),
+            field: Some("labels[0].value".to_owned()),
+        }
+    );
+
+    assert!(audit(&f, &DisclosureScope::Partner).is_empty());
+}
+
+#[test]
+fn provenance_identifiers_of_persons_leak_at_partner_scope() {
+    let f = file(
+        "partner",
+        serde_json::json!([
+            { "id": "p-1", "type": "person", "name": "Jane Doe",
+              "_provenance": [
+                  { "source_file": "file_0", "node_id": "p-1",
+                    "identifiers": ["internal:HR-00042"] }
+              ] }
+        ]),
+        serde_json::json!([]),
+    );
+    let diags = audit(&f, &DisclosureScope::Partner);
+    assert_eq!(codes(&diags), ["SDA-02"]);
+    assert_eq!(
+        diags[0].location,
+        Location::Node {
+            node_id: "p-1".to_owned(),
+            field: Some("_provenance[0].identifiers[0]".to_owned()),
+        }
+    );
+}
+
+#[test]
+fn identifier_records_and_their_values_in_extra_are_flagged() {
+    let f = file(
+        "public",
+        serde_json::json!([
+            { "id": "org-1", "type": "organization",
+              "tax": { "scheme": "vat", "value": "DE123456789", "authority": "DE" },
+              "note": "registered as DE123456789",
+              "vat_copy": "DE123456789" }
+        ]),
+        serde_json::json!([]),
+    );
+    let diags = audit(&f, &DisclosureScope::Public);
+    let fields: Vec<_> = diags
+        .iter()
+        .map(|d| match &d.location {
+            Location::Node { field, .. } => field.clone(),
+            Location::Header { .. }
+            | Location::Edge { .. }
+            | Location::Identifier { .. }
+            | Location::Global => None,
+        })
+        .collect();
+    assert_eq!(codes(&diags), ["SDA-02", "SDA-02"]);
+    assert_eq!(
+        fields,
+        [Some("tax".to_owned()), Some("vat_copy".to_owned())]
+    );
+
+    // A VAT number is restricted, which partners may see.
+    assert!(audit(&f, &DisclosureScope::Partner).is_empty());
+}
+
+#[test]
+fn edge_conflicts_on_stripped_properties_are_flagged() {
+    let f = file(
+        "public",
+        serde_json::json!([
+            { "id": "org-1", "type": "organization" },
+            { "id": "org-2", "type": "organization" }
+        ]),
+        serde_json::json!([
+            { "id": "e-1", "type": "supplies", "source": "org-1", "target": "org-2",
+              "properties": {
+                  "_conflicts": [
+                      { "field": "annual_value", "values": [
+                          { "value": 1000000, "source_file": "file_0" },
+                          { "value": 1200000, "source_file": "file_1" }
+                      ] },
+                      { "field": "commodity", "values": [
+                          { "value": "7208", "source_file": "file_0" },
+                          { "value": "7209", "source_file": "file_1" }
+                      ] }
+                  ]
+              } }
+        ]),
+    );
+    let diags = audit(&f, &DisclosureScope::Public);
+    assert_eq!(codes(&diags), ["SDA-03"]);
+    assert_eq!(
+        diags[0].location,
+        Location::Edge {
+            edge_id: "e-1".to_owned(),
+            field: Some("properties._conflicts[0]".to_owned()),
+        }
+    );
+    assert!(audit(&f, &DisclosureScope::Partner).is_empty());
+}
+
+#[test]
+fn source_file_paths_are_flagged() {
+    let f = file_with_header(
+        "partner",
+        serde_json::json!([
+            { "id": "org-1", "type": "organization",
+              "_conflicts": [
+                  { "field": "name", "values": [
+                      { "value": "Acme", "source_file": "C:\\exports\\acme.omts" },
+                      { "value": "ACME", "source_file": "file_1" }
+                  ] }
+              ] }
+        ]),
+        serde_json::json!([]),
+        serde_json::json!({
+            "merge_metadata": {
+                "source_files": ["/home/buyer/suppliers.omts", "file_1"],
+                "source_names": ["suppliers", "acme-export"],
+                "conflict_count": 1
+            }
+        }),
+    );
+    let diags = audit(&f, &DisclosureScope::Partner);
+    assert_eq!(codes(&diags), ["SDA-05", "SDA-05", "SDA-05", "SDA-05"]);
+    assert_eq!(diags[0].location, Location::Global);
+    assert!(diags[0].message.contains("merge_metadata.source_files[0]"));
+    assert!(diags[2].message.contains("merge_metadata.source_names[1]"));
+    assert!(matches!(&diags[3].location, Location::Node { node_id, .. } if node_id == "org-1"));
+}
+
+#[test]
+fn boundary_refs_must_be_bare_stubs() {
+    let f = file(
+        "partner",
+        serde_json::json!([
+            { "id": "bref-1", "type": "boundary_ref", "name": "Acme",
+              "identifiers": [{ "scheme": "opaque", "value": "abc123" }] }
+        ]),
+        serde_json::json!([]),
+    );
+    let diags = audit(&f, &DisclosureScope::Partner);
+    assert_eq!(codes(&diags), ["SDA-04"]);
+    assert_eq!(diags[0].severity, Severity::Error);
+}
+
+#[test]
+fn boundary_ref_neighbours_that_identify_it_are_flagged() {
+    let f = file(
+        "partner",
+        serde_json::json!([
+            { "id": "bref-1", "type": "boundary_ref",
+              "identifiers": [{ "scheme": "opaque", "value": "abc123" }] },
+            { "id": "sub-1", "type": "organization",
+              "identifiers": [{ "scheme": "lei", "value": "5493006MHB84DD0ZWV18" }] },
+            { "id": "fac-1", "type": "facility", "address": "1 Mill Road" },
+            { "id": "org-2", "type": "organization" }
+        ]),
+        serde_json::json!([
+            { "id": "e-1", "type": "legal_parentage", "source": "sub-1", "target": "bref-1" },
+            { "id": "e-2", "type": "operates", "source": "bref-1", "target": "fac-1" },
+            { "id": "e-3", "type": "same_as", "source": "bref-1", "target": "org-2" },
+            { "id": "e-4", "type": "supplies", "source": "bref-1", "target": "org-2" }
+        ]),
+    );
+    let diags = audit(&f, &DisclosureScope::Partner);
+    assert!(diags.iter().all(|d| d.rule_id == RuleId::Sda04));
+    let severities: Vec<Severity> = diags.iter().map(|d| d.severity).collect();
+    assert_eq!(
+        severities,
+        [Severity::Warning, Severity::Warning, Severity::Error]
+    );
+    assert!(diags[0].message.contains("legal_parentage"));
+    assert!(diags[1].message.contains("operates"));
+    assert_eq!(
+        diags[2].location,
+        Location::Edge {
+            edge_id: "e-3".to_owned(),
+            field: None,
+        }
+    );
+}
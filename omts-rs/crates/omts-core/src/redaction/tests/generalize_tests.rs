@@ -0,0 +1,117 @@
+#![allow(clippy::expect_used)]
+
+use crate::dynvalue::DynValue;
+use crate::redaction::GeneralizeMethod;
+use crate::redaction::generalize::{generalize_geo, generalize_number};
+
+fn json(value: &DynValue) -> serde_json::Value {
+    serde_json::Value::from(value.clone())
+}
+
+fn dyn_value(value: serde_json::Value) -> DynValue {
+    DynValue::from(value)
+}
+
+#[test]
+fn bands_place_values_in_half_open_ranges() {
+    let method = GeneralizeMethod::Bands {
+        bands: vec![25.0, 50.0],
+    };
+    let range = |v: f64| json(&generalize_number(v, &method, None).expect("range"));
+    assert_eq!(range(10.0), serde_json::json!({ "max": 25 }));
+    assert_eq!(range(25.0), serde_json::json!({ "min": 25, "max": 50 }));
+    assert_eq!(range(49.9), serde_json::json!({ "min": 25, "max": 50 }));
+    assert_eq!(range(51.0), serde_json::json!({ "min": 50 }));
+}
+
+#[test]
+fn magnitude_brackets_exact_powers_of_ten() {
+    let range = |v: f64| {
+        json(&generalize_number(v, &GeneralizeMethod::Magnitude, Some("kg")).expect("range"))
+    };
+    assert_eq!(
+        range(5000.0),
+        serde_json::json!({ "min": 1000, "max": 10000, "unit": "kg" })
+    );
+    assert_eq!(
+        range(1000.0),
+        serde_json::json!({ "min": 1000, "max": 10000, "unit": "kg" })
+    );
+    assert_eq!(
+        range(0.5),
+        serde_json::json!({ "min": 0.1, "max": 1, "unit": "kg" })
+    );
+    assert_eq!(
+        range(0.0),
+        serde_json::json!({ "min": 0, "max": 0, "unit": "kg" })
+    );
+}
+
+#[test]
+fn numeric_methods_ignore_geo_and_non_finite_values() {
+    assert!(generalize_number(f64::NAN, &GeneralizeMethod::Magnitude, None).is_none());
+    assert!(generalize_number(1.0, &GeneralizeMethod::Centroid, None).is_none());
+    let point = dyn_value(serde_json::json!({ "lat": 1.0, "lon": 2.0 }));
+    assert!(generalize_geo(&point, &GeneralizeMethod::Magnitude).is_none());
+}
+
+#[test]
+fn round_reduces_point_and_geojson_precision() {
+    let method = GeneralizeMethod::Round { decimals: 1 };
+    let point = dyn_value(serde_json::json!({ "lat": 51.50735, "lon": -0.12776 }));
+    assert_eq!(
+        json(&generalize_geo(&point, &method).expect("rounded")),
+        serde_json::json!({ "lat": 51.5, "lon": -0.1 })
+    );
+
+    let line = dyn_value(serde_json::json!({
+        "type": "LineString",
+        "coordinates": [[13.40495, 52.52001], [13.37698, 52.51627]]
+    }));
+    assert_eq!(
+        json(&generalize_geo(&line, &method).expect("rounded")),
+        serde_json::json!({
+            "type": "LineString",
+            "coordinates": [[13.4, 52.5], [13.4, 52.5]]
+        })
+    );
+}
+
+#[test]
+fn centroid_and_bbox_of_polygon() {
+    let square = dyn_value(serde_json::json!({
+        "type": "Polygon",
+        "coordinates": [[[0.0, 0.0], [4.0, 0.0], [4.0, 2.0], [0.0, 2.0], [0.0, 0.0]]]
+    }));
+    assert_eq!(
+        json(&generalize_geo(&square, &GeneralizeMethod::Centroid).expect("centroid")),
+        serde_json::json!({ "lat": 1.0, "lon": 2.0 })
+    );
+    assert_eq!(
+        json(&generalize_geo(&square, &GeneralizeMethod::Bbox).expect("bbox")),
+        serde_json::json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [4.0, 0.0], [4.0, 2.0], [0.0, 2.0], [0.0, 0.0]]]
+        })
+    );
+
+    // A point's centroid and bounding box are the point itself.
+    let point = dyn_value(serde_json::json!({ "lat": 1.5, "lon": 2.5 }));
+    assert_eq!(
+        json(&generalize_geo(&point, &GeneralizeMethod::Bbox).expect("bbox")),
+        serde_json::json!({ "lat": 1.5, "lon": 2.5 })
+    );
+}
+
+#[test]
+fn geo_without_coordinates_cannot_be_generalized() {
+    let empty = dyn_value(serde_json::json!({ "type": "Polygon", "coordinates": [] }));
+    assert!(generalize_geo(&empty, &GeneralizeMethod::Centroid).is_none());
+    assert!(
+        generalize_geo(
+            &DynValue::String("nowhere".to_owned()),
+            &GeneralizeMethod::Bbox
+        )
+        .is_none()
+    );
+}
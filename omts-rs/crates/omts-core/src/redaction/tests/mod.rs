@@ -1,2 +1,4 @@
+mod audit_tests;
 mod classify_tests;
 mod filter_tests;
+mod generalize_tests;
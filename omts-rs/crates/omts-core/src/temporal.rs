@@ -0,0 +1,270 @@
+/// Point-in-time projection of an [`OmtsFile`].
+///
+/// Nodes, edges, and identifiers carry `valid_from` / `valid_to` bounds
+/// (SPEC-001 Section 5, SPEC-002 Section 3), but a file is otherwise a
+/// timeless snapshot. [`as_of`] answers "what did this graph look like on a
+/// given date" by dropping every element whose validity interval excludes
+/// that date.
+///
+/// # Interval Semantics
+///
+/// An element is valid on date `d` when:
+/// - `valid_from` is absent, or `valid_from <= d`; and
+/// - `valid_to` is absent, explicit `null` (open-ended), or `d <= valid_to`.
+///
+/// Both bounds are inclusive, matching the overlap rule used by
+/// [`crate::identity::temporal_compatible`]. Dates are compared
+/// lexicographically, which is correct for the `YYYY-MM-DD` form enforced by
+/// [`CalendarDate`].
+///
+/// # What Is Dropped
+///
+/// - Edges whose own interval excludes the date.
+/// - Identifiers (on nodes and edges) whose interval excludes the date.
+/// - Nodes whose own interval excludes the date. In the core vocabulary only
+///   `attestation` nodes carry validity dates; extension nodes that reuse the
+///   fields are treated the same way.
+/// - Edges incident to a dropped node, so the result remains a valid graph.
+///
+/// The header is preserved, except that `reporting_entity` is cleared if the
+/// referenced node was dropped.
+use std::collections::HashSet;
+
+use crate::file::OmtsFile;
+use crate::newtypes::CalendarDate;
+use crate::structures::{Edge, Node};
+use crate::types::Identifier;
+
+/// Returns `true` when the interval `[valid_from, valid_to]` contains `date`.
+///
+/// `valid_to` follows the three-state convention used throughout the data
+/// model: `None` (absent) and `Some(None)` (explicit `null`) are both
+/// open-ended; `Some(Some(d))` is an inclusive end date.
+pub fn valid_on(
+    valid_from: Option<&CalendarDate>,
+    valid_to: Option<&Option<CalendarDate>>,
+    date: &CalendarDate,
+) -> bool {
+    if valid_from.is_some_and(|from| from > date) {
+        return false;
+    }
+    match valid_to {
+        None | Some(None) => true,
+        Some(Some(to)) => date <= to,
+    }
+}
+
+/// Returns a copy of `file` containing only the elements valid on `date`.
+///
+/// See the module documentation for the full list of projection rules.
+pub fn as_of(file: &OmtsFile, date: &CalendarDate) -> OmtsFile {
+    let mut dropped_nodes: HashSet<&str> = HashSet::new();
+    let mut nodes: Vec<Node> = Vec::with_capacity(file.nodes.len());
+
+    for node in &file.nodes {
+        if !valid_on(node.valid_from.as_ref(), node.valid_to.as_ref(), date) {
+            dropped_nodes.insert(&node.id);
+            continue;
+        }
+        let mut kept = node.clone();
+        kept.identifiers = retain_identifiers(kept.identifiers, date);
+        nodes.push(kept);
+    }
+
+    let edges: Vec<Edge> = file
+        .edges
+        .iter()
+        .filter(|edge| {
+            !dropped_nodes.contains(&*edge.source)
+                && !dropped_nodes.contains(&*edge.target)
+                && valid_on(
+                    edge.properties.valid_from.as_ref(),
+                    edge.properties.valid_to.as_ref(),
+                    date,
+                )
+        })
+        .map(|edge| {
+            let mut kept = edge.clone();
+            kept.identifiers = retain_identifiers(kept.identifiers, date);
+            kept
+        })
+        .collect();
+
+    let reporting_entity = match &file.reporting_entity {
+        Some(id) if dropped_nodes.contains(&**id) => None,
+        other => other.clone(),
+    };
+
+    OmtsFile {
+        omts_version: file.omts_version.clone(),
+        snapshot_date: file.snapshot_date.clone(),
+        file_salt: file.file_salt.clone(),
+        disclosure_scope: file.disclosure_scope.clone(),
+        previous_snapshot_ref: file.previous_snapshot_ref.clone(),
+        snapshot_sequence: file.snapshot_sequence,
+        reporting_entity,
+        nodes,
+        edges,
+        extra: file.extra.clone(),
+    }
+}
+
+/// Removes identifiers not valid on `date`, collapsing an emptied list to
+/// `None` so the output does not gain an `"identifiers": []` key.
+fn retain_identifiers(
+    identifiers: Option<Vec<Identifier>>,
+    date: &CalendarDate,
+) -> Option<Vec<Identifier>> {
+    let mut ids = identifiers?;
+    ids.retain(|id| valid_on(id.valid_from.as_ref(), id.valid_to.as_ref(), date));
+    if ids.is_empty() { None } else { Some(ids) }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::enums::{EdgeType, NodeType};
+    use crate::test_helpers::{
+        date, minimal_file, node_id, org_node, ownership_edge, supplies_edge, typed_edge,
+        typed_node,
+    };
+
+    fn identifier(scheme: &str, value: &str, from: Option<&str>, to: Option<&str>) -> Identifier {
+        Identifier {
+            scheme: scheme.to_owned(),
+            value: value.to_owned(),
+            authority: None,
+            valid_from: from.map(date),
+            valid_to: to.map(|t| Some(date(t))),
+            sensitivity: None,
+            verification_status: None,
+            verification_date: None,
+            extra: BTreeMap::new(),
+        }
+    }
+
+    fn dated_edge(mut edge: Edge, from: &str, to: Option<Option<&str>>) -> Edge {
+        edge.properties.valid_from = Some(date(from));
+        edge.properties.valid_to = to.map(|inner| inner.map(date));
+        edge
+    }
+
+    /// No bounds at all means always valid.
+    #[test]
+    fn test_valid_on_unbounded() {
+        assert!(valid_on(None, None, &date("2020-01-01")));
+    }
+
+    /// Both bounds are inclusive.
+    #[test]
+    fn test_valid_on_bounds_are_inclusive() {
+        let from = date("2024-01-01");
+        let to = Some(date("2024-12-31"));
+        assert!(valid_on(Some(&from), Some(&to), &date("2024-01-01")));
+        assert!(valid_on(Some(&from), Some(&to), &date("2024-12-31")));
+        assert!(!valid_on(Some(&from), Some(&to), &date("2023-12-31")));
+        assert!(!valid_on(Some(&from), Some(&to), &date("2025-01-01")));
+    }
+
+    /// Explicit `valid_to: null` is open-ended.
+    #[test]
+    fn test_valid_on_explicit_null_is_open_ended() {
+        let from = date("2024-01-01");
+        assert!(valid_on(Some(&from), Some(&None), &date("2099-01-01")));
+    }
+
+    /// Edges outside their validity window are dropped; open-ended ones kept.
+    #[test]
+    fn test_as_of_drops_expired_and_future_edges() {
+        let nodes = vec![org_node("a"), org_node("b"), org_node("c")];
+        let edges = vec![
+            dated_edge(
+                ownership_edge("e-old", "a", "b"),
+                "2018-01-01",
+                Some(Some("2020-12-31")),
+            ),
+            dated_edge(
+                ownership_edge("e-current", "a", "c"),
+                "2021-01-01",
+                Some(None),
+            ),
+            dated_edge(supplies_edge("e-future", "b", "c"), "2030-01-01", None),
+        ];
+        let file = minimal_file(nodes, edges);
+
+        let view = as_of(&file, &date("2022-06-30"));
+        let ids: Vec<&str> = view.edges.iter().map(|e| &*e.id).collect();
+        assert_eq!(ids, vec!["e-current"]);
+        assert_eq!(view.nodes.len(), 3, "organizations carry no dates");
+
+        let view = as_of(&file, &date("2019-06-30"));
+        let ids: Vec<&str> = view.edges.iter().map(|e| &*e.id).collect();
+        assert_eq!(ids, vec!["e-old"]);
+    }
+
+    /// Identifiers are filtered individually; an emptied list becomes `None`.
+    #[test]
+    fn test_as_of_filters_identifiers() {
+        let mut node = org_node("a");
+        node.identifiers = Some(vec![
+            identifier("duns", "111111111", Some("2010-01-01"), Some("2019-12-31")),
+            identifier("duns", "222222222", Some("2020-01-01"), None),
+        ]);
+        let mut other = org_node("b");
+        other.identifiers = Some(vec![identifier(
+            "internal",
+            "v-1",
+            None,
+            Some("2015-01-01"),
+        )]);
+        let file = minimal_file(vec![node, other], vec![]);
+
+        let view = as_of(&file, &date("2021-01-01"));
+        let a_ids = view.nodes[0]
+            .identifiers
+            .as_ref()
+            .expect("a keeps one identifier");
+        assert_eq!(a_ids.len(), 1);
+        assert_eq!(a_ids[0].value, "222222222");
+        assert!(view.nodes[1].identifiers.is_none());
+    }
+
+    /// An expired attestation is dropped together with its incident edges.
+    #[test]
+    fn test_as_of_drops_expired_attestation_and_incident_edges() {
+        let mut att = typed_node("att-1", NodeType::Attestation);
+        att.valid_from = Some(date("2020-01-01"));
+        att.valid_to = Some(Some(date("2022-12-31")));
+        let nodes = vec![org_node("org-1"), att];
+        let edges = vec![typed_edge("e-att", EdgeType::AttestedBy, "org-1", "att-1")];
+        let file = minimal_file(nodes, edges);
+
+        let view = as_of(&file, &date("2024-01-01"));
+        assert_eq!(view.nodes.len(), 1);
+        assert!(view.edges.is_empty());
+
+        let view = as_of(&file, &date("2021-01-01"));
+        assert_eq!(view.nodes.len(), 2);
+        assert_eq!(view.edges.len(), 1);
+    }
+
+    /// `reporting_entity` is cleared only when its node is dropped.
+    #[test]
+    fn test_as_of_reporting_entity() {
+        let mut att = typed_node("att-1", NodeType::Attestation);
+        att.valid_from = Some(date("2030-01-01"));
+        let mut file = minimal_file(vec![org_node("org-1"), att], vec![]);
+
+        file.reporting_entity = Some(node_id("org-1"));
+        let view = as_of(&file, &date("2025-01-01"));
+        assert_eq!(view.reporting_entity, Some(node_id("org-1")));
+
+        file.reporting_entity = Some(node_id("att-1"));
+        let view = as_of(&file, &date("2025-01-01"));
+        assert!(view.reporting_entity.is_none());
+    }
+}
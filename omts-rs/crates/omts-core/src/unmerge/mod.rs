@@ -0,0 +1,25 @@
+/// Reversing a merge using recorded provenance (merge.md Section 4.6).
+///
+/// A merge run with `MergeConfig::record_provenance` writes `_provenance` on
+/// each merged node (source file, original node ID, contributed identifiers)
+/// and edge (source file, original edge ID and endpoints). From that record:
+///
+/// - [`split_nodes`] replaces merged nodes with one node per constituent.
+///   Each constituent keeps only its own identifiers and takes its own value
+///   for every property recorded in `_conflicts` or `_resolutions`. Edges
+///   touching a split node are rewired to the constituent that contributed
+///   them; an edge that several constituents contributed is split into one
+///   edge per constituent.
+/// - [`extract_source`] rebuilds one source file's contribution with its
+///   original node and edge IDs.
+///
+/// Labels are a set union without provenance, and properties that did not
+/// conflict were shared, so both are copied to every constituent.
+mod pipeline;
+mod types;
+
+pub use pipeline::{extract_source, split_nodes};
+pub use types::{UnmergeError, UnmergeOutput};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,338 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::canonical::CanonicalId;
+use crate::conflict_review::ConflictResolution;
+use crate::dynvalue::{DynMap, DynValue};
+use crate::file::OmtsFile;
+use crate::merge::{Conflict, ConflictEntry, EdgeProvenance, MergeMetadata, NodeProvenance};
+use crate::newtypes::NodeId;
+use crate::structures::{Edge, Node};
+
+use super::types::{UnmergeError, UnmergeOutput};
+
+/// Splits each of `node_ids` back into the source nodes it was merged from.
+///
+/// Constituents reuse their original node IDs where these are free in the
+/// file, otherwise `{original}-{source_file}`. Edges touching a split node are
+/// rewired to the constituent their provenance names; an edge contributed by
+/// several constituents becomes one edge per constituent, the extra ones with
+/// IDs `{edge_id}-{k}`. A `reporting_entity` pointing at a split node moves to
+/// its first constituent.
+///
+/// # Errors
+///
+/// - [`UnmergeError::NodeNotFound`] — a node ID is not in the file.
+/// - [`UnmergeError::MissingProvenance`] — a split node, or an edge touching
+///   one, has no `_provenance`.
+/// - [`UnmergeError::InvalidProvenance`] — a `_provenance` record is malformed
+///   or names a constituent that does not exist.
+pub fn split_nodes(file: &OmtsFile, node_ids: &[NodeId]) -> Result<UnmergeOutput, UnmergeError> {
+    let mut used: HashSet<String> = file
+        .nodes
+        .iter()
+        .map(|n| n.id.to_string())
+        .chain(file.edges.iter().map(|e| e.id.to_string()))
+        .collect();
+
+    let mut targets: Vec<(&Node, Vec<NodeProvenance>)> = Vec::new();
+    for id in node_ids {
+        let node = file
+            .nodes
+            .iter()
+            .find(|n| n.id == *id)
+            .ok_or_else(|| UnmergeError::NodeNotFound(id.to_string()))?;
+        if targets.iter().any(|(t, _)| t.id == node.id) {
+            continue;
+        }
+        let provenance = read_provenance::<NodeProvenance>(&node.extra)?
+            .ok_or_else(|| UnmergeError::MissingProvenance(format!("node `{id}`")))?;
+        used.remove(&**id);
+        targets.push((node, provenance));
+    }
+
+    // Merged node ID -> (provenance entry, constituent ID) per constituent.
+    let mut constituents: HashMap<&str, Vec<(NodeProvenance, NodeId)>> = HashMap::new();
+    for (node, provenance) in targets {
+        let mut assigned = Vec::with_capacity(provenance.len());
+        for entry in provenance {
+            let id = node_id(&free_id(&mut used, &entry.node_id, &entry.source_file))?;
+            assigned.push((entry, id));
+        }
+        constituents.insert(&node.id, assigned);
+    }
+
+    let mut nodes_restored = 0;
+    let mut nodes = Vec::with_capacity(file.nodes.len());
+    for node in &file.nodes {
+        match constituents.get(&*node.id) {
+            Some(assigned) => {
+                for (entry, id) in assigned {
+                    nodes.push(constituent_node(node, entry, id.clone())?);
+                    nodes_restored += 1;
+                }
+            }
+            None => nodes.push(node.clone()),
+        }
+    }
+
+    let rewire = |endpoint: &NodeId, original: &str, source_file: &str| {
+        let Some(assigned) = constituents.get(&**endpoint) else {
+            return Ok(endpoint.clone());
+        };
+        assigned
+            .iter()
+            .find(|(entry, _)| entry.source_file == source_file && entry.node_id == original)
+            .map(|(_, id)| id.clone())
+            .ok_or_else(|| {
+                UnmergeError::InvalidProvenance(format!(
+                    "node `{endpoint}` has no constituent `{original}` from {source_file}"
+                ))
+            })
+    };
+
+    let mut edges_rewired = 0;
+    let mut edges = Vec::with_capacity(file.edges.len());
+    for edge in &file.edges {
+        let touches =
+            constituents.contains_key(&*edge.source) || constituents.contains_key(&*edge.target);
+        if !touches {
+            edges.push(edge.clone());
+            continue;
+        }
+        let provenance = read_provenance::<EdgeProvenance>(&edge.properties.extra)?
+            .ok_or_else(|| UnmergeError::MissingProvenance(format!("edge `{}`", edge.id)))?;
+
+        let mut split: Vec<((NodeId, NodeId), Vec<EdgeProvenance>)> = Vec::new();
+        for entry in provenance {
+            let endpoints = (
+                rewire(&edge.source, &entry.source, &entry.source_file)?,
+                rewire(&edge.target, &entry.target, &entry.source_file)?,
+            );
+            match split.iter_mut().find(|(e, _)| *e == endpoints) {
+                Some((_, entries)) => entries.push(entry),
+                None => split.push((endpoints, vec![entry])),
+            }
+        }
+
+        for (k, ((source, target), entries)) in split.into_iter().enumerate() {
+            let id = if k == 0 {
+                edge.id.clone()
+            } else {
+                node_id(&free_id(&mut used, &format!("{}-{k}", edge.id), ""))?
+            };
+            let mut properties = edge.properties.clone();
+            properties
+                .extra
+                .insert("_provenance".to_owned(), to_dyn(&entries)?);
+            edges.push(Edge {
+                id,
+                source,
+                target,
+                properties,
+                ..edge.clone()
+            });
+            edges_rewired += 1;
+        }
+    }
+
+    let mut out = file.clone();
+    if let Some(entity) = &file.reporting_entity {
+        if let Some((_, first)) = constituents.get(&**entity).and_then(|a| a.first()) {
+            out.reporting_entity = Some(first.clone());
+        }
+    }
+    out.nodes = nodes;
+    out.edges = edges;
+
+    Ok(UnmergeOutput {
+        file: out,
+        nodes_restored,
+        edges_rewired,
+    })
+}
+
+/// Rebuilds the part of a merged file that came from `source_file`: a label
+/// such as `file_1` from `merge_metadata.source_files`, or an input's file
+/// name as recorded in `merge_metadata.source_names`.
+///
+/// Nodes and edges take their original IDs and endpoints, their own
+/// identifiers, and their own values for properties recorded in `_conflicts`.
+/// The `reporting_entity` is kept when that source contributed to it, and
+/// `merge_metadata` is dropped.
+///
+/// # Errors
+///
+/// - [`UnmergeError::MissingProvenance`] — a node or edge has no
+///   `_provenance`.
+/// - [`UnmergeError::InvalidProvenance`] — a `_provenance` record is
+///   malformed.
+/// - [`UnmergeError::UnknownSource`] — no record names `source_file`.
+/// - [`UnmergeError::AmbiguousSource`] — `source_file` is the file name of
+///   several inputs.
+pub fn extract_source(file: &OmtsFile, source_file: &str) -> Result<UnmergeOutput, UnmergeError> {
+    let source_file = source_label(file, source_file)?;
+    let source_file = source_file.as_str();
+    let mut reporting_entity = None;
+    let mut nodes = Vec::new();
+    for node in &file.nodes {
+        let provenance = read_provenance::<NodeProvenance>(&node.extra)?
+            .ok_or_else(|| UnmergeError::MissingProvenance(format!("node `{}`", node.id)))?;
+        for entry in provenance.iter().filter(|e| e.source_file == source_file) {
+            let id = node_id(&entry.node_id)?;
+            if file.reporting_entity.as_ref() == Some(&node.id) && reporting_entity.is_none() {
+                reporting_entity = Some(id.clone());
+            }
+            nodes.push(constituent_node(node, entry, id)?);
+        }
+    }
+
+    let mut edges = Vec::new();
+    for edge in &file.edges {
+        let provenance = read_provenance::<EdgeProvenance>(&edge.properties.extra)?
+            .ok_or_else(|| UnmergeError::MissingProvenance(format!("edge `{}`", edge.id)))?;
+        for entry in provenance.iter().filter(|e| e.source_file == source_file) {
+            edges.push(Edge {
+                id: node_id(&entry.edge_id)?,
+                source: node_id(&entry.source)?,
+                target: node_id(&entry.target)?,
+                properties: own_values(&edge.properties, source_file)?,
+                ..edge.clone()
+            });
+        }
+    }
+
+    if nodes.is_empty() && edges.is_empty() {
+        return Err(UnmergeError::UnknownSource(source_file.to_owned()));
+    }
+
+    let mut out = file.clone();
+    out.extra.remove("merge_metadata");
+    out.reporting_entity = reporting_entity;
+    let (nodes_restored, edges_rewired) = (nodes.len(), edges.len());
+    out.nodes = nodes;
+    out.edges = edges;
+
+    Ok(UnmergeOutput {
+        file: out,
+        nodes_restored,
+        edges_rewired,
+    })
+}
+
+/// Rebuilds the constituent of `merged` described by `entry`.
+fn constituent_node(
+    merged: &Node,
+    entry: &NodeProvenance,
+    id: NodeId,
+) -> Result<Node, UnmergeError> {
+    let mut node: Node = own_values(merged, &entry.source_file)?;
+    node.id = id;
+    let own: HashSet<&str> = entry.identifiers.iter().map(String::as_str).collect();
+    let identifiers: Vec<_> = merged
+        .identifiers
+        .iter()
+        .flatten()
+        .filter(|id| own.contains(CanonicalId::from_identifier(id).as_str()))
+        .cloned()
+        .collect();
+    node.identifiers = (!identifiers.is_empty()).then_some(identifiers);
+    Ok(node)
+}
+
+/// Copies `value` with `_provenance`, `_conflicts`, and `_resolutions`
+/// removed and each conflicting property, resolved or not, set to
+/// `source_file`'s value (or cleared when that source had none).
+fn own_values<T>(value: &T, source_file: &str) -> Result<T, UnmergeError>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut json = serde_json::to_value(value).map_err(invalid)?;
+    let Some(map) = json.as_object_mut() else {
+        return Err(UnmergeError::InvalidProvenance(
+            "element is not a JSON object".to_owned(),
+        ));
+    };
+    map.remove("_provenance");
+    let mut recorded: Vec<(String, Vec<ConflictEntry>)> = Vec::new();
+    if let Some(conflicts) = map.remove("_conflicts") {
+        let conflicts: Vec<Conflict> = serde_json::from_value(conflicts).map_err(invalid)?;
+        recorded.extend(conflicts.into_iter().map(|c| (c.field, c.values)));
+    }
+    if let Some(resolutions) = map.remove("_resolutions") {
+        let resolutions: Vec<ConflictResolution> =
+            serde_json::from_value(resolutions).map_err(invalid)?;
+        recorded.extend(resolutions.into_iter().map(|r| (r.field, r.values)));
+    }
+    for (field, values) in recorded {
+        match values.into_iter().find(|e| e.source_file == source_file) {
+            Some(entry) => map.insert(field, entry.value),
+            None => map.remove(&field),
+        };
+    }
+    serde_json::from_value(json).map_err(invalid)
+}
+
+/// Reads a `_provenance` array from an element's extension fields.
+/// Resolves `source` to a `file_{i}` label. A recorded label is kept as is;
+/// otherwise `source` is looked up among the recorded input file names.
+fn source_label(file: &OmtsFile, source: &str) -> Result<String, UnmergeError> {
+    let metadata = file.extra.get("merge_metadata").and_then(|m| {
+        serde_json::from_value::<MergeMetadata>(serde_json::Value::from(m.clone())).ok()
+    });
+    match metadata {
+        Some(metadata) if !metadata.source_files.iter().any(|label| label == source) => metadata
+            .label_for_name(source)
+            .map(|label| label.unwrap_or_else(|| source.to_owned()))
+            .map_err(|labels| UnmergeError::AmbiguousSource {
+                name: source.to_owned(),
+                labels,
+            }),
+        Some(_) | None => Ok(source.to_owned()),
+    }
+}
+
+fn read_provenance<T: DeserializeOwned>(extra: &DynMap) -> Result<Option<Vec<T>>, UnmergeError> {
+    extra
+        .get("_provenance")
+        .map(|value| {
+            serde_json::from_value(serde_json::Value::from(value.clone())).map_err(invalid)
+        })
+        .transpose()
+}
+
+/// Returns `preferred` if unused, else `{preferred}-{suffix}`, else with a
+/// numeric suffix; the result is marked used.
+fn free_id(used: &mut HashSet<String>, preferred: &str, suffix: &str) -> String {
+    let mut candidate = preferred.to_owned();
+    if used.contains(&candidate) && !suffix.is_empty() {
+        candidate = format!("{preferred}-{suffix}");
+    }
+    let base = candidate.clone();
+    let mut n = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{base}-{n}");
+        n += 1;
+    }
+    used.insert(candidate.clone());
+    candidate
+}
+
+/// Parses an ID from a provenance record.
+fn node_id(id: &str) -> Result<NodeId, UnmergeError> {
+    NodeId::try_from(id).map_err(invalid)
+}
+
+/// Serializes a value for an extension field.
+fn to_dyn<T: Serialize>(value: &T) -> Result<DynValue, UnmergeError> {
+    serde_json::to_value(value)
+        .map(DynValue::from)
+        .map_err(invalid)
+}
+
+/// Wraps a serialization or ID error.
+fn invalid(e: impl std::fmt::Display) -> UnmergeError {
+    UnmergeError::InvalidProvenance(e.to_string())
+}
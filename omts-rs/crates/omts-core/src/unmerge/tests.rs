@@ -0,0 +1,272 @@
+#![allow(clippy::expect_used)]
+
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::enums::{NodeType, NodeTypeTag};
+use crate::file::OmtsFile;
+use crate::merge_pipeline::{MergeConfig, merge, merge_with_config};
+use crate::newtypes::NodeId;
+use crate::structures::{Edge, Node};
+use crate::test_helpers::{date, file_salt, node_id, semver, supplies_edge};
+use crate::types::Identifier;
+
+const SALT_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const SALT_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+
+fn org(id: &str, name: &str, identifiers: Vec<Identifier>) -> Node {
+    Node {
+        id: node_id(id),
+        node_type: NodeTypeTag::Known(NodeType::Organization),
+        identifiers: Some(identifiers),
+        name: Some(name.to_owned()),
+        ..Node::default()
+    }
+}
+
+fn identifier(scheme: &str, value: &str) -> Identifier {
+    Identifier {
+        scheme: scheme.to_owned(),
+        value: value.to_owned(),
+        authority: None,
+        valid_from: None,
+        valid_to: None,
+        sensitivity: None,
+        verification_status: None,
+        verification_date: None,
+        extra: BTreeMap::new(),
+    }
+}
+
+fn file(salt: &str, nodes: Vec<Node>, edges: Vec<Edge>) -> OmtsFile {
+    OmtsFile {
+        omts_version: semver("1.0.0"),
+        snapshot_date: date("2026-02-20"),
+        file_salt: file_salt(salt),
+        disclosure_scope: None,
+        previous_snapshot_ref: None,
+        snapshot_sequence: None,
+        reporting_entity: None,
+        nodes,
+        edges,
+        extra: BTreeMap::new(),
+    }
+}
+
+/// Two files describing the same supplier under different names; each also
+/// has its own buyer supplied by it. The first file's supplier carries an
+/// extra DUNS number.
+fn overmerged() -> OmtsFile {
+    overmerged_from(&["in/a.omts", "in/b.omts"])
+}
+
+/// [`overmerged`] with the two inputs read from `source_paths`.
+fn overmerged_from(source_paths: &[&str]) -> OmtsFile {
+    let a = file(
+        SALT_A,
+        vec![
+            org(
+                "sup",
+                "Acme Metals",
+                vec![
+                    identifier("lei", "5493006MHB84DD0ZWV18"),
+                    identifier("duns", "081466849"),
+                ],
+            ),
+            org("buyer-a", "Buyer A", vec![identifier("duns", "111111111")]),
+        ],
+        vec![supplies_edge("e1", "sup", "buyer-a")],
+    );
+    let b = file(
+        SALT_B,
+        vec![
+            org(
+                "s-1",
+                "Acme Plastics",
+                vec![identifier("lei", "5493006MHB84DD0ZWV18")],
+            ),
+            org("buyer-b", "Buyer B", vec![identifier("duns", "222222222")]),
+        ],
+        vec![supplies_edge("e9", "s-1", "buyer-b")],
+    );
+    let config = MergeConfig {
+        record_provenance: true,
+        source_paths: source_paths.iter().map(|p| (*p).to_owned()).collect(),
+        ..MergeConfig::default()
+    };
+    merge_with_config(&[a, b], &config)
+        .expect("merge succeeds")
+        .file
+}
+
+fn merged_supplier(file: &OmtsFile) -> NodeId {
+    file.nodes
+        .iter()
+        .find(|n| n.identifiers.iter().flatten().any(|id| id.scheme == "lei"))
+        .map(|n| n.id.clone())
+        .expect("merged supplier present")
+}
+
+fn node<'a>(file: &'a OmtsFile, id: &str) -> &'a Node {
+    file.nodes
+        .iter()
+        .find(|n| &*n.id == id)
+        .expect("node present")
+}
+
+#[test]
+fn provenance_is_recorded_only_when_requested() {
+    let merged = overmerged();
+    let supplier = node(&merged, &merged_supplier(&merged));
+    assert!(supplier.extra.contains_key("_provenance"));
+    assert!(
+        merged
+            .edges
+            .iter()
+            .all(|e| e.properties.extra.contains_key("_provenance"))
+    );
+    let metadata = serde_json::Value::from(merged.extra["merge_metadata"].clone());
+    assert_eq!(
+        metadata["source_names"],
+        serde_json::json!(["a.omts", "b.omts"])
+    );
+
+    let plain = merge(&[merged.clone()]).expect("re-merge succeeds");
+    assert!(
+        plain
+            .file
+            .nodes
+            .iter()
+            .all(|n| !n.extra.contains_key("_provenance"))
+    );
+    assert!(plain.metadata.source_names.is_empty());
+}
+
+#[test]
+fn split_restores_constituents_and_rewires_edges() {
+    let merged = overmerged();
+    let supplier = merged_supplier(&merged);
+    let out = split_nodes(&merged, &[supplier.clone()]).expect("split succeeds");
+
+    assert_eq!(out.nodes_restored, 2);
+    assert_eq!(out.file.nodes.len(), merged.nodes.len() + 1);
+    assert!(out.file.nodes.iter().all(|n| n.id != supplier));
+
+    let sup = node(&out.file, "sup");
+    assert_eq!(sup.name.as_deref(), Some("Acme Metals"));
+    assert_eq!(sup.identifiers.as_ref().map(Vec::len), Some(2));
+    assert!(!sup.extra.contains_key("_conflicts"));
+
+    let s1 = node(&out.file, "s-1");
+    assert_eq!(s1.name.as_deref(), Some("Acme Plastics"));
+    let schemes: Vec<&str> = s1
+        .identifiers
+        .iter()
+        .flatten()
+        .map(|id| id.scheme.as_str())
+        .collect();
+    assert_eq!(schemes, ["lei"]);
+
+    assert_eq!(out.edges_rewired, 2);
+    let sources: Vec<&str> = out.file.edges.iter().map(|e| &*e.source).collect();
+    assert!(sources.contains(&"sup"));
+    assert!(sources.contains(&"s-1"));
+}
+
+#[test]
+fn split_divides_edges_contributed_by_several_constituents() {
+    let a = file(
+        SALT_A,
+        vec![
+            org(
+                "sup",
+                "Acme",
+                vec![identifier("lei", "5493006MHB84DD0ZWV18")],
+            ),
+            org("buyer", "Buyer", vec![identifier("duns", "111111111")]),
+        ],
+        vec![supplies_edge("e1", "sup", "buyer")],
+    );
+    let mut b = a.clone();
+    b.file_salt = file_salt(SALT_B);
+    let config = MergeConfig {
+        record_provenance: true,
+        ..MergeConfig::default()
+    };
+    let merged = merge_with_config(&[a, b], &config)
+        .expect("merge succeeds")
+        .file;
+    assert_eq!(merged.edges.len(), 1);
+
+    let supplier = merged_supplier(&merged);
+    let out = split_nodes(&merged, &[supplier]).expect("split succeeds");
+
+    assert_eq!(out.file.edges.len(), 2);
+    let mut sources: Vec<&str> = out.file.edges.iter().map(|e| &*e.source).collect();
+    sources.sort_unstable();
+    assert_eq!(sources, ["sup", "sup-file_1"]);
+    assert_ne!(out.file.edges[0].id, out.file.edges[1].id);
+}
+
+#[test]
+fn extract_source_rebuilds_original_file() {
+    let merged = overmerged();
+    let out = extract_source(&merged, "file_1").expect("extract succeeds");
+
+    let mut ids: Vec<&str> = out.file.nodes.iter().map(|n| &*n.id).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, ["buyer-b", "s-1"]);
+    assert_eq!(
+        node(&out.file, "s-1").name.as_deref(),
+        Some("Acme Plastics")
+    );
+
+    assert_eq!(out.file.edges.len(), 1);
+    let edge = &out.file.edges[0];
+    assert_eq!(
+        (&*edge.id, &*edge.source, &*edge.target),
+        ("e9", "s-1", "buyer-b")
+    );
+    assert!(!edge.properties.extra.contains_key("_provenance"));
+    assert!(!out.file.extra.contains_key("merge_metadata"));
+}
+
+#[test]
+fn extract_source_accepts_recorded_file_name() {
+    let merged = overmerged();
+    let by_label = extract_source(&merged, "file_1").expect("extract by label");
+    for name in ["b.omts", "elsewhere/b.omts"] {
+        let by_name = extract_source(&merged, name).expect("extract by name");
+        assert_eq!(by_name.file.nodes, by_label.file.nodes);
+        assert_eq!(by_name.file.edges, by_label.file.edges);
+    }
+
+    let same_name = overmerged_from(&["2025/suppliers.omts", "2026/suppliers.omts"]);
+    assert_eq!(
+        extract_source(&same_name, "suppliers.omts").map(|out| out.nodes_restored),
+        Err(UnmergeError::AmbiguousSource {
+            name: "suppliers.omts".to_owned(),
+            labels: vec!["file_0".to_owned(), "file_1".to_owned()],
+        })
+    );
+}
+
+#[test]
+fn unmerge_errors() {
+    let merged = overmerged();
+    assert!(matches!(
+        split_nodes(&merged, &[node_id("nope")]),
+        Err(UnmergeError::NodeNotFound(_))
+    ));
+    assert!(matches!(
+        extract_source(&merged, "file_7"),
+        Err(UnmergeError::UnknownSource(_))
+    ));
+
+    let plain = merge(&[merged.clone()]).expect("re-merge succeeds").file;
+    let supplier = merged_supplier(&plain);
+    assert!(matches!(
+        split_nodes(&plain, &[supplier]),
+        Err(UnmergeError::MissingProvenance(_))
+    ));
+}
@@ -0,0 +1,57 @@
+use crate::file::OmtsFile;
+
+/// Errors from [`super::split_nodes`] and [`super::extract_source`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnmergeError {
+    /// A node to split does not exist in the file.
+    NodeNotFound(String),
+    /// A node or edge that must be reversed has no `_provenance` record; the
+    /// file was not merged with provenance recording.
+    MissingProvenance(String),
+    /// A `_provenance` record is malformed or does not match the file.
+    InvalidProvenance(String),
+    /// No provenance record names the requested source file.
+    UnknownSource(String),
+    /// The requested file name matches several inputs of the merge, listed
+    /// by label; one of the labels must be given instead.
+    AmbiguousSource {
+        /// The requested file name.
+        name: String,
+        /// Labels of the inputs recorded under that name.
+        labels: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for UnmergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NodeNotFound(id) => write!(f, "node `{id}` not found"),
+            Self::MissingProvenance(what) => write!(
+                f,
+                "{what} has no _provenance record; merge with provenance recording to unmerge"
+            ),
+            Self::InvalidProvenance(msg) => write!(f, "invalid _provenance record: {msg}"),
+            Self::UnknownSource(source) => {
+                write!(f, "no _provenance record names source `{source}`")
+            }
+            Self::AmbiguousSource { name, labels } => write!(
+                f,
+                "source `{name}` names several inputs ({}); give a label instead",
+                labels.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UnmergeError {}
+
+/// The result of an unmerge operation.
+#[derive(Debug, Clone)]
+pub struct UnmergeOutput {
+    /// The rewritten file.
+    pub file: OmtsFile,
+    /// Number of nodes written for split or extracted constituents.
+    pub nodes_restored: usize,
+    /// Number of edges whose endpoints were rewired to a constituent.
+    pub edges_rewired: usize,
+}
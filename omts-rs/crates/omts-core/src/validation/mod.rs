@@ -141,6 +141,17 @@ pub enum RuleId {
     /// L3-MRG-02: Legal parentage cycle detection via topological sort.
     L3Mrg02,
 
+    /// SDA-01: A `person` node in a file disclosed at `public` scope.
+    Sda01,
+    /// SDA-02: An identifier above the scope threshold copied into `extra` or a label.
+    Sda02,
+    /// SDA-03: A `_conflicts` or `_resolutions` record carrying values above the scope threshold.
+    Sda03,
+    /// SDA-04: A `boundary_ref` node that its own fields or its neighbours re-identify.
+    Sda04,
+    /// SDA-05: Merge metadata or provenance naming source file paths.
+    Sda05,
+
     /// An extension rule defined outside the core spec. Must not use `L1-*`, `L2-*`, or `L3-*` prefixes.
     Extension(String),
     /// An internal validator bug. Indicates a logic error in the validator itself.
@@ -194,6 +205,11 @@ impl RuleId {
             Self::L3Eid05 => "L3-EID-05",
             Self::L3Mrg01 => "L3-MRG-01",
             Self::L3Mrg02 => "L3-MRG-02",
+            Self::Sda01 => "SDA-01",
+            Self::Sda02 => "SDA-02",
+            Self::Sda03 => "SDA-03",
+            Self::Sda04 => "SDA-04",
+            Self::Sda05 => "SDA-05",
             Self::Extension(s) => s.as_str(),
             Self::Internal => "internal",
         }
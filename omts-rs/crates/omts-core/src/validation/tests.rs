@@ -80,6 +80,15 @@ fn rule_id_code_l3() {
     assert_eq!(RuleId::L3Mrg02.code(), "L3-MRG-02");
 }
 
+#[test]
+fn rule_id_code_sda() {
+    assert_eq!(RuleId::Sda01.code(), "SDA-01");
+    assert_eq!(RuleId::Sda02.code(), "SDA-02");
+    assert_eq!(RuleId::Sda03.code(), "SDA-03");
+    assert_eq!(RuleId::Sda04.code(), "SDA-04");
+    assert_eq!(RuleId::Sda05.code(), "SDA-05");
+}
+
 #[test]
 fn rule_id_code_extension() {
     let r = RuleId::Extension("com.acme.custom-check".to_owned());
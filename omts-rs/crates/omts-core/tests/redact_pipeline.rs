@@ -9,17 +9,24 @@
 //! - `person` nodes absent in `public` output.
 //! - `beneficial_ownership` edges absent in `public` output.
 //! - Replaced nodes produce exactly one `boundary_ref` stub (deduplication).
+//!
+//! The `redact_policy_*` tests cover [`redact_with_policy`] and the
+//! [`RedactionPolicy`] document format; `redact_report_*` covers the audit
+//! report of [`redact_with_report`]; `resolve_boundary_*` covers
+//! [`resolve_boundary_refs`] on redacted output; `redact_generalize_*` covers
+//! the `generalize` rules of a policy.
 #![allow(clippy::expect_used)]
 
 use std::collections::{BTreeMap, HashSet};
 
-use omts_core::newtypes::{EdgeId, FileSalt, NodeId, SemVer};
+use omts_core::newtypes::{CountryCode, EdgeId, FileSalt, NodeId, SemVer};
 use omts_core::structures::{Edge, EdgeProperties, Node};
 use omts_core::types::Identifier;
 use omts_core::validation::{RuleId, ValidationConfig, validate};
 use omts_core::{
-    CalendarDate, DisclosureScope, EdgeType, EdgeTypeTag, NodeType, NodeTypeTag, OmtsFile,
-    Sensitivity, redact,
+    CalendarDate, DisclosureScope, DropReason, EdgeOmitReason, EdgeType, EdgeTypeTag, NodeAction,
+    NodeReason, NodeType, NodeTypeTag, OmtsFile, RedactOutput, RedactionPolicy, Sensitivity,
+    redact, redact_with_policy, redact_with_report, resolve_boundary_refs,
 };
 
 const SALT: &str = "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
@@ -711,3 +718,551 @@ fn redact_full_featured_fixture_to_public() {
 
     assert_l1_valid(&output);
 }
+
+fn policy(json: serde_json::Value) -> RedactionPolicy {
+    serde_json::from_value(json).expect("valid policy")
+}
+
+fn make_facility_node(id: &str, jurisdiction: &str) -> Node {
+    Node {
+        id: nid(id),
+        node_type: NodeTypeTag::Known(NodeType::Facility),
+        name: Some(id.to_owned()),
+        jurisdiction: Some(CountryCode::try_from(jurisdiction).expect("valid country code")),
+        ..Node::default()
+    }
+}
+
+#[test]
+fn redact_policy_rules_override_retain_ids_and_sensitivity() {
+    let mut props = EdgeProperties {
+        annual_value: Some(1_000_000.0),
+        volume: Some(500.0),
+        contract_ref: Some("C-2026-17".to_owned()),
+        ..EdgeProperties::default()
+    };
+    props.extra.insert(
+        "_property_sensitivity".to_owned(),
+        serde_json::json!({ "contract_ref": "confidential" }).into(),
+    );
+    let nodes = vec![
+        make_org_node(
+            "org-a",
+            vec![
+                lei_id("5493006MHB84DD0ZWV18"),
+                restricted_id("duns", "081466849"),
+            ],
+        ),
+        make_org_node("org-b", vec![]),
+        make_org_node("org-c", vec![]),
+        make_facility_node("fac-cn", "CN"),
+        make_facility_node("fac-de", "DE"),
+        make_person_node("person-1"),
+    ];
+    let edges = vec![
+        make_edge_with_props("e-1", EdgeType::Supplies, "org-a", "org-b", props),
+        make_edge("e-2", EdgeType::Operates, "org-a", "fac-cn"),
+        make_edge("e-3", EdgeType::Operates, "org-a", "fac-de"),
+    ];
+    let file = make_file(nodes, edges);
+    let retain_ids: HashSet<NodeId> = ["org-a", "org-b", "fac-cn", "fac-de", "person-1"]
+        .into_iter()
+        .map(nid)
+        .collect();
+    let policy = policy(serde_json::json!({
+        "nodes": [
+            { "where": "type=facility AND jurisdiction=CN", "action": "replace" },
+            { "where": "type=person", "action": "omit" },
+            { "where": "name=org-c", "action": "retain" }
+        ],
+        "drop_identifiers": [{ "scheme": "duns", "where": "type=organization" }],
+        "edge_properties": [
+            { "property": "annual_value", "action": "drop" },
+            { "where": "type=supplies", "property": "contract_ref", "action": "keep" }
+        ]
+    }));
+
+    let output = redact_with_policy(&file, DisclosureScope::Partner, &retain_ids, &policy)
+        .expect("partner redact must succeed");
+
+    let fac_cn = find_node(&output, "fac-cn").expect("fac-cn present");
+    assert_eq!(
+        fac_cn.node_type,
+        NodeTypeTag::Known(NodeType::BoundaryRef),
+        "the replace rule takes precedence over retain_ids"
+    );
+    let fac_de = find_node(&output, "fac-de").expect("fac-de present");
+    assert_eq!(fac_de.node_type, NodeTypeTag::Known(NodeType::Facility));
+    assert!(find_node(&output, "person-1").is_none());
+    let org_c = find_node(&output, "org-c").expect("org-c present");
+    assert_eq!(org_c.name.as_deref(), Some("org-c"));
+
+    let org_a = find_node(&output, "org-a").expect("org-a present");
+    let schemes: Vec<&str> = org_a
+        .identifiers
+        .as_deref()
+        .unwrap_or(&[])
+        .iter()
+        .map(|id| id.scheme.as_str())
+        .collect();
+    assert_eq!(schemes, ["lei"]);
+
+    let supplies = output
+        .edges
+        .iter()
+        .find(|e| e.edge_type == EdgeTypeTag::Known(EdgeType::Supplies))
+        .expect("supplies edge retained");
+    assert_eq!(supplies.properties.annual_value, None);
+    assert_eq!(supplies.properties.volume, Some(500.0));
+    assert_eq!(
+        supplies.properties.contract_ref.as_deref(),
+        Some("C-2026-17"),
+        "keep overrides the confidential sensitivity"
+    );
+
+    assert_l1_valid(&output);
+}
+
+#[test]
+fn redact_policy_cannot_loosen_scope_and_drops_hash_inputs() {
+    let nodes = vec![
+        make_org_node("org-a", vec![]),
+        make_org_node("org-x", vec![lei_id("5493006MHB84DD0ZWV18")]),
+        make_person_node("person-1"),
+    ];
+    let edges = vec![
+        make_edge("e-1", EdgeType::Supplies, "org-x", "org-a"),
+        make_edge("e-2", EdgeType::BeneficialOwnership, "person-1", "org-a"),
+    ];
+    let file = make_file(nodes, edges);
+    let retain_ids: HashSet<NodeId> = [nid("org-a")].into_iter().collect();
+    let opaque = |policy: &RedactionPolicy| -> String {
+        let output = redact_with_policy(&file, DisclosureScope::Public, &retain_ids, policy)
+            .expect("public redact must succeed");
+        assert!(find_node(&output, "person-1").is_none());
+        assert_l1_valid(&output);
+        let bref = find_node(&output, "org-x").expect("org-x present");
+        bref.identifiers.as_deref().unwrap_or(&[])[0].value.clone()
+    };
+
+    let retain_person = policy(serde_json::json!({
+        "nodes": [{ "where": "type=person", "action": "retain" }]
+    }));
+    assert_eq!(
+        opaque(&retain_person),
+        opaque(&retain_person),
+        "the LEI gives a deterministic boundary reference"
+    );
+
+    let drop_lei = policy(serde_json::json!({
+        "drop_identifiers": [{ "scheme": "lei" }]
+    }));
+    assert_ne!(
+        opaque(&drop_lei),
+        opaque(&drop_lei),
+        "a dropped LEI must not feed the boundary reference hash"
+    );
+}
+
+#[test]
+fn redact_policy_rejects_malformed_documents() {
+    let parse = |json: serde_json::Value| {
+        serde_json::from_value::<RedactionPolicy>(json)
+            .expect_err("policy must be rejected")
+            .to_string()
+    };
+
+    let bad_expr = parse(serde_json::json!({
+        "nodes": [{ "where": "type=", "action": "omit" }]
+    }));
+    assert!(bad_expr.contains("nodes[0]"), "message: {bad_expr}");
+
+    let edge_only = parse(serde_json::json!({
+        "nodes": [{ "where": "edge.type=supplies", "action": "omit" }]
+    }));
+    assert!(
+        edge_only.contains("never matches a node"),
+        "message: {edge_only}"
+    );
+
+    let node_only = parse(serde_json::json!({
+        "edge_properties": [
+            { "where": "jurisdiction=CN", "property": "volume", "action": "drop" }
+        ]
+    }));
+    assert!(
+        node_only.contains("edge_properties[0]"),
+        "message: {node_only}"
+    );
+
+    parse(serde_json::json!({ "nodes": [], "scopes": [] }));
+    parse(serde_json::json!({
+        "edge_properties": [{ "property": "volume", "action": "hide" }]
+    }));
+}
+
+#[test]
+fn redact_report_records_every_disposition_and_removal() {
+    let mut props = EdgeProperties {
+        annual_value: Some(1_000_000.0),
+        volume_unit: Some("kg".to_owned()),
+        ..EdgeProperties::default()
+    };
+    props.extra.insert(
+        "_property_sensitivity".to_owned(),
+        serde_json::json!({ "volume_unit": "public" }).into(),
+    );
+    let nodes = vec![
+        make_org_node(
+            "org-a",
+            vec![
+                lei_id("5493006MHB84DD0ZWV18"),
+                restricted_id("nat-reg", "HRB 86891"),
+                Identifier {
+                    scheme: "duns".to_owned(),
+                    ..lei_id("081466849")
+                },
+            ],
+        ),
+        make_org_node("org-b", vec![]),
+        make_org_node("org-c", vec![]),
+        make_person_node("person-1"),
+    ];
+    let edges = vec![
+        make_edge_with_props("e-1", EdgeType::Supplies, "org-a", "org-b", props),
+        make_edge("e-2", EdgeType::Supplies, "org-b", "org-c"),
+        make_edge("e-3", EdgeType::BeneficialOwnership, "person-1", "org-a"),
+    ];
+    let file = make_file(nodes, edges);
+    let retain_ids: HashSet<NodeId> = [nid("org-a")].into_iter().collect();
+    let policy = policy(serde_json::json!({
+        "drop_identifiers": [{ "scheme": "duns" }]
+    }));
+
+    let RedactOutput {
+        file: output,
+        report,
+    } = redact_with_report(&file, DisclosureScope::Public, &retain_ids, &policy)
+        .expect("public redact must succeed");
+    assert_l1_valid(&output);
+    assert_eq!(report.scope, DisclosureScope::Public);
+
+    let dispositions: Vec<(&str, &NodeAction, NodeReason)> = report
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_ref(), &n.action, n.reason))
+        .collect();
+    assert_eq!(
+        dispositions,
+        [
+            ("org-a", &NodeAction::Retain, NodeReason::RetainSet),
+            ("org-b", &NodeAction::Replace, NodeReason::NotRetained),
+            ("org-c", &NodeAction::Replace, NodeReason::NotRetained),
+            ("person-1", &NodeAction::Omit, NodeReason::Scope),
+        ]
+    );
+    assert_eq!(report.count(&NodeAction::Replace), 2);
+    let org_b = find_node(&output, "org-b").expect("org-b present");
+    assert_eq!(
+        report.nodes[1].opaque_value.as_deref(),
+        org_b
+            .identifiers
+            .as_deref()
+            .map(|ids| ids[0].value.as_str()),
+        "the report carries the boundary ref's opaque value"
+    );
+
+    let dropped: Vec<(&str, &DropReason)> = report
+        .identifiers_dropped
+        .iter()
+        .map(|d| (d.value.as_str(), &d.reason))
+        .collect();
+    assert_eq!(
+        dropped,
+        [
+            (
+                "HRB 86891",
+                &DropReason::Sensitivity {
+                    sensitivity: Sensitivity::Restricted
+                }
+            ),
+            ("081466849", &DropReason::Policy),
+        ]
+    );
+
+    let omitted: Vec<(&str, EdgeOmitReason)> = report
+        .edges_omitted
+        .iter()
+        .map(|e| (e.id.as_ref(), e.reason))
+        .collect();
+    assert_eq!(
+        omitted,
+        [
+            ("e-2", EdgeOmitReason::BothEndpointsReplaced),
+            ("e-3", EdgeOmitReason::BeneficialOwnership),
+        ]
+    );
+
+    let mut stripped: Vec<(&str, &DropReason)> = report
+        .properties_stripped
+        .iter()
+        .map(|p| (p.property.as_str(), &p.reason))
+        .collect();
+    stripped.sort_by_key(|(property, _)| *property);
+    assert_eq!(
+        stripped,
+        [
+            ("_property_sensitivity", &DropReason::PublicScope),
+            (
+                "annual_value",
+                &DropReason::Sensitivity {
+                    sensitivity: Sensitivity::Restricted
+                }
+            ),
+        ]
+    );
+
+    let json = serde_json::to_value(&report.identifiers_dropped[0]).expect("serialize");
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "node_id": "org-a",
+            "scheme": "nat-reg",
+            "value": "HRB 86891",
+            "reason": "sensitivity",
+            "sensitivity": "restricted"
+        })
+    );
+}
+
+fn public_id(scheme: &str, value: &str) -> Identifier {
+    Identifier {
+        scheme: scheme.to_owned(),
+        ..lei_id(value)
+    }
+}
+
+/// Replaced nodes resolve to candidates holding their public identifiers,
+/// whether the candidates come from the original file or from richer master
+/// data; random-path references stay unresolved.
+#[test]
+fn resolve_boundary_refs_matches_original_and_master_data_candidates() {
+    let nodes = vec![
+        make_org_node("org-a", vec![lei_id("5493006MHB84DD0ZWV18")]),
+        make_org_node(
+            "org-b",
+            vec![
+                lei_id("529900T8BM49AURSDO55"),
+                public_id("duns", "081466849"),
+                restricted_id("vat", "DE123456789"),
+            ],
+        ),
+        make_org_node("org-c", vec![restricted_id("vat", "FR12345678901")]),
+    ];
+    let edges = vec![
+        make_edge("e-1", EdgeType::Supplies, "org-b", "org-a"),
+        make_edge("e-2", EdgeType::Supplies, "org-c", "org-a"),
+    ];
+    let file = make_file(nodes, edges);
+    let retain_ids: HashSet<NodeId> = [nid("org-a")].into_iter().collect();
+    let redacted =
+        redact(&file, DisclosureScope::Partner, &retain_ids).expect("partner redact succeeds");
+
+    let resolved = resolve_boundary_refs(&redacted, &file.nodes).expect("resolve succeeds");
+    assert_eq!(resolved.len(), 2, "one entry per boundary_ref");
+    assert_eq!(resolved[0].node_id, nid("org-b"));
+    assert_eq!(resolved[0].matches.len(), 1);
+    let exact = &resolved[0].matches[0];
+    assert_eq!(exact.candidate_id, nid("org-b"));
+    assert!(exact.complete);
+    assert_eq!(
+        exact.identifiers,
+        ["duns:081466849", "lei:529900T8BM49AURSDO55"]
+    );
+    assert_eq!(resolved[1].node_id, nid("org-c"));
+    assert!(resolved[1].matches.is_empty(), "random path never resolves");
+
+    // Master data knows the supplier by more identifiers than the sender.
+    let master = vec![
+        make_org_node(
+            "sup-17",
+            vec![
+                lei_id("529900T8BM49AURSDO55"),
+                public_id("duns", "081466849"),
+                public_id("gln", "4012345000009"),
+            ],
+        ),
+        make_org_node("sup-18", vec![lei_id("529900T8BM49AURSDO55")]),
+    ];
+    let resolved = resolve_boundary_refs(&redacted, &master).expect("resolve succeeds");
+    let matches = &resolved[0].matches;
+    assert_eq!(matches.len(), 1, "sup-18 lacks the sender's DUNS number");
+    assert_eq!(matches[0].candidate_id, nid("sup-17"));
+    assert!(!matches[0].complete);
+}
+
+/// Public-scope `generalize` rules disclose bands and magnitudes in place of
+/// restricted values, coarsen facility coordinates, and leave partner output
+/// exact when scoped to `public`.
+#[test]
+fn redact_generalize_discloses_ranges_instead_of_exact_values() {
+    let supplies = make_edge_with_props(
+        "e-1",
+        EdgeType::Supplies,
+        "org-b",
+        "org-a",
+        EdgeProperties {
+            annual_value: Some(2_500_000.0),
+            value_currency: Some("EUR".to_owned()),
+            volume: Some(5000.0),
+            volume_unit: Some("kg".to_owned()),
+            ..EdgeProperties::default()
+        },
+    );
+    let ownership = make_edge_with_props(
+        "e-2",
+        EdgeType::Ownership,
+        "org-a",
+        "org-b",
+        EdgeProperties {
+            percentage: Some(30.0),
+            ..EdgeProperties::default()
+        },
+    );
+    let mut facility = make_facility_node("fac-1", "DE");
+    facility.geo = Some(serde_json::json!({ "lat": 52.520_08, "lon": 13.404_95 }).into());
+    let file = make_file(
+        vec![
+            make_org_node("org-a", vec![lei_id("5493006MHB84DD0ZWV18")]),
+            make_org_node("org-b", vec![lei_id("529900T8BM49AURSDO55")]),
+            facility,
+        ],
+        vec![supplies, ownership],
+    );
+    let policy = policy(serde_json::json!({
+        "generalize": [
+            { "property": "annual_value", "method": "bands",
+              "bands": [100_000, 1_000_000, 10_000_000], "scopes": ["public"] },
+            { "property": "volume", "method": "magnitude", "scopes": ["public"] },
+            { "property": "percentage", "method": "bands", "bands": [25, 50] },
+            { "property": "geo", "where": "type=facility", "method": "round", "decimals": 1 }
+        ]
+    }));
+    let retain_ids: HashSet<NodeId> = ["org-a", "org-b", "fac-1"].into_iter().map(nid).collect();
+
+    let RedactOutput {
+        file: output,
+        report,
+    } = redact_with_report(&file, DisclosureScope::Public, &retain_ids, &policy)
+        .expect("public redact succeeds");
+    assert_l1_valid(&output);
+
+    let e1 = output
+        .edges
+        .iter()
+        .find(|e| e.id == eid("e-1"))
+        .expect("e-1");
+    assert_eq!(e1.properties.annual_value, None);
+    assert_eq!(e1.properties.volume, None);
+    assert_eq!(
+        serde_json::Value::from(e1.properties.extra["_generalized"].clone()),
+        serde_json::json!({
+            "annual_value": { "min": 1_000_000, "max": 10_000_000, "unit": "EUR" },
+            "volume": { "min": 1000, "max": 10000, "unit": "kg" }
+        })
+    );
+    let e2 = output
+        .edges
+        .iter()
+        .find(|e| e.id == eid("e-2"))
+        .expect("e-2");
+    assert_eq!(e2.properties.percentage, None);
+    assert_eq!(
+        serde_json::Value::from(e2.properties.extra["_generalized"].clone()),
+        serde_json::json!({ "percentage": { "min": 25, "max": 50 } })
+    );
+    let fac = find_node(&output, "fac-1").expect("fac-1 retained");
+    assert_eq!(
+        fac.geo.clone().map(serde_json::Value::from),
+        Some(serde_json::json!({ "lat": 52.5, "lon": 13.4 }))
+    );
+    assert_eq!(
+        serde_json::Value::from(fac.extra["_generalized"].clone()),
+        serde_json::json!({ "geo": { "method": "round", "decimals": 1 } })
+    );
+
+    let generalized: Vec<(&str, &str)> = report
+        .properties_generalized
+        .iter()
+        .map(|g| (g.edge_id.as_ref(), g.property.as_str()))
+        .collect();
+    assert_eq!(
+        generalized,
+        [
+            ("e-1", "annual_value"),
+            ("e-1", "volume"),
+            ("e-2", "percentage")
+        ]
+    );
+    assert!(
+        report
+            .properties_stripped
+            .iter()
+            .all(|p| p.property != "annual_value" && p.property != "volume"),
+        "generalised properties are not reported as stripped"
+    );
+    assert_eq!(report.geo_generalized.len(), 1);
+    assert!(!report.geo_generalized[0].removed);
+
+    // The `public`-only rules do not apply at `partner`, which keeps the
+    // restricted figures exact.
+    let partner = redact_with_policy(&file, DisclosureScope::Partner, &retain_ids, &policy)
+        .expect("partner redact succeeds");
+    let e1 = partner
+        .edges
+        .iter()
+        .find(|e| e.id == eid("e-1"))
+        .expect("e-1");
+    assert_eq!(e1.properties.annual_value, Some(2_500_000.0));
+    assert_eq!(e1.properties.volume, Some(5000.0));
+    assert!(!e1.properties.extra.contains_key("_generalized"));
+}
+
+#[test]
+fn redact_generalize_rejects_mismatched_methods() {
+    let parse = |json: serde_json::Value| {
+        serde_json::from_value::<RedactionPolicy>(json)
+            .expect_err("policy must be rejected")
+            .to_string()
+    };
+
+    let geo_bands = parse(serde_json::json!({
+        "generalize": [{ "property": "geo", "method": "bands", "bands": [1] }]
+    }));
+    assert!(geo_bands.contains("generalize[0]"), "message: {geo_bands}");
+
+    let number_round = parse(serde_json::json!({
+        "generalize": [{ "property": "volume", "method": "round", "decimals": 2 }]
+    }));
+    assert!(
+        number_round.contains("edge property"),
+        "message: {number_round}"
+    );
+
+    let unsorted = parse(serde_json::json!({
+        "generalize": [{ "property": "percentage", "method": "bands", "bands": [50, 25] }]
+    }));
+    assert!(
+        unsorted.contains("strictly increasing"),
+        "message: {unsorted}"
+    );
+
+    let internal = parse(serde_json::json!({
+        "generalize": [{ "property": "volume", "method": "magnitude", "scopes": ["internal"] }]
+    }));
+    assert!(internal.contains("internal"), "message: {internal}");
+
+    parse(serde_json::json!({
+        "generalize": [{ "property": "percentage", "method": "bands" }]
+    }));
+}